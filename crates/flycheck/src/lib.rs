@@ -4,6 +4,8 @@
 
 #![warn(rust_2018_idioms, unused_lifetimes, semicolon_in_expressions_from_macros)]
 
+mod test_runner;
+
 use std::{
     ffi::OsString,
     fmt, io,
@@ -24,6 +26,8 @@ pub use cargo_metadata::diagnostic::{
     DiagnosticSpanMacroExpansion,
 };
 
+pub use crate::test_runner::{CargoTestHandle, CargoTestMessage, TestState};
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum InvocationStrategy {
     Once,
@@ -171,7 +175,7 @@ struct FlycheckActor {
     /// doesn't provide a way to read sub-process output without blocking, so we
     /// have to wrap sub-processes output handling in a thread and pass messages
     /// back over a channel.
-    command_handle: Option<CommandHandle>,
+    command_handle: Option<CommandHandle<CargoMessage>>,
 }
 
 enum Event {
@@ -227,7 +231,7 @@ impl FlycheckActor {
                     let formatted_command = format!("{:?}", command);
 
                     tracing::debug!(?command, "will restart flycheck");
-                    match CommandHandle::spawn(command) {
+                    match CommandHandle::spawn(command, parse_cargo_message) {
                         Ok(command_handle) => {
                             tracing::debug!(command = formatted_command, "did  restart flycheck");
                             self.command_handle = Some(command_handle);
@@ -398,18 +402,18 @@ impl Drop for JodGroupChild {
 }
 
 /// A handle to a cargo process used for fly-checking.
-struct CommandHandle {
+struct CommandHandle<T> {
     /// The handle to the actual cargo process. As we cannot cancel directly from with
     /// a read syscall dropping and therefore terminating the process is our best option.
     child: JodGroupChild,
     thread: stdx::thread::JoinHandle<io::Result<(bool, String)>>,
-    receiver: Receiver<CargoMessage>,
+    receiver: Receiver<T>,
     program: OsString,
     arguments: Vec<OsString>,
     current_dir: Option<PathBuf>,
 }
 
-impl fmt::Debug for CommandHandle {
+impl<T> fmt::Debug for CommandHandle<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("CommandHandle")
             .field("program", &self.program)
@@ -419,8 +423,12 @@ impl fmt::Debug for CommandHandle {
     }
 }
 
-impl CommandHandle {
-    fn spawn(mut command: Command) -> std::io::Result<CommandHandle> {
+/// Parses a line of output of a command, sending the messages it contains. Returns `false` if
+/// the line couldn't be parsed.
+type LineParser<T> = fn(&str, &Sender<T>) -> bool;
+
+impl<T: Send + 'static> CommandHandle<T> {
+    fn spawn(mut command: Command, parser: LineParser<T>) -> std::io::Result<CommandHandle<T>> {
         command.stdout(Stdio::piped()).stderr(Stdio::piped()).stdin(Stdio::null());
         let mut child = command.group_spawn().map(JodGroupChild)?;
 
@@ -432,7 +440,7 @@ impl CommandHandle {
         let stderr = child.0.inner().stderr.take().unwrap();
 
         let (sender, receiver) = unbounded();
-        let actor = CargoActor::new(sender, stdout, stderr);
+        let actor = CargoActor::new(sender, parser, stdout, stderr);
         let thread = stdx::thread::Builder::new(stdx::thread::ThreadIntent::Worker)
             .name("CargoHandle".to_owned())
             .spawn(move || actor.run())
@@ -459,15 +467,21 @@ impl CommandHandle {
     }
 }
 
-struct CargoActor {
-    sender: Sender<CargoMessage>,
+struct CargoActor<T> {
+    sender: Sender<T>,
+    parser: LineParser<T>,
     stdout: ChildStdout,
     stderr: ChildStderr,
 }
 
-impl CargoActor {
-    fn new(sender: Sender<CargoMessage>, stdout: ChildStdout, stderr: ChildStderr) -> CargoActor {
-        CargoActor { sender, stdout, stderr }
+impl<T> CargoActor<T> {
+    fn new(
+        sender: Sender<T>,
+        parser: LineParser<T>,
+        stdout: ChildStdout,
+        stderr: ChildStderr,
+    ) -> CargoActor<T> {
+        CargoActor { sender, parser, stdout, stderr }
     }
 
     fn run(self) -> io::Result<(bool, String)> {
@@ -485,25 +499,7 @@ impl CargoActor {
         let mut read_at_least_one_stdout_message = false;
         let mut read_at_least_one_stderr_message = false;
         let process_line = |line: &str, error: &mut String| {
-            // Try to deserialize a message from Cargo or Rustc.
-            let mut deserializer = serde_json::Deserializer::from_str(line);
-            deserializer.disable_recursion_limit();
-            if let Ok(message) = JsonMessage::deserialize(&mut deserializer) {
-                match message {
-                    // Skip certain kinds of messages to only spend time on what's useful
-                    JsonMessage::Cargo(message) => match message {
-                        cargo_metadata::Message::CompilerArtifact(artifact) if !artifact.fresh => {
-                            self.sender.send(CargoMessage::CompilerArtifact(artifact)).unwrap();
-                        }
-                        cargo_metadata::Message::CompilerMessage(msg) => {
-                            self.sender.send(CargoMessage::Diagnostic(msg.message)).unwrap();
-                        }
-                        _ => (),
-                    },
-                    JsonMessage::Rustc(message) => {
-                        self.sender.send(CargoMessage::Diagnostic(message)).unwrap();
-                    }
-                }
+            if (self.parser)(line, &self.sender) {
                 return true;
             }
 
@@ -537,6 +533,29 @@ impl CargoActor {
     }
 }
 
+fn parse_cargo_message(line: &str, sender: &Sender<CargoMessage>) -> bool {
+    // Try to deserialize a message from Cargo or Rustc.
+    let mut deserializer = serde_json::Deserializer::from_str(line);
+    deserializer.disable_recursion_limit();
+    let Ok(message) = JsonMessage::deserialize(&mut deserializer) else { return false };
+    match message {
+        // Skip certain kinds of messages to only spend time on what's useful
+        JsonMessage::Cargo(message) => match message {
+            cargo_metadata::Message::CompilerArtifact(artifact) if !artifact.fresh => {
+                sender.send(CargoMessage::CompilerArtifact(artifact)).unwrap();
+            }
+            cargo_metadata::Message::CompilerMessage(msg) => {
+                sender.send(CargoMessage::Diagnostic(msg.message)).unwrap();
+            }
+            _ => (),
+        },
+        JsonMessage::Rustc(message) => {
+            sender.send(CargoMessage::Diagnostic(message)).unwrap();
+        }
+    }
+    true
+}
+
 enum CargoMessage {
     CompilerArtifact(cargo_metadata::Artifact),
     Diagnostic(Diagnostic),
@@ -0,0 +1,102 @@
+//! Runs `cargo test` in a background thread and reports the outcome of each test, using the
+//! JSON output of the test harness.
+
+use std::{io, process::Command};
+
+use crossbeam_channel::{Receiver, Sender};
+use serde::Deserialize;
+
+use crate::CommandHandle;
+
+/// A message of the test harness, or a line of other output of `cargo test`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CargoTestMessage {
+    Test {
+        name: String,
+        #[serde(flatten)]
+        state: TestState,
+    },
+    Suite,
+    #[serde(skip)]
+    Custom {
+        text: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "event", rename_all = "camelCase")]
+pub enum TestState {
+    Started,
+    Ok,
+    Ignored,
+    Failed {
+        /// The captured output of the test.
+        #[serde(default)]
+        stdout: String,
+    },
+}
+
+/// A running `cargo test` process. The process is killed when this is dropped, and the
+/// receiver disconnects once it has exited.
+#[derive(Debug)]
+pub struct CargoTestHandle {
+    handle: CommandHandle<CargoTestMessage>,
+}
+
+impl CargoTestHandle {
+    /// Spawns `command`, a `cargo test` invocation selecting the tests to build, passing
+    /// `test_args` to the test harness.
+    pub fn spawn(mut command: Command, test_args: &[String]) -> io::Result<CargoTestHandle> {
+        // The JSON output of the test harness is unstable.
+        command.env("RUSTC_BOOTSTRAP", "1");
+        command.arg("--");
+        command.args(test_args);
+        command.args(["-Z", "unstable-options", "--format=json"]);
+        Ok(CargoTestHandle { handle: CommandHandle::spawn(command, parse_test_message)? })
+    }
+
+    pub fn receiver(&self) -> &Receiver<CargoTestMessage> {
+        &self.handle.receiver
+    }
+}
+
+fn parse_test_message(line: &str, sender: &Sender<CargoTestMessage>) -> bool {
+    let message = serde_json::from_str(line)
+        .unwrap_or_else(|_| CargoTestMessage::Custom { text: line.to_owned() });
+    // The receiver is gone if the test run was aborted.
+    sender.send(message).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> CargoTestMessage {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        assert!(parse_test_message(line, &sender));
+        receiver.recv().unwrap()
+    }
+
+    #[test]
+    fn parses_harness_output() {
+        let message = parse(r#"{ "type": "test", "event": "started", "name": "tests::a" }"#);
+        assert!(matches!(
+            message,
+            CargoTestMessage::Test { name, state: TestState::Started } if name == "tests::a"
+        ));
+        let message = parse(
+            r#"{ "type": "test", "name": "tests::b", "event": "failed", "stdout": "oh no\n" }"#,
+        );
+        assert!(matches!(
+            message,
+            CargoTestMessage::Test { state: TestState::Failed { stdout }, .. } if stdout == "oh no\n"
+        ));
+        let message = parse(r#"{ "type": "suite", "event": "ok", "passed": 1, "failed": 0 }"#);
+        assert!(matches!(message, CargoTestMessage::Suite));
+        let message = parse("   Compiling foo v0.1.0");
+        assert!(
+            matches!(message, CargoTestMessage::Custom { text } if text == "   Compiling foo v0.1.0")
+        );
+    }
+}
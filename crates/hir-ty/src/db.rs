@@ -18,7 +18,7 @@ use crate::{
     consteval::ConstEvalError,
     layout::{Layout, LayoutError},
     method_resolution::{InherentImpls, TraitImpls, TyFingerprint},
-    mir::{BorrowckResult, MirBody, MirLowerError},
+    mir::{BorrowckResult, MirBody, MirLowerError, ScopeEndDrop},
     Binders, CallableDefId, ClosureId, Const, FnDefId, GenericArg, ImplTraitId, InferenceResult,
     Interner, PolyFnSig, QuantifiedWhereClause, ReturnTypeImplTraits, Substitution, TraitRef, Ty,
     TyDefId, ValueTyDefId,
@@ -61,6 +61,9 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::invoke(crate::mir::borrowck_query)]
     fn borrowck(&self, def: DefWithBodyId) -> Result<Arc<[BorrowckResult]>, MirLowerError>;
 
+    #[salsa::invoke(crate::mir::scope_end_drops_query)]
+    fn scope_end_drops(&self, def: DefWithBodyId) -> Result<Arc<[ScopeEndDrop]>, MirLowerError>;
+
     #[salsa::invoke(crate::lower::ty_query)]
     #[salsa::cycle(crate::lower::ty_recover)]
     fn ty(&self, def: TyDefId) -> Binders<Ty>;
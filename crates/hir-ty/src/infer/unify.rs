@@ -82,7 +82,9 @@ pub fn could_unify(
     unify(db, env, tys).is_some()
 }
 
-pub(crate) fn unify(
+/// Unifies the two types of `tys`, returning what their bound variables got instantiated with.
+/// Variables that stay unconstrained are given back as bound variables.
+pub fn unify(
     db: &dyn HirDatabase,
     env: Arc<TraitEnvironment>,
     tys: &Canonical<(Ty, Ty)>,
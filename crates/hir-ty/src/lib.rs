@@ -67,9 +67,10 @@ pub use builder::{ParamKind, TyBuilder};
 pub use chalk_ext::*;
 pub use infer::{
     closure::{CaptureKind, CapturedItem},
-    could_coerce, could_unify, Adjust, Adjustment, AutoBorrow, BindingMode, InferenceDiagnostic,
-    InferenceResult, OverloadedDeref, PointerCast,
+    could_coerce, could_unify, unify, Adjust, Adjustment, AutoBorrow, BindingMode,
+    InferenceDiagnostic, InferenceResult, OverloadedDeref, PointerCast,
 };
+pub use inhabitedness::{is_enum_variant_uninhabited_from, is_ty_uninhabited_from};
 pub use interner::Interner;
 pub use lower::{
     associated_type_shorthand_candidates, CallableDefId, ImplTraitLoweringMode, TyDefId,
@@ -81,7 +82,7 @@ pub use mapping::{
     to_placeholder_idx,
 };
 pub use traits::TraitEnvironment;
-pub use utils::{all_super_traits, is_fn_unsafe_to_call};
+pub use utils::{all_super_traits, direct_super_traits, is_fn_unsafe_to_call};
 
 pub use chalk_ir::{
     cast::Cast, AdtId, BoundVar, DebruijnIndex, Mutability, Safety, Scalar, TyVariableKind,
@@ -615,6 +616,17 @@ where
     Canonical { value, binders: chalk_ir::CanonicalVarKinds::from_iter(Interner, kinds) }
 }
 
+/// Binds the variables of `value`, which are of the given kinds, in a [`Canonical`].
+pub fn make_canonical<T: HasInterner<Interner = Interner>>(
+    value: T,
+    kinds: impl IntoIterator<Item = VariableKind>,
+) -> Canonical<T> {
+    let kinds = kinds
+        .into_iter()
+        .map(|kind| chalk_ir::CanonicalVarKind::new(kind, chalk_ir::UniverseIndex::ROOT));
+    Canonical { value, binders: CanonicalVarKinds::from_iter(Interner, kinds) }
+}
+
 pub fn callable_sig_from_fnonce(
     mut self_ty: &Ty,
     env: Arc<TraitEnvironment>,
@@ -253,6 +253,11 @@ impl TraitImpls {
             .flat_map(|v| v.iter().copied())
     }
 
+    /// Queries all impls that may apply to any self type, like `impl<T> Trait for T`.
+    pub fn blanket_impls(&self) -> impl Iterator<Item = ImplId> + '_ {
+        self.map.values().flat_map(|map| map.get(&None).into_iter().flat_map(|v| v.iter().copied()))
+    }
+
     pub fn all_impls(&self) -> impl Iterator<Item = ImplId> + '_ {
         self.map.values().flat_map(|map| map.values().flat_map(|v| v.iter().copied()))
     }
@@ -727,12 +732,54 @@ pub(crate) fn lookup_impl_method_query(
     )
 }
 
+/// Looks up the impl a call of the trait method `func` dispatches to, whether or not the impl
+/// overrides the method.
+pub fn lookup_impl_for_method(
+    db: &dyn HirDatabase,
+    env: Arc<TraitEnvironment>,
+    func: FunctionId,
+    fn_subst: Substitution,
+) -> Option<ImplId> {
+    let ItemContainerId::TraitId(trait_id) = func.lookup(db.upcast()).container else {
+        return None;
+    };
+    let trait_params = db.generic_params(trait_id.into()).type_or_consts.len();
+    let fn_params = fn_subst.len(Interner) - trait_params;
+    let trait_ref = TraitRef {
+        trait_id: to_chalk_trait_id(trait_id),
+        substitution: Substitution::from_iter(Interner, fn_subst.iter(Interner).skip(fn_params)),
+    };
+    lookup_impl_for_trait_ref(trait_ref, db, env).map(|(impl_id, _)| impl_id)
+}
+
 fn lookup_impl_assoc_item_for_trait_ref(
     trait_ref: TraitRef,
     db: &dyn HirDatabase,
     env: Arc<TraitEnvironment>,
     name: &Name,
 ) -> Option<(AssocItemId, Substitution)> {
+    let (impl_id, impl_subst) = lookup_impl_for_trait_ref(trait_ref, db, env)?;
+    let impl_data = db.impl_data(impl_id);
+    let item = impl_data.items.iter().find_map(|&it| match it {
+        AssocItemId::FunctionId(f) => {
+            (db.function_data(f).name == *name).then_some(AssocItemId::FunctionId(f))
+        }
+        AssocItemId::ConstId(c) => db
+            .const_data(c)
+            .name
+            .as_ref()
+            .map(|n| n == name)
+            .and_then(|result| if result { Some(AssocItemId::ConstId(c)) } else { None }),
+        AssocItemId::TypeAliasId(_) => None,
+    })?;
+    Some((item, impl_subst))
+}
+
+fn lookup_impl_for_trait_ref(
+    trait_ref: TraitRef,
+    db: &dyn HirDatabase,
+    env: Arc<TraitEnvironment>,
+) -> Option<(ImplId, Substitution)> {
     let hir_trait_id = trait_ref.hir_trait_id();
     let self_ty = trait_ref.self_type_parameter(Interner);
     let self_ty_fp = TyFingerprint::for_trait_impl(&self_ty)?;
@@ -750,31 +797,17 @@ fn lookup_impl_assoc_item_for_trait_ref(
 
     let table = InferenceTable::new(db, env);
 
-    let (impl_data, impl_subst) = find_matching_impl(impls, table, trait_ref)?;
-    let item = impl_data.items.iter().find_map(|&it| match it {
-        AssocItemId::FunctionId(f) => {
-            (db.function_data(f).name == *name).then_some(AssocItemId::FunctionId(f))
-        }
-        AssocItemId::ConstId(c) => db
-            .const_data(c)
-            .name
-            .as_ref()
-            .map(|n| n == name)
-            .and_then(|result| if result { Some(AssocItemId::ConstId(c)) } else { None }),
-        AssocItemId::TypeAliasId(_) => None,
-    })?;
-    Some((item, impl_subst))
+    find_matching_impl(impls, table, trait_ref)
 }
 
 fn find_matching_impl(
     mut impls: impl Iterator<Item = ImplId>,
     mut table: InferenceTable<'_>,
     actual_trait_ref: TraitRef,
-) -> Option<(Arc<ImplData>, Substitution)> {
+) -> Option<(ImplId, Substitution)> {
     let db = table.db;
     impls.find_map(|impl_| {
         table.run_in_snapshot(|table| {
-            let impl_data = db.impl_data(impl_);
             let impl_substs =
                 TyBuilder::subst_for_def(db, impl_, None).fill_with_inference_vars(table).build();
             let trait_ref = db
@@ -792,7 +825,7 @@ fn find_matching_impl(
             let goal = crate::Goal::all(Interner, wcs);
             table.try_obligation(goal.clone())?;
             table.register_obligation(goal);
-            Some((impl_data, table.resolve_completely(impl_substs)))
+            Some((impl_, table.resolve_completely(impl_substs)))
         })
     })
 }
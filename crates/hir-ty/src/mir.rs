@@ -23,10 +23,12 @@ use la_arena::{Arena, ArenaMap, Idx, RawIdx};
 mod eval;
 mod lower;
 mod borrowck;
+mod drops;
 mod pretty;
 mod monomorphization;
 
 pub use borrowck::{borrowck_query, BorrowckResult, MutabilityReason};
+pub use drops::{scope_end_drops_query, DroppedValue, ScopeEndDrop};
 pub use eval::{
     interpret_mir, pad16, render_const_using_debug_impl, Evaluator, MirEvalError, VTableMap,
 };
@@ -1066,6 +1068,10 @@ pub struct MirBody {
     /// This field stores the closures directly owned by this body. It is used
     /// in traversing every mir body.
     pub closures: Vec<ClosureId>,
+    /// The expressions whose end drops the locals of a scope, for the blocks ending with such a
+    /// `Drop`. The terminators themselves keep an unknown span, this is only used to show the
+    /// drop order to the user.
+    pub scope_end_drops: ArenaMap<BasicBlockId, MirSpan>,
 }
 
 impl MirBody {
@@ -1166,6 +1172,7 @@ impl MirBody {
             param_locals,
             closures,
             projection_store,
+            scope_end_drops,
         } = self;
         projection_store.shrink_to_fit();
         basic_blocks.shrink_to_fit();
@@ -1173,6 +1180,7 @@ impl MirBody {
         binding_locals.shrink_to_fit();
         param_locals.shrink_to_fit();
         closures.shrink_to_fit();
+        scope_end_drops.shrink_to_fit();
         for (_, b) in basic_blocks.iter_mut() {
             let BasicBlock { statements, terminator: _, is_cleanup: _ } = b;
             statements.shrink_to_fit();
@@ -1180,7 +1188,7 @@ impl MirBody {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum MirSpan {
     ExprId(ExprId),
     PatId(PatId),
@@ -0,0 +1,394 @@
+//! Collects the values MIR drops when scopes end, for showing the drop order to the user.
+//!
+//! MIR lowering emits a `Drop` for every non-`Copy` local at the end of its scope, and leaves
+//! checking whether it was moved out of to the interpreter. We only report the locals that may
+//! still be initialized at the drop and whose type has drop glue.
+//!
+//! MIR lowering also drops the temporaries of `let` initializers at the end of the block, while
+//! they are dropped at the end of the `let` statement unless their lifetime is extended, so we
+//! move those to the statement.
+
+use std::iter;
+
+use chalk_ir::cast::Cast;
+use either::Either;
+use hir_def::{
+    body::Body,
+    data::adt::StructFlags,
+    hir::{Array, BindingId, Expr, ExprId, Statement},
+    lang_item::LangItem,
+    AdtId, DefWithBodyId, EnumVariantId, HasModule, VariantId,
+};
+use la_arena::ArenaMap;
+use rustc_hash::{FxHashMap, FxHashSet};
+use triomphe::Arc;
+
+use crate::{
+    db::HirDatabase, mir::Operand, utils::ClosureSubst, Canonical, CanonicalVarKinds,
+    InEnvironment, Interner, Ty, TyBuilder, TyExt, TyKind,
+};
+
+use super::{
+    BasicBlockId, LocalId, MirBody, MirLowerError, MirSpan, ProjectionId, Rvalue, StatementKind,
+    TerminatorKind,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeEndDrop {
+    /// The expression whose end drops the value. This is the block of the scope, the statement or
+    /// `let` initializer owning a temporary, or an expression leaving the scope early like `return`
+    /// and `break`.
+    pub span: MirSpan,
+    pub value: DroppedValue,
+    pub ty: Ty,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DroppedValue {
+    Binding(BindingId),
+    /// A temporary holding the result of the expression at the span.
+    Temporary(MirSpan),
+}
+
+/// Returns the values dropped at scope ends in `def` and the closures in it, in the order they are
+/// dropped.
+pub fn scope_end_drops_query(
+    db: &dyn HirDatabase,
+    def: DefWithBodyId,
+) -> Result<Arc<[ScopeEndDrop]>, MirLowerError> {
+    let _p = profile::span("scope_end_drops_query");
+    let mut result = vec![];
+    let let_temporaries = let_temporaries(&db.body(def));
+    let mut bodies = vec![db.mir_body(def)?];
+    while let Some(body) = bodies.pop() {
+        db.unwind_if_cancelled();
+        drops_of_body(db, &body, &let_temporaries, &mut result);
+        for &closure in &body.closures {
+            bodies.push(db.mir_body_for_closure(closure)?);
+        }
+    }
+    Ok(result.into())
+}
+
+fn drops_of_body(
+    db: &dyn HirDatabase,
+    body: &MirBody,
+    let_temporaries: &FxHashMap<ExprId, (ExprId, ExprId)>,
+    acc: &mut Vec<ScopeEndDrop>,
+) {
+    let bindings: FxHashMap<LocalId, BindingId> =
+        body.binding_locals.iter().map(|(binding, &local)| (local, binding)).collect();
+    let mut created_at = FxHashMap::default();
+    for (_, block) in body.basic_blocks.iter() {
+        for statement in &block.statements {
+            if let StatementKind::StorageLive(local) = statement.kind {
+                created_at.entry(local).or_insert(statement.span);
+            }
+        }
+    }
+    let maybe_initialized = maybe_initialized_drops(db, body);
+
+    let mut seen = FxHashSet::default();
+    for (block_id, block) in body.basic_blocks.iter() {
+        let Some(terminator) = &block.terminator else { continue };
+        let TerminatorKind::Drop { place, .. } = &terminator.kind else { continue };
+        let Some(&scope_end) = body.scope_end_drops.get(block_id) else { continue };
+        if !maybe_initialized.contains(&block_id) {
+            continue;
+        }
+        let value = match bindings.get(&place.local) {
+            Some(&binding) => DroppedValue::Binding(binding),
+            None => match created_at.get(&place.local) {
+                Some(&span) if span != MirSpan::Unknown => DroppedValue::Temporary(span),
+                _ => continue,
+            },
+        };
+        let mut span = scope_end;
+        if let DroppedValue::Temporary(MirSpan::ExprId(temp)) = value {
+            match let_temporaries.get(&temp) {
+                Some(&(block, initializer)) if span == MirSpan::ExprId(block) => {
+                    span = MirSpan::ExprId(initializer)
+                }
+                _ => (),
+            }
+        }
+        let ty = body.locals[place.local].ty.clone();
+        if !seen.insert((span, value))
+            || !has_drop_glue(db, &ty, body.owner, &mut FxHashSet::default())
+        {
+            continue;
+        }
+        acc.push(ScopeEndDrop { span, value, ty });
+    }
+}
+
+/// Maps the expressions in `let` initializers whose temporaries aren't lifetime extended to the
+/// block of the statement and the initializer.
+fn let_temporaries(body: &Body) -> FxHashMap<ExprId, (ExprId, ExprId)> {
+    let mut result = FxHashMap::default();
+    for (block, expr) in body.exprs.iter() {
+        let (Expr::Block { statements, .. }
+        | Expr::Async { statements, .. }
+        | Expr::Unsafe { statements, .. }) = expr
+        else {
+            continue;
+        };
+        for statement in statements.iter() {
+            let &Statement::Let { initializer: Some(initializer), .. } = statement else {
+                continue;
+            };
+            let mut extended = FxHashSet::default();
+            extended_temporaries(body, initializer, &mut extended);
+            let mut stack = vec![initializer];
+            while let Some(expr) = stack.pop() {
+                if !extended.contains(&expr) {
+                    result.insert(expr, (block, initializer));
+                }
+                body[expr].walk_child_exprs(|it| stack.push(it));
+            }
+        }
+    }
+    result
+}
+
+/// Collects the operands of the extending borrows in the extending expression `expr`, whose
+/// temporaries live until the end of the enclosing block, like the `temp()` in `let x = &temp();`.
+fn extended_temporaries(body: &Body, expr: ExprId, acc: &mut FxHashSet<ExprId>) {
+    match &body[expr] {
+        &Expr::Ref { expr, .. } => {
+            acc.insert(expr);
+            extended_temporaries(body, expr, acc);
+        }
+        &Expr::Cast { expr, .. } => extended_temporaries(body, expr, acc),
+        Expr::Tuple { exprs, .. } | Expr::Array(Array::ElementList { elements: exprs, .. }) => {
+            exprs.iter().for_each(|&it| extended_temporaries(body, it, acc))
+        }
+        Expr::RecordLit { fields, .. } => {
+            fields.iter().for_each(|it| extended_temporaries(body, it.expr, acc))
+        }
+        Expr::Block { tail: Some(tail), .. } | Expr::Unsafe { tail: Some(tail), .. } => {
+            extended_temporaries(body, *tail, acc)
+        }
+        _ => (),
+    }
+}
+
+/// The blocks ending with a `Drop` of a local that may still be initialized there, that is which
+/// isn't moved out of on every path leading to the drop. Moving out of a part of a local counts as
+/// moving out of all of it.
+fn maybe_initialized_drops(db: &dyn HirDatabase, body: &MirBody) -> FxHashSet<BasicBlockId> {
+    let mut result = FxHashSet::default();
+    let mut entry_states: ArenaMap<BasicBlockId, FxHashSet<LocalId>> = ArenaMap::default();
+    entry_states.insert(body.start_block, body.param_locals.iter().copied().collect());
+    let mut worklist = vec![body.start_block];
+    while let Some(block_id) = worklist.pop() {
+        let mut initialized = entry_states[block_id].clone();
+        let move_out = |op: &Operand, initialized: &mut FxHashSet<LocalId>| {
+            if let Some(local) = moved_local(db, body, op) {
+                initialized.remove(&local);
+            }
+        };
+        let block = &body.basic_blocks[block_id];
+        for statement in &block.statements {
+            match &statement.kind {
+                StatementKind::Assign(place, rvalue) => {
+                    rvalue_operands(rvalue).for_each(|op| move_out(op, &mut initialized));
+                    initialized.insert(place.local);
+                }
+                StatementKind::StorageDead(local) => {
+                    initialized.remove(local);
+                }
+                StatementKind::Deinit(_)
+                | StatementKind::FakeRead(_)
+                | StatementKind::Nop
+                | StatementKind::StorageLive(_) => (),
+            }
+        }
+        let Some(terminator) = &block.terminator else { continue };
+        let successors: Vec<BasicBlockId> = match &terminator.kind {
+            TerminatorKind::Goto { target } => vec![*target],
+            TerminatorKind::SwitchInt { discr, targets } => {
+                move_out(discr, &mut initialized);
+                targets.all_targets().to_vec()
+            }
+            TerminatorKind::Call { func, args, destination, target, cleanup, .. } => {
+                move_out(func, &mut initialized);
+                args.iter().for_each(|op| move_out(op, &mut initialized));
+                initialized.insert(destination.local);
+                target.iter().chain(cleanup).copied().collect()
+            }
+            TerminatorKind::Drop { place, target, unwind } => {
+                if place.projection == ProjectionId::EMPTY && initialized.remove(&place.local) {
+                    result.insert(block_id);
+                }
+                iter::once(target).chain(unwind).copied().collect()
+            }
+            TerminatorKind::DropAndReplace { place, value, target, unwind } => {
+                move_out(value, &mut initialized);
+                initialized.insert(place.local);
+                iter::once(target).chain(unwind).copied().collect()
+            }
+            TerminatorKind::Assert { cond, target, cleanup, .. } => {
+                move_out(cond, &mut initialized);
+                iter::once(target).chain(cleanup).copied().collect()
+            }
+            TerminatorKind::Yield { value, resume, resume_arg, drop } => {
+                move_out(value, &mut initialized);
+                initialized.insert(resume_arg.local);
+                iter::once(resume).chain(drop).copied().collect()
+            }
+            TerminatorKind::FalseEdge { real_target, imaginary_target } => {
+                vec![*real_target, *imaginary_target]
+            }
+            TerminatorKind::FalseUnwind { real_target, unwind } => {
+                iter::once(real_target).chain(unwind).copied().collect()
+            }
+            TerminatorKind::UnwindResume
+            | TerminatorKind::GeneratorDrop
+            | TerminatorKind::Abort
+            | TerminatorKind::Return
+            | TerminatorKind::Unreachable => vec![],
+        };
+        for successor in successors {
+            match entry_states.get_mut(successor) {
+                Some(state) => {
+                    let len = state.len();
+                    state.extend(initialized.iter().copied());
+                    if state.len() != len {
+                        worklist.push(successor);
+                    }
+                }
+                None => {
+                    entry_states.insert(successor, initialized.clone());
+                    worklist.push(successor);
+                }
+            }
+        }
+    }
+    result
+}
+
+fn rvalue_operands(rvalue: &Rvalue) -> impl Iterator<Item = &Operand> {
+    let operands: &[Operand] = match rvalue {
+        Rvalue::ShallowInitBox(o, _)
+        | Rvalue::UnaryOp(_, o)
+        | Rvalue::Cast(_, o, _)
+        | Rvalue::Repeat(o, _)
+        | Rvalue::Use(o) => std::slice::from_ref(o),
+        Rvalue::CheckedBinaryOp(_, o1, o2) => return Either::Left([o1, o2].into_iter()),
+        Rvalue::Aggregate(_, ops) => ops,
+        Rvalue::ShallowInitBoxWithAlloc(_)
+        | Rvalue::CopyForDeref(_)
+        | Rvalue::Discriminant(_)
+        | Rvalue::Len(_)
+        | Rvalue::Ref(_, _) => &[],
+    };
+    Either::Right(operands.iter())
+}
+
+/// The local `op` moves out of in whole or in part, if any.
+fn moved_local(db: &dyn HirDatabase, body: &MirBody, op: &Operand) -> Option<LocalId> {
+    let (Operand::Copy(p) | Operand::Move(p)) = op else { return None };
+    let mut ty: Ty = body.locals[p.local].ty.clone();
+    for proj in p.projection.lookup(&body.projection_store) {
+        ty = proj.projected_ty(
+            ty,
+            db,
+            |c, subst, f| {
+                let (def, _) = db.lookup_intern_closure(c.into());
+                let infer = db.infer(def);
+                let (captures, _) = infer.closure_info(&c);
+                let parent_subst = ClosureSubst(subst).parent_subst();
+                captures
+                    .get(f)
+                    .expect("broken closure field")
+                    .ty
+                    .clone()
+                    .substitute(Interner, parent_subst)
+            },
+            body.owner.module(db.upcast()).krate(),
+        );
+    }
+    (!ty.is_copy(db, body.owner)).then_some(p.local)
+}
+
+/// Whether dropping a value of `ty` runs any code, that is whether it or one of its fields
+/// implements `Drop`. Types we can't see through, like type parameters, have drop glue unless they
+/// are `Copy`.
+fn has_drop_glue(
+    db: &dyn HirDatabase,
+    ty: &Ty,
+    owner: DefWithBodyId,
+    visited: &mut FxHashSet<Ty>,
+) -> bool {
+    if !visited.insert(ty.clone()) {
+        // A recursive type has drop glue only if one of its other fields has it.
+        return false;
+    }
+    match ty.kind(Interner) {
+        TyKind::Adt(adt, subst) => {
+            if implements_drop(db, ty, owner) {
+                return true;
+            }
+            let variants: Vec<VariantId> = match adt.0 {
+                AdtId::StructId(s) => {
+                    if db.struct_data(s).flags.contains(StructFlags::IS_MANUALLY_DROP) {
+                        return false;
+                    }
+                    vec![s.into()]
+                }
+                // Fields of unions are never dropped.
+                AdtId::UnionId(_) => return false,
+                AdtId::EnumId(e) => db
+                    .enum_data(e)
+                    .variants
+                    .iter()
+                    .map(|(local_id, _)| EnumVariantId { parent: e, local_id }.into())
+                    .collect(),
+            };
+            variants.into_iter().any(|variant| {
+                db.field_types(variant).iter().any(|(_, field_ty)| {
+                    let field_ty = field_ty.clone().substitute(Interner, subst);
+                    has_drop_glue(db, &field_ty, owner, visited)
+                })
+            })
+        }
+        TyKind::Tuple(_, subst) => subst
+            .iter(Interner)
+            .any(|it| has_drop_glue(db, it.assert_ty_ref(Interner), owner, visited)),
+        TyKind::Array(ty, _) | TyKind::Slice(ty) => has_drop_glue(db, ty, owner, visited),
+        TyKind::Ref(..)
+        | TyKind::Raw(..)
+        | TyKind::Scalar(_)
+        | TyKind::Str
+        | TyKind::Never
+        | TyKind::FnDef(..)
+        | TyKind::Function(_)
+        | TyKind::Foreign(_)
+        | TyKind::Error
+        | TyKind::InferenceVar(..) => false,
+        TyKind::Closure(..)
+        | TyKind::Dyn(_)
+        | TyKind::Placeholder(_)
+        | TyKind::BoundVar(_)
+        | TyKind::Alias(_)
+        | TyKind::OpaqueType(..)
+        | TyKind::AssociatedType(..)
+        | TyKind::Generator(..)
+        | TyKind::GeneratorWitness(..) => !ty.clone().is_copy(db, owner),
+    }
+}
+
+fn implements_drop(db: &dyn HirDatabase, ty: &Ty, owner: DefWithBodyId) -> bool {
+    let krate = owner.module(db.upcast()).krate();
+    let Some(drop_trait) = db.lang_item(krate, LangItem::Drop).and_then(|it| it.as_trait()) else {
+        return false;
+    };
+    let trait_ref = TyBuilder::trait_ref(db, drop_trait).push(ty.clone()).build();
+    let env = db.trait_environment_for_body(owner);
+    let goal = Canonical {
+        value: InEnvironment::new(&env.env, trait_ref.cast(Interner)),
+        binders: CanonicalVarKinds::empty(Interner),
+    };
+    db.trait_solve(krate, None, goal).is_some()
+}
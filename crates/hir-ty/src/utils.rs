@@ -113,7 +113,8 @@ impl Iterator for SuperTraits<'_> {
     }
 }
 
-fn direct_super_traits(db: &dyn DefDatabase, trait_: TraitId, cb: impl FnMut(TraitId)) {
+/// Calls `cb` with the traits named in the supertrait bounds of `trait_`.
+pub fn direct_super_traits(db: &dyn DefDatabase, trait_: TraitId, cb: impl FnMut(TraitId)) {
     let resolver = trait_.resolver(db);
     let generic_params = db.generic_params(trait_.into());
     let trait_self = generic_params.find_trait_self_param();
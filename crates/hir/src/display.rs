@@ -18,7 +18,7 @@ use hir_ty::{
 
 use crate::{
     Adt, AsAssocItem, AssocItemContainer, Const, ConstParam, Enum, ExternCrateDecl, Field,
-    Function, GenericParam, HasCrate, HasVisibility, LifetimeParam, Macro, Module, SelfParam,
+    Function, GenericParam, HasCrate, HasVisibility, Impl, LifetimeParam, Macro, Module, SelfParam,
     Static, Struct, Trait, TraitAlias, TyBuilder, Type, TypeAlias, TypeOrConstParam, TypeParam,
     Union, Variant,
 };
@@ -590,6 +590,33 @@ impl HirDisplay for Trait {
     }
 }
 
+impl HirDisplay for Impl {
+    fn hir_fmt(&self, f: &mut HirFormatter<'_>) -> Result<(), HirDisplayError> {
+        let data = f.db.impl_data(self.id);
+        f.write_str("impl")?;
+        let def_id = GenericDefId::ImplId(self.id);
+        write_generic_params(def_id, f)?;
+        f.write_char(' ')?;
+        if let Some(trait_ref) = self.trait_ref(f.db) {
+            if data.is_negative {
+                f.write_char('!')?;
+            }
+            let trait_ = trait_ref.trait_();
+            write!(f, "{}", trait_.name(f.db).display(f.db.upcast()))?;
+            let args = &trait_ref.trait_ref.substitution.as_slice(Interner)[1..];
+            if !args.is_empty() {
+                f.write_char('<')?;
+                f.write_joined(args, ", ")?;
+                f.write_char('>')?;
+            }
+            f.write_str(" for ")?;
+        }
+        self.self_ty(f.db).hir_fmt(f)?;
+        write_where_clause(def_id, f)?;
+        Ok(())
+    }
+}
+
 impl HirDisplay for TraitAlias {
     fn hir_fmt(&self, f: &mut HirFormatter<'_>) -> Result<(), HirDisplayError> {
         write_visibility(self.module(f.db).id, self.visibility(f.db), f)?;
@@ -30,6 +30,7 @@ mod has_source;
 pub mod diagnostics;
 pub mod db;
 pub mod symbols;
+pub mod signatures;
 
 mod display;
 
@@ -63,7 +64,7 @@ use hir_ty::{
     all_super_traits, autoderef,
     consteval::{try_const_usize, unknown_const_as_generic, ConstEvalError, ConstExt},
     diagnostics::BodyValidationDiagnostic,
-    known_const_to_ast,
+    direct_super_traits, known_const_to_ast,
     layout::{Layout as TyLayout, RustcEnumVariantIdx, RustcFieldIdx, TagEncoding},
     method_resolution::{self, TyFingerprint},
     mir::{self, interpret_mir},
@@ -81,7 +82,7 @@ use rustc_hash::FxHashSet;
 use stdx::{impl_from, never};
 use syntax::{
     ast::{self, HasAttrs as _, HasDocComments, HasName},
-    AstNode, AstPtr, SmolStr, SyntaxNode, SyntaxNodePtr, TextRange, T,
+    AstNode, AstPtr, SmolStr, SyntaxNode, SyntaxNodePtr, TextRange, TextSize, T,
 };
 use triomphe::Arc;
 
@@ -204,6 +205,10 @@ impl Crate {
         db.crate_graph().transitive_rev_deps(self.id).map(|id| Crate { id })
     }
 
+    pub fn transitive_dependencies(self, db: &dyn HirDatabase) -> impl Iterator<Item = Crate> {
+        db.crate_graph().transitive_deps(self.id).map(|id| Crate { id })
+    }
+
     pub fn root_module(self) -> Module {
         Module { id: CrateRootModuleId::from(self.id).into() }
     }
@@ -229,6 +234,10 @@ impl Crate {
         db.crate_graph()[self.id].display_name.clone()
     }
 
+    pub fn is_unstable_feature_enabled(self, db: &dyn HirDatabase, feature: &str) -> bool {
+        db.crate_def_map(self.id).is_unstable_feature_enabled(feature)
+    }
+
     pub fn query_external_importables(
         self,
         db: &dyn DefDatabase,
@@ -263,6 +272,13 @@ impl Crate {
         let data = &db.crate_graph()[self.id];
         data.potential_cfg_options.clone().unwrap_or_else(|| data.cfg_options.clone())
     }
+
+    /// The cfg options the crate could be built with, if they are known, e.g. from the features
+    /// declared in its `Cargo.toml`. Unlike [`Crate::potential_cfg`], this doesn't fall back to
+    /// the enabled cfg options.
+    pub fn declared_potential_cfg(&self, db: &dyn HirDatabase) -> Option<CfgOptions> {
+        db.crate_graph()[self.id].potential_cfg_options.clone()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -1439,6 +1455,36 @@ impl DefWithBody {
         }
     }
 
+    /// The values with drop glue this body and its closures drop when scopes end, in the order
+    /// they are dropped. Values that may have been moved out of are left out.
+    pub fn scope_end_drops(self, db: &dyn HirDatabase) -> Vec<ScopeEndDrop> {
+        let Ok(drops) = db.scope_end_drops(self.id()) else { return Vec::new() };
+        let krate = self.module(db).id.krate();
+        let (_, source_map) = db.body_with_source_map(self.id());
+        let expr_source = |span| match span {
+            mir::MirSpan::ExprId(e) => source_map.expr_syntax(e).ok(),
+            mir::MirSpan::PatId(_) | mir::MirSpan::Unknown => None,
+        };
+        drops
+            .iter()
+            .filter_map(|drop| {
+                let value = match drop.value {
+                    mir::DroppedValue::Binding(binding_id) => {
+                        DroppedValue::Local(Local { parent: self.id(), binding_id })
+                    }
+                    mir::DroppedValue::Temporary(span) => {
+                        DroppedValue::Temporary(expr_source(span)?)
+                    }
+                };
+                Some(ScopeEndDrop {
+                    scope: expr_source(drop.span)?,
+                    value,
+                    ty: Type::new_for_crate(krate, drop.ty.clone()),
+                })
+            })
+            .collect()
+    }
+
     pub fn diagnostics(self, db: &dyn HirDatabase, acc: &mut Vec<AnyDiagnostic>) {
         db.unwind_if_cancelled();
         let krate = self.module(db).id.krate();
@@ -1962,6 +2008,28 @@ impl Function {
         db.function_data(self.id).has_async_kw()
     }
 
+    /// Resolves this method of a trait to the method of the impl used when the trait is
+    /// instantiated with `trait_args`, `Self` first. Returns `None` if the method is not a
+    /// non-generic method of a trait, or if no impl overrides it.
+    pub fn impl_method_for(self, db: &dyn HirDatabase, trait_args: &[Type]) -> Option<Function> {
+        let ItemContainerId::TraitId(trait_id) = self.id.lookup(db.upcast()).container else {
+            return None;
+        };
+        let env = trait_args.first()?.env.clone();
+        // The substitution of a method without generic parameters coincides with the one of its
+        // trait.
+        if !db.generic_params(self.id.into()).type_or_consts.is_empty() {
+            return None;
+        }
+        let builder = TyBuilder::subst_for_def(db, trait_id, None);
+        if builder.remaining() != trait_args.len() {
+            return None;
+        }
+        let substs = trait_args.iter().fold(builder, |b, it| b.push(it.ty.clone())).build();
+        let (func, _) = db.lookup_impl_method(env, self.id, substs);
+        (func != self.id).then_some(Function { id: func })
+    }
+
     /// Does this function have `#[test]` attribute?
     pub fn is_test(self, db: &dyn HirDatabase) -> bool {
         db.function_data(self.id).attrs.is_test()
@@ -2257,6 +2325,23 @@ impl Const {
         let r = format!("{}", c.display(db));
         return Ok(r);
     }
+
+    /// Evaluates a `bool` constant, returning `None` if it is of another type or can't be
+    /// evaluated.
+    pub fn eval_bool(self, db: &dyn HirDatabase) -> Option<bool> {
+        let c = db.const_eval(self.id.into(), Substitution::empty(Interner), None).ok()?;
+        let data = &c.data(Interner);
+        if !matches!(data.ty.kind(Interner), TyKind::Scalar(Scalar::Bool)) {
+            return None;
+        }
+        match &data.value {
+            hir_ty::ConstValue::Concrete(c) => match &c.interned {
+                hir_ty::ConstScalar::Bytes(b, _) => Some(b.first()? != &0),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
 }
 
 impl HasVisibility for Const {
@@ -2322,6 +2407,14 @@ impl Trait {
         db.trait_data(self.id).items.iter().map(|(_name, it)| (*it).into()).collect()
     }
 
+    /// The traits named in the supertrait bounds of this trait, not including their own
+    /// supertraits.
+    pub fn direct_supertraits(self, db: &dyn HirDatabase) -> Vec<Trait> {
+        let mut res = Vec::new();
+        direct_super_traits(db.upcast(), self.id, |it| res.push(Trait { id: it }));
+        res
+    }
+
     pub fn items_with_supertraits(self, db: &dyn HirDatabase) -> Vec<AssocItem> {
         let traits = all_super_traits(db.upcast(), self.into());
         traits.iter().flat_map(|tr| Trait::from(*tr).items(db)).collect()
@@ -2549,6 +2642,113 @@ impl Macro {
     pub fn is_derive(&self, db: &dyn HirDatabase) -> bool {
         matches!(self.kind(db), MacroKind::Derive)
     }
+
+    /// Returns what the rules of this declarative macro accept at `offset` inside `tt`, the
+    /// argument of one of its invocations. Only the tokens ending before `offset` are considered.
+    pub fn expected_input(
+        self,
+        db: &dyn HirDatabase,
+        tt: &ast::TokenTree,
+        offset: TextSize,
+    ) -> Vec<MacroInput> {
+        let id = macro_id_to_def_id(db.upcast(), self.id);
+        let hir_expand::db::TokenExpander::DeclarativeMacro(expander) = db.macro_expander(id)
+        else {
+            return Vec::new();
+        };
+
+        // Rebuild the argument up to the cursor, closing the groups it leaves open.
+        let mut tokens = tt.syntax().descendants_with_tokens().filter_map(|it| it.into_token());
+        if tokens.next().map_or(true, |it| it.text_range().end() > offset) {
+            return Vec::new();
+        }
+        let mut text = String::new();
+        let mut open = Vec::new();
+        for token in tokens.take_while(|it| it.text_range().end() <= offset) {
+            match token.kind() {
+                T!['('] => open.push(')'),
+                T!['['] => open.push(']'),
+                T!['{'] => open.push('}'),
+                T![')'] | T![']'] | T!['}'] => {
+                    // Nothing to pop means this closes the argument itself, so the cursor is not
+                    // inside of it.
+                    let Some(_) = open.pop() else { return Vec::new() };
+                }
+                _ => (),
+            }
+            text.push_str(token.text());
+        }
+        let depth = open.len();
+        text.extend(open.into_iter().rev());
+        let Some((input, _)) = mbe::parse_to_token_tree(&text) else { return Vec::new() };
+
+        expander
+            .mac
+            .expected_next(&input, depth)
+            .into_iter()
+            .filter_map(|it| {
+                Some(match it {
+                    mbe::Expected::Fragment(kind) => MacroInput::Fragment(kind.into()),
+                    mbe::Expected::Token(text) => MacroInput::Token(text),
+                    mbe::Expected::Delimited(kind) => match kind {
+                        tt::DelimiterKind::Parenthesis => MacroInput::Delimited('(', ')'),
+                        tt::DelimiterKind::Bracket => MacroInput::Delimited('[', ']'),
+                        tt::DelimiterKind::Brace => MacroInput::Delimited('{', '}'),
+                        tt::DelimiterKind::Invisible => return None,
+                    },
+                })
+            })
+            .collect()
+    }
+}
+
+/// Something the rules of a declarative macro accept next, see [`Macro::expected_input`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MacroInput {
+    /// A fragment, like `$e:expr`.
+    Fragment(MacroFragmentKind),
+    /// A literal token of a matcher, like `=>` or `where`.
+    Token(SmolStr),
+    /// A group with the given opening and closing delimiters.
+    Delimited(char, char),
+}
+
+/// The fragment specifier of a macro matcher variable, like `expr` in `$e:expr`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacroFragmentKind {
+    Path,
+    Ty,
+    Pat,
+    Stmt,
+    Block,
+    Meta,
+    Item,
+    Vis,
+    Expr,
+    Ident,
+    Tt,
+    Lifetime,
+    Literal,
+}
+
+impl From<mbe::MetaVarKind> for MacroFragmentKind {
+    fn from(kind: mbe::MetaVarKind) -> Self {
+        match kind {
+            mbe::MetaVarKind::Path => MacroFragmentKind::Path,
+            mbe::MetaVarKind::Ty => MacroFragmentKind::Ty,
+            mbe::MetaVarKind::Pat | mbe::MetaVarKind::PatParam => MacroFragmentKind::Pat,
+            mbe::MetaVarKind::Stmt => MacroFragmentKind::Stmt,
+            mbe::MetaVarKind::Block => MacroFragmentKind::Block,
+            mbe::MetaVarKind::Meta => MacroFragmentKind::Meta,
+            mbe::MetaVarKind::Item => MacroFragmentKind::Item,
+            mbe::MetaVarKind::Vis => MacroFragmentKind::Vis,
+            mbe::MetaVarKind::Expr => MacroFragmentKind::Expr,
+            mbe::MetaVarKind::Ident => MacroFragmentKind::Ident,
+            mbe::MetaVarKind::Tt => MacroFragmentKind::Tt,
+            mbe::MetaVarKind::Lifetime => MacroFragmentKind::Lifetime,
+            mbe::MetaVarKind::Literal => MacroFragmentKind::Literal,
+        }
+    }
 }
 
 impl HasVisibility for Macro {
@@ -2816,6 +3016,23 @@ impl GenericDef {
     }
 }
 
+/// A value dropped when a scope ends, see [`DefWithBody::scope_end_drops`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScopeEndDrop {
+    /// The expression whose end drops the value: the block of the scope, the expression statement
+    /// owning a temporary, or an expression like `return` or `break` leaving the scope early.
+    pub scope: InFile<AstPtr<ast::Expr>>,
+    pub value: DroppedValue,
+    pub ty: Type,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DroppedValue {
+    Local(Local),
+    /// The temporary holding the result of an expression.
+    Temporary(InFile<AstPtr<ast::Expr>>),
+}
+
 /// A single local definition.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Local {
@@ -3351,6 +3568,16 @@ impl Impl {
         all
     }
 
+    /// The trait impls of `krate` whose self type is a type parameter, like
+    /// `impl<T: Display> ToString for T`.
+    pub fn blanket_impls_in_crate(db: &dyn HirDatabase, krate: Crate) -> Vec<Impl> {
+        db.trait_impls_in_crate(krate.id)
+            .blanket_impls()
+            .map(Self::from)
+            .filter(|imp| imp.self_ty(db).as_type_param(db).is_some())
+            .collect()
+    }
+
     pub fn all_for_trait(db: &dyn HirDatabase, trait_: Trait) -> Vec<Impl> {
         let krate = trait_.module(db).krate();
         let mut all = Vec::new();
@@ -3939,6 +4166,48 @@ impl Type {
             .collect()
     }
 
+    /// Returns the fields of `variant` with the generic arguments of this enum type substituted.
+    pub fn variant_fields(&self, db: &dyn HirDatabase, variant: Variant) -> Vec<(Field, Type)> {
+        let substs = match self.ty.kind(Interner) {
+            TyKind::Adt(hir_ty::AdtId(AdtId::EnumId(e)), substs) if *e == variant.parent.id => {
+                substs
+            }
+            _ => return Vec::new(),
+        };
+        let variant_id = EnumVariantId { parent: variant.parent.id, local_id: variant.id };
+        db.field_types(variant_id.into())
+            .iter()
+            .map(|(local_id, ty)| {
+                let def = Field { parent: variant.into(), id: local_id };
+                let ty = ty.clone().substitute(Interner, substs);
+                (def, self.derived(ty))
+            })
+            .collect()
+    }
+
+    /// Checks whether no value of this type can be constructed, as far as visible from `module`.
+    pub fn is_uninhabited_from(&self, db: &dyn HirDatabase, module: Module) -> bool {
+        hir_ty::is_ty_uninhabited_from(&self.ty, module.id, db)
+    }
+
+    /// Checks whether `variant` of this enum type can't be constructed, as far as visible from
+    /// `module`. This takes the generic arguments of the type into account, so `Err` of
+    /// `Result<T, Infallible>` is uninhabited.
+    pub fn is_variant_uninhabited_from(
+        &self,
+        db: &dyn HirDatabase,
+        variant: Variant,
+        module: Module,
+    ) -> bool {
+        match self.ty.kind(Interner) {
+            TyKind::Adt(hir_ty::AdtId(AdtId::EnumId(e)), substs) if *e == variant.parent.id => {
+                let variant_id = EnumVariantId { parent: variant.parent.id, local_id: variant.id };
+                hir_ty::is_enum_variant_uninhabited_from(variant_id, substs, module.id, db)
+            }
+            _ => false,
+        }
+    }
+
     pub fn tuple_fields(&self, _db: &dyn HirDatabase) -> Vec<Type> {
         if let TyKind::Tuple(_, substs) = &self.ty.kind(Interner) {
             substs
@@ -4503,6 +4772,55 @@ impl Callable {
     pub fn return_type(&self) -> Type {
         self.ty.derived(self.sig.ret().clone())
     }
+
+    /// The generic arguments a called function was instantiated with.
+    pub fn fn_generic_args(&self, db: &dyn HirDatabase) -> Option<FnGenericArgs> {
+        let Callee::Def(CallableDefId::FunctionId(func)) = self.callee else { return None };
+        let TyKind::FnDef(_, subst) = self.ty.ty.kind(Interner) else { return None };
+        let params = db.generic_params(func.into());
+        if subst.len(Interner) < params.type_or_consts.len() {
+            return None;
+        }
+        let (own, parent) = subst.as_slice(Interner).split_at(params.type_or_consts.len());
+        let as_ty = |arg: &GenericArg| arg.ty(Interner).map(|ty| self.ty.derived(ty.clone()));
+
+        let only_type_params = params.type_or_consts.iter().all(|(_, param)| {
+            matches!(
+                param,
+                TypeOrConstParamData::TypeParamData(it)
+                    if it.provenance == TypeParamProvenance::TypeParamList
+            )
+        });
+        let own = only_type_params.then(|| own.iter().map(as_ty).collect()).flatten();
+        let (self_ty, trait_args) = match func.lookup(db.upcast()).container {
+            ItemContainerId::ImplId(impl_id) => {
+                let parent_subst = Substitution::from_iter(Interner, parent.iter().cloned());
+                let self_ty = db.impl_self_ty(impl_id).substitute(Interner, &parent_subst);
+                (Some(self.ty.derived(self_ty)), Vec::new())
+            }
+            ItemContainerId::TraitId(_) => {
+                match parent.iter().map(as_ty).collect::<Option<Vec<_>>>() {
+                    Some(mut args) if !args.is_empty() => (Some(args.remove(0)), args),
+                    _ => (None, Vec::new()),
+                }
+            }
+            _ => (None, Vec::new()),
+        };
+        Some(FnGenericArgs { function: func.into(), own, self_ty, trait_args })
+    }
+}
+
+/// The generic arguments of a call of a function, see [`Callable::fn_generic_args`].
+#[derive(Debug)]
+pub struct FnGenericArgs {
+    pub function: Function,
+    /// The arguments of the function's own type parameters. `None` if they can't be spelled out
+    /// because the function has const or `impl Trait` parameters.
+    pub own: Option<Vec<Type>>,
+    /// The self type of the impl or trait the function is in.
+    pub self_ty: Option<Type>,
+    /// The arguments of the trait the function is in, without `Self`.
+    pub trait_args: Vec<Type>,
 }
 
 fn closure_source(db: &dyn HirDatabase, closure: ClosureId) -> Option<ast::ClosureExpr> {
@@ -4573,6 +4891,60 @@ impl Layout {
             };
         Some(tag_size)
     }
+
+    /// Returns where and how the discriminant of this enum layout is stored, or `None` if the
+    /// layout has only a single variant.
+    pub fn enum_tag(&self, parent: Enum) -> Option<EnumTag> {
+        let layout::Variants::Multiple { tag, tag_encoding, tag_field, .. } = &self.0.variants
+        else {
+            return None;
+        };
+        let offset = self.tuple_field_offset(*tag_field)?;
+        let size = tag.size(&*self.1).bytes();
+        let encoding = match tag_encoding {
+            TagEncoding::Direct => EnumTagEncoding::Direct,
+            TagEncoding::Niche { untagged_variant, niche_start, .. } => EnumTagEncoding::Niche {
+                untagged_variant: Variant { parent, id: untagged_variant.0 },
+                niche_start: *niche_start,
+            },
+        };
+        Some(EnumTag { offset, size, encoding })
+    }
+
+    /// Returns the layout of `variant` within this enum layout. Unlike [`Variant::layout`], this
+    /// works for layouts of instantiated generic enums as well.
+    pub fn variant_layout(&self, variant: Variant) -> Option<Layout> {
+        match &self.0.variants {
+            layout::Variants::Multiple { variants, .. } => Some(Layout(
+                Arc::new(variants.get(RustcEnumVariantIdx(variant.id))?.clone()),
+                self.1.clone(),
+            )),
+            layout::Variants::Single { index } => (index.0 == variant.id).then(|| self.clone()),
+        }
+    }
+
+    /// Returns the offset and size of the scalar with the most invalid values in this layout.
+    pub fn largest_niche_range(&self) -> Option<(u64, u64)> {
+        let niche = self.0.largest_niche?;
+        Some((niche.offset.bytes(), niche.value.size(&*self.1).bytes()))
+    }
+}
+
+/// Describes where the discriminant of an enum with multiple variants lives.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnumTag {
+    pub offset: u64,
+    pub size: u64,
+    pub encoding: EnumTagEncoding,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EnumTagEncoding {
+    /// The tag stores the discriminant directly.
+    Direct,
+    /// The tag is stored in invalid values of a field of `untagged_variant`, starting at
+    /// `niche_start`.
+    Niche { untagged_variant: Variant, niche_start: u128 },
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -13,7 +13,7 @@ use hir_def::{
     nameres::MacroSubNs,
     resolver::{self, HasResolver, Resolver, TypeNs},
     type_ref::Mutability,
-    AsMacroCall, DefWithBodyId, FieldId, FunctionId, MacroId, TraitId, VariantId,
+    AsMacroCall, DefWithBodyId, FieldId, FunctionId, ImplId, MacroId, TraitId, VariantId,
 };
 use hir_expand::{db::ExpandDatabase, name::AsName, ExpansionInfo, MacroCallId};
 use itertools::Itertools;
@@ -28,6 +28,7 @@ use syntax::{
 use crate::{
     db::HirDatabase,
     semantics::source_to_def::{ChildContainer, SourceToDefCache, SourceToDefCtx},
+    signatures::SignaturePattern,
     source_analyzer::{resolve_hir_path, SourceAnalyzer},
     Access, Adjust, Adjustment, AutoBorrow, BindingMode, BuiltinAttr, Callable, ConstParam, Crate,
     DeriveHelper, Field, Function, HasSource, HirFileId, Impl, InFile, Label, LifetimeParam, Local,
@@ -186,6 +187,12 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.imp.resolve_method_call(call).map(Function::from)
     }
 
+    /// Resolves the impl a call of a trait method dispatches to, including calls of default methods
+    /// the impl doesn't override.
+    pub fn resolve_method_call_impl(&self, call: &ast::MethodCallExpr) -> Option<Impl> {
+        self.imp.resolve_method_call_impl(call).map(Impl::from)
+    }
+
     /// Attempts to resolve this call expression as a method call falling back to resolving it as a field.
     pub fn resolve_method_call_field_fallback(
         &self,
@@ -941,6 +948,10 @@ impl<'db> SemanticsImpl<'db> {
         self.analyze(call.syntax())?.resolve_method_call(self.db, call)
     }
 
+    fn resolve_method_call_impl(&self, call: &ast::MethodCallExpr) -> Option<ImplId> {
+        self.analyze(call.syntax())?.resolve_method_call_impl(self.db, call)
+    }
+
     fn resolve_method_call_fallback(
         &self,
         call: &ast::MethodCallExpr,
@@ -1455,6 +1466,22 @@ impl SemanticsScope<'_> {
         resolve_hir_path(self.db, &self.resolver, &path)
     }
 
+    /// Resolve a type as-if it was written at the given scope. Like [`Self::speculative_resolve`],
+    /// this doesn't take hygiene into account.
+    pub fn speculative_resolve_type(&self, ty: &ast::Type) -> Type {
+        let ctx = LowerCtx::with_file_id(self.db.upcast(), self.file_id);
+        let ty =
+            hir_ty::TyLoweringContext::new(self.db, &self.resolver, self.resolver.module().into())
+                .lower_ty(&crate::TypeRef::from_ast(&ctx, ty.clone()));
+        Type::new_with_resolver(self.db, &self.resolver, ty)
+    }
+
+    /// Lowers a `fn(..) -> ..` signature pattern as-if it was written at the given scope. See
+    /// [`SignaturePattern`] for the syntax and how it matches functions.
+    pub fn signature_pattern(&self, ty: &ast::Type) -> Option<SignaturePattern> {
+        SignaturePattern::lower(self.db, &self.resolver, self.file_id, ty)
+    }
+
     /// Iterates over associated types that may be specified after the given path (using
     /// `Ty::Assoc` syntax).
     pub fn assoc_type_shorthand_candidates<R>(
@@ -0,0 +1,294 @@
+//! Searching functions by their type signatures, like Hoogle does for Haskell.
+//!
+//! A [`SignaturePattern`] is lowered from a `fn(..) -> ..` type, optionally with generic
+//! parameters as in `for<T> fn(&[T]) -> Option<&T>`. `_` and the generic parameters stand for
+//! any type, and names that can't be resolved only constrain the name of the type. A function
+//! matches the pattern if its signature unifies with it once the generic parameters of the
+//! function are replaced by inference variables. References on the parameters and on the return
+//! type are ignored, so `fn(&Url)` also finds `fn(Url)`.
+
+use hir_def::{lower::LowerCtx, resolver::Resolver};
+use hir_expand::name::AsName;
+use hir_ty::{
+    consteval::unknown_const_as_generic, display::HirDisplay, make_canonical, static_lifetime,
+    BoundVar, CallableDefId, Cast, DebruijnIndex, GenericArg, Interner, Mutability, ParamKind,
+    Substitution, Ty, TyBuilder, TyDefId, TyExt, TyKind, TyVariableKind, VariableKind,
+};
+use syntax::{
+    ast::{self, HasName},
+    SmolStr,
+};
+
+use crate::{
+    db::HirDatabase, source_analyzer::resolve_hir_path, Adt, Function, HirFileId, ModuleDef, Name,
+    Path, PathResolution,
+};
+
+/// A function signature with holes, see the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct SignaturePattern {
+    /// The parameters and the return type, without their outermost references.
+    slots: Vec<Slot>,
+    /// The kinds of the variables bound in `slots`.
+    vars: Vec<VariableKind>,
+    /// Variables standing for types that couldn't be resolved, which must have this name.
+    named: Vec<(usize, SmolStr)>,
+    /// Variables standing for the generic parameters of the pattern.
+    generics: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct Slot {
+    ty: Ty,
+    by_ref: bool,
+}
+
+impl SignaturePattern {
+    pub(crate) fn lower(
+        db: &dyn HirDatabase,
+        resolver: &Resolver,
+        file_id: HirFileId,
+        ty: &ast::Type,
+    ) -> Option<SignaturePattern> {
+        let (generics, fn_ty) = match ty {
+            ast::Type::ForType(it) => (it.generic_param_list(), it.ty()?),
+            ty => (None, ty.clone()),
+        };
+        let ast::Type::FnPtrType(fn_ty) = fn_ty else { return None };
+        let generics = generics
+            .into_iter()
+            .flat_map(|it| it.generic_params())
+            .filter_map(|it| match it {
+                ast::GenericParam::TypeParam(it) => it.name(),
+                _ => None,
+            })
+            .map(|name| (name.as_name(), None))
+            .collect();
+        let mut ctx = PatternLowerCtx {
+            db,
+            resolver,
+            lower_ctx: LowerCtx::with_file_id(db.upcast(), file_id),
+            generics,
+            vars: Vec::new(),
+            named: Vec::new(),
+        };
+        let params = fn_ty.param_list().into_iter().flat_map(|it| it.params()).map(|it| it.ty());
+        let ret = fn_ty.ret_type().map(|it| it.ty());
+        let slots = params
+            .chain(Some(ret.unwrap_or_else(|| Some(ast::make::ty_unit()))))
+            .map(|ty| match ty {
+                Some(ast::Type::RefType(ty)) => Slot { ty: ctx.lower_opt(ty.ty()), by_ref: true },
+                ty => Slot { ty: ctx.lower_opt(ty), by_ref: false },
+            })
+            .collect();
+        let generics = ctx.generics.iter().filter_map(|(_, var)| *var).collect();
+        Some(SignaturePattern { slots, vars: ctx.vars, named: ctx.named, generics })
+    }
+
+    /// The number of parameters a function needs to match, counting the receiver of methods.
+    pub fn arity(&self) -> usize {
+        self.slots.len() - 1
+    }
+
+    /// Returns how far `func` is from matching the pattern exactly, or `None` if it doesn't
+    /// match at all. Every generic parameter that had to be instantiated, be it of `func` or of
+    /// the pattern, and every reference that got ignored adds one to the distance.
+    pub fn matches(&self, db: &dyn HirDatabase, func: Function) -> Option<u32> {
+        let sig = db.callable_item_signature(CallableDefId::FunctionId(func.id));
+        if sig.skip_binders().params().len() + 1 != self.slots.len() {
+            return None;
+        }
+        let offset = self.vars.len();
+        let fn_vars = sig.binders.iter(Interner).cloned().collect::<Vec<_>>();
+        let subst = Substitution::from_iter(
+            Interner,
+            fn_vars.iter().enumerate().map(|(idx, kind)| bound_var(kind, offset + idx)),
+        );
+        let sig = sig.substitute(Interner, &subst);
+
+        let mut distance = 0;
+        let candidate =
+            sig.params().iter().chain(Some(sig.ret())).zip(&self.slots).map(|(ty, slot)| {
+                let stripped = ty.strip_references();
+                if slot.by_ref != (stripped != ty) {
+                    distance += 1;
+                }
+                stripped.clone().cast(Interner)
+            });
+        let candidate =
+            TyKind::Tuple(self.slots.len(), Substitution::from_iter(Interner, candidate))
+                .intern(Interner);
+        let pattern = TyKind::Tuple(
+            self.slots.len(),
+            Substitution::from_iter(Interner, self.slots.iter().map(|slot| slot.ty.clone())),
+        )
+        .intern(Interner);
+        let tys = make_canonical((pattern, candidate), self.vars.iter().chain(&fn_vars).cloned());
+        let solution = hir_ty::unify(db, db.trait_environment(func.id.into()), &tys)?;
+
+        let resolved = |idx: usize| solution.at(Interner, idx).ty(Interner).cloned();
+        for (idx, name) in &self.named {
+            let ty = resolved(*idx)?;
+            match ty.kind(Interner) {
+                TyKind::BoundVar(_) => distance += 1,
+                TyKind::Adt(hir_ty::AdtId(id), _) => {
+                    if Adt::from(*id).name(db).to_smol_str() != *name {
+                        return None;
+                    }
+                }
+                TyKind::Scalar(_) | TyKind::Str => {
+                    if ty.display(db).to_string() != *name {
+                        return None;
+                    }
+                }
+                _ => return None,
+            }
+        }
+        distance += self
+            .generics
+            .iter()
+            .copied()
+            .chain(offset..offset + fn_vars.len())
+            .filter_map(resolved)
+            .filter(|ty| !matches!(ty.kind(Interner), TyKind::BoundVar(_)))
+            .count() as u32;
+        Some(distance)
+    }
+}
+
+struct PatternLowerCtx<'a> {
+    db: &'a dyn HirDatabase,
+    resolver: &'a Resolver,
+    lower_ctx: LowerCtx<'a>,
+    /// The generic parameters of the pattern, with their variable once they are used.
+    generics: Vec<(Name, Option<usize>)>,
+    vars: Vec<VariableKind>,
+    named: Vec<(usize, SmolStr)>,
+}
+
+impl PatternLowerCtx<'_> {
+    fn lower_opt(&mut self, ty: Option<ast::Type>) -> Ty {
+        match ty {
+            Some(ty) => self.lower(ty),
+            None => self.new_var(),
+        }
+    }
+
+    fn lower(&mut self, ty: ast::Type) -> Ty {
+        match ty {
+            ast::Type::PathType(ty) => match ty.path() {
+                Some(path) => self.lower_path(path),
+                None => self.new_var(),
+            },
+            ast::Type::RefType(ty) => {
+                let mutability = mutability(ty.mut_token().is_some());
+                TyKind::Ref(mutability, static_lifetime(), self.lower_opt(ty.ty())).intern(Interner)
+            }
+            ast::Type::PtrType(ty) => {
+                let mutability = mutability(ty.mut_token().is_some());
+                TyKind::Raw(mutability, self.lower_opt(ty.ty())).intern(Interner)
+            }
+            ast::Type::SliceType(ty) => TyKind::Slice(self.lower_opt(ty.ty())).intern(Interner),
+            ast::Type::ArrayType(ty) => {
+                let elem = self.lower_opt(ty.ty());
+                let len = self.new_var_of(VariableKind::Const(TyBuilder::usize()));
+                TyKind::Array(elem, len.constant(Interner).unwrap().clone()).intern(Interner)
+            }
+            ast::Type::TupleType(ty) => {
+                let fields = ty.fields().map(|it| self.lower(it)).collect::<Vec<_>>();
+                TyKind::Tuple(fields.len(), Substitution::from_iter(Interner, fields))
+                    .intern(Interner)
+            }
+            ast::Type::ParenType(ty) => self.lower_opt(ty.ty()),
+            ast::Type::NeverType(_) => TyKind::Never.intern(Interner),
+            // `_`, but also function pointers, trait objects and `impl Trait`, which are too
+            // unlikely to be written the same way in the pattern and the signature.
+            _ => self.new_var(),
+        }
+    }
+
+    fn lower_path(&mut self, path: ast::Path) -> Ty {
+        let Some(segment) = path.segment() else { return self.new_var() };
+        let args = segment
+            .generic_arg_list()
+            .into_iter()
+            .flat_map(|it| it.generic_args())
+            .filter_map(|arg| match arg {
+                ast::GenericArg::TypeArg(arg) => Some(arg.ty()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        if path.qualifier().is_none() && args.is_empty() {
+            if let Some(name) = segment.name_ref() {
+                let name = name.as_name();
+                if let Some(idx) = self.generics.iter().position(|(it, _)| *it == name) {
+                    return match self.generics[idx].1 {
+                        Some(var) => bound_ty(var),
+                        None => {
+                            let ty = self.new_var();
+                            self.generics[idx].1 = Some(self.vars.len() - 1);
+                            ty
+                        }
+                    };
+                }
+            }
+        }
+
+        let def = Path::from_src(path.clone(), &self.lower_ctx)
+            .and_then(|path| resolve_hir_path(self.db, self.resolver, &path));
+        let def: TyDefId = match def {
+            Some(PathResolution::Def(ModuleDef::Adt(adt))) => hir_def::AdtId::from(adt).into(),
+            Some(PathResolution::Def(ModuleDef::BuiltinType(it))) => it.inner.into(),
+            Some(PathResolution::Def(ModuleDef::TypeAlias(it))) => it.id.into(),
+            _ => {
+                let ty = self.new_var();
+                if let Some(name) = segment.name_ref() {
+                    self.named.push((self.vars.len() - 1, name.text().into()));
+                }
+                return ty;
+            }
+        };
+        let mut args = args.into_iter();
+        TyBuilder::def_ty(self.db, def, None)
+            .fill(|kind| match kind {
+                ParamKind::Type => match args.next() {
+                    Some(ty) => self.lower_opt(ty).cast(Interner),
+                    None => self.new_var().cast(Interner),
+                },
+                ParamKind::Const(ty) => unknown_const_as_generic(ty.clone()),
+            })
+            .build()
+    }
+
+    fn new_var(&mut self) -> Ty {
+        self.new_var_of(VariableKind::Ty(TyVariableKind::General)).ty(Interner).unwrap().clone()
+    }
+
+    fn new_var_of(&mut self, kind: VariableKind) -> GenericArg {
+        let arg = bound_var(&kind, self.vars.len());
+        self.vars.push(kind);
+        arg
+    }
+}
+
+fn bound_var(kind: &VariableKind, idx: usize) -> GenericArg {
+    let var = BoundVar::new(DebruijnIndex::INNERMOST, idx);
+    match kind {
+        VariableKind::Ty(_) => var.to_ty(Interner).cast(Interner),
+        VariableKind::Lifetime => var.to_lifetime(Interner).cast(Interner),
+        VariableKind::Const(ty) => var.to_const(Interner, ty.clone()).cast(Interner),
+    }
+}
+
+fn bound_ty(idx: usize) -> Ty {
+    TyKind::BoundVar(BoundVar::new(DebruijnIndex::INNERMOST, idx)).intern(Interner)
+}
+
+fn mutability(is_mut: bool) -> Mutability {
+    if is_mut {
+        Mutability::Mut
+    } else {
+        Mutability::Not
+    }
+}
@@ -1,6 +1,7 @@
 //! File symbol extraction.
 
 use base_db::FileRange;
+use either::Either;
 use hir_def::{
     item_scope::ItemInNs,
     src::{HasChildSource, HasSource},
@@ -9,9 +10,16 @@ use hir_def::{
 };
 use hir_expand::{HirFileId, InFile};
 use hir_ty::db::HirDatabase;
-use syntax::{ast::HasName, AstNode, SmolStr, SyntaxNode, SyntaxNodePtr};
+use itertools::Itertools;
+use syntax::{
+    ast::{self, HasName},
+    AstNode, SmolStr, SyntaxNode, SyntaxNodePtr,
+};
 
-use crate::{Module, ModuleDef, Semantics};
+use crate::{
+    Adt, DefWithBody, Field, FieldSource, HasAttrs, HasSource as _, Local, Module, ModuleDef,
+    Semantics, Variant,
+};
 
 /// The actual data that is stored in the index. It should be as compact as
 /// possible.
@@ -25,6 +33,24 @@ pub struct FileSymbol {
     pub is_alias: bool,
 }
 
+/// A symbol of an extended index, see [`SymbolCollector::new_extended`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExtendedFileSymbol {
+    pub symbol: FileSymbol,
+    /// The symbol itself if it is not a module level definition, `symbol.def` is then the
+    /// definition containing it.
+    pub inner: Option<InnerSymbol>,
+    /// The text of the doc comments of the symbol.
+    pub docs: Option<Box<str>>,
+}
+
+/// A symbol of an extended index that lives inside of a [`ModuleDef`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InnerSymbol {
+    Field(Field),
+    Local(Local),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DeclarationLocation {
     /// The file id for both the `ptr` and `name_ptr`.
@@ -79,6 +105,10 @@ struct SymbolCollectorWork {
 pub struct SymbolCollector<'a> {
     db: &'a dyn HirDatabase,
     symbols: Vec<FileSymbol>,
+    /// The symbols of an extended index, which are collected instead of `symbols`.
+    extended_symbols: Option<Vec<ExtendedFileSymbol>>,
+    /// Whether the extended index also contains the locals of bodies.
+    collect_locals: bool,
     work: Vec<SymbolCollectorWork>,
     current_container_name: Option<SmolStr>,
 }
@@ -90,11 +120,24 @@ impl<'a> SymbolCollector<'a> {
         SymbolCollector {
             db,
             symbols: Default::default(),
+            extended_symbols: None,
+            collect_locals: false,
             work: Default::default(),
             current_container_name: None,
         }
     }
 
+    /// Creates a collector for an extended index, which also contains the fields and enum
+    /// variants of the collected definitions, optionally the locals of their bodies, as well as
+    /// their doc comments.
+    pub fn new_extended(db: &'a dyn HirDatabase, collect_locals: bool) -> Self {
+        SymbolCollector {
+            extended_symbols: Some(Vec::new()),
+            collect_locals,
+            ..SymbolCollector::new(db)
+        }
+    }
+
     pub fn collect(&mut self, module: Module) {
         // The initial work is the root module we're collecting, additional work will
         // be populated as we traverse the module's definitions.
@@ -109,12 +152,26 @@ impl<'a> SymbolCollector<'a> {
         self.symbols
     }
 
+    pub fn finish_extended(self) -> Vec<ExtendedFileSymbol> {
+        self.extended_symbols.unwrap_or_default()
+    }
+
     pub fn collect_module(db: &dyn HirDatabase, module: Module) -> Vec<FileSymbol> {
         let mut symbol_collector = SymbolCollector::new(db);
         symbol_collector.collect(module);
         symbol_collector.finish()
     }
 
+    pub fn collect_module_extended(
+        db: &dyn HirDatabase,
+        module: Module,
+        collect_locals: bool,
+    ) -> Vec<ExtendedFileSymbol> {
+        let mut symbol_collector = SymbolCollector::new_extended(db, collect_locals);
+        symbol_collector.collect(module);
+        symbol_collector.finish_extended()
+    }
+
     fn do_work(&mut self, work: SymbolCollectorWork) {
         self.db.unwind_if_cancelled();
 
@@ -133,9 +190,14 @@ impl<'a> SymbolCollector<'a> {
                     self.push_decl(id);
                     self.collect_from_body(id);
                 }
-                ModuleDefId::AdtId(AdtId::StructId(id)) => self.push_decl(id),
-                ModuleDefId::AdtId(AdtId::EnumId(id)) => self.push_decl(id),
-                ModuleDefId::AdtId(AdtId::UnionId(id)) => self.push_decl(id),
+                ModuleDefId::AdtId(id) => {
+                    match id {
+                        AdtId::StructId(id) => self.push_decl(id),
+                        AdtId::EnumId(id) => self.push_decl(id),
+                        AdtId::UnionId(id) => self.push_decl(id),
+                    }
+                    self.collect_from_adt(id.into());
+                }
                 ModuleDefId::ConstId(id) => {
                     self.push_decl(id);
                     self.collect_from_body(id);
@@ -193,13 +255,17 @@ impl<'a> SymbolCollector<'a> {
                     name_ptr: SyntaxNodePtr::new(name.syntax()),
                 };
 
-                self.symbols.push(FileSymbol {
-                    name: name.text().into(),
-                    def,
-                    container_name: self.current_container_name.clone(),
-                    loc: dec_loc,
-                    is_alias: false,
-                });
+                self.push_symbol(
+                    FileSymbol {
+                        name: name.text().into(),
+                        def,
+                        container_name: self.current_container_name.clone(),
+                        loc: dec_loc,
+                        is_alias: false,
+                    },
+                    None,
+                    None,
+                );
             });
         }
 
@@ -222,6 +288,7 @@ impl<'a> SymbolCollector<'a> {
 
     fn collect_from_body(&mut self, body_id: impl Into<DefWithBodyId>) {
         let body_id = body_id.into();
+        self.collect_locals(body_id);
         let body = self.db.body(body_id);
 
         // Descend into the blocks and enqueue collection of all modules within.
@@ -235,6 +302,121 @@ impl<'a> SymbolCollector<'a> {
         }
     }
 
+    fn collect_locals(&mut self, body_id: DefWithBodyId) {
+        if !self.collect_locals {
+            return;
+        }
+        let def = match DefWithBody::from(body_id) {
+            DefWithBody::Function(it) => ModuleDef::Function(it),
+            DefWithBody::Static(it) => ModuleDef::Static(it),
+            DefWithBody::Const(it) => ModuleDef::Const(it),
+            DefWithBody::Variant(it) => ModuleDef::Variant(it),
+            DefWithBody::InTypeConst(_) => return,
+        };
+        let container_name = self.def_with_body_id_name(body_id);
+        let (body, source_map) = self.db.body_with_source_map(body_id);
+        for (binding_id, binding) in body.bindings.iter() {
+            let Some(&pat) = binding.definitions.first() else { continue };
+            let Ok(source) = source_map.pat_syntax(pat) else { continue };
+            let root = source.file_syntax(self.db.upcast());
+            let Either::Left(ast::Pat::IdentPat(ident_pat)) = source.value.to_node(&root) else {
+                continue;
+            };
+            let Some(name_node) = ident_pat.name() else { continue };
+            self.push_symbol(
+                FileSymbol {
+                    name: name_node.text().into(),
+                    def,
+                    loc: DeclarationLocation {
+                        hir_file_id: source.file_id,
+                        ptr: SyntaxNodePtr::new(ident_pat.syntax()),
+                        name_ptr: SyntaxNodePtr::new(name_node.syntax()),
+                    },
+                    container_name: container_name.clone(),
+                    is_alias: false,
+                },
+                Some(InnerSymbol::Local(Local { parent: body_id, binding_id })),
+                None,
+            );
+        }
+    }
+
+    fn collect_from_adt(&mut self, adt: Adt) {
+        if self.extended_symbols.is_none() {
+            return;
+        }
+        self.with_container_name(Some(adt.name(self.db).to_smol_str()), |s| match adt {
+            Adt::Struct(it) => it.fields(s.db).into_iter().for_each(|field| s.push_field(field)),
+            Adt::Union(it) => it.fields(s.db).into_iter().for_each(|field| s.push_field(field)),
+            Adt::Enum(it) => it.variants(s.db).into_iter().for_each(|variant| {
+                s.push_variant(variant);
+                s.with_container_name(Some(variant.name(s.db).to_smol_str()), |s| {
+                    variant.fields(s.db).into_iter().for_each(|field| s.push_field(field))
+                });
+            }),
+        });
+    }
+
+    fn push_variant(&mut self, variant: Variant) {
+        let Some(source) = variant.source(self.db) else { return };
+        let Some(name_node) = source.value.name() else { return };
+        self.push_symbol(
+            FileSymbol {
+                name: name_node.text().into(),
+                def: ModuleDef::Variant(variant),
+                loc: DeclarationLocation {
+                    hir_file_id: source.file_id,
+                    ptr: SyntaxNodePtr::new(source.value.syntax()),
+                    name_ptr: SyntaxNodePtr::new(name_node.syntax()),
+                },
+                container_name: self.current_container_name.clone(),
+                is_alias: false,
+            },
+            None,
+            self.docs(&variant.attrs(self.db)),
+        );
+    }
+
+    fn push_field(&mut self, field: Field) {
+        let Some(source) = field.source(self.db) else { return };
+        // Tuple fields have no name to search for.
+        let FieldSource::Named(record_field) = source.value else { return };
+        let Some(name_node) = record_field.name() else { return };
+        self.push_symbol(
+            FileSymbol {
+                name: name_node.text().into(),
+                def: field.parent_def(self.db).into(),
+                loc: DeclarationLocation {
+                    hir_file_id: source.file_id,
+                    ptr: SyntaxNodePtr::new(record_field.syntax()),
+                    name_ptr: SyntaxNodePtr::new(name_node.syntax()),
+                },
+                container_name: self.current_container_name.clone(),
+                is_alias: false,
+            },
+            Some(InnerSymbol::Field(field)),
+            self.docs(&field.attrs(self.db)),
+        );
+    }
+
+    fn docs(&self, attrs: &hir_def::attr::Attrs) -> Option<Box<str>> {
+        self.extended_symbols.as_ref()?;
+        let docs = attrs.by_key("doc").attrs().filter_map(|attr| attr.string_value()).join("\n");
+        (!docs.is_empty()).then(|| docs.into())
+    }
+
+    fn push_symbol(
+        &mut self,
+        symbol: FileSymbol,
+        inner: Option<InnerSymbol>,
+        docs: Option<Box<str>>,
+    ) {
+        match &mut self.extended_symbols {
+            Some(symbols) => symbols.push(ExtendedFileSymbol { symbol, inner, docs }),
+            None => self.symbols.push(symbol),
+        }
+    }
+
     fn collect_from_impl(&mut self, impl_id: ImplId) {
         let impl_data = self.db.impl_data(impl_id);
         for &assoc_item_id in &impl_data.items {
@@ -275,8 +457,14 @@ impl<'a> SymbolCollector<'a> {
 
     fn push_assoc_item(&mut self, assoc_item_id: AssocItemId) {
         match assoc_item_id {
-            AssocItemId::FunctionId(id) => self.push_decl(id),
-            AssocItemId::ConstId(id) => self.push_decl(id),
+            AssocItemId::FunctionId(id) => {
+                self.push_decl(id);
+                self.collect_locals(id.into());
+            }
+            AssocItemId::ConstId(id) => {
+                self.push_decl(id);
+                self.collect_locals(id.into());
+            }
             AssocItemId::TypeAliasId(id) => self.push_decl(id),
         }
     }
@@ -297,25 +485,35 @@ impl<'a> SymbolCollector<'a> {
             name_ptr: SyntaxNodePtr::new(name_node.syntax()),
         };
 
-        if let Some(attrs) = def.attrs(self.db) {
+        let attrs = def.attrs(self.db);
+        let docs = attrs.as_ref().and_then(|attrs| self.docs(attrs));
+        if let Some(attrs) = attrs {
             for alias in attrs.doc_aliases() {
-                self.symbols.push(FileSymbol {
-                    name: alias,
-                    def,
-                    loc: dec_loc.clone(),
-                    container_name: self.current_container_name.clone(),
-                    is_alias: true,
-                });
+                self.push_symbol(
+                    FileSymbol {
+                        name: alias,
+                        def,
+                        loc: dec_loc.clone(),
+                        container_name: self.current_container_name.clone(),
+                        is_alias: true,
+                    },
+                    None,
+                    docs.clone(),
+                );
             }
         }
 
-        self.symbols.push(FileSymbol {
-            name: name_node.text().into(),
-            def,
-            container_name: self.current_container_name.clone(),
-            loc: dec_loc,
-            is_alias: false,
-        });
+        self.push_symbol(
+            FileSymbol {
+                name: name_node.text().into(),
+                def,
+                container_name: self.current_container_name.clone(),
+                loc: dec_loc,
+                is_alias: false,
+            },
+            None,
+            docs,
+        );
     }
 
     fn push_module(&mut self, module_id: ModuleId) {
@@ -332,24 +530,34 @@ impl<'a> SymbolCollector<'a> {
 
         let def = ModuleDef::Module(module_id.into());
 
-        if let Some(attrs) = def.attrs(self.db) {
+        let attrs = def.attrs(self.db);
+        let docs = attrs.as_ref().and_then(|attrs| self.docs(attrs));
+        if let Some(attrs) = attrs {
             for alias in attrs.doc_aliases() {
-                self.symbols.push(FileSymbol {
-                    name: alias,
-                    def,
-                    loc: dec_loc.clone(),
-                    container_name: self.current_container_name.clone(),
-                    is_alias: true,
-                });
+                self.push_symbol(
+                    FileSymbol {
+                        name: alias,
+                        def,
+                        loc: dec_loc.clone(),
+                        container_name: self.current_container_name.clone(),
+                        is_alias: true,
+                    },
+                    None,
+                    docs.clone(),
+                );
             }
         }
 
-        self.symbols.push(FileSymbol {
-            name: name_node.text().into(),
-            def: ModuleDef::Module(module_id.into()),
-            container_name: self.current_container_name.clone(),
-            loc: dec_loc,
-            is_alias: false,
-        });
+        self.push_symbol(
+            FileSymbol {
+                name: name_node.text().into(),
+                def: ModuleDef::Module(module_id.into()),
+                container_name: self.current_container_name.clone(),
+                loc: dec_loc,
+                is_alias: false,
+            },
+            None,
+            docs,
+        );
     }
 }
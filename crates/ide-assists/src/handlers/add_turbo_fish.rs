@@ -1,6 +1,10 @@
-use ide_db::defs::{Definition, NameRefClass};
-use itertools::Itertools;
-use syntax::{ast, AstNode, SyntaxKind, T};
+use std::iter;
+
+use ide_db::{
+    defs::{Definition, NameRefClass},
+    syntax_helpers::turbofish::{turbofish, turbofish_offset},
+};
+use syntax::{ast, AstNode, SyntaxKind};
 
 use crate::{
     assist_context::{AssistContext, Assists},
@@ -34,12 +38,11 @@ pub(crate) fn add_turbo_fish(acc: &mut Assists, ctx: &AssistContext<'_>) -> Opti
         cov_mark::hit!(add_type_ascription_after_call);
         arg_list.l_paren_token()?.prev_token().filter(|it| it.kind() == SyntaxKind::IDENT)
     })?;
-    let next_token = ident.next_token()?;
-    if next_token.kind() == T![::] {
+    let name_ref = ast::NameRef::cast(ident.parent()?)?;
+    let Some(offset) = turbofish_offset(&name_ref) else {
         cov_mark::hit!(add_turbo_fish_one_fish_is_enough);
         return None;
-    }
-    let name_ref = ast::NameRef::cast(ident.parent()?)?;
+    };
     let def = match NameRefClass::classify(&ctx.sema, &name_ref)? {
         NameRefClass::Definition(def) => def,
         NameRefClass::FieldShorthand { .. } | NameRefClass::ExternCrateShorthand { .. } => {
@@ -95,31 +98,19 @@ pub(crate) fn add_turbo_fish(acc: &mut Assists, ctx: &AssistContext<'_>) -> Opti
             builder.trigger_signature_help();
             match ctx.config.snippet_cap {
                 Some(cap) => {
-                    let fish_head = get_snippet_fish_head(number_of_arguments);
-                    let snip = format!("::<{fish_head}>");
-                    builder.insert_snippet(cap, ident.text_range().end(), snip)
+                    // Tabstop 0 is a special case and always the last one.
+                    let tabstops = (1..number_of_arguments).chain(iter::once(0));
+                    let snip = turbofish(tabstops.map(|i| format!("${{{i}:_}}")));
+                    builder.insert_snippet(cap, offset, snip)
                 }
                 None => {
-                    let fish_head = std::iter::repeat("_").take(number_of_arguments).format(", ");
-                    let snip = format!("::<{fish_head}>");
-                    builder.insert(ident.text_range().end(), snip);
+                    builder.insert(offset, turbofish(iter::repeat("_").take(number_of_arguments)))
                 }
             }
         },
     )
 }
 
-/// This will create a snippet string with tabstops marked
-fn get_snippet_fish_head(number_of_arguments: usize) -> String {
-    let mut fish_head = (1..number_of_arguments)
-        .format_with("", |i, f| f(&format_args!("${{{i}:_}}, ")))
-        .to_string();
-
-    // tabstop 0 is a special case and always the last one
-    fish_head.push_str("${0:_}");
-    fish_head
-}
-
 #[cfg(test)]
 mod tests {
     use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
@@ -1,7 +1,7 @@
 use either::Either;
-use ide_db::FxHashMap;
+use ide_db::{syntax_helpers::node_ext::reorder_nodes, FxHashMap};
 use itertools::Itertools;
-use syntax::{ast, ted, AstNode};
+use syntax::{ast, AstNode};
 
 use crate::{AssistContext, AssistId, AssistKind, Assists};
 
@@ -66,24 +66,15 @@ pub(crate) fn reorder_fields(acc: &mut Assists, ctx: &AssistContext<'_>) -> Opti
         target,
         |builder| match fields {
             Either::Left((sorted, field_list)) => {
-                replace(builder.make_mut(field_list).fields(), sorted)
+                reorder_nodes(builder.make_mut(field_list).fields(), sorted)
             }
             Either::Right((sorted, field_list)) => {
-                replace(builder.make_mut(field_list).fields(), sorted)
+                reorder_nodes(builder.make_mut(field_list).fields(), sorted)
             }
         },
     )
 }
 
-fn replace<T: AstNode + PartialEq>(
-    fields: impl Iterator<Item = T>,
-    sorted_fields: impl IntoIterator<Item = T>,
-) {
-    fields.zip(sorted_fields).for_each(|(field, sorted_field)| {
-        ted::replace(field.syntax(), sorted_field.syntax().clone_for_update())
-    });
-}
-
 fn compute_fields_ranks(
     path: &ast::Path,
     ctx: &AssistContext<'_>,
@@ -11,6 +11,7 @@ pub(crate) mod format_string;
 pub(crate) mod item_list;
 pub(crate) mod keyword;
 pub(crate) mod lifetime;
+pub(crate) mod macro_input;
 pub(crate) mod mod_;
 pub(crate) mod pattern;
 pub(crate) mod postfix;
@@ -36,7 +37,7 @@ use crate::{
     item::Builder,
     render::{
         const_::render_const,
-        function::{render_fn, render_method},
+        function::{render_fn, render_method, render_qualified_fn},
         literal::{render_struct_literal, render_variant_lit},
         macro_::render_macro,
         pattern::{render_struct_pat, render_variant_pat},
@@ -310,6 +311,37 @@ impl Completions {
         );
     }
 
+    pub(crate) fn add_qualified_function(
+        &mut self,
+        ctx: &CompletionContext<'_>,
+        path_ctx: &PathCompletionCtx,
+        func: hir::Function,
+        qualifier: hir::ModPath,
+        import: Option<LocatedImport>,
+    ) {
+        if !ctx.check_stability(Some(&func.attrs(ctx.db))) {
+            return;
+        }
+        let is_private_editable = match ctx.is_visible(&func) {
+            Visible::Yes => false,
+            Visible::Editable => true,
+            Visible::No => return,
+        };
+        let doc_aliases = ctx.doc_aliases(&func);
+        self.add(
+            render_qualified_fn(
+                RenderContext::new(ctx)
+                    .private_editable(is_private_editable)
+                    .doc_aliases(doc_aliases)
+                    .import_to_add(import),
+                path_ctx,
+                func,
+                qualifier,
+            )
+            .build(ctx.db),
+        );
+    }
+
     pub(crate) fn add_method(
         &mut self,
         ctx: &CompletionContext<'_>,
@@ -423,13 +455,18 @@ impl Completions {
         path_ctx: &PathCompletionCtx,
         variant: hir::Variant,
         path: hir::ModPath,
+        import: Option<LocatedImport>,
     ) {
         if !ctx.check_stability(Some(&variant.attrs(ctx.db))) {
             return;
         }
-        if let Some(builder) =
-            render_variant_lit(RenderContext::new(ctx), path_ctx, None, variant, Some(path))
-        {
+        if let Some(builder) = render_variant_lit(
+            RenderContext::new(ctx).import_to_add(import),
+            path_ctx,
+            None,
+            variant,
+            Some(path),
+        ) {
             self.add(builder.build(ctx.db));
         }
     }
@@ -491,13 +528,18 @@ impl Completions {
         strukt: hir::Struct,
         path: Option<hir::ModPath>,
         local_name: Option<hir::Name>,
+        import: Option<LocatedImport>,
     ) {
         if !ctx.check_stability(Some(&strukt.attrs(ctx.db))) {
             return;
         }
-        if let Some(builder) =
-            render_struct_literal(RenderContext::new(ctx), path_ctx, strukt, path, local_name)
-        {
+        if let Some(builder) = render_struct_literal(
+            RenderContext::new(ctx).import_to_add(import),
+            path_ctx,
+            strukt,
+            path,
+            local_name,
+        ) {
             self.add(builder.build(ctx.db));
         }
     }
@@ -765,4 +807,5 @@ fn complete_patterns(
     fn_param::complete_fn_param(acc, ctx, pattern_ctx);
     pattern::complete_pattern(acc, ctx, pattern_ctx);
     record::complete_record_pattern_fields(acc, ctx, pattern_ctx);
+    snippet::complete_pattern_snippet(acc, ctx, pattern_ctx);
 }
@@ -27,7 +27,7 @@ mod derive;
 mod lint;
 mod repr;
 
-pub(crate) use self::derive::complete_derive_path;
+pub(crate) use self::{cfg::complete_cfg, derive::complete_derive_path};
 
 /// Complete inputs to known builtin attributes as well as derive attributes
 pub(crate) fn complete_known_attribute_input(
@@ -65,7 +65,7 @@ pub(crate) fn complete_known_attribute_input(
 
             lint::complete_lint(acc, ctx, colon_prefix, &existing_lints, &lints);
         }
-        "cfg" => cfg::complete_cfg(acc, ctx),
+        "cfg" | "cfg_attr" => cfg::complete_cfg(acc, ctx),
         _ => (),
     }
     Some(())
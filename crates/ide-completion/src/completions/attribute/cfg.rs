@@ -2,14 +2,33 @@
 
 use ide_db::SymbolKind;
 use itertools::Itertools;
-use syntax::{algo, ast::Ident, AstToken, Direction, NodeOrToken, SyntaxKind};
+use syntax::{
+    algo,
+    ast::{self, Ident, IsString},
+    AstNode, AstToken, Direction, NodeOrToken, SmolStr, SyntaxKind, SyntaxToken, TextSize, T,
+};
 
 use crate::{completions::Completions, context::CompletionContext, CompletionItem};
 
+/// Completes the keys and values of the cfg predicate of a `cfg` or `cfg_attr` attribute or of a
+/// `cfg!` call, both as bare idents and inside of the string literal of a `key = "value"` pair.
 pub(crate) fn complete_cfg(acc: &mut Completions, ctx: &CompletionContext<'_>) {
-    let add_completion = |item: &str| {
-        let mut completion = CompletionItem::new(SymbolKind::BuiltinAttr, ctx.source_range(), item);
-        completion.insert_text(format!(r#""{item}""#));
+    if !is_in_cfg_predicate(&ctx.original_token, ctx.position.offset) {
+        return;
+    }
+    let string = ast::String::cast(ctx.original_token.clone());
+    let range = match &string {
+        Some(string) => match string.text_range_between_quotes() {
+            Some(it) => it,
+            None => return,
+        },
+        None => ctx.source_range(),
+    };
+    let mut add_completion = |item: &str| {
+        let mut completion = CompletionItem::new(SymbolKind::BuiltinAttr, range, item);
+        if string.is_none() {
+            completion.insert_text(format!(r#""{item}""#));
+        }
         acc.add(completion.build(ctx.db));
     };
 
@@ -32,27 +51,94 @@ pub(crate) fn complete_cfg(acc: &mut Completions, ctx: &CompletionContext<'_>) {
         });
     match previous {
         Some(None) => (),
-        Some(Some(p)) => match p.text() {
-            "target_arch" => KNOWN_ARCH.iter().copied().for_each(add_completion),
-            "target_env" => KNOWN_ENV.iter().copied().for_each(add_completion),
-            "target_os" => KNOWN_OS.iter().copied().for_each(add_completion),
-            "target_vendor" => KNOWN_VENDOR.iter().copied().for_each(add_completion),
-            "target_endian" => ["little", "big"].into_iter().for_each(add_completion),
-            name => ctx.krate.potential_cfg(ctx.db).get_cfg_values(name).cloned().for_each(|s| {
-                let insert_text = format!(r#""{s}""#);
-                let mut item = CompletionItem::new(SymbolKind::BuiltinAttr, ctx.source_range(), s);
-                item.insert_text(insert_text);
+        Some(Some(p)) => {
+            let known: &[&str] = match p.text() {
+                "target_arch" => &KNOWN_ARCH,
+                "target_env" => &KNOWN_ENV,
+                "target_family" => &["unix", "wasm", "windows"],
+                "target_os" => &KNOWN_OS,
+                "target_vendor" => &KNOWN_VENDOR,
+                "target_endian" => &["little", "big"],
+                "target_pointer_width" => &["16", "32", "64"],
+                "target_has_atomic" => &["8", "16", "32", "64", "128", "ptr"],
+                "panic" => &["abort", "unwind"],
+                _ => &[],
+            };
+            let potential = ctx.krate.potential_cfg(ctx.db);
+            known
+                .iter()
+                .map(|&it| SmolStr::new(it))
+                .chain(potential.get_cfg_values(p.text()).cloned())
+                .unique()
+                .for_each(|it| add_completion(&it));
+        }
+        // keys are never quoted
+        None if string.is_some() => (),
+        None => {
+            let potential = ctx.krate.potential_cfg(ctx.db);
+            KNOWN_KEYS
+                .iter()
+                .map(|&it| SmolStr::new(it))
+                .chain(potential.get_cfg_keys().cloned())
+                .unique()
+                .for_each(|s| {
+                    let item = CompletionItem::new(SymbolKind::BuiltinAttr, range, s);
+                    acc.add(item.build(ctx.db));
+                })
+        }
+    }
+}
 
-                acc.add(item.build(ctx.db));
-            }),
+/// Whether `token` is part of a cfg predicate, that is the input of a `cfg` attribute or of a
+/// `cfg!` call, or the first argument of a `cfg_attr` attribute.
+fn is_in_cfg_predicate(token: &SyntaxToken, offset: TextSize) -> bool {
+    // trailing whitespace of an unclosed attribute is not part of its token tree
+    let Some(tt) = algo::skip_trivia_token(token.clone(), Direction::Prev)
+        .and_then(|it| it.parent())
+        .into_iter()
+        .flat_map(|it| it.ancestors())
+        .take_while(|it| it.kind() == SyntaxKind::TOKEN_TREE)
+        .last()
+        .and_then(ast::TokenTree::cast)
+    else {
+        return false;
+    };
+    let path = match tt.syntax().parent() {
+        Some(parent) => match ast::Meta::cast(parent.clone()) {
+            Some(meta) => meta.path(),
+            None => ast::MacroCall::cast(parent).and_then(|it| it.path()),
         },
-        None => ctx.krate.potential_cfg(ctx.db).get_cfg_keys().cloned().unique().for_each(|s| {
-            let item = CompletionItem::new(SymbolKind::BuiltinAttr, ctx.source_range(), s);
-            acc.add(item.build(ctx.db));
-        }),
+        None => None,
+    };
+    match path.and_then(|it| it.as_single_name_ref()) {
+        Some(name) if name.text() == "cfg" => true,
+        Some(name) if name.text() == "cfg_attr" => !tt
+            .syntax()
+            .children_with_tokens()
+            .any(|it| it.kind() == T![,] && it.text_range().end() <= offset),
+        _ => false,
     }
 }
 
+const KNOWN_KEYS: [&str; 16] = [
+    "debug_assertions",
+    "doc",
+    "feature",
+    "miri",
+    "panic",
+    "test",
+    "target_arch",
+    "target_endian",
+    "target_env",
+    "target_family",
+    "target_has_atomic",
+    "target_os",
+    "target_pointer_width",
+    "target_vendor",
+    "unix",
+    "windows",
+];
+
 const KNOWN_ARCH: [&str; 20] = [
     "aarch64",
     "arm",
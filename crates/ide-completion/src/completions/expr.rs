@@ -1,6 +1,7 @@
 //! Completion of names from the current scope in expression position.
 
-use hir::ScopeDef;
+use hir::{ItemInNs, ModuleDef, ScopeDef};
+use ide_db::imports::import_assets::LocatedImport;
 use syntax::ast;
 
 use crate::{
@@ -161,63 +162,7 @@ pub(crate) fn complete_expr_path(
         Qualified::Absolute => acc.add_crate_roots(ctx, path_ctx),
         Qualified::No => {
             acc.add_nameref_keywords_with_colon(ctx);
-            if let Some(adt) =
-                ctx.expected_type.as_ref().and_then(|ty| ty.strip_references().as_adt())
-            {
-                let self_ty = (|| ctx.sema.to_def(impl_.as_ref()?)?.self_ty(ctx.db).as_adt())();
-                let complete_self = self_ty == Some(adt);
-
-                match adt {
-                    hir::Adt::Struct(strukt) => {
-                        let path = ctx
-                            .module
-                            .find_use_path(
-                                ctx.db,
-                                hir::ModuleDef::from(strukt),
-                                ctx.config.prefer_no_std,
-                            )
-                            .filter(|it| it.len() > 1);
-
-                        acc.add_struct_literal(ctx, path_ctx, strukt, path, None);
-
-                        if complete_self {
-                            acc.add_struct_literal(
-                                ctx,
-                                path_ctx,
-                                strukt,
-                                None,
-                                Some(hir::known::SELF_TYPE),
-                            );
-                        }
-                    }
-                    hir::Adt::Union(un) => {
-                        let path = ctx
-                            .module
-                            .find_use_path(
-                                ctx.db,
-                                hir::ModuleDef::from(un),
-                                ctx.config.prefer_no_std,
-                            )
-                            .filter(|it| it.len() > 1);
-
-                        acc.add_union_literal(ctx, un, path, None);
-                        if complete_self {
-                            acc.add_union_literal(ctx, un, None, Some(hir::known::SELF_TYPE));
-                        }
-                    }
-                    hir::Adt::Enum(e) => {
-                        super::enum_variants_with_paths(
-                            acc,
-                            ctx,
-                            e,
-                            impl_,
-                            |acc, ctx, variant, path| {
-                                acc.add_qualified_enum_variant(ctx, path_ctx, variant, path)
-                            },
-                        );
-                    }
-                }
-            }
+            complete_expected_type(acc, ctx, path_ctx, impl_);
             ctx.process_all_names(&mut |name, def, doc_aliases| match def {
                 ScopeDef::ModuleDef(hir::ModuleDef::Trait(t)) => {
                     let assocs = t.items_with_supertraits(ctx.db);
@@ -326,3 +271,90 @@ pub(crate) fn complete_expr_path(
         }
     }
 }
+
+/// Completes the ways to construct a value of the expected type without the user having to type
+/// its name first: enum variants, struct and union literals and associated functions returning
+/// the type. When the type isn't in scope they are qualified with its name and import it if
+/// imports on the fly are enabled, and with its full path otherwise.
+fn complete_expected_type(
+    acc: &mut Completions,
+    ctx: &CompletionContext<'_>,
+    path_ctx: &PathCompletionCtx,
+    impl_: &Option<ast::Impl>,
+) {
+    let Some(ty) = ctx.expected_type.as_ref().map(|ty| ty.strip_references()) else { return };
+    let Some(adt) = ty.as_adt() else { return };
+    let self_ty = (|| ctx.sema.to_def(impl_.as_ref()?)?.self_ty(ctx.db).as_adt())();
+    let complete_self = self_ty == Some(adt);
+
+    let path = ctx
+        .module
+        .find_use_path(ctx.db, ModuleDef::from(adt), ctx.config.prefer_no_std)
+        .filter(|it| it.len() > 1);
+    let import = path.as_ref().filter(|_| ctx.config.enable_imports_on_the_fly).map(|path| {
+        let item = ItemInNs::from(ModuleDef::from(adt));
+        LocatedImport::new(path.clone(), item, item, Some(path.clone()))
+    });
+    // The path the type is referred to by, once the import is added.
+    let qualifier = match (&path, &import) {
+        (Some(path), None) => path.clone(),
+        _ => hir::ModPath::from_segments(hir::PathKind::Plain, Some(adt.name(ctx.db))),
+    };
+
+    match adt {
+        hir::Adt::Struct(strukt) => {
+            match &import {
+                Some(_) => {
+                    acc.add_struct_literal(ctx, path_ctx, strukt, None, None, import.clone())
+                }
+                None => acc.add_struct_literal(ctx, path_ctx, strukt, path, None, None),
+            }
+            if complete_self {
+                acc.add_struct_literal(
+                    ctx,
+                    path_ctx,
+                    strukt,
+                    None,
+                    Some(hir::known::SELF_TYPE),
+                    None,
+                );
+            }
+        }
+        hir::Adt::Union(un) => {
+            acc.add_union_literal(ctx, un, path, None);
+            if complete_self {
+                acc.add_union_literal(ctx, un, None, Some(hir::known::SELF_TYPE));
+            }
+        }
+        hir::Adt::Enum(e) if import.is_some() => {
+            for variant in e.variants(ctx.db) {
+                let path = hir::ModPath::from_segments(
+                    hir::PathKind::Plain,
+                    qualifier.segments().iter().cloned().chain(Some(variant.name(ctx.db))),
+                );
+                acc.add_qualified_enum_variant(ctx, path_ctx, variant, path, import.clone());
+            }
+        }
+        hir::Adt::Enum(e) => {
+            super::enum_variants_with_paths(acc, ctx, e, impl_, |acc, ctx, variant, path| {
+                acc.add_qualified_enum_variant(ctx, path_ctx, variant, path, None)
+            });
+        }
+    }
+
+    ctx.iterate_path_candidates(&ty, |item| {
+        let hir::AssocItem::Function(func) = item else { return };
+        if func.self_param(ctx.db).is_some() {
+            return;
+        }
+        // Unresolved return types unify with anything.
+        if func.ret_type(ctx.db).contains_unknown() {
+            return;
+        }
+        // The generic parameters are left unknown here, so that they are inferred when unifying.
+        let Some(callable) = func.ty(ctx.db).as_callable(ctx.db) else { return };
+        if callable.return_type().could_unify_with(ctx.db, &ty) {
+            acc.add_qualified_function(ctx, path_ctx, func, qualifier.clone(), import.clone());
+        }
+    });
+}
@@ -31,6 +31,11 @@ pub(crate) fn complete_item_list(
     let _p = profile::span("complete_item_list");
     if path_ctx.is_trivial_path() {
         add_keywords(acc, ctx, Some(kind));
+        if matches!(kind, ItemListKind::SourceFile | ItemListKind::Module)
+            && ctx.qualifier_ctx.none()
+        {
+            trait_impl::complete_trait_impl_skeletons(acc, ctx);
+        }
     }
 
     match qualified {
@@ -30,15 +30,22 @@
 //!     fn foo() {}$0
 //! }
 //! ```
+//!
+//! It also completes whole `impl Trait for Type { ... }` blocks at item level for the type defined
+//! right before the cursor once `impl` is being typed, with all required items of the trait
+//! stubbed out.
+
+use std::iter;
 
 use hir::{self, HasAttrs};
 use ide_db::{
-    documentation::HasDocs, path_transform::PathTransform,
-    syntax_helpers::insert_whitespace_into_node, traits::get_missing_assoc_items, SymbolKind,
+    base_db::CrateOrigin, documentation::HasDocs, path_transform::PathTransform,
+    syntax_helpers::insert_whitespace_into_node, traits::get_missing_assoc_items, FxHashMap,
+    SymbolKind,
 };
 use syntax::{
-    ast::{self, edit_in_place::AttrsOwnerEdit, HasTypeBounds},
-    AstNode, SyntaxElement, SyntaxKind, TextRange, T,
+    ast::{self, edit_in_place::AttrsOwnerEdit, HasGenericParams, HasName, HasTypeBounds},
+    AstNode, SmolStr, SyntaxElement, SyntaxKind, TextRange, T,
 };
 use text_edit::TextEdit;
 
@@ -348,6 +355,286 @@ fn add_const_impl(
     }
 }
 
+/// Traits of the standard library that are commonly implemented by hand.
+const COMMON_TRAITS: &[&str] = &[
+    "AsRef",
+    "Clone",
+    "Debug",
+    "Default",
+    "Deref",
+    "DerefMut",
+    "Display",
+    "Drop",
+    "Eq",
+    "Error",
+    "Extend",
+    "From",
+    "FromIterator",
+    "FromStr",
+    "Hash",
+    "IntoIterator",
+    "Iterator",
+    "Ord",
+    "PartialEq",
+    "PartialOrd",
+    "TryFrom",
+];
+
+/// The type `impl Trait for Type { ... }` skeletons are completed for.
+struct ImplTarget {
+    /// The generic parameters of the impl, those of the type without their defaults.
+    generics: Option<ast::GenericParamList>,
+    self_ty: String,
+    where_clause: Option<ast::WhereClause>,
+}
+
+/// Completes `impl Trait for Type { ... }` skeletons for the type defined right before the cursor,
+/// for the commonly implemented traits of the standard library and the traits of the crate that
+/// are in scope. They are only offered once `impl` is being typed, so that they don't crowd the
+/// completions after every type definition.
+pub(crate) fn complete_trait_impl_skeletons(acc: &mut Completions, ctx: &CompletionContext<'_>) {
+    let typed = &ctx.original_token;
+    if typed.kind() != SyntaxKind::IDENT || !"impl".starts_with(typed.text()) {
+        return;
+    }
+    let Some(adt_def) = preceding_adt(ctx) else { return };
+    let Some(adt) = ctx.sema.source(adt_def).map(|it| it.value) else { return };
+    let Some(adt_name) = adt.name() else { return };
+    let generics = adt.generic_param_list().map(|params| {
+        let params = params.clone_for_update();
+        for param in params.generic_params() {
+            match param {
+                ast::GenericParam::TypeParam(it) => it.remove_default(),
+                ast::GenericParam::ConstParam(it) => it.remove_default(),
+                ast::GenericParam::LifetimeParam(_) => (),
+            }
+        }
+        params
+    });
+    let target = ImplTarget {
+        self_ty: match &generics {
+            Some(params) => format!("{adt_name}{}", params.to_generic_args()),
+            None => adt_name.to_string(),
+        },
+        generics,
+        where_clause: adt.where_clause(),
+    };
+    let ty = adt_def.ty(ctx.db);
+    let mut impls_per_trait = FxHashMap::default();
+    for trait_ in
+        hir::Impl::all_in_crate(ctx.db, ctx.krate).iter().filter_map(|it| it.trait_(ctx.db))
+    {
+        *impls_per_trait.entry(trait_).or_insert(0usize) += 1;
+    }
+
+    ctx.process_all_names(&mut |name, def, _| {
+        let hir::ScopeDef::ModuleDef(hir::ModuleDef::Trait(trait_)) = def else { return };
+        let is_local = trait_.module(ctx.db).krate() == ctx.krate;
+        let is_common = !is_local
+            && matches!(trait_.module(ctx.db).krate().origin(ctx.db), CrateOrigin::Lang(_))
+            && COMMON_TRAITS.contains(&name.to_smol_str().as_str());
+        if !(is_local || is_common) || trait_.is_auto(ctx.db) || trait_.is_unsafe(ctx.db) {
+            return;
+        }
+        let params = trait_params(ctx, trait_);
+        if params.is_empty() && ty.impls_trait(ctx.db, trait_, &[]) {
+            return;
+        }
+        if !ctx.check_stability(Some(&trait_.attrs(ctx.db))) {
+            return;
+        }
+        let usage = impls_per_trait.get(&trait_).copied().unwrap_or(0);
+        add_trait_impl_skeleton(acc, ctx, trait_, &name, &params, &target, usage);
+    });
+}
+
+fn add_trait_impl_skeleton(
+    acc: &mut Completions,
+    ctx: &CompletionContext<'_>,
+    trait_: hir::Trait,
+    trait_name: &hir::Name,
+    params: &[SmolStr],
+    target: &ImplTarget,
+    usage: usize,
+) {
+    let snippet = ctx.config.snippet_cap.is_some();
+    let trait_name = trait_name.display(ctx.db).to_string();
+    let mut tab_stop = 0;
+    let mut next_tab_stop = || {
+        tab_stop += 1;
+        tab_stop
+    };
+    let trait_ref = match params {
+        [] => trait_name.clone(),
+        _ if !snippet => format!("{trait_name}<{}>", params.join(", ")),
+        _ => {
+            let args = params.iter().map(|param| format!("${{{}:{param}}}", next_tab_stop()));
+            format!("{trait_name}<{}>", args.collect::<Vec<_>>().join(", "))
+        }
+    };
+    let param_tab_stop =
+        |param: &str| params.iter().position(|it| it == param).filter(|_| snippet).map(|it| it + 1);
+
+    let mut members = Vec::new();
+    for item in trait_.items(ctx.db) {
+        let member = match item {
+            hir::AssocItem::Function(func) => {
+                let Some(source) = ctx.sema.source(func) else { continue };
+                if source.value.body().is_some() {
+                    continue;
+                }
+                let Some(func) = transform_trait_item(ctx, source.value, source.file_id.is_macro())
+                else {
+                    continue;
+                };
+                let signature = render_signature(&func, &param_tab_stop);
+                match snippet {
+                    true => format!("{signature} {{\n        ${}\n    }}", next_tab_stop()),
+                    false => format!("{signature} {{}}"),
+                }
+            }
+            hir::AssocItem::TypeAlias(type_alias) => {
+                let Some(source) = ctx.sema.source(type_alias) else { continue };
+                if source.value.ty().is_some() {
+                    continue;
+                }
+                let name = type_alias.name(ctx.db).display(ctx.db).to_string();
+                let value = if snippet { format!("${}", next_tab_stop()) } else { String::new() };
+                format!("type {name} = {value};")
+            }
+            hir::AssocItem::Const(const_) => {
+                let Some(source) = ctx.sema.source(const_) else { continue };
+                if source.value.body().is_some() {
+                    continue;
+                }
+                let Some(const_) =
+                    transform_trait_item(ctx, source.value, source.file_id.is_macro())
+                else {
+                    continue;
+                };
+                let decl = render_signature(&const_, &param_tab_stop);
+                let value = if snippet { format!("${}", next_tab_stop()) } else { String::new() };
+                format!("{decl} = {value};")
+            }
+        };
+        members.push(format!("    {member}"));
+    }
+
+    let ImplTarget { generics, self_ty, where_clause } = target;
+    let generics = generics.as_ref().map_or_else(String::new, |it| it.to_string());
+    let header = format!("impl{generics} {trait_ref} for {self_ty}");
+    let body = match (&*members, snippet) {
+        ([], true) => "{$0}".to_owned(),
+        ([], false) => "{}".to_owned(),
+        (_, _) => format!("{{\n{}\n}}", members.join("\n\n")),
+    };
+    let text = match where_clause {
+        Some(where_clause) => format!("{header}\n{where_clause}\n{body}"),
+        None => format!("{header} {body}"),
+    };
+    let type_name = self_ty.split_once('<').map_or(&**self_ty, |(it, _)| it);
+    let label = format!("impl {trait_name} for {type_name}");
+
+    let mut item = CompletionItem::new(SymbolKind::Impl, ctx.source_range(), label);
+    item.detail(format!("{header} {{…}}")).set_documentation(trait_.docs(ctx.db)).set_relevance(
+        CompletionRelevance {
+            usage_score: usage.try_into().unwrap_or(u8::MAX),
+            ..Default::default()
+        },
+    );
+    match ctx.config.snippet_cap {
+        Some(cap) => item.insert_snippet(cap, text),
+        None => item.insert_text(text),
+    };
+    item.add_to(acc, ctx.db);
+}
+
+/// The ADT defined by the item right before the one the cursor is in, or implemented by it if that
+/// item is an impl.
+fn preceding_adt(ctx: &CompletionContext<'_>) -> Option<hir::Adt> {
+    let mut element = SyntaxElement::Token(ctx.original_token.clone());
+    while let Some(parent) = element.parent() {
+        if matches!(parent.kind(), SyntaxKind::SOURCE_FILE | SyntaxKind::ITEM_LIST) {
+            break;
+        }
+        element = parent.into();
+    }
+    let item = iter::successors(element.prev_sibling_or_token(), |it| it.prev_sibling_or_token())
+        .find_map(|it| it.into_node().and_then(ast::Item::cast))?;
+    match item {
+        ast::Item::Enum(it) => Some(ctx.sema.to_def(&it)?.into()),
+        ast::Item::Struct(it) => Some(ctx.sema.to_def(&it)?.into()),
+        ast::Item::Union(it) => Some(ctx.sema.to_def(&it)?.into()),
+        ast::Item::Impl(it) => ctx.sema.to_def(&it)?.self_ty(ctx.db).as_adt(),
+        _ => None,
+    }
+}
+
+/// The names of the generic parameters of `trait_`, excluding `Self`.
+fn trait_params(ctx: &CompletionContext<'_>, trait_: hir::Trait) -> Vec<SmolStr> {
+    let Some(source) = ctx.sema.source(trait_) else { return Vec::new() };
+    source
+        .value
+        .generic_param_list()
+        .into_iter()
+        .flat_map(|it| it.generic_params())
+        .filter_map(|param| match param {
+            ast::GenericParam::TypeParam(it) => it.name(),
+            ast::GenericParam::ConstParam(it) => it.name(),
+            ast::GenericParam::LifetimeParam(_) => None,
+        })
+        .map(|it| it.text().into())
+        .collect()
+}
+
+/// Qualifies the paths of a trait item for the completion position and removes its attributes and
+/// docs.
+fn transform_trait_item<N: AstNode + AttrsOwnerEdit>(
+    ctx: &CompletionContext<'_>,
+    item: N,
+    needs_whitespace: bool,
+) -> Option<N> {
+    let source_scope = ctx.sema.scope(item.syntax())?;
+    let item = if needs_whitespace {
+        N::cast(insert_whitespace_into_node::insert_ws_into(item.syntax().clone()))?
+    } else {
+        item.clone_for_update()
+    };
+    PathTransform::generic_transformation(&ctx.scope, &source_scope).apply(item.syntax());
+    item.remove_attrs_and_docs();
+    Some(item)
+}
+
+/// Renders `item` up to its body or value, turning the uses of the trait's generic parameters into
+/// the tab stops given by `param_tab_stop`.
+fn render_signature<N: AstNode>(
+    item: &N,
+    param_tab_stop: &dyn Fn(&str) -> Option<usize>,
+) -> String {
+    let node = item.syntax();
+    let end = node
+        .children_with_tokens()
+        .find(|it| matches!(it.kind(), T![;] | T![=] | SyntaxKind::BLOCK_EXPR))
+        .map_or(node.text_range().end(), |it| it.text_range().start());
+    let mut res = String::new();
+    for token in node.descendants_with_tokens().filter_map(|it| it.into_token()) {
+        if token.text_range().start() >= end {
+            break;
+        }
+        let is_param = token.kind() == SyntaxKind::IDENT
+            && token
+                .parent_ancestors()
+                .nth(2)
+                .and_then(ast::Path::cast)
+                .is_some_and(|path| path.qualifier().is_none() && path.parent_path().is_none());
+        match param_tab_stop(token.text()).filter(|_| is_param) {
+            Some(tab_stop) => res.push_str(&format!("${{{tab_stop}:{}}}", token.text())),
+            None => res.push_str(token.text()),
+        }
+    }
+    res.trim_end().to_owned()
+}
+
 fn make_const_compl_syntax(const_: &ast::Const, needs_whitespace: bool) -> String {
     let const_ = if needs_whitespace {
         insert_whitespace_into_node::insert_ws_into(const_.syntax().clone())
@@ -1393,6 +1680,125 @@ trait Tr {
 impl Tr for () {
     type Item = $0;
 }
+"#,
+        );
+    }
+
+    #[test]
+    fn completes_trait_impl_skeleton() {
+        check_edit(
+            "impl From for Foo",
+            r#"
+//- minicore: from
+struct Foo<T>(T);
+im$0
+"#,
+            r#"
+struct Foo<T>(T);
+impl<T> From<${1:T}> for Foo<T> {
+    fn from(_: ${1:T}) -> Self {
+        $2
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn trait_impl_skeleton_keeps_where_clause() {
+        check_edit(
+            "impl Default for Foo",
+            r#"
+//- minicore: default
+struct Foo<T = u32, const N: usize = 1>(T)
+where
+    T: Copy;
+i$0
+"#,
+            r#"
+struct Foo<T = u32, const N: usize = 1>(T)
+where
+    T: Copy;
+impl<T, const N: usize> Default for Foo<T, N>
+where
+    T: Copy
+{
+    fn default() -> Self {
+        $1
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn trait_impl_skeletons_once_impl_is_typed() {
+        check(
+            r#"
+//- minicore: default
+struct Foo;
+f$0
+"#,
+            expect![[r#"
+                md core
+            "#]],
+        );
+        check(
+            r#"
+//- minicore: default
+struct Foo;
+im$0
+"#,
+            expect![[r#"
+                im impl Default for Foo impl Default for Foo {…}
+                md core
+            "#]],
+        );
+    }
+
+    #[test]
+    fn trait_impl_skeleton_stubs_required_items() {
+        check_edit(
+            "impl Shape for Square",
+            r#"
+mod geometry {
+    pub struct Point;
+    pub trait Shape {
+        type Unit;
+        const CORNERS: usize;
+        fn area(&self) -> Point;
+        fn name(&self) -> &str { "shape" }
+    }
+}
+use geometry::Shape;
+
+struct Square;
+impl Square {}
+imp$0
+"#,
+            r#"
+mod geometry {
+    pub struct Point;
+    pub trait Shape {
+        type Unit;
+        const CORNERS: usize;
+        fn area(&self) -> Point;
+        fn name(&self) -> &str { "shape" }
+    }
+}
+use geometry::Shape;
+
+struct Square;
+impl Square {}
+impl Shape for Square {
+    type Unit = $1;
+
+    const CORNERS: usize = $2;
+
+    fn area(&self) -> geometry::Point {
+        $3
+    }
+}
 "#,
         );
     }
@@ -0,0 +1,275 @@
+//! Completion of the input of declarative macro calls that fail to expand.
+//!
+//! Speculative expansion only helps once the input matches one of the macro's rules, which is
+//! rarely the case while it is being typed. Instead we ask the rules what they accept after the
+//! tokens before the cursor, and offer the literal tokens they require as well as names fitting
+//! the expected fragments.
+//!
+//! ```
+//! macro_rules! route {
+//!     (GET $path:literal => $handler:expr) => {};
+//! }
+//!
+//! route!(GET "/" $0);
+//! //             ^ suggests `=>`
+//! ```
+
+use hir::{MacroFragmentKind, MacroInput, ScopeDef};
+use syntax::{ast, SyntaxKind};
+
+use crate::{
+    context::{CompletionContext, Visible},
+    render::{render_resolution_simple, RenderContext},
+    CompletionItem, CompletionItemKind, Completions,
+};
+
+pub(crate) fn complete_macro_input(
+    acc: &mut Completions,
+    ctx: &CompletionContext<'_>,
+    call: &ast::MacroCall,
+) -> Option<()> {
+    let mac = ctx.sema.resolve_macro_call(call)?;
+    if mac.kind(ctx.db) != hir::MacroKind::Declarative {
+        return None;
+    }
+    let tt = call.token_tree()?;
+    // The token being typed is replaced by the completion, so it is not part of the prefix.
+    let token = &ctx.token;
+    let offset = match token.kind() {
+        SyntaxKind::IDENT | SyntaxKind::LIFETIME_IDENT | SyntaxKind::UNDERSCORE => {
+            token.text_range().start()
+        }
+        kind if kind.is_keyword() || kind.is_trivia() => token.text_range().start(),
+        _ => token.text_range().end(),
+    };
+
+    let expected = mac.expected_input(ctx.db, &tt, offset);
+    let mut fragments = Vec::new();
+    for input in expected {
+        match input {
+            MacroInput::Token(text) => {
+                CompletionItem::new(CompletionItemKind::Keyword, ctx.source_range(), text)
+                    .add_to(acc, ctx.db);
+            }
+            MacroInput::Delimited(open, close) => {
+                let label = format!("{open}{close}");
+                let mut item =
+                    CompletionItem::new(CompletionItemKind::Keyword, ctx.source_range(), label);
+                if let Some(cap) = ctx.config.snippet_cap {
+                    item.insert_snippet(cap, format!("{open}$0{close}"));
+                }
+                item.add_to(acc, ctx.db);
+            }
+            MacroInput::Fragment(kind) => {
+                if !fragments.contains(&kind) {
+                    fragments.push(kind);
+                }
+            }
+        }
+    }
+    if fragments.is_empty() {
+        return Some(());
+    }
+
+    for &kind in &fragments {
+        match kind {
+            MacroFragmentKind::Literal => {
+                ["true", "false"].into_iter().for_each(|kw| acc.add_keyword(ctx, kw))
+            }
+            MacroFragmentKind::Vis => ["pub", "pub(crate)", "pub(super)"]
+                .into_iter()
+                .for_each(|kw| acc.add_keyword(ctx, kw)),
+            _ => (),
+        }
+    }
+    ctx.process_all_names(&mut |name, def, doc_aliases| {
+        if !fragments.iter().any(|&kind| fits_fragment(kind, def)) {
+            return;
+        }
+        if !ctx.check_stability(def.attrs(ctx.db).as_deref()) {
+            return;
+        }
+        let is_private_editable = match ctx.def_is_visible(&def) {
+            Visible::Yes => false,
+            Visible::Editable => true,
+            Visible::No => return,
+        };
+        render_resolution_simple(
+            RenderContext::new(ctx).private_editable(is_private_editable).doc_aliases(doc_aliases),
+            name,
+            def,
+        )
+        .add_to(acc, ctx.db);
+    });
+    Some(())
+}
+
+/// Whether a name resolving to `def` can start a fragment of the given kind.
+fn fits_fragment(kind: MacroFragmentKind, def: ScopeDef) -> bool {
+    use hir::ModuleDef;
+    match kind {
+        MacroFragmentKind::Path | MacroFragmentKind::Expr | MacroFragmentKind::Stmt => !matches!(
+            def,
+            ScopeDef::Label(_) | ScopeDef::GenericParam(hir::GenericParam::LifetimeParam(_))
+        ),
+        MacroFragmentKind::Ty => matches!(
+            def,
+            ScopeDef::ModuleDef(
+                ModuleDef::Module(_)
+                    | ModuleDef::Adt(_)
+                    | ModuleDef::Trait(_)
+                    | ModuleDef::TraitAlias(_)
+                    | ModuleDef::TypeAlias(_)
+                    | ModuleDef::BuiltinType(_)
+            ) | ScopeDef::GenericParam(hir::GenericParam::TypeParam(_))
+                | ScopeDef::AdtSelfType(_)
+                | ScopeDef::ImplSelfType(_)
+        ),
+        MacroFragmentKind::Pat => matches!(
+            def,
+            ScopeDef::ModuleDef(
+                ModuleDef::Module(_)
+                    | ModuleDef::Adt(_)
+                    | ModuleDef::Variant(_)
+                    | ModuleDef::Const(_)
+                    | ModuleDef::Static(_)
+            ) | ScopeDef::GenericParam(hir::GenericParam::ConstParam(_))
+                | ScopeDef::AdtSelfType(_)
+        ),
+        MacroFragmentKind::Lifetime => {
+            matches!(def, ScopeDef::GenericParam(hir::GenericParam::LifetimeParam(_)))
+        }
+        MacroFragmentKind::Ident
+        | MacroFragmentKind::Literal
+        | MacroFragmentKind::Vis
+        | MacroFragmentKind::Block
+        | MacroFragmentKind::Meta
+        | MacroFragmentKind::Item
+        | MacroFragmentKind::Tt => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::tests::{check_edit, completion_list};
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let actual = completion_list(ra_fixture);
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn literal_tokens_of_all_arms() {
+        check(
+            r#"
+macro_rules! cmd {
+    (add $e:expr) => {};
+    (remove $i:ident) => {};
+}
+fn main() {
+    cmd!($0);
+}
+"#,
+            expect![[r#"
+                kw add
+                kw remove
+            "#]],
+        );
+        check(
+            r#"
+macro_rules! route {
+    (GET $path:literal => $handler:expr) => {};
+}
+fn main() {
+    route!(GET "/" $0);
+}
+"#,
+            expect![[r#"
+                kw =>
+            "#]],
+        );
+    }
+
+    #[test]
+    fn type_fragment() {
+        check(
+            r#"
+macro_rules! field {
+    ($name:ident : $ty:ty) => {};
+}
+struct Foo;
+fn bar() {}
+fn main() {
+    field!(x: $0);
+}
+"#,
+            expect![[r#"
+                st Foo
+                bt u32
+            "#]],
+        );
+    }
+
+    #[test]
+    fn expr_fragment_after_tokens() {
+        check(
+            r#"
+macro_rules! route {
+    (GET $path:literal => $handler:expr) => { $path };
+}
+struct Unit;
+fn handler() {}
+fn main() {
+    let local = 0;
+    route!(GET "/" => $0);
+}
+"#,
+            expect![[r#"
+                fn handler
+                fn main
+                lc local
+                ma route
+                st Unit
+                bt u32
+            "#]],
+        );
+    }
+
+    #[test]
+    fn inside_delimited_group() {
+        check(
+            r#"
+macro_rules! record {
+    (struct $name:ident { $($field:ident : $ty:ty),* }) => {};
+}
+fn main() {
+    record!(struct Foo { a: u32 $0 });
+}
+"#,
+            expect![[r#"
+                kw ,
+            "#]],
+        );
+        check_edit(
+            "{}",
+            r#"
+macro_rules! record {
+    (struct $name:ident { $($field:ident : $ty:ty),* }) => {};
+}
+fn main() {
+    record!(struct Foo $0);
+}
+"#,
+            r#"
+macro_rules! record {
+    (struct $name:ident { $($field:ident : $ty:ty),* }) => {};
+}
+fn main() {
+    record!(struct Foo {$0});
+}
+"#,
+        );
+    }
+}
@@ -2,9 +2,10 @@
 
 mod format_like;
 
+use hir::{ItemInNs, ModuleDef};
 use ide_db::{
     documentation::{Documentation, HasDocs},
-    imports::insert_use::ImportScope,
+    imports::{import_assets::LocatedImport, insert_use::ImportScope},
     ty_filter::TryEnum,
     SnippetCap,
 };
@@ -124,6 +125,15 @@ pub(crate) fn complete_postfix(
         }
     }
 
+    add_method_chain_completions(
+        acc,
+        ctx,
+        &postfix_snippet,
+        &receiver_text,
+        receiver_ty,
+        try_enum.as_ref(),
+    );
+
     postfix_snippet("ref", "&expr", &format!("&{receiver_text}")).add_to(acc, ctx.db);
     postfix_snippet("refm", "&mut expr", &format!("&mut {receiver_text}")).add_to(acc, ctx.db);
 
@@ -155,7 +165,7 @@ pub(crate) fn complete_postfix(
     };
 
     if !ctx.config.snippets.is_empty() {
-        add_custom_postfix_completions(acc, ctx, &postfix_snippet, &receiver_text);
+        add_custom_postfix_completions(acc, ctx, &postfix_snippet, &receiver_text, receiver_ty);
     }
 
     match try_enum {
@@ -191,6 +201,7 @@ pub(crate) fn complete_postfix(
 
     postfix_snippet("box", "Box::new(expr)", &format!("Box::new({receiver_text})"))
         .add_to(acc, ctx.db);
+    add_wrapping_completions(acc, ctx, &postfix_snippet, &receiver_text, receiver_ty);
     postfix_snippet("dbg", "dbg!(expr)", &format!("dbg!({receiver_text})")).add_to(acc, ctx.db); // fixme
     postfix_snippet("dbgr", "dbg!(&expr)", &format!("dbg!(&{receiver_text})")).add_to(acc, ctx.db);
     postfix_snippet("call", "function(expr)", &format!("${{1}}({receiver_text})"))
@@ -212,6 +223,218 @@ pub(crate) fn complete_postfix(
     }
 }
 
+/// Completes the templates that call methods on the receiver, depending on the traits it
+/// implements and the dependencies of the crate.
+fn add_method_chain_completions(
+    acc: &mut Completions,
+    ctx: &CompletionContext<'_>,
+    postfix_snippet: impl Fn(&str, &str, &str) -> Builder,
+    receiver_text: &str,
+    receiver_ty: &hir::Type,
+    try_enum: Option<&TryEnum>,
+) {
+    let famous_defs = ctx.famous_defs();
+
+    // `iter` only stands in for `into_iter` if the receiver has no `iter` method of its own
+    if let Some(into_iter) = famous_defs.core_iter_IntoIterator() {
+        let is_iterator = famous_defs
+            .core_iter_Iterator()
+            .is_some_and(|iterator| receiver_ty.impls_trait(ctx.db, iterator, &[]));
+        if !is_iterator
+            && receiver_ty.impls_trait(ctx.db, into_iter, &[])
+            && receiver_ty
+                .iterate_method_candidates(
+                    ctx.db,
+                    &ctx.scope,
+                    None,
+                    Some(&hir::known::iter),
+                    |_| Some(()),
+                )
+                .is_none()
+        {
+            postfix_snippet("iter", "expr.into_iter()", &format!("{receiver_text}.into_iter()"))
+                .add_to(acc, ctx.db);
+        }
+    }
+
+    if let Some((trait_, import)) =
+        dependency_trait(ctx, &["rayon", "iter", "IntoParallelIterator"])
+    {
+        if receiver_ty.impls_trait(ctx.db, trait_, &[]) {
+            let mut item = postfix_snippet(
+                "par",
+                "expr.into_par_iter()",
+                &format!("{receiver_text}.into_par_iter()"),
+            );
+            if let Some(import) = import {
+                item.add_import(import);
+            }
+            item.add_to(acc, ctx.db);
+        }
+    }
+
+    if let Some(TryEnum::Result) = try_enum {
+        let args = receiver_ty.type_arguments().collect::<Vec<_>>();
+        let context = [(["anyhow", "Context"], "context"), (["eyre", "WrapErr"], "wrap_err")]
+            .into_iter()
+            .find_map(|(path, method)| {
+                let (trait_, import) = dependency_trait(ctx, &path)?;
+                receiver_ty.impls_trait(ctx.db, trait_, &args).then_some((method, import))
+            });
+        if let Some((method, import)) = context {
+            let mut item = postfix_snippet(
+                "ctx",
+                &format!("expr.{method}(\"…\")"),
+                &format!("{receiver_text}.{method}(\"$0\")"),
+            );
+            if let Some(import) = import {
+                item.add_import(import);
+            }
+            item.add_to(acc, ctx.db);
+        }
+    }
+
+    if let Some(chain) = unwrap_or_default_chain(ctx, receiver_ty) {
+        postfix_snippet(
+            "unwrap_or_default",
+            &format!("expr{chain}"),
+            &format!("{receiver_text}{chain}"),
+        )
+        .add_to(acc, ctx.db);
+    }
+}
+
+/// The method chain that unwraps the (possibly nested) `Option`s and `Result`s of `ty` into the
+/// default value of their payload, like `.ok().flatten().unwrap_or_default()`.
+fn unwrap_or_default_chain(ctx: &CompletionContext<'_>, ty: &hir::Type) -> Option<String> {
+    let default = ctx.famous_defs().core_default_Default()?;
+    let mut layers = Vec::new();
+    let mut ty = ty.clone();
+    while let Some(try_enum) = TryEnum::from_ty(&ctx.sema, &ty) {
+        if !layers.is_empty() && matches!(try_enum, TryEnum::Result) {
+            break;
+        }
+        layers.push(try_enum);
+        let payload = ty.type_arguments().next()?;
+        ty = payload;
+    }
+    if layers.is_empty() || !ty.impls_trait(ctx.db, default, &[]) {
+        return None;
+    }
+
+    let mut chain = String::new();
+    if layers.len() > 1 {
+        if matches!(layers[0], TryEnum::Result) {
+            chain.push_str(".ok()");
+        }
+        chain.push_str(&".flatten()".repeat(layers.len() - 1));
+    }
+    chain.push_str(".unwrap_or_default()");
+    Some(chain)
+}
+
+/// Completes the templates that wrap the receiver into a smart pointer or lock, or spawn it as a
+/// task.
+fn add_wrapping_completions(
+    acc: &mut Completions,
+    ctx: &CompletionContext<'_>,
+    postfix_snippet: impl Fn(&str, &str, &str) -> Builder,
+    receiver_text: &str,
+    receiver_ty: &hir::Type,
+) {
+    let famous_defs = ctx.famous_defs();
+    let wrappers = [
+        ("arc", famous_defs.std_sync_Arc()),
+        ("rc", famous_defs.std_rc_Rc()),
+        ("mutex", famous_defs.std_sync_Mutex()),
+    ];
+    for (label, strukt) in wrappers {
+        let Some(strukt) = strukt else { continue };
+        let Some((path, import)) = item_path(ctx, ModuleDef::from(strukt)) else { continue };
+        let mut item = postfix_snippet(
+            label,
+            &format!("{}::new(expr)", strukt.name(ctx.db).display(ctx.db)),
+            &format!("{path}::new({receiver_text})"),
+        );
+        if let Some(import) = import {
+            item.add_import(import);
+        }
+        item.add_to(acc, ctx.db);
+    }
+
+    let is_future = famous_defs
+        .core_future_Future()
+        .is_some_and(|future| receiver_ty.impls_trait(ctx.db, future, &[]));
+    if is_future {
+        let spawn = [&["tokio", "spawn"][..], &["async_std", "task", "spawn"]]
+            .into_iter()
+            .find_map(|path| dependency_def(ctx, path))
+            .and_then(|def| ctx.module.find_use_path(ctx.db, def, ctx.config.prefer_no_std));
+        if let Some(spawn) = spawn {
+            let spawn = spawn.display(ctx.db).to_string();
+            postfix_snippet(
+                "spawn",
+                &format!("{spawn}(expr)"),
+                &format!("{spawn}({receiver_text})"),
+            )
+            .add_to(acc, ctx.db);
+        }
+    }
+}
+
+/// Looks up the item at `path` in the dependency of the current crate named by its first segment.
+fn dependency_def(ctx: &CompletionContext<'_>, path: &[&str]) -> Option<ModuleDef> {
+    let (krate, path) = path.split_first()?;
+    let dep =
+        ctx.krate.dependencies(ctx.db).into_iter().find(|dep| dep.name.to_smol_str() == *krate)?;
+    let mut def = ModuleDef::Module(dep.krate.root_module());
+    for segment in path {
+        let ModuleDef::Module(module) = def else { return None };
+        def = module.scope(ctx.db, None).into_iter().find_map(|(name, def)| match def {
+            hir::ScopeDef::ModuleDef(def) if name.to_smol_str() == *segment => Some(def),
+            _ => None,
+        })?;
+    }
+    Some(def)
+}
+
+/// Looks up a trait of a dependency like [`dependency_def`], together with the import that brings
+/// it into scope if it isn't yet. Returns `None` if it would need to be imported but imports on the
+/// fly are disabled, as methods can't be called through a qualified trait path.
+fn dependency_trait(
+    ctx: &CompletionContext<'_>,
+    path: &[&str],
+) -> Option<(hir::Trait, Option<LocatedImport>)> {
+    let ModuleDef::Trait(trait_) = dependency_def(ctx, path)? else { return None };
+    if ctx.traits_in_scope().0.contains(&trait_.into()) {
+        return Some((trait_, None));
+    }
+    if !ctx.config.enable_imports_on_the_fly {
+        return None;
+    }
+    let path =
+        ctx.module.find_use_path(ctx.db, ModuleDef::from(trait_), ctx.config.prefer_no_std)?;
+    let item = ItemInNs::from(ModuleDef::from(trait_));
+    Some((trait_, Some(LocatedImport::new(path.clone(), item, item, Some(path)))))
+}
+
+/// The path `def` is referred to by at the completion position, together with the import to add
+/// for it. Items that aren't in scope are imported if imports on the fly are enabled, and referred
+/// to by their full path otherwise.
+fn item_path(
+    ctx: &CompletionContext<'_>,
+    def: ModuleDef,
+) -> Option<(String, Option<LocatedImport>)> {
+    let path = ctx.module.find_use_path(ctx.db, def, ctx.config.prefer_no_std)?;
+    if path.len() > 1 && ctx.config.enable_imports_on_the_fly {
+        let name = def.name(ctx.db)?.display(ctx.db).to_string();
+        let item = ItemInNs::from(def);
+        return Some((name, Some(LocatedImport::new(path.clone(), item, item, Some(path)))));
+    }
+    let path = path.display(ctx.db).to_string();
+    Some((path, None))
+}
+
 fn get_receiver_text(receiver: &ast::Expr, receiver_is_ambiguous_float_literal: bool) -> String {
     let text = if receiver_is_ambiguous_float_literal {
         let text = receiver.syntax().text();
@@ -305,6 +528,7 @@ fn add_custom_postfix_completions(
     ctx: &CompletionContext<'_>,
     postfix_snippet: impl Fn(&str, &str, &str) -> Builder,
     receiver_text: &str,
+    receiver_ty: &hir::Type,
 ) -> Option<()> {
     if ImportScope::find_insert_use_container(&ctx.token.parent()?, &ctx.sema).is_none() {
         return None;
@@ -315,7 +539,9 @@ fn add_custom_postfix_completions(
                 Some(imports) => imports,
                 None => return,
             };
-            let body = snippet.postfix_snippet(receiver_text);
+            let Some(body) = snippet.expand(ctx, Some((receiver_text, receiver_ty))) else {
+                return;
+            };
             let mut builder =
                 postfix_snippet(trigger, snippet.description.as_deref().unwrap_or_default(), &body);
             builder.documentation(Documentation::new(format!("```rust\n{body}\n```")));
@@ -766,4 +992,200 @@ fn test() {
             expect![[r#""#]],
         );
     }
+
+    #[test]
+    fn postfix_iter_on_into_iterator() {
+        check_edit(
+            "iter",
+            r#"
+//- minicore: iterator
+struct Bag;
+impl IntoIterator for Bag {
+    type Item = u8;
+    type IntoIter = core::iter::Empty<u8>;
+    fn into_iter(self) -> Self::IntoIter { loop {} }
+}
+fn main() {
+    Bag.$0
+}
+"#,
+            r#"
+struct Bag;
+impl IntoIterator for Bag {
+    type Item = u8;
+    type IntoIter = core::iter::Empty<u8>;
+    fn into_iter(self) -> Self::IntoIter { loop {} }
+}
+fn main() {
+    Bag.into_iter()
+}
+"#,
+        );
+        check(
+            r#"
+//- minicore: iterator
+struct Bag;
+impl Bag {
+    fn iter(&self) {}
+}
+impl IntoIterator for Bag {
+    type Item = u8;
+    type IntoIter = core::iter::Empty<u8>;
+    fn into_iter(self) -> Self::IntoIter { loop {} }
+}
+fn main() {
+    Bag.$0
+}
+"#,
+            expect![[r#"
+                me into_iter() (as IntoIterator) fn(self) -> <Self as IntoIterator>::IntoIter
+                me iter()                 fn(&self)
+                sn box                    Box::new(expr)
+                sn call                   function(expr)
+                sn dbg                    dbg!(expr)
+                sn dbgr                   dbg!(&expr)
+                sn for                    for ele in expr {}
+                sn let                    let
+                sn letm                   let mut
+                sn match                  match expr {}
+                sn ref                    &expr
+                sn refm                   &mut expr
+                sn unsafe                 unsafe {}
+            "#]],
+        );
+    }
+
+    #[test]
+    fn postfix_par_with_rayon() {
+        check_edit(
+            "par",
+            r#"
+//- /main.rs crate:main deps:rayon
+struct Items;
+impl rayon::iter::IntoParallelIterator for Items {}
+fn main() {
+    Items.$0
+}
+//- /rayon.rs crate:rayon
+pub mod iter {
+    pub trait IntoParallelIterator {
+        fn into_par_iter(self) {}
+    }
+}
+"#,
+            r#"
+use rayon::iter::IntoParallelIterator;
+
+struct Items;
+impl rayon::iter::IntoParallelIterator for Items {}
+fn main() {
+    Items.into_par_iter()
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn postfix_ctx_with_anyhow() {
+        check_edit(
+            "ctx",
+            r#"
+//- minicore: result
+//- /main.rs crate:main deps:anyhow
+fn main() {
+    let res: Result<u8, ()> = Ok(0);
+    res.$0
+}
+//- /anyhow.rs crate:anyhow
+pub trait Context<T, E> {
+    fn context(self, context: &str) -> Result<T, E>;
+}
+impl<T, E> Context<T, E> for Result<T, E> {
+    fn context(self, context: &str) -> Result<T, E> { self }
+}
+"#,
+            r#"
+use anyhow::Context;
+
+fn main() {
+    let res: Result<u8, ()> = Ok(0);
+    res.context("$0")
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn postfix_unwrap_or_default_chain() {
+        check_edit(
+            "unwrap_or_default",
+            r#"
+//- minicore: option, result, default
+struct S;
+impl Default for S {
+    fn default() -> S { S }
+}
+fn main() {
+    let res: Result<Option<Option<S>>, ()> = Ok(None);
+    res.$0
+}
+"#,
+            r#"
+struct S;
+impl Default for S {
+    fn default() -> S { S }
+}
+fn main() {
+    let res: Result<Option<Option<S>>, ()> = Ok(None);
+    res.ok().flatten().flatten().unwrap_or_default()
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn postfix_wrapping_templates() {
+        check_edit(
+            "arc",
+            r#"
+//- /main.rs crate:main deps:std
+fn main() {
+    let x = 1;
+    x.$0
+}
+//- /std.rs crate:std
+pub mod sync {
+    pub struct Arc<T>(T);
+    pub struct Mutex<T>(T);
+}
+"#,
+            r#"
+use std::sync::Arc;
+
+fn main() {
+    let x = 1;
+    Arc::new(x)
+}
+"#,
+        );
+        check_edit(
+            "spawn",
+            r#"
+//- minicore: future
+//- /main.rs crate:main deps:tokio
+async fn work() {}
+fn main() {
+    work().$0
+}
+//- /tokio.rs crate:tokio
+pub fn spawn<F>(future: F) {}
+"#,
+            r#"
+async fn work() {}
+fn main() {
+    tokio::spawn(work())
+}
+"#,
+        );
+    }
 }
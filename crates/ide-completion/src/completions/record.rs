@@ -274,6 +274,34 @@ impl submod::Foo {
     #[test]
     fn literal_struct_completion_from_sub_modules() {
         check_edit(
+            "Struct{}",
+            r#"
+mod submod {
+    pub struct Struct {
+        pub a: u64,
+    }
+}
+
+fn f() -> submod::Struct {
+    Stru$0
+}
+            "#,
+            r#"
+use submod::Struct;
+
+mod submod {
+    pub struct Struct {
+        pub a: u64,
+    }
+}
+
+fn f() -> submod::Struct {
+    Struct { a: ${1:()} }$0
+}
+            "#,
+        );
+        check_edit_with_config(
+            CompletionConfig { enable_imports_on_the_fly: false, ..TEST_CONFIG },
             "submod::Struct{}",
             r#"
 mod submod {
@@ -3,8 +3,9 @@
 use ide_db::{documentation::Documentation, imports::insert_use::ImportScope, SnippetCap};
 
 use crate::{
-    context::{ExprCtx, ItemListKind, PathCompletionCtx, Qualified},
+    context::{ExprCtx, ItemListKind, PathCompletionCtx, PatternContext, Qualified},
     item::Builder,
+    snippet::in_test_module,
     CompletionContext, CompletionItem, CompletionItemKind, Completions, SnippetScope,
 };
 
@@ -27,7 +28,7 @@ pub(crate) fn complete_expr_snippet(
     };
 
     if !ctx.config.snippets.is_empty() {
-        add_custom_completions(acc, ctx, cap, SnippetScope::Expr);
+        add_custom_completions(acc, ctx, cap, &[SnippetScope::Expr]);
     }
 
     if in_block_expr {
@@ -66,7 +67,15 @@ pub(crate) fn complete_item_snippet(
     };
 
     if !ctx.config.snippets.is_empty() {
-        add_custom_completions(acc, ctx, cap, SnippetScope::Item);
+        let mut scopes = vec![SnippetScope::Item];
+        match kind {
+            ItemListKind::Impl | ItemListKind::TraitImpl(_) => scopes.push(SnippetScope::ImplBody),
+            ItemListKind::SourceFile | ItemListKind::Module if in_test_module(ctx) => {
+                scopes.push(SnippetScope::TestModule)
+            }
+            _ => (),
+        }
+        add_custom_completions(acc, ctx, cap, &scopes);
     }
 
     // Test-related snippets shouldn't be shown in blocks.
@@ -117,6 +126,19 @@ macro_rules! $1 {
     }
 }
 
+pub(crate) fn complete_pattern_snippet(
+    acc: &mut Completions,
+    ctx: &CompletionContext<'_>,
+    pattern_ctx: &PatternContext,
+) {
+    if pattern_ctx.record_pat.is_some() || ctx.config.snippets.is_empty() {
+        return;
+    }
+    if let Some(cap) = ctx.config.snippet_cap {
+        add_custom_completions(acc, ctx, cap, &[SnippetScope::Pattern]);
+    }
+}
+
 fn snippet(ctx: &CompletionContext<'_>, cap: SnippetCap, label: &str, snippet: &str) -> Builder {
     let mut item = CompletionItem::new(CompletionItemKind::Snippet, ctx.source_range(), label);
     item.insert_snippet(cap, snippet);
@@ -127,18 +149,18 @@ fn add_custom_completions(
     acc: &mut Completions,
     ctx: &CompletionContext<'_>,
     cap: SnippetCap,
-    scope: SnippetScope,
+    scopes: &[SnippetScope],
 ) -> Option<()> {
     if ImportScope::find_insert_use_container(&ctx.token.parent()?, &ctx.sema).is_none() {
         return None;
     }
-    ctx.config.prefix_snippets().filter(|(_, snip)| snip.scope == scope).for_each(
+    ctx.config.prefix_snippets().filter(|(_, snip)| scopes.contains(&snip.scope)).for_each(
         |(trigger, snip)| {
             let imports = match snip.imports(ctx) {
                 Some(imports) => imports,
                 None => return,
             };
-            let body = snip.snippet();
+            let Some(body) = snip.expand(ctx, None) else { return };
             let mut builder = snippet(ctx, cap, trigger, &body);
             builder.documentation(Documentation::new(format!("```rust\n{body}\n```")));
             for import in imports.into_iter() {
@@ -154,10 +176,21 @@ fn add_custom_completions(
 #[cfg(test)]
 mod tests {
     use crate::{
-        tests::{check_edit_with_config, TEST_CONFIG},
-        CompletionConfig, Snippet,
+        tests::{check_edit_with_config, do_completion_with_config, TEST_CONFIG},
+        CompletionConfig, CompletionItemKind, Snippet, SnippetScope,
     };
 
+    fn config(trigger: &str, body: &str, scope: SnippetScope) -> CompletionConfig {
+        let snippet = Snippet::new(&[trigger.into()], &[], &[body.into()], "", &[], scope);
+        CompletionConfig { snippets: vec![snippet.unwrap()], ..TEST_CONFIG }
+    }
+
+    fn has_snippet(config: CompletionConfig, trigger: &str, ra_fixture: &str) -> bool {
+        do_completion_with_config(config, ra_fixture, CompletionItemKind::Snippet)
+            .iter()
+            .any(|it| it.label == trigger)
+    }
+
     #[test]
     fn custom_snippet_completion() {
         check_edit_with_config(
@@ -182,6 +215,111 @@ fn main() { $0 }
 use core::ops::ControlFlow;
 
 fn main() { ControlFlow::Break(()) }
+"#,
+        );
+    }
+
+    #[test]
+    fn custom_snippet_scopes() {
+        let impl_config = config("ctor", "fn new() -> Self {}", SnippetScope::ImplBody);
+        assert!(has_snippet(impl_config.clone(), "ctor", "struct S; impl S { $0 }"));
+        assert!(!has_snippet(impl_config, "ctor", "$0"));
+
+        let test_config = config("case", "#[test]\nfn $1() {}", SnippetScope::TestModule);
+        assert!(has_snippet(test_config.clone(), "case", "#[cfg(test)]\nmod tests { $0 }"));
+        assert!(!has_snippet(test_config, "case", "mod not_tests { $0 }"));
+
+        let pat_config = config("wild", "_", SnippetScope::Pattern);
+        assert!(has_snippet(pat_config.clone(), "wild", "fn f(x: u8) { match x { w$0 } }"));
+        assert!(!has_snippet(pat_config, "wild", "fn f() { w$0 }"));
+    }
+
+    #[test]
+    fn custom_snippet_variables() {
+        check_edit_with_config(
+            config(
+                "log",
+                "log(\"${crate_name}::${fn_name}\", ${self_ty}::NAME)",
+                SnippetScope::Expr,
+            ),
+            "log",
+            r#"
+//- /lib.rs crate:my_crate
+struct Service;
+impl Service {
+    fn start(&self) { $0 }
+}
+"#,
+            r#"
+struct Service;
+impl Service {
+    fn start(&self) { log("my_crate::start", Service::NAME) }
+}
+"#,
+        );
+        check_edit_with_config(
+            config("deflt", "<${expected_type}>::default()", SnippetScope::Expr),
+            "deflt",
+            r#"
+struct Wrapper<T>(T);
+fn take(_: Wrapper<u32>) {}
+fn main() { take($0) }
+"#,
+            r#"
+struct Wrapper<T>(T);
+fn take(_: Wrapper<u32>) {}
+fn main() { take(<Wrapper<u32>>::default()) }
+"#,
+        );
+        // Variables without a value at the position make the snippet inapplicable.
+        assert!(!has_snippet(config("fnname", "${fn_name}", SnippetScope::Item), "fnname", "$0"));
+    }
+
+    #[test]
+    fn custom_snippet_computed_choices() {
+        check_edit_with_config(
+            CompletionConfig {
+                snippets: vec![Snippet::new(
+                    &[],
+                    &["arm".into()],
+                    &["match ${receiver} { ${1|@variants|} => $0 }".into()],
+                    "",
+                    &[],
+                    SnippetScope::Expr,
+                )
+                .unwrap()],
+                ..TEST_CONFIG
+            },
+            "arm",
+            r#"
+mod cmp {
+    pub enum Ordering { Less, Equal, Greater }
+}
+fn f(o: cmp::Ordering) { o.$0 }
+"#,
+            r#"
+mod cmp {
+    pub enum Ordering { Less, Equal, Greater }
+}
+fn f(o: cmp::Ordering) { match o { ${1|cmp::Ordering::Less,cmp::Ordering::Equal,cmp::Ordering::Greater|} => $0 } }
+"#,
+        );
+        check_edit_with_config(
+            config("field", "${1|@fields|}", SnippetScope::Expr),
+            "field",
+            r#"
+mod m {
+    pub struct Point { pub x: u8, pub y: u8, hidden: u8 }
+}
+fn take(_: m::Point) {}
+fn main() { take($0) }
+"#,
+            r#"
+mod m {
+    pub struct Point { pub x: u8, pub y: u8, hidden: u8 }
+}
+fn take(_: m::Point) {}
+fn main() { take(${1|x,y|}) }
 "#,
         );
     }
@@ -372,6 +372,10 @@ pub(super) enum CompletionAnalysis {
         colon_prefix: bool,
         fake_attribute_under_caret: Option<ast::Attr>,
     },
+    /// Set if we are currently completing in the token tree of a `cfg!` call, like `cfg!(unix$0)`
+    CfgMacroTT,
+    /// Set if we are currently completing in the token tree of a macro call that failed to expand, like `m!(key $0)`
+    MacroCallTT(ast::MacroCall),
 }
 
 /// Information about the field or method access we are completing.
@@ -260,6 +260,20 @@ fn analyze(
                         .find_map(ast::Attr::cast),
                     colon_prefix,
                 }
+            } else if p.kind() == SyntaxKind::TOKEN_TREE
+                && p.ancestors().find_map(ast::MacroCall::cast).is_some_and(|call| {
+                    call.path()
+                        .and_then(|it| it.as_single_name_ref())
+                        .is_some_and(|it| it.text() == "cfg")
+                })
+            {
+                CompletionAnalysis::CfgMacroTT
+            } else if let Some(call) = p
+                .ancestors()
+                .find_map(ast::MacroCall::cast)
+                .filter(|_| p.kind() == SyntaxKind::TOKEN_TREE)
+            {
+                CompletionAnalysis::MacroCallTT(call)
             } else {
                 return None;
             }
@@ -160,6 +160,14 @@ pub struct CompletionRelevance {
     pub postfix_match: Option<CompletionRelevancePostfixMatch>,
     /// This is set for type inference results
     pub is_definite: bool,
+    /// How often and how recently the user accepted this item before, at most
+    /// [`CompletionRelevance::MAX_HISTORY_SCORE`]. This is never set by the completion engine
+    /// itself, but by clients keeping a history of accepted completions.
+    pub history_score: u8,
+    /// How often the completed item is already used in the crate, at most
+    /// [`CompletionRelevance::MAX_USAGE_SCORE`]. This is set for `impl Trait for Type` skeletons
+    /// by the number of impls the crate has of the trait.
+    pub usage_score: u8,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -202,6 +210,9 @@ pub enum CompletionRelevancePostfixMatch {
 }
 
 impl CompletionRelevance {
+    pub const MAX_HISTORY_SCORE: u8 = 8;
+    pub const MAX_USAGE_SCORE: u8 = 8;
+
     /// Provides a relevance score. Higher values are more relevant.
     ///
     /// The absolute value of the relevance score is not meaningful, for
@@ -224,6 +235,8 @@ impl CompletionRelevance {
             is_private_editable,
             postfix_match,
             is_definite,
+            history_score,
+            usage_score,
         } = self;
 
         // lower rank private things
@@ -265,6 +278,8 @@ impl CompletionRelevance {
         if is_definite {
             score += 10;
         }
+        score += u32::from(history_score.min(Self::MAX_HISTORY_SCORE));
+        score += u32::from(usage_score.min(Self::MAX_USAGE_SCORE));
         score
     }
 
@@ -292,8 +307,8 @@ pub enum CompletionItemKind {
 impl_from!(SymbolKind for CompletionItemKind);
 
 impl CompletionItemKind {
-    #[cfg(test)]
-    pub(crate) fn tag(self) -> &'static str {
+    /// A short, stable name of the kind.
+    pub fn tag(self) -> &'static str {
         match self {
             CompletionItemKind::SymbolKind(kind) => match kind {
                 SymbolKind::Attribute => "at",
@@ -630,8 +645,14 @@ mod tests {
             vec![Cr { is_private_editable: true, ..default }],
             vec![default],
             vec![Cr { is_local: true, ..default }],
+            vec![Cr { history_score: 2, ..default }, Cr { usage_score: 2, ..default }],
             vec![Cr { type_match: Some(CompletionRelevanceTypeMatch::CouldUnify), ..default }],
-            vec![Cr { type_match: Some(CompletionRelevanceTypeMatch::Exact), ..default }],
+            vec![
+                Cr { type_match: Some(CompletionRelevanceTypeMatch::Exact), ..default },
+                Cr { history_score: Cr::MAX_HISTORY_SCORE, ..default },
+                Cr { history_score: u8::MAX, ..default },
+                Cr { usage_score: u8::MAX, ..default },
+            ],
             vec![Cr { exact_name_match: true, ..default }],
             vec![Cr { exact_name_match: true, is_local: true, ..default }],
             vec![Cr {
@@ -203,10 +203,13 @@ pub fn completions(
                 completions::lifetime::complete_label(acc, ctx, lifetime_ctx);
                 completions::lifetime::complete_lifetime(acc, ctx, lifetime_ctx);
             }
-            CompletionAnalysis::String { original, expanded: Some(expanded) } => {
-                completions::extern_abi::complete_extern_abi(acc, ctx, expanded);
-                completions::format_string::format_string(acc, ctx, original, expanded);
-                completions::env_vars::complete_cargo_env_vars(acc, ctx, expanded);
+            CompletionAnalysis::String { original, expanded } => {
+                completions::attribute::complete_cfg(acc, ctx);
+                if let Some(expanded) = expanded {
+                    completions::extern_abi::complete_extern_abi(acc, ctx, expanded);
+                    completions::format_string::format_string(acc, ctx, original, expanded);
+                    completions::env_vars::complete_cargo_env_vars(acc, ctx, expanded);
+                }
             }
             CompletionAnalysis::UnexpandedAttrTT {
                 colon_prefix,
@@ -219,7 +222,11 @@ pub fn completions(
                     attr,
                 );
             }
-            CompletionAnalysis::UnexpandedAttrTT { .. } | CompletionAnalysis::String { .. } => (),
+            CompletionAnalysis::CfgMacroTT => completions::attribute::complete_cfg(acc, ctx),
+            CompletionAnalysis::MacroCallTT(call) => {
+                completions::macro_input::complete_macro_input(acc, ctx, call);
+            }
+            CompletionAnalysis::UnexpandedAttrTT { .. } => (),
         }
     }
 
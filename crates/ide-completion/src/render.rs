@@ -213,6 +213,14 @@ pub(crate) fn render_pattern_resolution(
     render_resolution_pat(ctx, pattern_ctx, local_name, None, resolution)
 }
 
+pub(crate) fn render_resolution_simple(
+    ctx: RenderContext<'_>,
+    local_name: hir::Name,
+    resolution: ScopeDef,
+) -> Builder {
+    render_resolution_simple_(ctx, &local_name, None, resolution)
+}
+
 pub(crate) fn render_resolution_with_import(
     ctx: RenderContext<'_>,
     path_ctx: &PathCompletionCtx,
@@ -754,15 +762,15 @@ fn main() { let _: m::Spam = S$0 }
                         ),
                     },
                     CompletionItem {
-                        label: "m::Spam::Bar(…)",
+                        label: "Spam::Bar(…) (use m::Spam)",
                         source_range: 75..76,
                         delete: 75..76,
-                        insert: "m::Spam::Bar(${1:()})$0",
+                        insert: "Spam::Bar(${1:()})$0",
                         kind: SymbolKind(
                             Variant,
                         ),
                         lookup: "Spam::Bar()",
-                        detail: "m::Spam::Bar(i32)",
+                        detail: "Spam::Bar(i32)",
                         relevance: CompletionRelevance {
                             exact_name_match: false,
                             type_match: Some(
@@ -771,24 +779,26 @@ fn main() { let _: m::Spam = S$0 }
                             is_local: false,
                             is_item_from_trait: false,
                             is_name_already_imported: false,
-                            requires_import: false,
+                            requires_import: true,
                             is_op_method: false,
                             is_private_editable: false,
                             postfix_match: None,
                             is_definite: false,
+                            history_score: 0,
+                            usage_score: 0,
                         },
                         trigger_call_info: true,
                     },
                     CompletionItem {
-                        label: "m::Spam::Foo",
+                        label: "Spam::Foo (use m::Spam)",
                         source_range: 75..76,
                         delete: 75..76,
-                        insert: "m::Spam::Foo$0",
+                        insert: "Spam::Foo$0",
                         kind: SymbolKind(
                             Variant,
                         ),
                         lookup: "Spam::Foo",
-                        detail: "m::Spam::Foo",
+                        detail: "Spam::Foo",
                         relevance: CompletionRelevance {
                             exact_name_match: false,
                             type_match: Some(
@@ -797,11 +807,13 @@ fn main() { let _: m::Spam = S$0 }
                             is_local: false,
                             is_item_from_trait: false,
                             is_name_already_imported: false,
-                            requires_import: false,
+                            requires_import: true,
                             is_op_method: false,
                             is_private_editable: false,
                             postfix_match: None,
                             is_definite: false,
+                            history_score: 0,
+                            usage_score: 0,
                         },
                         trigger_call_info: true,
                     },
@@ -880,6 +892,8 @@ fn foo() { A { the$0 } }
                             is_private_editable: false,
                             postfix_match: None,
                             is_definite: false,
+                            history_score: 0,
+                            usage_score: 0,
                         },
                     },
                 ]
@@ -26,7 +26,19 @@ pub(crate) fn render_fn(
     func: hir::Function,
 ) -> Builder {
     let _p = profile::span("render_fn");
-    render(ctx, local_name, func, FuncKind::Function(path_ctx))
+    render(ctx, local_name, func, FuncKind::Function(path_ctx), None)
+}
+
+/// Renders a function as `qualifier::name`, e.g. an associated function of a type that isn't the
+/// one being completed on.
+pub(crate) fn render_qualified_fn(
+    ctx: RenderContext<'_>,
+    path_ctx: &PathCompletionCtx,
+    func: hir::Function,
+    qualifier: hir::ModPath,
+) -> Builder {
+    let _p = profile::span("render_qualified_fn");
+    render(ctx, None, func, FuncKind::Function(path_ctx), Some(qualifier))
 }
 
 pub(crate) fn render_method(
@@ -37,7 +49,7 @@ pub(crate) fn render_method(
     func: hir::Function,
 ) -> Builder {
     let _p = profile::span("render_method");
-    render(ctx, local_name, func, FuncKind::Method(dot_access, receiver))
+    render(ctx, local_name, func, FuncKind::Method(dot_access, receiver), None)
 }
 
 fn render(
@@ -45,6 +57,7 @@ fn render(
     local_name: Option<hir::Name>,
     func: hir::Function,
     func_kind: FuncKind<'_>,
+    qualifier: Option<hir::ModPath>,
 ) -> Builder {
     let db = completion.db;
 
@@ -60,7 +73,18 @@ fn render(
             .into(),
             format!("{}.{}", receiver.display(ctx.db()), name.display(ctx.db())).into(),
         ),
-        _ => (name.unescaped().to_smol_str(), name.to_smol_str()),
+        _ => match &qualifier {
+            Some(qualifier) => (
+                format!(
+                    "{}::{}",
+                    qualifier.unescaped().display(ctx.db()),
+                    name.unescaped().display(ctx.db())
+                )
+                .into(),
+                format!("{}::{}", qualifier.display(ctx.db()), name.display(ctx.db())).into(),
+            ),
+            None => (name.unescaped().to_smol_str(), name.to_smol_str()),
+        },
     };
     let mut item = CompletionItem::new(
         if func.self_param(db).is_some() {
@@ -106,7 +130,10 @@ fn render(
     item.set_documentation(ctx.docs(func))
         .set_deprecated(ctx.is_deprecated(func) || ctx.is_deprecated_assoc_item(func))
         .detail(detail)
-        .lookup_by(name.unescaped().to_smol_str());
+        .lookup_by(match qualifier {
+            Some(_) => call.clone(),
+            None => name.unescaped().to_smol_str(),
+        });
 
     match ctx.completion.config.snippet_cap {
         Some(cap) => {
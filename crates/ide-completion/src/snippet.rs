@@ -43,9 +43,12 @@
 // the items aren't yet in scope.
 //
 // * `scope` is an optional filter for when the snippet should be applicable. Possible values are:
-// ** for Snippet-Scopes: `expr`, `item` (default: `item`)
+// ** for Snippet-Scopes: `expr`, `item`, `impl_body`, `test_module`, `pattern` (default: `expr`)
 // ** for Postfix-Snippet-Scopes: `expr`, `type` (default: `expr`)
 //
+// `impl_body` snippets are offered for associated items in impl blocks, `test_module` snippets for items in `#[cfg(test)]`
+// modules and `pattern` snippets wherever a pattern is expected. `item` snippets are offered in all item lists.
+//
 // The `body` field also has access to placeholders as visible in the example as `$0`.
 // These placeholders take the form of `$number` or `${number:placeholder_text}` which can be traversed as tabstop in ascending order starting from 1,
 // with `$0` being a special case that always comes last.
@@ -53,6 +56,37 @@
 // There is also a special placeholder, `${receiver}`, which will be replaced by the receiver expression for postfix snippets, or a `$0` tabstop in case of normal snippets.
 // This replacement for normal snippets allows you to reuse a snippet for both post- and prefix in a single definition.
 //
+// The following variables are replaced by what surrounds the completion, the snippet is not offered where they have no value:
+//
+// * `${fn_name}`: the name of the enclosing function.
+// * `${self_ty}`: the self type of the enclosing impl block.
+// * `${crate_name}`: the name of the current crate.
+// * `${expected_type}`: the type expected at the completion position.
+//
+// Choices can be computed as well, by using one of these sources in place of the choice list of a `${number|...|}` tabstop.
+// They apply to the type of the receiver for postfix snippets, and to the expected type for normal snippets:
+//
+// * `${1|@variants|}`: the variants of an enum, e.g. `Ordering::Less`.
+// * `${1|@fields|}`: the names of the fields of a struct.
+//
+// [source,json]
+// ----
+// {
+//   "rust-analyzer.completion.snippets.custom": {
+//     "match arm": {
+//       "postfix": "arm",
+//       "body": "match ${receiver} {\n\t${1|@variants|} => $0,\n\t_ => {}\n}",
+//       "scope": "expr"
+//     },
+//     "constructor": {
+//       "prefix": "new",
+//       "body": "pub fn new() -> ${self_ty} {\n\t$0\n}",
+//       "scope": "impl_body"
+//     }
+//   }
+// }
+// ----
+//
 // For the VSCode editor, rust-analyzer also ships with a small set of defaults which can be removed
 // by overwriting the settings object mentioned above, the defaults are:
 // [source,json]
@@ -100,9 +134,15 @@
 // }
 // ----
 
+use std::iter;
+
+use hir::{HasVisibility, HirDisplay, ModuleDef};
 use ide_db::imports::import_assets::LocatedImport;
 use itertools::Itertools;
-use syntax::{ast, AstNode, GreenNode, SyntaxNode};
+use syntax::{
+    ast::{self, HasAttrs, HasName},
+    AstNode, GreenNode, SyntaxNode,
+};
 
 use crate::context::CompletionContext;
 
@@ -113,6 +153,11 @@ pub enum SnippetScope {
     Item,
     Expr,
     Type,
+    /// The associated items of an impl block.
+    ImplBody,
+    /// The items of a `#[cfg(test)]` module.
+    TestModule,
+    Pattern,
 }
 
 /// A user supplied snippet.
@@ -165,6 +210,104 @@ impl Snippet {
     pub fn postfix_snippet(&self, receiver: &str) -> String {
         self.snippet.replace("${receiver}", receiver)
     }
+
+    /// Returns the snippet with its variables and computed choices filled in for the completion
+    /// position, or [`None`] if one of them has no value there. `receiver` is the text and type of
+    /// the receiver of postfix snippets.
+    pub(crate) fn expand(
+        &self,
+        ctx: &CompletionContext<'_>,
+        receiver: Option<(&str, &hir::Type)>,
+    ) -> Option<String> {
+        let mut snippet = match receiver {
+            Some((text, _)) => self.postfix_snippet(text),
+            None => self.snippet(),
+        };
+        for variable in ["fn_name", "self_ty", "crate_name", "expected_type"] {
+            let placeholder = format!("${{{variable}}}");
+            if snippet.contains(&placeholder) {
+                snippet = snippet.replace(&placeholder, &variable_value(ctx, variable)?);
+            }
+        }
+        for source in ["variants", "fields"] {
+            let placeholder = format!("|@{source}|");
+            if snippet.contains(&placeholder) {
+                let ty = match receiver {
+                    Some((_, ty)) => ty,
+                    None => ctx.expected_type.as_ref()?,
+                };
+                let choices = computed_choices(ctx, source, &ty.strip_references())?;
+                snippet = snippet.replace(&placeholder, &format!("|{}|", choices.join(",")));
+            }
+        }
+        Some(snippet)
+    }
+}
+
+fn variable_value(ctx: &CompletionContext<'_>, variable: &str) -> Option<String> {
+    let db = ctx.db;
+    match variable {
+        "fn_name" => {
+            let func = ctx.token.parent_ancestors().find_map(ast::Fn::cast)?;
+            Some(func.name()?.text().to_string())
+        }
+        "self_ty" => {
+            let impl_ = ctx.token.parent_ancestors().find_map(ast::Impl::cast)?;
+            Some(impl_.self_ty()?.syntax().text().to_string())
+        }
+        "crate_name" => Some(ctx.krate.display_name(db)?.crate_name().to_string()),
+        "expected_type" => {
+            let ty = ctx.expected_type.as_ref()?;
+            ty.display_source_code(db, ctx.module.into(), true).ok()
+        }
+        _ => None,
+    }
+}
+
+fn computed_choices(
+    ctx: &CompletionContext<'_>,
+    source: &str,
+    ty: &hir::Type,
+) -> Option<Vec<String>> {
+    let db = ctx.db;
+    let choices: Vec<_> = match (source, ty.as_adt()?) {
+        ("variants", hir::Adt::Enum(enum_)) => enum_
+            .variants(db)
+            .into_iter()
+            .filter_map(|variant| {
+                let path = ctx.module.find_use_path(
+                    db,
+                    ModuleDef::from(variant),
+                    ctx.config.prefer_no_std,
+                )?;
+                let path = path.display(db).to_string();
+                Some(path)
+            })
+            .collect(),
+        ("fields", hir::Adt::Struct(strukt)) => strukt
+            .fields(db)
+            .into_iter()
+            .filter(|field| field.is_visible_from(db, ctx.module))
+            .map(|field| field.name(db).display(db).to_string())
+            .collect(),
+        _ => return None,
+    };
+    (!choices.is_empty()).then_some(choices)
+}
+
+/// Whether the completion is in a `#[cfg(test)]` module. Inline modules are checked in the
+/// syntax tree too, as they are missing from the module tree when `test` isn't enabled.
+pub(crate) fn in_test_module(ctx: &CompletionContext<'_>) -> bool {
+    let is_cfg_test = |module: ast::Module| {
+        module
+            .attrs()
+            .filter_map(|attr| attr.as_simple_call())
+            .any(|(name, tt)| name == "cfg" && tt.syntax().text() == "(test)")
+    };
+    ctx.token.parent_ancestors().filter_map(ast::Module::cast).any(is_cfg_test)
+        || iter::successors(Some(ctx.module), |it| it.parent(ctx.db))
+            .filter_map(|module| module.declaration_source(ctx.db))
+            .any(|source| is_cfg_test(source.value))
 }
 
 fn import_edits(ctx: &CompletionContext<'_>, requires: &[GreenNode]) -> Option<Vec<LocatedImport>> {
@@ -639,8 +639,23 @@ mod cfg {
 "#,
             expect![[r#"
                 ba dbg
+                ba debug_assertions
+                ba doc
+                ba feature
+                ba miri
                 ba opt_level
+                ba panic
+                ba target_arch
+                ba target_endian
+                ba target_env
+                ba target_family
+                ba target_has_atomic
+                ba target_os
+                ba target_pointer_width
+                ba target_vendor
                 ba test
+                ba unix
+                ba windows
             "#]],
         );
         check(
@@ -650,8 +665,23 @@ mod cfg {
 "#,
             expect![[r#"
                 ba dbg
+                ba debug_assertions
+                ba doc
+                ba feature
+                ba miri
                 ba opt_level
+                ba panic
+                ba target_arch
+                ba target_endian
+                ba target_env
+                ba target_family
+                ba target_has_atomic
+                ba target_os
+                ba target_pointer_width
+                ba target_vendor
                 ba test
+                ba unix
+                ba windows
             "#]],
         );
     }
@@ -673,6 +703,122 @@ mod cfg {
             "#]],
         );
     }
+
+    #[test]
+    fn cfg_well_known_values() {
+        check(
+            r#"#[cfg(panic = $0"#,
+            expect![[r#"
+                ba abort
+                ba unwind
+            "#]],
+        );
+        check_edit(
+            "unix",
+            r#"#[cfg(target_family = $0)] fn f() {}"#,
+            r#"#[cfg(target_family = "unix")] fn f() {}"#,
+        );
+    }
+
+    #[test]
+    fn cfg_feature_in_string() {
+        check(
+            r#"
+//- /main.rs cfg:feature=std,feature=alloc
+#[cfg(feature = "$0")]
+fn f() {}
+"#,
+            expect![[r#"
+                ba alloc
+                ba std
+            "#]],
+        );
+        check_edit(
+            "alloc",
+            r#"
+//- /main.rs cfg:feature=std,feature=alloc
+#[cfg(all(unix, feature = "al$0"))]
+fn f() {}
+"#,
+            r#"
+#[cfg(all(unix, feature = "alloc"))]
+fn f() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn cfg_attr_predicate() {
+        check_edit(
+            "std",
+            r#"
+//- /main.rs cfg:feature=std
+#[cfg_attr(feature = $0, derive(Debug))]
+struct S;
+"#,
+            r#"
+#[cfg_attr(feature = "std", derive(Debug))]
+struct S;
+"#,
+        );
+        check(
+            r#"
+//- /main.rs cfg:feature=std
+#[cfg_attr(feature = "std", doc = "$0")]
+struct S;
+"#,
+            expect![[r#""#]],
+        );
+    }
+
+    #[test]
+    fn cfg_macro() {
+        check(
+            r#"
+//- /main.rs cfg:test
+#[rustc_builtin_macro]
+macro_rules! cfg {}
+fn f() {
+    cfg!($0);
+}
+"#,
+            expect![[r#"
+                ba debug_assertions
+                ba doc
+                ba feature
+                ba miri
+                ba panic
+                ba target_arch
+                ba target_endian
+                ba target_env
+                ba target_family
+                ba target_has_atomic
+                ba target_os
+                ba target_pointer_width
+                ba target_vendor
+                ba test
+                ba unix
+                ba windows
+            "#]],
+        );
+        check_edit(
+            "linux",
+            r#"
+#[rustc_builtin_macro]
+macro_rules! cfg {}
+fn f() {
+    cfg!(target_os = "$0");
+}
+"#,
+            r#"
+#[rustc_builtin_macro]
+macro_rules! cfg {}
+fn f() {
+    cfg!(target_os = "linux");
+}
+"#,
+        );
+    }
 }
 
 mod derive {
@@ -521,7 +521,12 @@ fn quux(x: i32) {
     m!(x$0
 }
 "#,
-        expect![[r#""#]],
+        expect![[r#"
+            fn quux
+            lc x
+            ma m
+            bt u32
+        "#]],
     );
 }
 
@@ -1094,3 +1099,140 @@ pub struct UnstableButWeAreOnNightlyAnyway;
         "#]],
     );
 }
+
+#[test]
+fn completes_constructors_of_expected_type() {
+    check_empty(
+        r#"
+mod config {
+    pub struct Config { pub verbose: bool }
+    impl Config {
+        pub fn new() -> Self { Config { verbose: false } }
+        pub fn from_name(name: &str) -> Config { Config::new() }
+        pub fn is_verbose(&self) -> bool { self.verbose }
+        pub fn parse(s: &str) -> Option<Config> { None }
+        fn private() -> Config { Config::new() }
+    }
+}
+fn run(config: config::Config) {}
+fn main() {
+    run($0);
+}
+"#,
+        expect![[r#"
+            fn Config::from_name(…) (use config::Config) fn(&str) -> Config
+            fn Config::new() (use config::Config) fn() -> Config
+            fn main()                 fn()
+            fn run(…)                 fn(Config)
+            md config
+            st Config {…} (use config::Config) Config { verbose: bool }
+            bt u32
+            kw crate::
+            kw false
+            kw for
+            kw if
+            kw if let
+            kw loop
+            kw match
+            kw return
+            kw self::
+            kw true
+            kw unsafe
+            kw while
+            kw while let
+        "#]],
+    );
+    check_edit(
+        "Config::new",
+        r#"
+mod config {
+    pub struct Config;
+    impl Config {
+        pub fn new() -> Self { Config }
+    }
+}
+fn run(config: config::Config) {}
+fn main() {
+    run($0);
+}
+"#,
+        r#"
+use config::Config;
+
+mod config {
+    pub struct Config;
+    impl Config {
+        pub fn new() -> Self { Config }
+    }
+}
+fn run(config: config::Config) {}
+fn main() {
+    run(Config::new()$0);
+}
+"#,
+    );
+}
+
+#[test]
+fn completes_generic_constructors_of_expected_type() {
+    check_empty(
+        r#"
+mod wrap {
+    pub struct Wrapper<T>(T);
+    impl<T> Wrapper<T> {
+        pub fn new(value: T) -> Self { Wrapper(value) }
+        pub fn empty() -> Wrapper<()> { Wrapper(()) }
+    }
+}
+fn run(wrapper: wrap::Wrapper<u32>) {}
+fn main() {
+    run($0);
+}
+"#,
+        expect![[r#"
+            fn Wrapper::new(…) (use wrap::Wrapper) fn(T) -> Wrapper<T>
+            fn main()                 fn()
+            fn run(…)                 fn(Wrapper<u32>)
+            md wrap
+            bt u32
+            kw crate::
+            kw false
+            kw for
+            kw if
+            kw if let
+            kw loop
+            kw match
+            kw return
+            kw self::
+            kw true
+            kw unsafe
+            kw while
+            kw while let
+        "#]],
+    );
+}
+
+#[test]
+fn completes_variants_of_expected_type() {
+    check_edit(
+        "Ordering::Less",
+        r#"
+mod cmp {
+    pub enum Ordering { Less, Equal, Greater }
+}
+fn compare() -> cmp::Ordering {
+    $0
+}
+"#,
+        r#"
+use cmp::Ordering;
+
+mod cmp {
+    pub enum Ordering { Less, Equal, Greater }
+}
+fn compare() -> cmp::Ordering {
+    Ordering::Less$0
+}
+"#,
+    );
+}
@@ -182,12 +182,13 @@ fn main() {
 "#,
         expect![[r#"
             fd ..Default::default()
-            fn main()               fn()
-            lc foo                  Foo
-            lc thing                i32
+            fn Foo::default() (as Default) fn() -> Self
+            fn main()                 fn()
+            lc foo                    Foo
+            lc thing                  i32
             md core
             st Foo
-            st Foo {…}              Foo { foo1: u32, foo2: u32 }
+            st Foo {…}                Foo { foo1: u32, foo2: u32 }
             tt Default
             bt u32
             kw crate::
@@ -148,6 +148,7 @@ impl RootDatabase {
             hir::db::InferQueryQuery
             hir::db::MirBodyQuery
             hir::db::BorrowckQuery
+            hir::db::ScopeEndDropsQuery
             hir::db::TyQuery
             hir::db::ValueTyQuery
             hir::db::ImplSelfTyQuery
@@ -190,6 +191,8 @@ impl RootDatabase {
             // SymbolsDatabase
             crate::symbol_index::ModuleSymbolsQuery
             crate::symbol_index::LibrarySymbolsQuery
+            crate::symbol_index::ModuleSymbolsExtendedQuery
+            crate::symbol_index::LibrarySymbolsExtendedQuery
             crate::symbol_index::LocalRootsQuery
             crate::symbol_index::LibraryRootsQuery
 
@@ -0,0 +1,187 @@
+//! Resolves the trait methods the compiler calls on behalf of the user: the operator traits for
+//! overloaded operators, `From::from` for the error conversion of `?`,
+//! `IntoIterator::into_iter` for `for` loops, `Deref::deref` for autoderef and `Drop::drop` for
+//! values going out of scope.
+
+use hir::{Adjust, AsAssocItem, AssocItemContainer, Function, Mutability, Semantics, Type};
+use syntax::{
+    ast::{self, AstNode},
+    match_ast,
+};
+
+use crate::{famous_defs::FamousDefs, RootDatabase};
+
+/// Returns the impl method implicitly called by `expr`, if it is implemented by a user visible
+/// trait impl.
+pub fn implicit_trait_call(
+    sema: &Semantics<'_, RootDatabase>,
+    expr: &ast::Expr,
+) -> Option<Function> {
+    let db = sema.db;
+    let func = match expr {
+        ast::Expr::BinExpr(bin_expr) => {
+            let operands = [bin_expr.lhs()?, bin_expr.rhs()?];
+            if operands.iter().all(|it| is_builtin_operand(sema, it)) {
+                return None;
+            }
+            sema.resolve_bin_expr(bin_expr)?
+        }
+        ast::Expr::PrefixExpr(prefix_expr) => {
+            let ty = sema.type_of_expr(&prefix_expr.expr()?)?.original;
+            if ty.is_reference() || ty.is_raw_ptr() || ty.is_scalar() {
+                return None;
+            }
+            sema.resolve_prefix_expr(prefix_expr)?
+        }
+        ast::Expr::IndexExpr(index_expr) => {
+            let ty = sema.type_of_expr(&index_expr.base()?)?.adjusted().strip_references();
+            if ty.is_array() || ty.is_slice() {
+                return None;
+            }
+            sema.resolve_index_expr(index_expr)?
+        }
+        ast::Expr::TryExpr(try_expr) => try_expr_conversion(sema, try_expr)?,
+        ast::Expr::ForExpr(for_expr) => for_loop_into_iter(sema, for_expr)?,
+        _ => return None,
+    };
+    match func.as_assoc_item(db)?.container(db) {
+        AssocItemContainer::Impl(imp) if imp.trait_(db).is_some() => Some(func),
+        _ => None,
+    }
+}
+
+/// Returns the `Deref::deref` and `DerefMut::deref_mut` impl methods the autoderef of `expr` calls,
+/// in the order they are called.
+pub fn implicit_deref_calls(sema: &Semantics<'_, RootDatabase>, expr: &ast::Expr) -> Vec<Function> {
+    let db = sema.db;
+    let Some(adjustments) = sema.expr_adjustments(expr) else { return Vec::new() };
+    let Some(scope) = sema.scope(expr.syntax()) else { return Vec::new() };
+    let famous_defs = FamousDefs(sema, scope.krate());
+    adjustments
+        .into_iter()
+        .filter_map(|adjustment| {
+            let Adjust::Deref(Some(hir::OverloadedDeref(mutability))) = adjustment.kind else {
+                return None;
+            };
+            let trait_ = match mutability {
+                Mutability::Shared => famous_defs.core_ops_Deref()?,
+                Mutability::Mut => famous_defs.core_ops_DerefMut()?,
+            };
+            trait_fn(db, trait_)?.impl_method_for(db, &[adjustment.source])
+        })
+        .collect()
+}
+
+/// Returns the `Drop::drop` impl methods that run when a value of type `ty` is dropped, in the
+/// order they run, together with the field path of the value they are called on (empty for the
+/// value itself). Besides the `Drop` impl of `ty`, this includes the impls of the fields of
+/// structs and tuples, which make up the drop glue of `ty`.
+pub fn drop_calls(
+    sema: &Semantics<'_, RootDatabase>,
+    krate: hir::Crate,
+    ty: &Type,
+) -> Vec<(String, Function)> {
+    let mut acc = Vec::new();
+    if let Some(drop_fn) =
+        FamousDefs(sema, krate).core_ops_Drop().and_then(|it| trait_fn(sema.db, it))
+    {
+        drop_calls_rec(sema.db, drop_fn, ty, String::new(), &mut acc);
+    }
+    acc
+}
+
+fn drop_calls_rec(
+    db: &RootDatabase,
+    drop_fn: Function,
+    ty: &Type,
+    path: String,
+    acc: &mut Vec<(String, Function)>,
+) {
+    if let Some(func) = drop_fn.impl_method_for(db, std::slice::from_ref(ty)) {
+        acc.push((path.clone(), func));
+    }
+    if ty.is_tuple() {
+        for (idx, field_ty) in ty.tuple_fields(db).iter().enumerate() {
+            drop_calls_rec(db, drop_fn, field_ty, format!("{path}.{idx}"), acc);
+        }
+    } else if let Some(hir::Adt::Struct(_)) = ty.as_adt() {
+        // Union fields are never dropped implicitly and the variant of an enum is only known at
+        // runtime.
+        for (field, field_ty) in ty.fields(db) {
+            let path = format!("{path}.{}", field.name(db).display(db));
+            drop_calls_rec(db, drop_fn, &field_ty, path, acc);
+        }
+    }
+}
+
+/// Resolves the `From::from` impl `?` uses to convert the error of `try_expr` into the error type
+/// of the function or closure it returns from.
+fn try_expr_conversion(
+    sema: &Semantics<'_, RootDatabase>,
+    try_expr: &ast::TryExpr,
+) -> Option<Function> {
+    let db = sema.db;
+    let famous_defs = FamousDefs(sema, sema.scope(try_expr.syntax())?.krate());
+    let result_enum = famous_defs.core_result_Result()?;
+    let error_ty = |ty: Type| match ty.as_adt()? {
+        hir::Adt::Enum(it) if it == result_enum => ty.type_arguments().nth(1),
+        _ => None,
+    };
+
+    let from_ty = error_ty(sema.type_of_expr(&try_expr.expr()?)?.original)?;
+    let body_ty = try_expr.syntax().ancestors().find_map(|node| {
+        match_ast! {
+            match node {
+                ast::Fn(fn_) => {
+                    let func = sema.to_def(&fn_)?;
+                    Some(if func.is_async(db) { func.async_ret_type(db) } else { Some(func.ret_type(db)) })
+                },
+                ast::ClosureExpr(closure) => {
+                    let ty = sema.type_of_expr(&closure.into())?.original;
+                    Some(ty.as_callable(db).map(|it| it.return_type()))
+                },
+                // `?` in these blocks doesn't return from the enclosing function.
+                ast::BlockExpr(block_expr) => block_expr.modifier().map(|_| None),
+                ast::Item(_) => Some(None),
+                _ => None,
+            }
+        }
+    })??;
+    let into_ty = error_ty(body_ty)?;
+    if from_ty.is_unknown() || into_ty.is_unknown() || from_ty.could_unify_with(db, &into_ty) {
+        return None;
+    }
+
+    let from = famous_defs.core_convert_From()?;
+    let from_fn = trait_fn(db, from)?;
+    from_fn.impl_method_for(db, &[into_ty, from_ty])
+}
+
+/// Resolves the `IntoIterator::into_iter` impl a `for` loop calls on its iterable, unless the
+/// iterable already is an iterator.
+fn for_loop_into_iter(
+    sema: &Semantics<'_, RootDatabase>,
+    for_expr: &ast::ForExpr,
+) -> Option<Function> {
+    let db = sema.db;
+    let famous_defs = FamousDefs(sema, sema.scope(for_expr.syntax())?.krate());
+    let ty = sema.type_of_expr(&for_expr.iterable()?)?.original;
+    if ty.is_unknown() || ty.impls_trait(db, famous_defs.core_iter_Iterator()?, &[]) {
+        return None;
+    }
+    let into_iter = famous_defs.core_iter_IntoIterator()?;
+    let into_iter_fn = trait_fn(db, into_iter)?;
+    into_iter_fn.impl_method_for(db, &[ty])
+}
+
+/// The method of a trait with a single method, like `From`, `Deref` or `IntoIterator`.
+fn trait_fn(db: &RootDatabase, trait_: hir::Trait) -> Option<Function> {
+    trait_.items(db).into_iter().find_map(|it| match it {
+        hir::AssocItem::Function(it) => Some(it),
+        _ => None,
+    })
+}
+
+fn is_builtin_operand(sema: &Semantics<'_, RootDatabase>, expr: &ast::Expr) -> bool {
+    sema.type_of_expr(expr).map_or(true, |it| it.original.strip_references().is_scalar())
+}
@@ -11,6 +11,7 @@ pub mod assists;
 pub mod defs;
 pub mod famous_defs;
 pub mod helpers;
+pub mod implicit_trait_calls;
 pub mod items_locator;
 pub mod label;
 pub mod path_transform;
@@ -39,6 +40,7 @@ pub mod syntax_helpers {
     pub mod insert_whitespace_into_node;
     pub mod format_string;
     pub mod format_string_exprs;
+    pub mod turbofish;
 
     pub use parser::LexedStr;
 }
@@ -296,6 +298,8 @@ impl RootDatabase {
             // SymbolsDatabase
             symbol_index::ModuleSymbolsQuery
             symbol_index::LibrarySymbolsQuery
+            symbol_index::ModuleSymbolsExtendedQuery
+            symbol_index::LibrarySymbolsExtendedQuery
             // symbol_index::LocalRootsQuery
             // symbol_index::LibraryRootsQuery
 
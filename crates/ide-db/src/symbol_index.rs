@@ -22,6 +22,7 @@
 
 use std::{
     cmp::Ordering,
+    collections::BTreeMap,
     fmt,
     hash::{Hash, Hasher},
     mem,
@@ -31,10 +32,10 @@ use base_db::{
     salsa::{self, ParallelDatabase},
     SourceDatabaseExt, SourceRootId, Upcast,
 };
-use fst::{self, Streamer};
+use fst::{self, Automaton, IntoStreamer, Streamer};
 use hir::{
     db::HirDatabase,
-    symbols::{FileSymbol, SymbolCollector},
+    symbols::{ExtendedFileSymbol, FileSymbol, SymbolCollector},
     Crate, Module,
 };
 use rayon::prelude::*;
@@ -58,6 +59,7 @@ pub struct Query {
     libs: bool,
     mode: SearchMode,
     case_sensitive: bool,
+    extended: bool,
     limit: usize,
 }
 
@@ -71,6 +73,7 @@ impl Query {
             libs: false,
             mode: SearchMode::Fuzzy,
             case_sensitive: false,
+            extended: false,
             limit: usize::max_value(),
         }
     }
@@ -99,6 +102,18 @@ impl Query {
         self.case_sensitive = true;
     }
 
+    /// Searches the extended indices, which also contain fields, enum variants and locals of
+    /// bodies, and matches the query against doc comments too. Results are ranked, preferring
+    /// exact and camel hump matches as well as symbols of the workspace.
+    pub fn extended(&mut self) {
+        self.extended = true;
+    }
+
+    /// Whether the query is to be run with [`world_symbols_extended`].
+    pub fn is_extended(&self) -> bool {
+        self.extended
+    }
+
     pub fn limit(&mut self, limit: usize) {
         self.limit = limit
     }
@@ -117,6 +132,19 @@ pub trait SymbolsDatabase: HirDatabase + SourceDatabaseExt + Upcast<dyn HirDatab
     /// The symbol indices of modules that make up a given crate.
     fn crate_symbols(&self, krate: Crate) -> Box<[Arc<SymbolIndex>]>;
 
+    /// The extended symbol index for a given module, see [`Query::extended`]. These modules
+    /// should only be in source roots that are inside local_roots, the locals of their bodies are
+    /// indexed as well.
+    fn module_symbols_extended(&self, module: Module) -> Arc<ExtendedSymbolIndex>;
+
+    /// The extended symbol index for a given source root within library_roots, without the
+    /// locals of its bodies.
+    fn library_symbols_extended(&self, source_root_id: SourceRootId) -> Arc<ExtendedSymbolIndex>;
+
+    #[salsa::transparent]
+    /// The extended symbol indices of modules that make up a given crate.
+    fn crate_symbols_extended(&self, krate: Crate) -> Box<[Arc<ExtendedSymbolIndex>]>;
+
     /// The set of "local" (that is, from the current workspace) roots.
     /// Files in local roots are assumed to change frequently.
     #[salsa::input]
@@ -132,7 +160,32 @@ fn library_symbols(db: &dyn SymbolsDatabase, source_root_id: SourceRootId) -> Ar
     let _p = profile::span("library_symbols");
 
     let mut symbol_collector = SymbolCollector::new(db.upcast());
+    collect_library_symbols(db, source_root_id, &mut symbol_collector);
+
+    let mut symbols = symbol_collector.finish();
+    symbols.shrink_to_fit();
+    Arc::new(SymbolIndex::new(symbols))
+}
+
+fn library_symbols_extended(
+    db: &dyn SymbolsDatabase,
+    source_root_id: SourceRootId,
+) -> Arc<ExtendedSymbolIndex> {
+    let _p = profile::span("library_symbols_extended");
+
+    let mut symbol_collector = SymbolCollector::new_extended(db.upcast(), false);
+    collect_library_symbols(db, source_root_id, &mut symbol_collector);
+
+    let mut symbols = symbol_collector.finish_extended();
+    symbols.shrink_to_fit();
+    Arc::new(ExtendedSymbolIndex::new(symbols, true))
+}
 
+fn collect_library_symbols(
+    db: &dyn SymbolsDatabase,
+    source_root_id: SourceRootId,
+    symbol_collector: &mut SymbolCollector<'_>,
+) {
     db.source_root_crates(source_root_id)
         .iter()
         .flat_map(|&krate| Crate::from(krate).modules(db.upcast()))
@@ -140,10 +193,6 @@ fn library_symbols(db: &dyn SymbolsDatabase, source_root_id: SourceRootId) -> Ar
         // as the index for a library is not going to really ever change, and we do not want to store each
         // the module or crate indices for those in salsa unless we need to.
         .for_each(|module| symbol_collector.collect(module));
-
-    let mut symbols = symbol_collector.finish();
-    symbols.shrink_to_fit();
-    Arc::new(SymbolIndex::new(symbols))
 }
 
 fn module_symbols(db: &dyn SymbolsDatabase, module: Module) -> Arc<SymbolIndex> {
@@ -158,6 +207,25 @@ pub fn crate_symbols(db: &dyn SymbolsDatabase, krate: Crate) -> Box<[Arc<SymbolI
     krate.modules(db.upcast()).into_iter().map(|module| db.module_symbols(module)).collect()
 }
 
+fn module_symbols_extended(db: &dyn SymbolsDatabase, module: Module) -> Arc<ExtendedSymbolIndex> {
+    let _p = profile::span("module_symbols_extended");
+
+    let symbols = SymbolCollector::collect_module_extended(db.upcast(), module, true);
+    Arc::new(ExtendedSymbolIndex::new(symbols, false))
+}
+
+pub fn crate_symbols_extended(
+    db: &dyn SymbolsDatabase,
+    krate: Crate,
+) -> Box<[Arc<ExtendedSymbolIndex>]> {
+    let _p = profile::span("crate_symbols_extended");
+    krate
+        .modules(db.upcast())
+        .into_iter()
+        .map(|module| db.module_symbols_extended(module))
+        .collect()
+}
+
 /// Need to wrap Snapshot to provide `Clone` impl for `map_with`
 struct Snap<DB>(DB);
 impl<DB: ParallelDatabase> Snap<salsa::Snapshot<DB>> {
@@ -194,6 +262,11 @@ impl<DB> std::ops::Deref for Snap<DB> {
 // That is, `#` switches from "types" to all symbols, `*` switches from the current
 // workspace to dependencies.
 //
+// With the `rust-analyzer.workspace.symbol.search.extended` setting, the search also covers
+// struct fields, enum variants, locals of the workspace's function bodies and the words of doc
+// comments, and `*` searches the workspace together with its dependencies. Results are then ranked, putting
+// exact and camel hump matches (`hm` for `HashMap`) as well as workspace symbols first.
+//
 // Note that filtering does not currently work in VSCode due to the editor never
 // sending the special symbols to the language server. Instead, you can configure
 // the filtering via the `rust-analyzer.workspace.symbol.search.scope` and
@@ -228,37 +301,79 @@ pub fn world_symbols(db: &RootDatabase, query: Query) -> Vec<FileSymbol> {
     query.search(&indices)
 }
 
+/// Like [`world_symbols`], but searches the extended indices, see [`Query::extended`]. Searches of
+/// dependencies look through the workspace as well, ranking it first.
+pub fn world_symbols_extended(db: &RootDatabase, query: Query) -> Vec<ExtendedFileSymbol> {
+    let _p = profile::span("world_symbols_extended").detail(|| query.query.clone());
+
+    let mut crates = Vec::new();
+    for &root in db.local_roots().iter() {
+        crates.extend(db.source_root_crates(root).iter().copied())
+    }
+    let indices: Vec<_> = crates
+        .into_par_iter()
+        .map_with(Snap::new(db), |snap, krate| snap.crate_symbols_extended(krate.into()))
+        .collect();
+    let mut indices: Vec<_> = indices.iter().flat_map(|indices| indices.iter().cloned()).collect();
+    if query.libs {
+        indices.par_extend(
+            db.library_roots()
+                .par_iter()
+                .map_with(Snap::new(db), |snap, &root| snap.library_symbols_extended(root)),
+        );
+    }
+
+    query.search_extended(&indices)
+}
+
+/// A symbol a [`SymbolIndex`] can be built from.
+pub trait IndexedSymbol {
+    fn file_symbol(&self) -> &FileSymbol;
+}
+
+impl IndexedSymbol for FileSymbol {
+    fn file_symbol(&self) -> &FileSymbol {
+        self
+    }
+}
+
+impl IndexedSymbol for ExtendedFileSymbol {
+    fn file_symbol(&self) -> &FileSymbol {
+        &self.symbol
+    }
+}
+
 #[derive(Default)]
-pub struct SymbolIndex {
-    symbols: Vec<FileSymbol>,
+pub struct SymbolIndex<S = FileSymbol> {
+    symbols: Vec<S>,
     map: fst::Map<Vec<u8>>,
 }
 
-impl fmt::Debug for SymbolIndex {
+impl<S> fmt::Debug for SymbolIndex<S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("SymbolIndex").field("n_symbols", &self.symbols.len()).finish()
     }
 }
 
-impl PartialEq for SymbolIndex {
-    fn eq(&self, other: &SymbolIndex) -> bool {
+impl<S: PartialEq> PartialEq for SymbolIndex<S> {
+    fn eq(&self, other: &SymbolIndex<S>) -> bool {
         self.symbols == other.symbols
     }
 }
 
-impl Eq for SymbolIndex {}
+impl<S: Eq> Eq for SymbolIndex<S> {}
 
-impl Hash for SymbolIndex {
+impl<S: Hash> Hash for SymbolIndex<S> {
     fn hash<H: Hasher>(&self, hasher: &mut H) {
         self.symbols.hash(hasher)
     }
 }
 
-impl SymbolIndex {
-    fn new(mut symbols: Vec<FileSymbol>) -> SymbolIndex {
-        fn cmp(lhs: &FileSymbol, rhs: &FileSymbol) -> Ordering {
-            let lhs_chars = lhs.name.chars().map(|c| c.to_ascii_lowercase());
-            let rhs_chars = rhs.name.chars().map(|c| c.to_ascii_lowercase());
+impl<S: IndexedSymbol + Send> SymbolIndex<S> {
+    fn new(mut symbols: Vec<S>) -> SymbolIndex<S> {
+        fn cmp<S: IndexedSymbol>(lhs: &S, rhs: &S) -> Ordering {
+            let lhs_chars = lhs.file_symbol().name.chars().map(|c| c.to_ascii_lowercase());
+            let rhs_chars = rhs.file_symbol().name.chars().map(|c| c.to_ascii_lowercase());
             lhs_chars.cmp(rhs_chars)
         }
 
@@ -279,8 +394,8 @@ impl SymbolIndex {
             let end = idx + 1;
             last_batch_start = end;
 
-            let key = symbols[start].name.as_str().to_ascii_lowercase();
-            let value = SymbolIndex::range_to_map_value(start, end);
+            let key = symbols[start].file_symbol().name.as_str().to_ascii_lowercase();
+            let value = range_to_map_value(start, end);
 
             builder.insert(key, value).unwrap();
         }
@@ -299,21 +414,117 @@ impl SymbolIndex {
     }
 
     pub fn memory_size(&self) -> usize {
-        self.map.as_fst().size() + self.symbols.len() * mem::size_of::<FileSymbol>()
+        self.map.as_fst().size() + self.symbols.len() * mem::size_of::<S>()
+    }
+}
+
+fn range_to_map_value(start: usize, end: usize) -> u64 {
+    debug_assert![start <= (u32::MAX as usize)];
+    debug_assert![end <= (u32::MAX as usize)];
+
+    ((start as u64) << 32) | end as u64
+}
+
+fn map_value_to_range(value: u64) -> (usize, usize) {
+    let end = value as u32 as usize;
+    let start = (value >> 32) as usize;
+    (start, end)
+}
+
+/// The index of [`ExtendedFileSymbol`]s, which also indexes the words of their doc comments.
+pub struct ExtendedSymbolIndex {
+    index: SymbolIndex<ExtendedFileSymbol>,
+    /// Maps the lowercased words of the doc comments to the ranges of `doc_symbols` holding the
+    /// positions of the symbols documented with them.
+    doc_words: fst::Map<Vec<u8>>,
+    doc_symbols: Vec<u32>,
+    is_library: bool,
+}
+
+impl fmt::Debug for ExtendedSymbolIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExtendedSymbolIndex").field("n_symbols", &self.index.len()).finish()
+    }
+}
+
+impl PartialEq for ExtendedSymbolIndex {
+    fn eq(&self, other: &ExtendedSymbolIndex) -> bool {
+        self.index == other.index && self.is_library == other.is_library
+    }
+}
+
+impl Eq for ExtendedSymbolIndex {}
+
+impl Hash for ExtendedSymbolIndex {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.index.hash(hasher);
+        self.is_library.hash(hasher);
+    }
+}
+
+impl ExtendedSymbolIndex {
+    fn new(symbols: Vec<ExtendedFileSymbol>, is_library: bool) -> ExtendedSymbolIndex {
+        let index = SymbolIndex::new(symbols);
+
+        let mut words = BTreeMap::<String, Vec<u32>>::new();
+        for (idx, symbol) in index.symbols.iter().enumerate() {
+            let Some(docs) = &symbol.docs else { continue };
+            for word in words_of(&docs.to_lowercase()) {
+                let symbols = words.entry(word.to_owned()).or_default();
+                if symbols.last() != Some(&(idx as u32)) {
+                    symbols.push(idx as u32);
+                }
+            }
+        }
+        let mut builder = fst::MapBuilder::memory();
+        let mut doc_symbols = Vec::new();
+        for (word, symbols) in words {
+            let start = doc_symbols.len();
+            doc_symbols.extend(symbols);
+            builder.insert(word, range_to_map_value(start, doc_symbols.len())).unwrap();
+        }
+        let doc_words = fst::Map::new(builder.into_inner().unwrap()).unwrap();
+
+        ExtendedSymbolIndex { index, doc_words, doc_symbols, is_library }
     }
 
-    fn range_to_map_value(start: usize, end: usize) -> u64 {
-        debug_assert![start <= (std::u32::MAX as usize)];
-        debug_assert![end <= (std::u32::MAX as usize)];
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
 
-        ((start as u64) << 32) | end as u64
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
-    fn map_value_to_range(value: u64) -> (usize, usize) {
-        let end = value as u32 as usize;
-        let start = (value >> 32) as usize;
-        (start, end)
+    pub fn memory_size(&self) -> usize {
+        self.index.memory_size()
+            + self.doc_words.as_fst().size()
+            + self.doc_symbols.len() * mem::size_of::<u32>()
     }
+
+    /// The positions of the symbols whose doc comments have words starting with each of `words`.
+    fn documented_with(&self, words: &[&str]) -> FxHashSet<usize> {
+        let mut res: Option<FxHashSet<usize>> = None;
+        for word in words {
+            let mut matches = FxHashSet::default();
+            let automaton = fst::automaton::Str::new(word).starts_with();
+            let mut stream = self.doc_words.search(automaton).into_stream();
+            while let Some((_, value)) = stream.next() {
+                let (start, end) = map_value_to_range(value);
+                matches.extend(self.doc_symbols[start..end].iter().map(|&idx| idx as usize));
+            }
+            res = Some(match res {
+                Some(res) => res.intersection(&matches).copied().collect(),
+                None => matches,
+            });
+        }
+        res.unwrap_or_default()
+    }
+}
+
+/// The words of a doc comment, as they are indexed.
+fn words_of(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_').filter(|it| !it.is_empty())
 }
 
 impl Query {
@@ -329,38 +540,10 @@ impl Query {
         while let Some((_, indexed_values)) = stream.next() {
             for indexed_value in indexed_values {
                 let symbol_index = &indices[indexed_value.index];
-                let (start, end) = SymbolIndex::map_value_to_range(indexed_value.value);
+                let (start, end) = map_value_to_range(indexed_value.value);
 
                 for symbol in &symbol_index.symbols[start..end] {
-                    if self.only_types
-                        && !matches!(
-                            symbol.def,
-                            hir::ModuleDef::Adt(..)
-                                | hir::ModuleDef::TypeAlias(..)
-                                | hir::ModuleDef::BuiltinType(..)
-                                | hir::ModuleDef::TraitAlias(..)
-                                | hir::ModuleDef::Trait(..)
-                        )
-                    {
-                        continue;
-                    }
-                    let skip = match self.mode {
-                        SearchMode::Fuzzy => {
-                            self.case_sensitive
-                                && self.query.chars().any(|c| !symbol.name.contains(c))
-                        }
-                        SearchMode::Exact => symbol.name != self.query,
-                        SearchMode::Prefix if self.case_sensitive => {
-                            !symbol.name.starts_with(&self.query)
-                        }
-                        SearchMode::Prefix => symbol
-                            .name
-                            .chars()
-                            .zip(self.lowercased.chars())
-                            .all(|(n, q)| n.to_lowercase().next() == Some(q)),
-                    };
-
-                    if skip {
+                    if !self.matches_kind(symbol) || !self.matches_name(symbol) {
                         continue;
                     }
 
@@ -373,13 +556,144 @@ impl Query {
         }
         res
     }
+
+    fn search_extended(self, indices: &[Arc<ExtendedSymbolIndex>]) -> Vec<ExtendedFileSymbol> {
+        let _p = profile::span("symbol_index::Query::search_extended");
+        let matches_kind = |it: &ExtendedFileSymbol| {
+            self.matches_kind(&it.symbol) && !(self.only_types && it.inner.is_some())
+        };
+        let mut op = fst::map::OpBuilder::new();
+        for extended_index in indices.iter() {
+            let automaton = fst::automaton::Subsequence::new(&self.lowercased);
+            op = op.add(extended_index.index.map.search(automaton))
+        }
+        let mut stream = op.union();
+        let mut ranked = Vec::new();
+        while let Some((_, indexed_values)) = stream.next() {
+            for indexed_value in indexed_values {
+                let symbols = &indices[indexed_value.index].index.symbols;
+                let (start, end) = map_value_to_range(indexed_value.value);
+
+                for (idx, symbol) in symbols[start..end].iter().enumerate() {
+                    if matches_kind(symbol) && self.matches_name(&symbol.symbol) {
+                        ranked.push((indexed_value.index, start + idx));
+                    }
+                }
+            }
+        }
+
+        if self.mode == SearchMode::Fuzzy {
+            let words: Vec<_> = words_of(&self.lowercased).collect();
+            let matched: FxHashSet<_> = ranked.iter().copied().collect();
+            for (index, extended_index) in indices.iter().enumerate() {
+                for idx in extended_index.documented_with(&words) {
+                    let symbol = &extended_index.index.symbols[idx];
+                    if matches_kind(symbol)
+                        && symbol.docs.as_deref().is_some_and(|docs| self.matches_docs(docs))
+                        && !matched.contains(&(index, idx))
+                    {
+                        ranked.push((index, idx));
+                    }
+                }
+            }
+        }
+        ranked.sort_by_cached_key(|&(index, idx)| {
+            let extended_index = &indices[index];
+            let symbol = &extended_index.index.symbols[idx].symbol;
+            (self.rank(symbol), extended_index.is_library, symbol.name.len())
+        });
+        ranked
+            .into_iter()
+            .take(self.limit)
+            .map(|(index, idx)| indices[index].index.symbols[idx].clone())
+            .collect()
+    }
+
+    fn matches_kind(&self, symbol: &FileSymbol) -> bool {
+        !self.only_types
+            || matches!(
+                symbol.def,
+                hir::ModuleDef::Adt(..)
+                    | hir::ModuleDef::TypeAlias(..)
+                    | hir::ModuleDef::BuiltinType(..)
+                    | hir::ModuleDef::TraitAlias(..)
+                    | hir::ModuleDef::Trait(..)
+            )
+    }
+
+    fn matches_name(&self, symbol: &FileSymbol) -> bool {
+        match self.mode {
+            SearchMode::Fuzzy => {
+                !self.case_sensitive || self.query.chars().all(|c| symbol.name.contains(c))
+            }
+            SearchMode::Exact => symbol.name == self.query,
+            SearchMode::Prefix if self.case_sensitive => symbol.name.starts_with(&self.query),
+            SearchMode::Prefix => !symbol
+                .name
+                .chars()
+                .zip(self.lowercased.chars())
+                .all(|(n, q)| n.to_lowercase().next() == Some(q)),
+        }
+    }
+
+    /// Whether all words of the query appear in `docs`.
+    fn matches_docs(&self, docs: &str) -> bool {
+        if self.case_sensitive {
+            self.query.split_whitespace().all(|word| docs.contains(word))
+        } else {
+            let docs = docs.to_lowercase();
+            self.lowercased.split_whitespace().all(|word| docs.contains(word))
+        }
+    }
+
+    /// How well `symbol` matches the query, lower is better.
+    fn rank(&self, symbol: &FileSymbol) -> u8 {
+        let name = symbol.name.to_lowercase();
+        if name == self.lowercased {
+            0
+        } else if camel_humps(&symbol.name) == self.lowercased {
+            1
+        } else if name.starts_with(&self.lowercased) {
+            2
+        } else if name.contains(&self.lowercased) {
+            3
+        } else if is_subsequence(&self.lowercased, &name) {
+            4
+        } else {
+            // Only the docs matched.
+            5
+        }
+    }
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut chars = haystack.chars();
+    needle.chars().all(|n| chars.any(|c| c == n))
+}
+
+/// The lowercased first letters of the words of a `CamelCase` or `snake_case` name, like `hm` for
+/// `HashMap`.
+fn camel_humps(name: &str) -> String {
+    let mut prev = None;
+    let mut humps = String::new();
+    for c in name.chars() {
+        let is_hump = match prev {
+            None | Some('_') => c != '_',
+            Some(prev) => c.is_uppercase() && !prev.is_uppercase(),
+        };
+        if is_hump {
+            humps.extend(c.to_lowercase());
+        }
+        prev = Some(c);
+    }
+    humps
 }
 
 #[cfg(test)]
 mod tests {
 
-    use base_db::fixture::WithFixture;
-    use expect_test::expect_file;
+    use base_db::{fixture::WithFixture, salsa::Durability};
+    use expect_test::{expect, expect_file};
     use hir::symbols::SymbolCollector;
 
     use super::*;
@@ -495,4 +809,61 @@ struct Duplicate;
 
         expect_file!["./test_data/test_doc_alias.txt"].assert_debug_eq(&symbols);
     }
+
+    #[test]
+    fn test_extended_search() {
+        let (mut db, files) = RootDatabase::with_many_files(
+            r#"
+//- /main.rs crate:main deps:dep
+/// Reads the configuration from disk.
+fn load_config() {
+    let config_path = ();
+}
+
+struct HashMap {
+    hash_builder: (),
+}
+
+struct HasMapping;
+
+enum Outer {
+    HashMapVariant { hm_field: () },
+}
+//- /dep.rs crate:dep new_source_root:library
+/// Reads the configuration from the environment.
+pub fn env_config() {
+    let dep_config = ();
+}
+"#,
+        );
+        let root = |file_id| Arc::new([db.file_source_root(file_id)].into_iter().collect());
+        let (local_root, library_root) = (root(files[0]), root(files[1]));
+        db.set_local_roots_with_durability(local_root, Durability::HIGH);
+        db.set_library_roots_with_durability(library_root, Durability::HIGH);
+
+        let search = |text: &str, libs: bool| {
+            let mut query = Query::new(text.to_owned());
+            query.extended();
+            if libs {
+                query.libs();
+            }
+            world_symbols_extended(&db, query)
+                .into_iter()
+                .map(|it| it.symbol.name.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        expect!["config_path, load_config"].assert_eq(&search("config", false));
+        expect!["load_config"].assert_eq(&search("disk configuration", false));
+        expect!["HashMap, HasMapping, hm_field, HashMapVariant"].assert_eq(&search("HM", false));
+        // Locals are only indexed for the workspace.
+        expect!["config_path, load_config, env_config"].assert_eq(&search("config", true));
+        expect!["load_config, env_config"].assert_eq(&search("reads config", true));
+
+        let mut query = Query::new("config_path".to_owned());
+        query.extended();
+        query.only_types();
+        assert!(world_symbols_extended(&db, query).is_empty());
+    }
 }
@@ -3,7 +3,7 @@ use itertools::Itertools;
 use parser::T;
 use syntax::{
     ast::{self, HasLoopBody, MacroCall, PathSegmentKind, VisibilityKind},
-    AstNode, AstToken, Preorder, RustLanguage, WalkEvent,
+    ted, AstNode, AstToken, Preorder, RustLanguage, WalkEvent,
 };
 
 pub fn expr_as_name_ref(expr: &ast::Expr) -> Option<ast::NameRef> {
@@ -471,3 +471,14 @@ pub fn macro_call_for_string_token(string: &ast::String) -> Option<MacroCall> {
     let macro_call = string.syntax().parent_ancestors().find_map(ast::MacroCall::cast)?;
     Some(macro_call)
 }
+
+/// Reorders a list of nodes, like the fields of a record, by replacing each of the mutable `nodes`
+/// with a copy of the node at the same position in `sorted`.
+pub fn reorder_nodes<T: AstNode>(
+    nodes: impl Iterator<Item = T>,
+    sorted: impl IntoIterator<Item = T>,
+) {
+    nodes
+        .zip(sorted)
+        .for_each(|(node, sorted)| ted::replace(node.syntax(), sorted.syntax().clone_for_update()));
+}
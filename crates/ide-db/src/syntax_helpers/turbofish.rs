@@ -0,0 +1,28 @@
+//! Helpers for spelling out the generic arguments of calls with a turbofish.
+use std::fmt;
+
+use itertools::Itertools;
+use syntax::{ast, match_ast, AstNode, TextSize};
+
+/// The offset after `name_ref`, the name of a path segment or of a method call, where a turbofish
+/// goes. Returns `None` if the generic arguments are given already.
+pub fn turbofish_offset(name_ref: &ast::NameRef) -> Option<TextSize> {
+    let has_generic_args = match_ast! {
+        match (name_ref.syntax().parent()?) {
+            ast::PathSegment(it) => it.generic_arg_list().is_some(),
+            ast::MethodCallExpr(it) => it.generic_arg_list().is_some(),
+            _ => return None,
+        }
+    };
+    (!has_generic_args).then(|| name_ref.syntax().text_range().end())
+}
+
+/// Renders `args` as a turbofish: `::<A, B>`.
+pub fn turbofish<T: fmt::Display>(args: impl IntoIterator<Item = T>) -> String {
+    format!("::{}", generic_arg_list(args))
+}
+
+/// Renders `args` as a generic argument list: `<A, B>`.
+pub fn generic_arg_list<T: fmt::Display>(args: impl IntoIterator<Item = T>) -> String {
+    format!("<{}>", args.into_iter().join(", "))
+}
@@ -0,0 +1,182 @@
+use hir::{HirDisplay, InFile, Semantics};
+use ide_db::{base_db::FileId, source_change::SourceChange, RootDatabase};
+use syntax::{
+    ast::{self, HasName},
+    AstNode, SyntaxNode,
+};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, DiagnosticCode, DiagnosticsConfig};
+
+// Diagnostic: large_enum_variant
+//
+// This diagnostic is triggered if the layout of one variant of an enum is much larger than the
+// layouts of all other variants, which makes every value of the enum that large.
+pub(crate) fn large_enum_variant(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+    config: &DiagnosticsConfig,
+) -> Option<()> {
+    let enum_ = ast::Enum::cast(node.clone())?;
+    let db = sema.db;
+    // Generic enums don't have a layout until they are instantiated.
+    let layout = sema.to_def(&enum_)?.layout(db).ok()?;
+
+    let mut variants = enum_
+        .variant_list()?
+        .variants()
+        .map(|variant| {
+            let def = sema.to_def(&variant)?;
+            let size = layout.variant_layout(def)?.size();
+            Some((variant, def, size))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if variants.len() < 2 {
+        return None;
+    }
+    variants.sort_by_key(|&(_, _, size)| std::cmp::Reverse(size));
+    let largest_size = variants[0].2;
+    let second_size = variants[1].2;
+    if largest_size - second_size < config.enum_variant_size_threshold {
+        return None;
+    }
+
+    let (variant, def, _) = &variants[0];
+    let range = variant.syntax().text_range();
+    let name = variant.name()?;
+    let diagnostic = Diagnostic::new(
+        DiagnosticCode::Clippy("large_enum_variant"),
+        format!(
+            "large size difference between variants: `{name}` is {largest_size} bytes while the \
+             second-largest variant is {second_size} bytes"
+        ),
+        range,
+    )
+    .with_main_node(InFile::new(file_id.into(), variant.syntax().clone()))
+    .experimental();
+
+    // Box the largest field of the variant, leaving the others in place.
+    let field_tys: Vec<ast::Type> = match variant.field_list()? {
+        ast::FieldList::RecordFieldList(it) => it.fields().filter_map(|it| it.ty()).collect(),
+        ast::FieldList::TupleFieldList(it) => it.fields().filter_map(|it| it.ty()).collect(),
+    };
+    let sizes = def
+        .fields(db)
+        .iter()
+        .map(|field| field.layout(db).ok().map(|layout| layout.size()))
+        .collect::<Option<Vec<_>>>();
+    let largest_field = sizes
+        .filter(|sizes| sizes.len() == field_tys.len())
+        .and_then(|sizes| field_tys.iter().zip(sizes).max_by_key(|&(_, size)| size));
+    let fixes = largest_field.map(|(field_ty, _)| {
+        let edit = TextEdit::replace(
+            field_ty.syntax().text_range(),
+            format!("Box<{}>", field_ty.syntax()),
+        );
+        let ty = sema
+            .resolve_type(field_ty)
+            .map_or_else(|| field_ty.to_string(), |ty| ty.display(db).to_string());
+        vec![fix(
+            "box_large_enum_variant",
+            &format!("Box the `{ty}` field"),
+            SourceChange::from_text_edit(file_id, edit),
+            range,
+        )]
+    });
+    acc.push(diagnostic.with_fixes(fixes));
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{check_diagnostics, check_diagnostics_with_config, check_fix},
+        DiagnosticsConfig,
+    };
+
+    #[test]
+    fn large_variant() {
+        check_diagnostics(
+            r#"
+enum E {
+    A(i32),
+    B([u8; 1000]),
+  //^^^^^^^^^^^^^ 💡 weak: large size difference between variants: `B` is 1001 bytes while the second-largest variant is 8 bytes
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn similar_variants() {
+        check_diagnostics(
+            r#"
+enum E {
+    A([u8; 900]),
+    B([u8; 1000]),
+    C,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generic_enum() {
+        check_diagnostics(
+            r#"
+enum E<T> {
+    A(T),
+    B([u8; 1000]),
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn allowed() {
+        check_diagnostics(
+            r#"
+#[allow(clippy::large_enum_variant)]
+enum E {
+    A(i32),
+    B([u8; 1000]),
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn configurable_threshold() {
+        let mut config = DiagnosticsConfig::test_sample();
+        config.enum_variant_size_threshold = 1000;
+        check_diagnostics_with_config(
+            config,
+            r#"
+enum E {
+    A(i32),
+    B([u8; 1000]),
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn box_largest_field() {
+        check_fix(
+            r#"
+enum E {
+    A(i32),
+    B$0 { small: u8, big: [u8; 1000] },
+}
+"#,
+            r#"
+enum E {
+    A(i32),
+    B { small: u8, big: Box<[u8; 1000]> },
+}
+"#,
+        );
+    }
+}
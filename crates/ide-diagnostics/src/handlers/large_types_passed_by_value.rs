@@ -0,0 +1,119 @@
+use hir::{HirDisplay, InFile, Semantics};
+use ide_db::{base_db::FileId, RootDatabase};
+use syntax::{ast, AstNode, SyntaxNode};
+
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsConfig};
+
+// Diagnostic: large_types_passed_by_value
+//
+// This diagnostic is triggered if a function takes an argument of a large `Copy` type by
+// value, which copies the whole value on every call.
+pub(crate) fn large_types_passed_by_value(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+    config: &DiagnosticsConfig,
+) -> Option<()> {
+    let fn_ = ast::Fn::cast(node.clone())?;
+    // The signature of trait impls and foreign functions can't be changed.
+    if fn_.abi().is_some() {
+        return None;
+    }
+    let in_trait_impl = fn_
+        .syntax()
+        .parent()
+        .and_then(ast::AssocItemList::cast)
+        .and_then(|it| it.syntax().parent())
+        .and_then(ast::Impl::cast)
+        .is_some_and(|impl_| impl_.trait_().is_some());
+    if in_trait_impl {
+        return None;
+    }
+
+    let db = sema.db;
+    for param in fn_.param_list()?.params() {
+        let Some(ty) = param.ty() else { continue };
+        let Some(resolved) = sema.resolve_type(&ty) else { continue };
+        if resolved.is_reference() || resolved.is_raw_ptr() || !resolved.is_copy(db) {
+            continue;
+        }
+        let Ok(layout) = resolved.layout(db) else { continue };
+        if layout.size() <= config.pass_by_value_size_limit {
+            continue;
+        }
+        acc.push(
+            Diagnostic::new(
+                DiagnosticCode::Clippy("large_types_passed_by_value"),
+                format!(
+                    "this argument `{}` is {} bytes and passed by value, consider passing it by reference",
+                    resolved.display(db),
+                    layout.size()
+                ),
+                ty.syntax().text_range(),
+            )
+            .with_main_node(InFile::new(file_id.into(), fn_.syntax().clone()))
+            .experimental(),
+        );
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{check_diagnostics, check_diagnostics_with_config},
+        DiagnosticsConfig,
+    };
+
+    #[test]
+    fn large_copy_argument() {
+        check_diagnostics(
+            r#"
+//- minicore: copy
+fn f(_small: [u8; 16], _big: [u8; 512], _by_ref: &[u8; 512]) {}
+                           //^^^^^^^^^ weak: this argument `[u8; 512]` is 512 bytes and passed by value, consider passing it by reference
+"#,
+        );
+    }
+
+    #[test]
+    fn non_copy_argument() {
+        check_diagnostics(
+            r#"
+//- minicore: copy
+struct Big([u8; 512]);
+fn f(_big: Big) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn trait_impl() {
+        check_diagnostics(
+            r#"
+//- minicore: copy
+trait Tr {
+    fn f(big: [u8; 512]);
+            //^^^^^^^^^ weak: this argument `[u8; 512]` is 512 bytes and passed by value, consider passing it by reference
+}
+impl Tr for () {
+    fn f(_big: [u8; 512]) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn configurable_limit() {
+        let mut config = DiagnosticsConfig::test_sample();
+        config.pass_by_value_size_limit = 1024;
+        check_diagnostics_with_config(
+            config,
+            r#"
+//- minicore: copy
+fn f(_big: [u8; 512]) {}
+"#,
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use hir::{InFile, Semantics};
+use ide_db::{
+    base_db::FileId, source_change::SourceChangeBuilder, syntax_helpers::node_ext::reorder_nodes,
+    RootDatabase,
+};
+use itertools::Itertools;
+use syntax::{
+    ast::{self, HasName},
+    AstNode, SyntaxNode,
+};
+
+use crate::{fix, Diagnostic, DiagnosticCode, Severity};
+
+// Diagnostic: repr-c-padding
+//
+// This diagnostic is triggered if the fields of a `#[repr(C)]` struct are ordered such that
+// the struct contains more padding than necessary. Unlike the default representation, the
+// compiler can't reorder the fields of such a struct itself.
+pub(crate) fn repr_c_padding(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let strukt = ast::Struct::cast(node.clone())?;
+    let ast::FieldList::RecordFieldList(field_list) = strukt.field_list()? else { return None };
+    let db = sema.db;
+    let def = sema.to_def(&strukt)?;
+    let repr = def.repr(db)?;
+    if !repr.c() || repr.packed() {
+        return None;
+    }
+    let layout = hir::Adt::from(def).layout(db).ok()?;
+
+    let fields = field_list.fields().collect::<Vec<_>>();
+    let field_layouts = fields
+        .iter()
+        .map(|field| {
+            let layout = sema.to_def(field)?.layout(db).ok()?;
+            Some((layout.size(), layout.align()))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    // Sorting by decreasing alignment never needs padding between fields, as the size of each
+    // field is a multiple of its alignment.
+    let sorted = (0..fields.len())
+        .sorted_by_key(|&idx| std::cmp::Reverse(field_layouts[idx].1))
+        .collect::<Vec<_>>();
+    let align = layout.align().max(1);
+    let unpadded_size = field_layouts.iter().map(|(size, _)| size).sum::<u64>();
+    let optimal_size = (unpadded_size + align - 1) / align * align;
+    let optimal_size = optimal_size.max(repr.align.map_or(0, |it| it.bytes()));
+    if optimal_size >= layout.size() {
+        return None;
+    }
+
+    let name = strukt.name()?;
+    let range = name.syntax().text_range();
+    let mut builder = SourceChangeBuilder::new(file_id);
+    reorder_nodes(
+        builder.make_mut(field_list).fields(),
+        sorted.iter().map(|&idx| fields[idx].clone()),
+    );
+
+    acc.push(
+        Diagnostic::new(
+            DiagnosticCode::Ra("repr-c-padding", Severity::WeakWarning),
+            format!(
+                "`{name}` is {} bytes, but would be {optimal_size} bytes with its fields ordered by alignment",
+                layout.size()
+            ),
+            range,
+        )
+        .with_main_node(InFile::new(file_id.into(), strukt.syntax().clone()))
+        .experimental()
+        .with_fixes(Some(vec![fix(
+            "reorder_fields_by_alignment",
+            "Reorder fields to reduce padding",
+            builder.finish(),
+            range,
+        )])),
+    );
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix};
+
+    #[test]
+    fn padded_struct() {
+        check_diagnostics(
+            r#"
+#[repr(C)]
+struct S {
+     //^ 💡 weak: `S` is 24 bytes, but would be 16 bytes with its fields ordered by alignment
+    a: u8,
+    b: u64,
+    c: u16,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn optimal_order() {
+        check_diagnostics(
+            r#"
+#[repr(C)]
+struct S {
+    b: u64,
+    c: u16,
+    a: u8,
+}
+
+#[repr(C)]
+struct Tail {
+    a: u64,
+    b: u8,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_repr_c() {
+        check_diagnostics(
+            r#"
+struct S {
+    a: u8,
+    b: u64,
+    c: u16,
+}
+
+#[repr(C, packed)]
+struct P {
+    a: u8,
+    b: u64,
+    c: u16,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn reorder_fields() {
+        check_fix(
+            r#"
+#[repr(C)]
+struct S$0 {
+    a: u8,
+    /// Docs move with the field.
+    b: u64,
+    c: u16,
+    d: [u32; 2],
+}
+"#,
+            r#"
+#[repr(C)]
+struct S {
+    /// Docs move with the field.
+    b: u64,
+    d: [u32; 2],
+    c: u16,
+    a: u8,
+}
+"#,
+        );
+    }
+}
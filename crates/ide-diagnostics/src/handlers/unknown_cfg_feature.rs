@@ -0,0 +1,151 @@
+use hir::{InFile, MacroKind, Semantics};
+use ide_db::{base_db::FileId, RootDatabase};
+use syntax::{
+    algo::non_trivia_sibling, ast, AstNode, AstToken, Direction, NodeOrToken, SyntaxKind,
+    SyntaxNode, T,
+};
+
+use crate::{Diagnostic, DiagnosticCode, Severity};
+
+// Diagnostic: unknown-cfg-feature
+//
+// This diagnostic is triggered if a `feature = "..."` cfg predicate refers to a feature that is not
+// declared in the `Cargo.toml` of the crate. It is only emitted for crates that declare at least
+// one feature, which crates not loaded from Cargo don't.
+pub(crate) fn unknown_cfg_feature(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let (name, tt) = cfg_input(sema, node)?;
+    let predicate_end = match name.text().as_str() {
+        "cfg" => tt.syntax().text_range().end(),
+        // only the first argument of `cfg_attr` is a predicate
+        "cfg_attr" => tt
+            .syntax()
+            .children_with_tokens()
+            .find(|it| it.kind() == T![,])
+            .map_or(tt.syntax().text_range().end(), |it| it.text_range().start()),
+        _ => return None,
+    };
+
+    let krate = sema.to_module_def(file_id)?.krate();
+    let potential_cfg = krate.declared_potential_cfg(sema.db)?;
+    let mut declared = potential_cfg.get_cfg_values("feature").peekable();
+    declared.peek()?;
+    let declared = declared.collect::<Vec<_>>();
+
+    let features = tt
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .take_while(|it| it.text_range().end() <= predicate_end)
+        .filter(|it| it.kind() == SyntaxKind::IDENT && it.text() == "feature")
+        .filter_map(|key| {
+            let eq = non_trivia_sibling(key.into(), Direction::Next)?;
+            if eq.kind() != T![=] {
+                return None;
+            }
+            ast::String::cast(non_trivia_sibling(eq, Direction::Next)?.into_token()?)
+        });
+    for feature in features {
+        let Some(value) = feature.value() else { continue };
+        if declared.iter().any(|it| **it == *value) {
+            continue;
+        }
+        acc.push(
+            Diagnostic::new(
+                DiagnosticCode::Ra("unknown-cfg-feature", Severity::Warning),
+                format!("feature `{value}` is not declared by this crate"),
+                feature.syntax().text_range(),
+            )
+            .with_main_node(InFile::new(file_id.into(), node.clone())),
+        );
+    }
+
+    Some(())
+}
+
+/// The name and token tree of the `cfg` or `cfg_attr` attribute or `cfg!` call `node` is.
+fn cfg_input(
+    sema: &Semantics<'_, RootDatabase>,
+    node: &SyntaxNode,
+) -> Option<(ast::NameRef, ast::TokenTree)> {
+    if let Some(meta) = ast::Meta::cast(node.clone()) {
+        return Some((meta.path()?.as_single_name_ref()?, meta.token_tree()?));
+    }
+    let call = ast::MacroCall::cast(node.clone())?;
+    let name = call.path()?.as_single_name_ref()?;
+    if name.text() != "cfg" || sema.resolve_macro_call(&call)?.kind(sema.db) != MacroKind::BuiltIn {
+        return None;
+    }
+    Some((name, call.token_tree()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use ide_db::{
+        assists::AssistResolveStrategy,
+        base_db::{fixture::WithFixture, salsa::Durability, SourceDatabase},
+        RootDatabase,
+    };
+
+    use crate::{tests::check_diagnostics, DiagnosticsConfig};
+
+    #[test]
+    fn unknown_feature() {
+        check_diagnostics(
+            r#"
+//- /lib.rs crate:foo cfg:feature=std
+#[rustc_builtin_macro]
+macro_rules! cfg {}
+
+#[cfg(any(feature = "std", feature = "sdt"))]
+                                   //^^^^^ warn: feature `sdt` is not declared by this crate
+fn f() {
+    let _ = cfg!(feature = "alloc");
+                         //^^^^^^^ warn: feature `alloc` is not declared by this crate
+}
+
+#[cfg_attr(feature = "std", doc = "feature = \"alloc\"")]
+struct S;
+"#,
+        );
+    }
+
+    #[test]
+    fn crate_without_features() {
+        check_diagnostics(
+            r#"
+#[cfg(feature = "std")]
+fn f() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn crate_without_declared_features() {
+        let (mut db, files) = RootDatabase::with_many_files(
+            r#"
+//- /lib.rs crate:foo cfg:feature=std
+#[cfg(feature = "alloc")]
+fn f() {}
+"#,
+        );
+        // Like crates not loaded from Cargo, which only know the enabled features.
+        let mut crate_graph = (*db.crate_graph()).clone();
+        for (_, data) in crate_graph.iter_mut() {
+            data.potential_cfg_options = None;
+        }
+        db.set_crate_graph_with_durability(crate_graph.into(), Durability::HIGH);
+
+        let diagnostics = crate::diagnostics(
+            &db,
+            &DiagnosticsConfig::test_sample(),
+            &AssistResolveStrategy::All,
+            files[0],
+        );
+        assert!(diagnostics.iter().all(|it| it.code.as_str() != "unknown-cfg-feature"));
+    }
+}
@@ -0,0 +1,522 @@
+use hir::{InFile, ModuleDef, PathResolution, Semantics};
+use ide_db::{base_db::FileId, source_change::SourceChange, RootDatabase};
+use syntax::{
+    ast::{self, AstNode, HasName},
+    SyntaxKind, SyntaxNode, TextRange,
+};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, DiagnosticCode, Severity};
+
+// Diagnostic: unreachable-code
+//
+// This diagnostic is triggered for statements following an expression that diverges, like
+// `return`, `panic!()` or a call to a function returning `!`.
+pub(crate) fn unreachable_code(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let stmt_list = ast::StmtList::cast(node.clone())?;
+    let mut stmts = stmt_list.statements();
+    stmts.by_ref().find(|stmt| diverges(sema, stmt))?;
+
+    // Nested items are not executed in the flow of the block, so they are left alone.
+    let unreachable: Vec<SyntaxNode> = stmts
+        .filter(|stmt| !matches!(stmt, ast::Stmt::Item(_)))
+        .map(|stmt| stmt.syntax().clone())
+        .chain(stmt_list.tail_expr().map(|expr| expr.syntax().clone()))
+        .collect();
+    let (first, last) = (unreachable.first()?, unreachable.last()?);
+    let range = TextRange::new(first.text_range().start(), last.text_range().end());
+
+    let mut edit = TextEdit::builder();
+    for node in &unreachable {
+        edit.delete(range_with_leading_whitespace(node));
+    }
+    let message = match ast::Expr::can_cast(first.kind()) {
+        true => "unreachable expression",
+        false => "unreachable statement",
+    };
+
+    acc.push(
+        Diagnostic::new(DiagnosticCode::RustcLint("unreachable_code"), message, range)
+            .with_main_node(InFile::new(file_id.into(), first.clone()))
+            .with_unused(true)
+            .experimental()
+            .with_fixes(Some(vec![fix(
+                "remove_unreachable_code",
+                "Remove unreachable code",
+                SourceChange::from_text_edit(file_id, edit.finish()),
+                range,
+            )])),
+    );
+
+    Some(())
+}
+
+// Diagnostic: unreachable-patterns
+//
+// This diagnostic is triggered for match arms matching an enum variant that can't be
+// constructed, like `Err(_)` for a `Result<T, Infallible>`.
+pub(crate) fn unreachable_patterns(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let arm = ast::MatchArm::cast(node.clone())?;
+    let pat = arm.pat()?;
+    let path = match &pat {
+        ast::Pat::TupleStructPat(it) => it.path()?,
+        ast::Pat::RecordPat(it) => it.path()?,
+        ast::Pat::PathPat(it) => it.path()?,
+        _ => return None,
+    };
+    let PathResolution::Def(ModuleDef::Variant(variant)) = sema.resolve_path(&path)? else {
+        return None;
+    };
+    let db = sema.db;
+    let match_expr = arm.syntax().ancestors().find_map(ast::MatchExpr::cast)?;
+    // Matching through a reference is not considered, as only the scrutinee itself is known
+    // to be an enum value.
+    let scrutinee_ty = sema.type_of_expr(&match_expr.expr()?)?.original;
+    if scrutinee_ty.as_adt() != Some(variant.parent_enum(db).into()) {
+        return None;
+    }
+    let module = sema.scope(arm.syntax())?.module();
+    if !scrutinee_ty.is_variant_uninhabited_from(db, variant, module) {
+        return None;
+    }
+
+    let range = arm.syntax().text_range();
+    // Without `exhaustive_patterns`, the arm is still required for the match to be exhaustive,
+    // so only its body can go.
+    let fix = if module.krate().is_unstable_feature_enabled(db, "exhaustive_patterns") {
+        let edit = TextEdit::delete(range_with_leading_whitespace(arm.syntax()));
+        fix(
+            "remove_unreachable_match_arm",
+            "Remove unreachable match arm",
+            SourceChange::from_text_edit(file_id, edit),
+            range,
+        )
+    } else {
+        let ast::Pat::TupleStructPat(tuple_pat) = &pat else { return None };
+        let binding = tuple_pat.fields().find_map(|field| match &field {
+            ast::Pat::IdentPat(ident) if ident.pat().is_none() => {
+                let ty = sema.type_of_pat(&field)?.original;
+                ty.is_uninhabited_from(db, module).then(|| ident.clone())
+            }
+            _ => None,
+        })?;
+        let name = binding.name()?;
+        let edit =
+            TextEdit::replace(arm.expr()?.syntax().text_range(), format!("match {name} {{}}"));
+        fix(
+            "remove_unreachable_match_arm_body",
+            &format!("Replace unreachable arm body with `match {name} {{}}`"),
+            SourceChange::from_text_edit(file_id, edit),
+            range,
+        )
+    };
+
+    acc.push(
+        Diagnostic::new(
+            DiagnosticCode::RustcLint("unreachable_patterns"),
+            format!("unreachable pattern: `{}` is uninhabited", variant.name(db).display(db)),
+            range,
+        )
+        .with_main_node(InFile::new(file_id.into(), arm.syntax().clone()))
+        .with_unused(true)
+        .experimental()
+        .with_fixes(Some(vec![fix])),
+    );
+
+    Some(())
+}
+
+// Diagnostic: unreachable-branch
+//
+// This diagnostic is shown for branches of an `if` whose condition is a constant, like a
+// `cfg!()` invocation or a `bool` constant, such that the branch is never taken.
+pub(crate) fn unreachable_branch(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let if_expr = ast::IfExpr::cast(node.clone())?;
+    let (condition, depends_on_cfg) = eval_condition(sema, &if_expr.condition()?)?;
+    let then_branch = if_expr.then_branch()?;
+    let if_range = if_expr.syntax().text_range();
+
+    let (range, edit) = if condition {
+        let else_token = if_expr.else_token()?;
+        let range = TextRange::new(else_token.text_range().start(), if_range.end());
+        (
+            range,
+            TextEdit::delete(TextRange::new(then_branch.syntax().text_range().end(), range.end())),
+        )
+    } else {
+        let range = then_branch.syntax().text_range();
+        let edit = match if_expr.else_branch() {
+            Some(ast::ElseBranch::Block(block)) => {
+                TextEdit::replace(if_range, block.syntax().to_string())
+            }
+            Some(ast::ElseBranch::IfExpr(else_if)) => {
+                TextEdit::replace(if_range, else_if.syntax().to_string())
+            }
+            None => {
+                let parent = if_expr.syntax().parent()?;
+                if let Some(parent_if) = ast::IfExpr::cast(parent.clone()) {
+                    let parent_then = parent_if.then_branch()?;
+                    TextEdit::delete(TextRange::new(
+                        parent_then.syntax().text_range().end(),
+                        if_range.end(),
+                    ))
+                } else if ast::ExprStmt::can_cast(parent.kind()) {
+                    TextEdit::delete(range_with_leading_whitespace(&parent))
+                } else if ast::StmtList::can_cast(parent.kind()) {
+                    TextEdit::delete(range_with_leading_whitespace(if_expr.syntax()))
+                } else {
+                    return None;
+                }
+            }
+        };
+        (range, edit)
+    };
+
+    // The branch may well be taken with other cfg options, so it is not offered for removal.
+    let fixes = (!depends_on_cfg).then(|| {
+        vec![fix(
+            "remove_unreachable_branch",
+            "Remove unreachable branch",
+            SourceChange::from_text_edit(file_id, edit),
+            range,
+        )]
+    });
+    acc.push(
+        Diagnostic::new(
+            DiagnosticCode::Ra("unreachable-branch", Severity::WeakWarning),
+            format!("this branch is never taken, as the condition is always `{condition}`"),
+            range,
+        )
+        .with_main_node(InFile::new(file_id.into(), if_expr.syntax().clone()))
+        .with_unused(true)
+        .experimental()
+        .with_fixes(fixes),
+    );
+
+    Some(())
+}
+
+fn diverges(sema: &Semantics<'_, RootDatabase>, stmt: &ast::Stmt) -> bool {
+    let expr = match stmt {
+        ast::Stmt::ExprStmt(it) => it.expr(),
+        ast::Stmt::LetStmt(it) => it.initializer(),
+        ast::Stmt::Item(_) => None,
+    };
+    expr.and_then(|expr| sema.type_of_expr(&expr)).is_some_and(|ty| ty.original.is_never())
+}
+
+/// Evaluates a constant condition, returning its value and whether it depends on the cfg options.
+fn eval_condition(sema: &Semantics<'_, RootDatabase>, expr: &ast::Expr) -> Option<(bool, bool)> {
+    match expr {
+        ast::Expr::ParenExpr(it) => eval_condition(sema, &it.expr()?),
+        ast::Expr::PrefixExpr(it) if it.op_kind()? == ast::UnaryOp::Not => {
+            eval_condition(sema, &it.expr()?)
+                .map(|(value, depends_on_cfg)| (!value, depends_on_cfg))
+        }
+        ast::Expr::PathExpr(it) => match sema.resolve_path(&it.path()?)? {
+            PathResolution::Def(ModuleDef::Const(konst)) => {
+                Some((konst.eval_bool(sema.db)?, false))
+            }
+            _ => None,
+        },
+        ast::Expr::MacroExpr(it) => {
+            let call = it.macro_call()?;
+            let mac = sema.resolve_macro_call(&call)?;
+            if mac.kind(sema.db) != hir::MacroKind::BuiltIn
+                || mac.name(sema.db).display(sema.db).to_string() != "cfg"
+            {
+                return None;
+            }
+            match sema.expand(&call)?.text().to_string().trim() {
+                "true" => Some((true, true)),
+                "false" => Some((false, true)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The range of `node` together with the whitespace separating it from the preceding element.
+fn range_with_leading_whitespace(node: &SyntaxNode) -> TextRange {
+    let start = match node.prev_sibling_or_token() {
+        Some(prev) if prev.kind() == SyntaxKind::WHITESPACE => prev.text_range().start(),
+        _ => node.text_range().start(),
+    };
+    TextRange::new(start, node.text_range().end())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{check_diagnostics_with_config, check_nth_fix_with_config},
+        DiagnosticsConfig,
+    };
+
+    fn config() -> DiagnosticsConfig {
+        DiagnosticsConfig { unreachable_code: true, ..DiagnosticsConfig::test_sample() }
+    }
+
+    #[track_caller]
+    fn check_diagnostics(ra_fixture: &str) {
+        let mut config = config();
+        config.disabled.insert("inactive-code".to_owned());
+        check_diagnostics_with_config(config, ra_fixture)
+    }
+
+    #[track_caller]
+    fn check_fix(ra_fixture_before: &str, ra_fixture_after: &str) {
+        check_nth_fix_with_config(config(), 0, ra_fixture_before, ra_fixture_after)
+    }
+
+    #[test]
+    fn statements_after_return() {
+        check_diagnostics(
+            r#"
+fn f() {
+    return;
+    fn nested() {}
+    let _x = 1;
+  //^^^^^^^^^^^ 💡 warn: unreachable statement
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn statements_after_diverging_call() {
+        check_diagnostics(
+            r#"
+//- minicore: panic
+fn exit() -> ! { loop {} }
+fn f() {
+    exit();
+    f();
+  //^^^^ 💡 warn: unreachable statement
+}
+fn g() -> u32 {
+    panic!("oh no");
+    0
+  //^ 💡 warn: unreachable expression
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_for_converging_code() {
+        check_diagnostics(
+            r#"
+fn f(cond: bool) -> i32 {
+    if cond {
+        return 1;
+    }
+    loop {
+        if cond {
+            break;
+        }
+    }
+    fn nested() {}
+    return 0;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_statements_after_return() {
+        check_fix(
+            r#"
+fn f() -> i32 {
+    return 0;
+    let _x$0 = 1;
+    fn nested() {}
+    2
+}
+"#,
+            r#"
+fn f() -> i32 {
+    return 0;
+    fn nested() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn uninhabited_variant_arm() {
+        check_diagnostics(
+            r#"
+//- minicore: result
+enum Never {}
+fn f(r: Result<u32, Never>) -> u32 {
+    match r {
+        Ok(x) => x,
+        Err(_e) => 0,
+      //^^^^^^^^^^^^^ 💡 warn: unreachable pattern: `Err` is uninhabited
+    }
+}
+fn g(r: Result<u32, u8>) -> u32 {
+    match r {
+        Ok(x) => x,
+        Err(_) => 0,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_uninhabited_arm_body() {
+        check_fix(
+            r#"
+//- minicore: result
+enum Never {}
+fn f(r: Result<u32, Never>) -> u32 {
+    match r {
+        Ok(x) => x,
+        Er$0r(_e) => 0,
+    }
+}
+"#,
+            r#"
+enum Never {}
+fn f(r: Result<u32, Never>) -> u32 {
+    match r {
+        Ok(x) => x,
+        Err(_e) => match _e {},
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_uninhabited_arm_with_exhaustive_patterns() {
+        check_fix(
+            r#"
+//- minicore: result
+#![feature(exhaustive_patterns)]
+enum Never {}
+fn f(r: Result<u32, Never>) -> u32 {
+    match r {
+        Ok(x) => x,
+        Err(_$0) => 0,
+    }
+}
+"#,
+            r#"
+#![feature(exhaustive_patterns)]
+enum Never {}
+fn f(r: Result<u32, Never>) -> u32 {
+    match r {
+        Ok(x) => x,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn constant_conditions() {
+        check_diagnostics(
+            r#"
+//- /main.rs cfg:feature=std
+#[rustc_builtin_macro]
+macro_rules! cfg {}
+const DEBUG: bool = false;
+fn f() {
+    if DEBUG {}
+           //^^ 💡 weak: this branch is never taken, as the condition is always `false`
+    if cfg!(feature = "std") {} else {}
+                              //^^^^^^^ weak: this branch is never taken, as the condition is always `true`
+    if !DEBUG {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_false_branch() {
+        check_fix(
+            r#"
+const DEBUG: bool = false;
+fn f() {
+    if DEBUG {$0
+        f();
+    } else {
+        f();
+    }
+}
+"#,
+            r#"
+const DEBUG: bool = false;
+fn f() {
+    {
+        f();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_false_branch_without_else() {
+        check_fix(
+            r#"
+const DEBUG: bool = false;
+fn f() {
+    f();
+    if DEBUG {$0
+        f();
+    }
+}
+"#,
+            r#"
+const DEBUG: bool = false;
+fn f() {
+    f();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_else_of_true_branch() {
+        check_fix(
+            r#"
+const RELEASE: bool = true;
+fn f() {
+    if RELEASE {
+        f();
+    } else$0 if f() == () {
+        f();
+    }
+}
+"#,
+            r#"
+const RELEASE: bool = true;
+fn f() {
+    if RELEASE {
+        f();
+    }
+}
+"#,
+        );
+    }
+}
@@ -63,6 +63,11 @@ mod handlers {
     pub(crate) mod useless_braces;
     pub(crate) mod unlinked_file;
     pub(crate) mod json_is_not_rust;
+    pub(crate) mod large_enum_variant;
+    pub(crate) mod large_types_passed_by_value;
+    pub(crate) mod repr_c_padding;
+    pub(crate) mod unknown_cfg_feature;
+    pub(crate) mod unreachable_code;
 }
 
 #[cfg(test)]
@@ -225,9 +230,21 @@ pub struct DiagnosticsConfig {
     // FIXME: We may want to include a whole `AssistConfig` here
     pub insert_use: InsertUseConfig,
     pub prefer_no_std: bool,
+    /// The size difference in bytes between the largest and second-largest variant of an enum
+    /// from which on `large_enum_variant` is reported.
+    pub enum_variant_size_threshold: u64,
+    /// The size in bytes from which on `large_types_passed_by_value` is reported.
+    pub pass_by_value_size_limit: u64,
+    /// Whether unreachable statements, match arms and branches are reported.
+    pub unreachable_code: bool,
 }
 
 impl DiagnosticsConfig {
+    /// The default of [`Self::enum_variant_size_threshold`], which is the one of clippy.
+    pub const DEFAULT_ENUM_VARIANT_SIZE_THRESHOLD: u64 = 200;
+    /// The default of [`Self::pass_by_value_size_limit`], which is the one of clippy.
+    pub const DEFAULT_PASS_BY_VALUE_SIZE_LIMIT: u64 = 256;
+
     pub fn test_sample() -> Self {
         use hir::PrefixKind;
         use ide_db::imports::insert_use::ImportGranularity;
@@ -247,6 +264,9 @@ impl DiagnosticsConfig {
                 skip_glob_imports: false,
             },
             prefer_no_std: false,
+            enum_variant_size_threshold: Self::DEFAULT_ENUM_VARIANT_SIZE_THRESHOLD,
+            pass_by_value_size_limit: Self::DEFAULT_PASS_BY_VALUE_SIZE_LIMIT,
+            unreachable_code: false,
         }
     }
 }
@@ -305,6 +325,17 @@ pub fn diagnostics(
         handlers::useless_braces::useless_braces(&mut res, file_id, &node);
         handlers::field_shorthand::field_shorthand(&mut res, file_id, &node);
         handlers::json_is_not_rust::json_in_items(&sema, &mut res, file_id, &node, config);
+        handlers::large_enum_variant::large_enum_variant(&sema, &mut res, file_id, &node, config);
+        handlers::large_types_passed_by_value::large_types_passed_by_value(
+            &sema, &mut res, file_id, &node, config,
+        );
+        handlers::repr_c_padding::repr_c_padding(&sema, &mut res, file_id, &node);
+        handlers::unknown_cfg_feature::unknown_cfg_feature(&sema, &mut res, file_id, &node);
+        if config.unreachable_code {
+            handlers::unreachable_code::unreachable_code(&sema, &mut res, file_id, &node);
+            handlers::unreachable_code::unreachable_patterns(&sema, &mut res, file_id, &node);
+            handlers::unreachable_code::unreachable_branch(&sema, &mut res, file_id, &node);
+        }
     }
 
     let module = sema.to_module_def(file_id);
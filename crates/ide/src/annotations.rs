@@ -12,7 +12,7 @@ use crate::{
     goto_implementation::goto_implementation,
     references::find_all_refs,
     runnables::{runnables, Runnable},
-    NavigationTarget, RunnableKind,
+    NavigationTarget, RunnableKind, TestConfig,
 };
 
 mod fn_references;
@@ -44,6 +44,7 @@ pub struct AnnotationConfig {
     pub annotate_method_references: bool,
     pub annotate_enum_variant_references: bool,
     pub location: AnnotationLocation,
+    pub test_config: TestConfig,
 }
 
 pub enum AnnotationLocation {
@@ -59,7 +60,7 @@ pub(crate) fn annotations(
     let mut annotations = Vec::default();
 
     if config.annotate_runnables {
-        for runnable in runnables(db, file_id) {
+        for runnable in runnables(db, &config.test_config, file_id) {
             if should_skip_runnable(&runnable.kind, config.binary_target) {
                 continue;
             }
@@ -207,7 +208,7 @@ fn should_skip_runnable(kind: &RunnableKind, binary_target: bool) -> bool {
 mod tests {
     use expect_test::{expect, Expect};
 
-    use crate::{fixture, Annotation, AnnotationConfig};
+    use crate::{fixture, Annotation, AnnotationConfig, TestConfig};
 
     use super::AnnotationLocation;
 
@@ -219,6 +220,7 @@ mod tests {
         annotate_method_references: true,
         annotate_enum_variant_references: true,
         location: AnnotationLocation::AboveName,
+        test_config: TestConfig { test_attributes: Vec::new() },
     };
 
     fn check_with_config(ra_fixture: &str, expect: Expect, config: &AnnotationConfig) {
@@ -0,0 +1,346 @@
+//! Clickable links to the files and URLs a source file refers to.
+
+use hir::{MacroKind, Semantics};
+use ide_db::{
+    base_db::{AnchoredPath, AnchoredPathBuf, FileId, FileLoader, SourceDatabase},
+    RootDatabase,
+};
+use syntax::{
+    ast::{self, HasAttrs, HasName, IsString},
+    AstNode, AstToken, NodeOrToken, SyntaxToken, TextRange, TextSize, T,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentLink {
+    pub range: TextRange,
+    pub target: DocumentLinkTarget,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocumentLinkTarget {
+    /// A file that is part of the analysis.
+    File(FileId),
+    /// A path that didn't resolve to a file of the analysis, like a text file loaded by
+    /// `include_str!`.
+    Path(AnchoredPathBuf),
+    Url(String),
+}
+
+// Feature: Document Links
+//
+// Makes the following clickable:
+//
+// * the path arguments of `include!`, `include_str!` and `include_bytes!`,
+// * `#[path = "..."]` attributes and the names of `mod foo;` declarations,
+// * paths written as `concat!(env!("CARGO_MANIFEST_DIR"), "...")`, on their own or as the
+//   argument of the `include` macros,
+// * URLs in doc comments.
+//
+// Paths are resolved the same way the builtin macros resolve them during expansion.
+pub(crate) fn document_links(db: &RootDatabase, file_id: FileId) -> Vec<DocumentLink> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(file_id);
+    let mut res = Vec::new();
+    for element in file.syntax().descendants_with_tokens() {
+        match element {
+            NodeOrToken::Node(node) => {
+                if let Some(module) = ast::Module::cast(node.clone()) {
+                    module_links(&sema, file_id, &module, &mut res);
+                } else if let Some(macro_call) = ast::MacroCall::cast(node) {
+                    macro_call_links(&sema, file_id, &macro_call, &mut res);
+                }
+            }
+            NodeOrToken::Token(token) => {
+                if let Some(comment) = ast::Comment::cast(token) {
+                    if comment.kind().doc.is_some() {
+                        doc_comment_links(&comment, &mut res);
+                    }
+                }
+            }
+        }
+    }
+    res
+}
+
+fn module_links(
+    sema: &Semantics<'_, RootDatabase>,
+    file_id: FileId,
+    module: &ast::Module,
+    acc: &mut Vec<DocumentLink>,
+) {
+    if module.item_list().is_some() {
+        return;
+    }
+    // An unresolved module is defined by its declaration.
+    let module_file = sema
+        .to_def(module)
+        .map(|it| it.definition_source(sema.db))
+        .filter(|it| matches!(it.value, hir::ModuleSource::SourceFile(_)))
+        .map(|it| it.file_id.original_file(sema.db));
+    if let (Some(name), Some(module_file)) = (module.name(), module_file) {
+        acc.push(DocumentLink {
+            range: name.syntax().text_range(),
+            target: DocumentLinkTarget::File(module_file),
+        });
+    }
+    let path_attr = module.attrs().find(|attr| attr.simple_name().as_deref() == Some("path"));
+    let Some(ast::Expr::Literal(literal)) = path_attr.and_then(|attr| attr.expr()) else {
+        return;
+    };
+    let ast::LiteralKind::String(string) = literal.kind() else { return };
+    let (Some(range), Some(path)) = (string.text_range_between_quotes(), string.value()) else {
+        return;
+    };
+    let target = match module_file {
+        Some(module_file) => DocumentLinkTarget::File(module_file),
+        None => DocumentLinkTarget::Path(AnchoredPathBuf { anchor: file_id, path: path.into() }),
+    };
+    acc.push(DocumentLink { range, target });
+}
+
+fn macro_call_links(
+    sema: &Semantics<'_, RootDatabase>,
+    file_id: FileId,
+    macro_call: &ast::MacroCall,
+    acc: &mut Vec<DocumentLink>,
+) {
+    let Some(mac) = sema.resolve_macro_call(macro_call) else { return };
+    if mac.kind(sema.db) != MacroKind::BuiltIn {
+        return;
+    }
+    let Some(token_tree) = macro_call.token_tree() else { return };
+    let args = macro_args(&token_tree);
+    let (range, path) = match mac.name(sema.db).to_smol_str().as_str() {
+        "include" | "include_str" | "include_bytes" => match args.as_slice() {
+            [NodeOrToken::Token(arg)] => {
+                let Some(string) = ast::String::cast(arg.clone()) else { return };
+                let (Some(range), Some(path)) =
+                    (string.text_range_between_quotes(), string.value())
+                else {
+                    return;
+                };
+                (range, path.into_owned())
+            }
+            // `include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/some/path"))`
+            [NodeOrToken::Token(concat), NodeOrToken::Token(bang), NodeOrToken::Node(concat_args)]
+                if concat.text() == "concat" && bang.kind() == T![!] =>
+            {
+                match manifest_dir_relative_path(sema, file_id, &macro_args(concat_args)) {
+                    Some(it) => it,
+                    None => return,
+                }
+            }
+            _ => return,
+        },
+        "concat" => match manifest_dir_relative_path(sema, file_id, &args) {
+            Some(it) => it,
+            None => return,
+        },
+        _ => return,
+    };
+    // Like the builtin macros, resolve the path relative to the file containing the call.
+    let target = match sema.db.resolve_path(AnchoredPath { anchor: file_id, path: &path }) {
+        Some(file) => DocumentLinkTarget::File(file),
+        None => DocumentLinkTarget::Path(AnchoredPathBuf { anchor: file_id, path }),
+    };
+    acc.push(DocumentLink { range, target });
+}
+
+/// The path of `concat!(env!("CARGO_MANIFEST_DIR"), "/some/path")`, and the range of the string
+/// literals that follow the manifest directory.
+fn manifest_dir_relative_path(
+    sema: &Semantics<'_, RootDatabase>,
+    file_id: FileId,
+    args: &[NodeOrToken<ast::TokenTree, SyntaxToken>],
+) -> Option<(TextRange, String)> {
+    let [NodeOrToken::Token(env), NodeOrToken::Token(bang), NodeOrToken::Node(env_args), rest @ ..] =
+        args
+    else {
+        return None;
+    };
+    if env.text() != "env" || bang.kind() != T![!] {
+        return None;
+    }
+    match macro_args(env_args).as_slice() {
+        [NodeOrToken::Token(var)] => {
+            let var = ast::String::cast(var.clone())?;
+            if var.value()? != "CARGO_MANIFEST_DIR" {
+                return None;
+            }
+        }
+        _ => return None,
+    }
+
+    let krate = sema.to_module_def(file_id)?.krate();
+    let mut path = sema.db.crate_graph()[krate.into()].env.get("CARGO_MANIFEST_DIR")?;
+    let mut range: Option<TextRange> = None;
+    for arg in rest {
+        match arg {
+            NodeOrToken::Token(token) if token.kind() == T![,] => (),
+            NodeOrToken::Token(token) => {
+                let string = ast::String::cast(token.clone())?;
+                path.push_str(&string.value()?);
+                let string_range = string.text_range_between_quotes()?;
+                range = Some(range.map_or(string_range, |it| it.cover(string_range)));
+            }
+            NodeOrToken::Node(_) => return None,
+        }
+    }
+    Some((range?, path))
+}
+
+/// The elements of a macro call's token tree, without its delimiters and trivia.
+fn macro_args(token_tree: &ast::TokenTree) -> Vec<NodeOrToken<ast::TokenTree, SyntaxToken>> {
+    let mut args: Vec<_> = token_tree
+        .syntax()
+        .children_with_tokens()
+        .filter(|it| !it.kind().is_trivia())
+        .filter_map(|it| match it {
+            NodeOrToken::Node(node) => ast::TokenTree::cast(node).map(NodeOrToken::Node),
+            NodeOrToken::Token(token) => Some(NodeOrToken::Token(token)),
+        })
+        .collect();
+    if args.len() >= 2 {
+        args.pop();
+        args.remove(0);
+    }
+    args
+}
+
+fn doc_comment_links(comment: &ast::Comment, acc: &mut Vec<DocumentLink>) {
+    let text = comment.text();
+    let start = comment.syntax().text_range().start();
+    let mut offset = 0;
+    while let Some(found) =
+        ["https://", "http://"].iter().filter_map(|scheme| text[offset..].find(scheme)).min()
+    {
+        let url_start = offset + found;
+        let url_len = text[url_start..]
+            .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '`' | '|'))
+            .unwrap_or(text.len() - url_start);
+        let mut url = &text[url_start..url_start + url_len];
+        // Punctuation and closing brackets of the surrounding text are not part of the URL.
+        loop {
+            let trimmed = url.trim_end_matches(['.', ',', ';', ':', '!', '?', '\'']);
+            let trimmed = match trimmed.strip_suffix(')') {
+                Some(it) if it.matches('(').count() < trimmed.matches(')').count() => it,
+                _ => trimmed,
+            };
+            let trimmed = trimmed.strip_suffix(']').unwrap_or(trimmed);
+            if trimmed.len() == url.len() {
+                break;
+            }
+            url = trimmed;
+        }
+        offset = url_start + url_len;
+        if url.contains("://") && !url.ends_with("://") {
+            let range = TextRange::at(TextSize::of(&text[..url_start]), TextSize::of(url)) + start;
+            acc.push(DocumentLink { range, target: DocumentLinkTarget::Url(url.to_owned()) });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use itertools::Itertools;
+
+    use crate::fixture;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let links = analysis.document_links(file_id).unwrap();
+        let text = analysis.file_text(file_id).unwrap();
+        let actual = links
+            .into_iter()
+            .map(|it| format!("{:?} {:?} {:?}", &text[it.range], it.range, it.target))
+            .join("\n");
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn modules() {
+        check(
+            r#"
+//- /main.rs
+mod foo;
+#[path = "bar_impl.rs"]
+mod bar;
+#[path = "missing.rs"]
+mod missing;
+mod inline {}
+//- /foo.rs
+//- /bar_impl.rs
+"#,
+            expect![[r#"
+                "foo" 4..7 File(FileId(1))
+                "bar" 37..40 File(FileId(2))
+                "bar_impl.rs" 19..30 File(FileId(2))
+                "missing.rs" 52..62 Path(AnchoredPathBuf { anchor: FileId(0), path: "missing.rs" })"#]],
+        );
+    }
+
+    #[test]
+    fn includes() {
+        check(
+            r#"
+//- minicore: include
+//- /main.rs env:CARGO_MANIFEST_DIR=/root
+#[rustc_builtin_macro]
+macro_rules! include_str { () => {} }
+#[rustc_builtin_macro]
+macro_rules! concat { () => {} }
+#[rustc_builtin_macro]
+macro_rules! env { () => {} }
+
+include!("foo.rs");
+const S: &str = include_str!("data/text.txt");
+const P: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets", "/a.png");
+const Q: &str = concat!("not", "a path");
+//- /foo.rs
+"#,
+            expect![[r#"
+                "foo.rs" 181..187 File(FileId(1))
+                "data/text.txt" 221..234 Path(AnchoredPathBuf { anchor: FileId(0), path: "data/text.txt" })
+                "/assets\", \"/a.png" 291..308 Path(AnchoredPathBuf { anchor: FileId(0), path: "/root/assets/a.png" })"#]],
+        );
+    }
+
+    #[test]
+    fn include_of_manifest_dir_relative_path() {
+        check(
+            r#"
+//- /main.rs env:CARGO_MANIFEST_DIR=/root
+#[rustc_builtin_macro]
+macro_rules! include_str { () => {} }
+#[rustc_builtin_macro]
+macro_rules! concat { () => {} }
+#[rustc_builtin_macro]
+macro_rules! env { () => {} }
+
+const S: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/data.txt"));
+//- /data.txt
+"#,
+            expect![[
+                r#""/data.txt" 237..246 Path(AnchoredPathBuf { anchor: FileId(0), path: "/root/data.txt" })"#
+            ]],
+        );
+    }
+
+    #[test]
+    fn doc_comment_urls() {
+        check(
+            r#"
+//! See https://example.com/docs.
+/// Links: <https://example.com/a>, [text](http://example.com/b_(c)) and `https://x.y/z`.
+// Not a doc comment: https://example.com/c
+fn f() {}
+"#,
+            expect![[r#"
+                "https://example.com/docs" 8..32 Url("https://example.com/docs")
+                "https://example.com/a" 46..67 Url("https://example.com/a")
+                "http://example.com/b_(c)" 77..101 Url("http://example.com/b_(c)")
+                "https://x.y/z" 108..121 Url("https://x.y/z")"#]],
+        );
+    }
+}
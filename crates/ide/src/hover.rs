@@ -22,7 +22,7 @@ use crate::{
     markdown_remove::remove_markdown,
     markup::Markup,
     runnables::{runnable_fn, runnable_mod},
-    FileId, FilePosition, NavigationTarget, RangeInfo, Runnable, TryToNav,
+    FileId, FilePosition, NavigationTarget, RangeInfo, Runnable, TestConfig, TryToNav,
 };
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct HoverConfig {
@@ -31,6 +31,7 @@ pub struct HoverConfig {
     pub documentation: bool,
     pub keywords: bool,
     pub format: HoverDocFormat,
+    pub test_config: TestConfig,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -60,6 +61,8 @@ pub enum HoverAction {
     Implementation(FilePosition),
     Reference(FilePosition),
     GoToType(Vec<HoverGotoTypeData>),
+    /// The impls a method call can dispatch to.
+    GoToImpl(Vec<HoverGotoTypeData>),
 }
 
 impl HoverAction {
@@ -201,7 +204,21 @@ fn hover_simple(
                 })
                 .flatten()
                 .unique_by(|&(def, _)| def)
-                .filter_map(|(def, node)| hover_for_definition(sema, file_id, def, &node, config))
+                .filter_map(|(def, node)| {
+                    let mut res = hover_for_definition(sema, file_id, def, &node, config)?;
+                    let method_call = node
+                        .parent()
+                        .and_then(ast::MethodCallExpr::cast)
+                        .filter(|it| it.name_ref().is_some_and(|name| name.syntax() == &node));
+                    if let Some(resolved) =
+                        method_call.and_then(|it| render::method_call_impl(sema, &it))
+                    {
+                        res.markup =
+                            Markup::from(format!("{}\n___\n\n{}", res.markup, resolved.markup));
+                        res.actions.extend(resolved.actions);
+                    }
+                    Some(res)
+                })
                 .reduce(|mut acc: HoverResult, HoverResult { markup, actions }| {
                     acc.actions.extend(actions);
                     acc.markup = Markup::from(format!("{}\n---\n{markup}", acc.markup));
@@ -257,6 +274,16 @@ fn hover_simple(
             })
         });
 
+    let implicit_call = descended().find_map(|token| render::implicit_trait_call(sema, token));
+    let result = match (result, implicit_call) {
+        (Some(mut res), Some(call)) => {
+            res.markup = Markup::from(format!("{}\n___\n\n{}", res.markup, call.markup));
+            res.actions.extend(call.actions);
+            Some(res)
+        }
+        (res, call) => res.or(call),
+    };
+
     result.map(|mut res: HoverResult| {
         res.actions = dedupe_or_merge_hover_actions(res.actions);
         RangeInfo::new(original_token.text_range(), res)
@@ -311,7 +338,7 @@ pub(crate) fn hover_for_definition(
             actions: [
                 show_implementations_action(sema.db, definition),
                 show_fn_references_action(sema.db, definition),
-                runnable_action(sema, definition, file_id),
+                runnable_action(sema, definition, file_id, &config.test_config),
                 goto_type_action_for_def(sema.db, definition),
             ]
             .into_iter()
@@ -354,9 +381,10 @@ fn runnable_action(
     sema: &hir::Semantics<'_, RootDatabase>,
     def: Definition,
     file_id: FileId,
+    test_config: &TestConfig,
 ) -> Option<HoverAction> {
     match def {
-        Definition::Module(it) => runnable_mod(sema, it).map(HoverAction::Runnable),
+        Definition::Module(it) => runnable_mod(sema, test_config, it).map(HoverAction::Runnable),
         Definition::Function(func) => {
             let src = func.source(sema.db)?;
             if src.file_id != file_id.into() {
@@ -365,7 +393,7 @@ fn runnable_action(
                 return None;
             }
 
-            runnable_fn(sema, func).map(HoverAction::Runnable)
+            runnable_fn(sema, test_config, func).map(HoverAction::Runnable)
         }
         _ => None,
     }
@@ -424,6 +452,7 @@ fn walk_and_push_ty(
 fn dedupe_or_merge_hover_actions(actions: Vec<HoverAction>) -> Vec<HoverAction> {
     let mut deduped_actions = Vec::with_capacity(actions.len());
     let mut go_to_type_targets = FxIndexSet::default();
+    let mut go_to_impl_targets = FxIndexSet::default();
 
     let mut seen_implementation = false;
     let mut seen_reference = false;
@@ -433,6 +462,9 @@ fn dedupe_or_merge_hover_actions(actions: Vec<HoverAction>) -> Vec<HoverAction>
             HoverAction::GoToType(targets) => {
                 go_to_type_targets.extend(targets);
             }
+            HoverAction::GoToImpl(targets) => {
+                go_to_impl_targets.extend(targets);
+            }
             HoverAction::Implementation(..) => {
                 if !seen_implementation {
                     seen_implementation = true;
@@ -457,6 +489,9 @@ fn dedupe_or_merge_hover_actions(actions: Vec<HoverAction>) -> Vec<HoverAction>
     if !go_to_type_targets.is_empty() {
         deduped_actions.push(HoverAction::GoToType(go_to_type_targets.into_iter().collect()));
     }
+    if !go_to_impl_targets.is_empty() {
+        deduped_actions.push(HoverAction::GoToImpl(go_to_impl_targets.into_iter().collect()));
+    }
 
     deduped_actions
 }
@@ -3,8 +3,8 @@ use std::fmt::Display;
 
 use either::Either;
 use hir::{
-    Adt, AsAssocItem, AttributeTemplate, CaptureKind, HasSource, HirDisplay, Layout, LayoutError,
-    Semantics, TypeInfo,
+    Adt, AsAssocItem, AssocItemContainer, AttributeTemplate, Callable, CaptureKind, HasSource,
+    HirDisplay, Impl, Layout, LayoutError, Semantics, TypeInfo,
 };
 use ide_db::{
     base_db::SourceDatabase,
@@ -12,6 +12,7 @@ use ide_db::{
     documentation::{Documentation, HasDocs},
     famous_defs::FamousDefs,
     generated::lints::{CLIPPY_LINTS, DEFAULT_LINTS, FEATURES},
+    implicit_trait_calls,
     syntax_helpers::insert_whitespace_into_node,
     RootDatabase,
 };
@@ -28,8 +29,8 @@ use syntax::{
 use crate::{
     doc_links::{remove_links, rewrite_links},
     hover::walk_and_push_ty,
-    HoverAction, HoverConfig, HoverResult, Markup, MemoryLayoutHoverConfig,
-    MemoryLayoutHoverRenderKind,
+    HoverAction, HoverConfig, HoverGotoTypeData, HoverResult, Markup, MemoryLayoutHoverConfig,
+    MemoryLayoutHoverRenderKind, TryToNav,
 };
 
 pub(super) fn type_info_of(
@@ -202,6 +203,117 @@ pub(super) fn deref_expr(
     Some(res)
 }
 
+/// The impl a call of a trait method dispatches to, with the signature of the method after
+/// substitution. For receivers that are trait objects or type parameters, the impl is only known
+/// at runtime or at the caller, so the candidate impls are listed instead.
+pub(super) fn method_call_impl(
+    sema: &Semantics<'_, RootDatabase>,
+    method_call: &ast::MethodCallExpr,
+) -> Option<HoverResult> {
+    const MAX_CANDIDATES: usize = 10;
+
+    let db = sema.db;
+    let func = sema.resolve_method_call(method_call)?;
+    let callable = sema.resolve_method_call_as_callable(method_call)?;
+    let (_, self_ty) = callable.receiver_param(db)?;
+    let receiver = self_ty.strip_references();
+    let (impls, is_candidates) = match func.as_assoc_item(db)?.container(db) {
+        AssocItemContainer::Impl(imp) if imp.trait_(db).is_some() => (vec![imp], false),
+        AssocItemContainer::Impl(_) => return None,
+        AssocItemContainer::Trait(trait_)
+            if receiver.as_dyn_trait().is_some() || receiver.as_type_param(db).is_some() =>
+        {
+            (Impl::all_for_trait(db, trait_), true)
+        }
+        // The impl doesn't override the method, so the call resolved to the trait's default.
+        AssocItemContainer::Trait(_) => (vec![sema.resolve_method_call_impl(method_call)?], false),
+    };
+    let signature = instantiated_signature(db, func, &callable);
+
+    let headers = impls.iter().map(|imp| imp.display(db).to_string()).collect::<Vec<_>>();
+    let mut markup = String::new();
+    if is_candidates {
+        format_to!(markup, "```rust\n{signature}\n```\n\nCandidate impls:\n\n```rust\n");
+        for header in headers.iter().take(MAX_CANDIDATES) {
+            format_to!(markup, "{header}\n");
+        }
+        if headers.len() > MAX_CANDIDATES {
+            format_to!(markup, "// and {} more\n", headers.len() - MAX_CANDIDATES);
+        }
+        markup.push_str("```");
+    } else {
+        let header = headers.first()?;
+        format_to!(markup, "```rust\n{header}\n```\n\n```rust\n{signature}\n```");
+    }
+
+    let targets = impls
+        .iter()
+        .zip(headers)
+        .take(MAX_CANDIDATES)
+        .filter_map(|(imp, mod_path)| {
+            Some(HoverGotoTypeData { mod_path, nav: imp.try_to_nav(db)? })
+        })
+        .collect();
+    Some(HoverResult { markup: markup.into(), actions: vec![HoverAction::GoToImpl(targets)] })
+}
+
+/// The signature of `func` with the types it was called with.
+fn instantiated_signature(db: &RootDatabase, func: hir::Function, callable: &Callable) -> String {
+    let mut params = Vec::new();
+    if let Some((_, ty)) = callable.receiver_param(db) {
+        params.push(format!("self: {}", ty.display(db)));
+    }
+    for (pat, ty) in callable.params(db) {
+        let pat = match pat {
+            Some(Either::Right(pat)) => pat.to_string(),
+            _ => "_".to_owned(),
+        };
+        params.push(format!("{pat}: {}", ty.display(db)));
+    }
+    let mut signature = format!("fn {}({})", func.name(db).display(db), params.join(", "));
+    let ret_ty = callable.return_type();
+    if !ret_ty.is_unit() {
+        format_to!(signature, " -> {}", ret_ty.display(db));
+    }
+    signature
+}
+
+/// The trait impl an overloaded operator, a `?` or the `in` of a `for` loop implicitly calls into.
+pub(super) fn implicit_trait_call(
+    sema: &Semantics<'_, RootDatabase>,
+    token: &SyntaxToken,
+) -> Option<HoverResult> {
+    let db = sema.db;
+    let parent = token.parent()?;
+    let expr: ast::Expr = match token.kind() {
+        T![?] => ast::TryExpr::cast(parent)?.into(),
+        T![in] => ast::ForExpr::cast(parent)?.into(),
+        T!['['] | T![']'] => ast::IndexExpr::cast(parent)?.into(),
+        _ => match ast::Expr::cast(parent)? {
+            ast::Expr::BinExpr(it) if it.op_token().as_ref() == Some(token) => it.into(),
+            ast::Expr::PrefixExpr(it) if it.op_token().as_ref() == Some(token) => it.into(),
+            _ => return None,
+        },
+    };
+    let func = implicit_trait_calls::implicit_trait_call(sema, &expr)?;
+    let AssocItemContainer::Impl(imp) = func.as_assoc_item(db)?.container(db) else {
+        return None;
+    };
+    let trait_ = imp.trait_(db)?;
+    let header = imp.display(db).to_string();
+    let markup = format!(
+        "Calls `{}::{}` of\n\n```rust\n{header}\n```",
+        trait_.name(db).display(db),
+        func.name(db).display(db)
+    );
+    let actions = imp
+        .try_to_nav(db)
+        .map(|nav| HoverAction::GoToImpl(vec![HoverGotoTypeData { mod_path: header, nav }]))
+        .into_iter()
+        .collect();
+    Some(HoverResult { markup: markup.into(), actions })
+}
+
 pub(super) fn underscore(
     sema: &Semantics<'_, RootDatabase>,
     config: &HoverConfig,
@@ -4,6 +4,7 @@ use syntax::TextRange;
 
 use crate::{
     fixture, HoverConfig, HoverDocFormat, MemoryLayoutHoverConfig, MemoryLayoutHoverRenderKind,
+    TestConfig,
 };
 
 const HOVER_BASE_CONFIG: HoverConfig = HoverConfig {
@@ -17,6 +18,7 @@ const HOVER_BASE_CONFIG: HoverConfig = HoverConfig {
     documentation: true,
     format: HoverDocFormat::Markdown,
     keywords: true,
+    test_config: TestConfig { test_attributes: Vec::new() },
 };
 
 fn check_hover_no_result(ra_fixture: &str) {
@@ -6164,6 +6166,13 @@ fn f() {
             ```rust
             fn deref(&self) -> &Self::Target
             ```
+            ___
+
+            Calls `Deref::deref` of
+
+            ```rust
+            impl Deref for Struct
+            ```
         "#]],
     );
 }
@@ -6613,3 +6622,388 @@ fn test() {
         "#]],
     );
 }
+
+#[test]
+fn hover_method_call_shows_selected_impl() {
+    check(
+        r#"
+trait Convert<T> {
+    fn convert(&self, value: T) -> T;
+}
+struct Foo;
+impl Convert<u8> for Foo {
+    fn convert(&self, value: u8) -> u8 { value }
+}
+impl<T: Clone> Convert<Vec<T>> for Foo {
+    fn convert(&self, value: Vec<T>) -> Vec<T> { value }
+}
+struct Vec<T>(T);
+fn main() {
+    Foo.conv$0ert(0u8);
+}
+"#,
+        expect![[r#"
+            *convert*
+
+            ```rust
+            test::Foo
+            ```
+
+            ```rust
+            fn convert(&self, value: u8) -> u8
+            ```
+            ___
+
+            ```rust
+            impl Convert<u8> for Foo
+            ```
+
+            ```rust
+            fn convert(self: &Foo, value: u8) -> u8
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn hover_method_call_default_method() {
+    check(
+        r#"
+trait Greet {
+    fn greet(&self) -> &'static str { "hi" }
+}
+struct Foo;
+impl Greet for Foo {}
+fn main() {
+    Foo.gre$0et();
+}
+"#,
+        expect![[r#"
+            *greet*
+
+            ```rust
+            test::Greet
+            ```
+
+            ```rust
+            fn greet(&self) -> &'static str
+            ```
+            ___
+
+            ```rust
+            impl Greet for Foo
+            ```
+
+            ```rust
+            fn greet(self: &Foo) -> &str
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn hover_method_call_default_method_selected_impl() {
+    check(
+        r#"
+trait Convert<T> {
+    fn convert<'a>(&'a self, value: &'a T) -> &'a T { value }
+}
+struct Wrapper<T>(T);
+impl Convert<u16> for Wrapper<u8> {}
+impl Convert<u8> for Wrapper<u16> {}
+fn main() {
+    Wrapper(0u16).conv$0ert(&0u8);
+}
+"#,
+        expect![[r#"
+            *convert*
+
+            ```rust
+            test::Convert
+            ```
+
+            ```rust
+            fn convert<'a>(&'a self, value: &'a T) -> &'a T
+            ```
+            ___
+
+            ```rust
+            impl Convert<u8> for Wrapper<u16>
+            ```
+
+            ```rust
+            fn convert(self: &Wrapper<u16>, value: &u8) -> &u8
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn hover_method_call_blanket_impl() {
+    check(
+        r#"
+//- minicore: from
+struct A;
+struct B;
+impl From<A> for B {
+    fn from(_: A) -> B { B }
+}
+fn main() {
+    let _: B = A.in$0to();
+}
+"#,
+        expect![[r#"
+            *into*
+
+            ```rust
+            core::convert
+            ```
+
+            ```rust
+            fn into(self) -> U
+            ```
+            ___
+
+            ```rust
+            impl<T, U> Into<U> for T
+            where
+                U: From<T>,
+            ```
+
+            ```rust
+            fn into(self: A) -> B
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn hover_method_call_impl_on_reference() {
+    check(
+        r#"
+trait Describe {
+    fn describe(self) -> u32;
+}
+struct Foo;
+impl Describe for &Foo {
+    fn describe(self) -> u32 { 0 }
+}
+fn main() {
+    (&Foo).descr$0ibe();
+}
+"#,
+        expect![[r#"
+            *describe*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            fn describe(self) -> u32
+            ```
+            ___
+
+            ```rust
+            impl Describe for &Foo
+            ```
+
+            ```rust
+            fn describe(self: &Foo) -> u32
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn hover_method_call_candidate_impls() {
+    check(
+        r#"
+trait Shape {
+    fn area(&self) -> u32;
+}
+struct Square;
+impl Shape for Square {
+    fn area(&self) -> u32 { 0 }
+}
+struct Circle;
+impl Shape for Circle {
+    fn area(&self) -> u32 { 0 }
+}
+fn total(shape: &dyn Shape) -> u32 {
+    shape.ar$0ea()
+}
+"#,
+        expect![[r#"
+            *area*
+
+            ```rust
+            test::Shape
+            ```
+
+            ```rust
+            fn area(&self) -> u32
+            ```
+            ___
+
+            ```rust
+            fn area(self: &dyn Shape) -> u32
+            ```
+
+            Candidate impls:
+
+            ```rust
+            impl Shape for Square
+            impl Shape for Circle
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn hover_method_call_impl_actions() {
+    check_actions(
+        r#"
+trait Shape {
+    fn area(&self) -> u32;
+}
+struct Square;
+impl Shape for Square {
+    fn area(&self) -> u32 { 0 }
+}
+fn total<T: Shape>(shape: T) -> u32 {
+    shape.ar$0ea()
+}
+"#,
+        expect![[r#"
+            [
+                Reference(
+                    FilePosition {
+                        file_id: FileId(
+                            0,
+                        ),
+                        offset: 21,
+                    },
+                ),
+                GoToImpl(
+                    [
+                        HoverGotoTypeData {
+                            mod_path: "impl Shape for Square",
+                            nav: NavigationTarget {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                full_range: 58..115,
+                                focus_range: 73..79,
+                                name: "impl",
+                                kind: Impl,
+                            },
+                        },
+                    ],
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn hover_try_expr_from_impl() {
+    check(
+        r#"
+//- minicore: result, from, try
+struct E1;
+struct E2;
+impl From<E1> for E2 {
+    fn from(_: E1) -> E2 { E2 }
+}
+fn f(r: Result<(), E1>) -> Result<(), E2> {
+    r?$0;
+    Ok(())
+}
+"#,
+        expect![[r#"
+            *?*
+
+            ```rust
+            core::ops::try_::Result
+            ```
+
+            ```rust
+            fn branch(self) -> ControlFlow<Self::Residual, Self::Output>
+            ```
+            ___
+
+            Calls `From::from` of
+
+            ```rust
+            impl From<E1> for E2
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn hover_operator_impl() {
+    check(
+        r#"
+//- minicore: add
+struct V;
+impl core::ops::Add for V {
+    type Output = V;
+    fn add(self, _: V) -> V { V }
+}
+fn f(a: V, b: V) {
+    let _ = a +$0 b;
+}
+"#,
+        expect![[r#"
+            *+*
+
+            ```rust
+            test::V
+            ```
+
+            ```rust
+            fn add(self, _: V) -> V
+            ```
+            ___
+
+            Calls `Add::add` of
+
+            ```rust
+            impl Add<V> for V
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn hover_for_loop_into_iter_impl() {
+    check(
+        r#"
+//- minicore: iterator
+struct Bag;
+struct Iter;
+impl Iterator for Iter {
+    type Item = u8;
+    fn next(&mut self) -> Option<u8> { None }
+}
+impl IntoIterator for Bag {
+    type Item = u8;
+    type IntoIter = Iter;
+    fn into_iter(self) -> Iter { Iter }
+}
+fn f() {
+    for _ i$0n Bag {}
+}
+"#,
+        expect![[r#"
+            *in*
+            Calls `IntoIterator::into_iter` of
+
+            ```rust
+            impl IntoIterator for Bag
+            ```
+        "#]],
+    );
+}
@@ -28,8 +28,11 @@ mod closing_brace;
 mod closure_ret;
 mod closure_captures;
 mod discriminant;
+mod drop_order;
 mod fn_lifetime_fn;
+mod generic_args;
 mod implicit_static;
+mod implicit_trait_calls;
 mod param_name;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -44,6 +47,9 @@ pub struct InlayHintsConfig {
     pub adjustment_hints_hide_outside_unsafe: bool,
     pub closure_return_type_hints: ClosureReturnTypeHints,
     pub closure_capture_hints: bool,
+    pub drop_hints: bool,
+    pub implicit_trait_call_hints: bool,
+    pub generic_arg_hints: bool,
     pub binding_mode_hints: bool,
     pub lifetime_elision_hints: LifetimeElisionHints,
     pub param_names_for_lifetime_elision_hints: bool,
@@ -52,6 +58,7 @@ pub struct InlayHintsConfig {
     pub closure_style: ClosureStyle,
     pub max_length: Option<usize>,
     pub closing_brace_hints_min_lines: Option<usize>,
+    pub generic_arg_hints_max_length: Option<usize>,
     pub fields_to_resolve: InlayFieldsToResolve,
 }
 
@@ -120,7 +127,10 @@ pub enum InlayKind {
     ClosingBrace,
     ClosureCapture,
     Discriminant,
+    Drop,
+    GenericArgs,
     GenericParamList,
+    ImplicitTraitCall,
     Lifetime,
     Parameter,
     Type,
@@ -434,6 +444,9 @@ fn ty_to_text_edit(
 // * return types of closure expressions
 // * elided lifetimes
 // * compiler inserted reborrows
+// * trait methods called by operators, `?`, `for` loops, autoderef and drops
+// * values dropped at the end of scopes, in drop order
+// * inferred generic arguments of calls
 //
 // image::https://user-images.githubusercontent.com/48062697/113020660-b5f98b80-917a-11eb-8d70-3be3fd558cdd.png[]
 pub(crate) fn inlay_hints(
@@ -452,7 +465,8 @@ pub(crate) fn inlay_hints(
     if let Some(scope) = sema.scope(file) {
         let famous_defs = FamousDefs(&sema, scope.krate());
 
-        let hints = |node| hints(&mut acc, &famous_defs, config, file_id, node);
+        let mut body_drops = drop_order::BodyDrops::default();
+        let hints = |node| hints(&mut acc, &famous_defs, config, &mut body_drops, file_id, node);
         match range_limit {
             Some(range) => match file.covering_element(range) {
                 NodeOrToken::Token(_) => return acc,
@@ -472,6 +486,7 @@ fn hints(
     hints: &mut Vec<InlayHint>,
     famous_defs @ FamousDefs(sema, _): &FamousDefs<'_, '_>,
     config: &InlayHintsConfig,
+    body_drops: &mut drop_order::BodyDrops,
     file_id: FileId,
     node: SyntaxNode,
 ) {
@@ -481,11 +496,19 @@ fn hints(
             ast::Expr(expr) => {
                 chaining::hints(hints, famous_defs, config, file_id, &expr);
                 adjustment::hints(hints, sema, config, &expr);
+                implicit_trait_calls::hints(hints, sema, config, &expr);
+                generic_args::hints(hints, sema, config, &expr);
                 match expr {
                     ast::Expr::CallExpr(it) => param_name::hints(hints, sema, config, ast::Expr::from(it)),
                     ast::Expr::MethodCallExpr(it) => {
                         param_name::hints(hints, sema, config, ast::Expr::from(it))
                     }
+                    ast::Expr::BlockExpr(it) => {
+                        implicit_trait_calls::drop_hints(
+                            hints, sema, config, body_drops, file_id, &it,
+                        );
+                        drop_order::hints(hints, sema, config, body_drops, file_id, &it)
+                    }
                     ast::Expr::ClosureExpr(it) => {
                         closure_captures::hints(hints, famous_defs, config, file_id, it.clone());
                         closure_ret::hints(hints, famous_defs, config, file_id, it)
@@ -579,6 +602,8 @@ mod tests {
         lifetime_elision_hints: LifetimeElisionHints::Never,
         closure_return_type_hints: ClosureReturnTypeHints::Never,
         closure_capture_hints: false,
+        drop_hints: false,
+        implicit_trait_call_hints: false,
         adjustment_hints: AdjustmentHints::Never,
         adjustment_hints_mode: AdjustmentHintsMode::Prefix,
         adjustment_hints_hide_outside_unsafe: false,
@@ -589,6 +614,8 @@ mod tests {
         param_names_for_lifetime_elision_hints: false,
         max_length: None,
         closing_brace_hints_min_lines: None,
+        generic_arg_hints: false,
+        generic_arg_hints_max_length: None,
         fields_to_resolve: InlayFieldsToResolve::empty(),
     };
     pub(super) const TEST_CONFIG: InlayHintsConfig = InlayHintsConfig {
@@ -0,0 +1,278 @@
+//! Implementation of "drop order" inlay hints:
+//! ```no_run
+//! if let Some(x) = cache.lock().get(0) {}/* drop(temp MutexGuard<Cache>)*/
+//! {
+//!     let guard = mutex.lock();
+//! /* drop(guard)*/}
+//! ```
+use hir::{DefWithBody, DroppedValue, HirDisplay, ScopeEndDrop, Semantics};
+use ide_db::{base_db::FileRange, FxHashMap, RootDatabase};
+use smallvec::SmallVec;
+use syntax::{
+    ast::{self, AstNode},
+    SyntaxNode, TextRange,
+};
+
+use crate::{
+    InlayHint, InlayHintLabel, InlayHintLabelPart, InlayHintPosition, InlayHintsConfig, InlayKind,
+};
+
+pub(super) fn hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<'_, RootDatabase>,
+    config: &InlayHintsConfig,
+    body_drops: &mut BodyDrops,
+    file_id: ide_db::base_db::FileId,
+    block: &ast::BlockExpr,
+) -> Option<()> {
+    if !config.drop_hints {
+        return None;
+    }
+    for (scope, drops) in body_drops.block_drops(sema, file_id, block)? {
+        let (range, position) = hint_position(block, scope)?;
+
+        let mut parts: SmallVec<[InlayHintLabelPart; 1]> = SmallVec::new();
+        for (idx, drop) in drops.iter().enumerate() {
+            let sep = if idx == 0 { "drop(" } else { ", " };
+            parts.push(InlayHintLabelPart {
+                text: sep.into(),
+                linked_location: None,
+                tooltip: None,
+            });
+            let (text, linked_location) = dropped_value(sema, config, file_id, drop);
+            parts.push(InlayHintLabelPart { text, linked_location, tooltip: None });
+        }
+        parts.push(InlayHintLabelPart { text: ")".into(), linked_location: None, tooltip: None });
+
+        let label = InlayHintLabel { parts };
+        let is_end_of_block = matches!(position, InlayHintPosition::Before);
+        acc.push(InlayHint {
+            needs_resolve: label.needs_resolve(),
+            range,
+            kind: InlayKind::Drop,
+            label,
+            text_edit: None,
+            position,
+            pad_left: !is_end_of_block,
+            pad_right: is_end_of_block,
+        });
+    }
+    Some(())
+}
+
+/// The drops of the bodies of a file, grouped by the block they happen in. They are computed once
+/// per body and shared by the blocks of a request.
+#[derive(Default)]
+pub(super) struct BodyDrops {
+    bodies: FxHashMap<DefWithBody, BlockDrops>,
+}
+
+/// The drops of a body by block, grouped by the expression ending their scope.
+type BlockDrops = FxHashMap<ast::BlockExpr, Vec<(SyntaxNode, Vec<ScopeEndDrop>)>>;
+
+impl BodyDrops {
+    /// The drops happening in `block`, grouped by the expression ending their scope, in the order
+    /// they are dropped.
+    pub(super) fn block_drops(
+        &mut self,
+        sema: &Semantics<'_, RootDatabase>,
+        file_id: ide_db::base_db::FileId,
+        block: &ast::BlockExpr,
+    ) -> Option<&[(SyntaxNode, Vec<ScopeEndDrop>)]> {
+        let owner = block.syntax().ancestors().find_map(|it| -> Option<DefWithBody> {
+            match ast::Item::cast(it)? {
+                ast::Item::Fn(it) => sema.to_def(&it).map(Into::into),
+                ast::Item::Const(it) => sema.to_def(&it).map(Into::into),
+                ast::Item::Static(it) => sema.to_def(&it).map(Into::into),
+                _ => None,
+            }
+        })?;
+        let root = block.syntax().ancestors().last()?;
+        let blocks =
+            self.bodies.entry(owner).or_insert_with(|| body_drops(sema, file_id, owner, &root));
+        blocks.get(block).map(Vec::as_slice)
+    }
+}
+
+fn body_drops(
+    sema: &Semantics<'_, RootDatabase>,
+    file_id: ide_db::base_db::FileId,
+    owner: DefWithBody,
+    root: &SyntaxNode,
+) -> BlockDrops {
+    let mut blocks = BlockDrops::default();
+    for drop in owner.scope_end_drops(sema.db) {
+        if drop.scope.file_id != file_id.into() {
+            continue;
+        }
+        let scope = drop.scope.value.to_node(root);
+        // Drops at the end of a block belong to that block, the others to the innermost block
+        // containing the expression.
+        let owning_block = match &scope {
+            ast::Expr::BlockExpr(it) => Some(it.clone()),
+            _ => scope.syntax().ancestors().find_map(ast::BlockExpr::cast),
+        };
+        let Some(owning_block) = owning_block else { continue };
+        let scopes = blocks.entry(owning_block).or_default();
+        match scopes.iter_mut().find(|(it, _)| it == scope.syntax()) {
+            Some((_, drops)) => drops.push(drop),
+            None => scopes.push((scope.syntax().clone(), vec![drop])),
+        }
+    }
+    blocks
+}
+
+/// Where to show the drops ending with `scope`: before the closing brace of `block` or after the
+/// statement leaving it.
+pub(super) fn hint_position(
+    block: &ast::BlockExpr,
+    scope: &SyntaxNode,
+) -> Option<(TextRange, InlayHintPosition)> {
+    if scope == block.syntax() {
+        Some((block.stmt_list()?.r_curly_token()?.text_range(), InlayHintPosition::Before))
+    } else {
+        // Show the hint after the statement, including its semicolon.
+        let stmt = scope
+            .parent()
+            .filter(|it| ast::ExprStmt::can_cast(it.kind()) || ast::LetStmt::can_cast(it.kind()));
+        let last_token = stmt.unwrap_or_else(|| scope.clone()).last_token()?;
+        Some((last_token.text_range(), InlayHintPosition::After))
+    }
+}
+
+/// The label of a dropped value and the location it links to.
+pub(super) fn dropped_value(
+    sema: &Semantics<'_, RootDatabase>,
+    config: &InlayHintsConfig,
+    file_id: ide_db::base_db::FileId,
+    drop: &ScopeEndDrop,
+) -> (String, Option<FileRange>) {
+    match &drop.value {
+        DroppedValue::Local(local) => {
+            let source = local.primary_source(sema.db);
+            (
+                local.name(sema.db).display(sema.db).to_string(),
+                source.name().and_then(|name| name.syntax().original_file_range_opt(sema.db)),
+            )
+        }
+        DroppedValue::Temporary(temp) => (
+            format!("temp {}", drop.ty.display_truncated(sema.db, config.max_length)),
+            Some(FileRange { file_id, range: temp.value.text_range() }),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        inlay_hints::tests::{check_with_config, DISABLED_CONFIG},
+        InlayHintsConfig,
+    };
+
+    #[track_caller]
+    fn check_drops(ra_fixture: &str) {
+        check_with_config(InlayHintsConfig { drop_hints: true, ..DISABLED_CONFIG }, ra_fixture);
+    }
+
+    #[test]
+    fn block_end() {
+        check_drops(
+            r#"
+//- minicore: drop
+struct Guard;
+impl Drop for Guard {
+    fn drop(&mut self) {}
+}
+struct Pair(Guard, u8);
+fn consume(_: Guard) {}
+fn f(param: Guard) {
+    let a = Guard;
+    let b = Pair(Guard, 0);
+    let moved = Guard;
+    let n = 5;
+    let r = &a;
+    consume(moved);
+    {
+        let inner = Guard;
+    }
+  //^ drop(inner)
+  }
+//^ drop(b, a, param)
+"#,
+        );
+    }
+
+    #[test]
+    fn statement_temporaries() {
+        check_drops(
+            r#"
+//- minicore: drop, option
+struct Guard;
+impl Drop for Guard {
+    fn drop(&mut self) {}
+}
+impl Guard {
+    fn get(&self) -> Option<u8> { None }
+}
+fn lock() -> Guard { Guard }
+fn f() {
+    if let Some(_) = lock().get() {}
+                                 //^ drop(temp Guard)
+    match lock().get() {
+        _ => {}
+    }
+  //^ drop(temp Guard)
+    let _ = lock().get();
+                      //^ drop(temp Guard)
+    let extended = &lock();
+  }
+//^ drop(temp Guard)
+"#,
+        );
+    }
+
+    #[test]
+    fn early_exit() {
+        check_drops(
+            r#"
+//- minicore: drop
+struct Guard;
+impl Drop for Guard {
+    fn drop(&mut self) {}
+}
+fn f(c: bool) {
+    let a = Guard;
+    if c {
+        let b = Guard;
+        return;
+            //^ drop(b, a)
+    }
+  }
+//^ drop(a)
+"#,
+        );
+    }
+
+    #[test]
+    fn conditional_moves() {
+        check_drops(
+            r#"
+//- minicore: drop
+struct Guard;
+impl Drop for Guard {
+    fn drop(&mut self) {}
+}
+fn consume(_: Guard) {}
+fn f(c: bool) {
+    let maybe_moved = Guard;
+    let moved = Guard;
+    if c {
+        consume(maybe_moved);
+    }
+    consume(moved);
+  }
+//^ drop(maybe_moved)
+"#,
+        );
+    }
+}
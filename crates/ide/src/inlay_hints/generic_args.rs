@@ -0,0 +1,314 @@
+//! Implementation of "generic argument" inlay hints:
+//! ```no_run
+//! let names = iter.collect/* ::<Vec<String>>*/();
+//! let map = HashMap/* ::<u32, String>*/::new();
+//! let foo: Foo = /* <Foo as */Default/* >*/::default();
+//! ```
+use hir::{HirDisplay, ModuleDef, PathResolution, Semantics, Type};
+use ide_db::{
+    syntax_helpers::turbofish::{generic_arg_list, turbofish, turbofish_offset},
+    RootDatabase,
+};
+use syntax::{
+    ast::{self, AstNode},
+    SyntaxNode,
+};
+use text_edit::TextEdit;
+
+use crate::{InlayHint, InlayHintLabel, InlayHintPosition, InlayHintsConfig, InlayKind};
+
+pub(super) fn hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<'_, RootDatabase>,
+    config: &InlayHintsConfig,
+    expr: &ast::Expr,
+) -> Option<()> {
+    if !config.generic_arg_hints {
+        return None;
+    }
+    let db = sema.db;
+    match expr {
+        ast::Expr::MethodCallExpr(call) => {
+            let name_ref = call.name_ref()?;
+            turbofish_offset(&name_ref)?;
+            let args = sema.resolve_method_call_as_callable(call)?.fn_generic_args(db)?;
+            turbofish_hint(acc, sema, config, &name_ref, &args.own?);
+        }
+        ast::Expr::CallExpr(call) => {
+            let ast::Expr::PathExpr(callee) = call.expr()? else { return None };
+            let path = callee.path()?;
+            let callable = sema.type_of_expr(&callee.clone().into())?.original.as_callable(db)?;
+            let args = callable.fn_generic_args(db)?;
+
+            if let (Some(name_ref), Some(own)) = (path.segment()?.name_ref(), &args.own) {
+                turbofish_hint(acc, sema, config, &name_ref, own);
+            }
+
+            let qualifier = path.qualifier()?;
+            let qualifier_name_ref = qualifier.segment()?.name_ref()?;
+            turbofish_offset(&qualifier_name_ref)?;
+            let self_ty = args.self_ty?;
+            match sema.resolve_path(&qualifier)? {
+                PathResolution::Def(ModuleDef::Adt(adt)) if self_ty.as_adt() == Some(adt) => {
+                    let params = hir::GenericDef::from(adt).params(db);
+                    if params.iter().any(|it| matches!(it, hir::GenericParam::ConstParam(_))) {
+                        return None;
+                    }
+                    let type_args: Vec<_> = self_ty.type_arguments().collect();
+                    turbofish_hint(acc, sema, config, &qualifier_name_ref, &type_args);
+                }
+                PathResolution::Def(ModuleDef::Trait(_)) => {
+                    qualified_self_hints(acc, sema, config, &qualifier, &self_ty, &args.trait_args);
+                }
+                _ => (),
+            }
+        }
+        _ => (),
+    }
+    Some(())
+}
+
+/// Shows `::<A, B>` after `name_ref`, with an edit inserting the turbofish.
+fn turbofish_hint(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<'_, RootDatabase>,
+    config: &InlayHintsConfig,
+    name_ref: &ast::NameRef,
+    args: &[Type],
+) -> Option<()> {
+    if args.is_empty() || args.iter().any(|it| it.contains_unknown()) {
+        return None;
+    }
+    let offset = turbofish_offset(name_ref)?;
+    let label = turbofish(render_truncated(sema, config, args));
+    let text_edit = render_source(sema, name_ref.syntax(), args)
+        .map(|rendered| TextEdit::insert(offset, turbofish(rendered)));
+    acc.push(InlayHint {
+        needs_resolve: text_edit.is_some(),
+        range: name_ref.syntax().text_range(),
+        kind: InlayKind::GenericArgs,
+        label: label.into(),
+        text_edit,
+        position: InlayHintPosition::After,
+        pad_left: false,
+        pad_right: false,
+    });
+    Some(())
+}
+
+/// Turns a trait qualifier like `Default` into `<Foo as Default>`, with an edit on the opening
+/// hint inserting both parts.
+fn qualified_self_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<'_, RootDatabase>,
+    config: &InlayHintsConfig,
+    qualifier: &ast::Path,
+    self_ty: &Type,
+    trait_args: &[Type],
+) -> Option<()> {
+    if self_ty.contains_unknown() || trait_args.iter().any(|it| it.contains_unknown()) {
+        return None;
+    }
+    let node = qualifier.syntax();
+    let range = node.text_range();
+    let closing = |rendered: Vec<String>| match trait_args {
+        [] => ">".to_owned(),
+        _ => format!("{}>", generic_arg_list(rendered)),
+    };
+
+    let self_label = self_ty.display_truncated(sema.db, config.generic_arg_hints_max_length);
+    let opening_label = format!("<{self_label} as");
+    let closing_label = closing(render_truncated(sema, config, trait_args));
+
+    let text_edit = (|| {
+        let self_ty = render_source(sema, node, std::slice::from_ref(self_ty))?.pop()?;
+        let trait_args = render_source(sema, node, trait_args)?;
+        let mut builder = TextEdit::builder();
+        builder.insert(range.start(), format!("<{self_ty} as "));
+        builder.insert(range.end(), closing(trait_args));
+        Some(builder.finish())
+    })();
+
+    acc.push(InlayHint {
+        needs_resolve: text_edit.is_some(),
+        range,
+        kind: InlayKind::GenericArgs,
+        label: InlayHintLabel::from(opening_label),
+        text_edit,
+        position: InlayHintPosition::Before,
+        pad_left: false,
+        pad_right: true,
+    });
+    acc.push(InlayHint {
+        needs_resolve: false,
+        range,
+        kind: InlayKind::GenericArgs,
+        label: InlayHintLabel::from(closing_label),
+        text_edit: None,
+        position: InlayHintPosition::After,
+        pad_left: false,
+        pad_right: false,
+    });
+    Some(())
+}
+
+fn render_truncated(
+    sema: &Semantics<'_, RootDatabase>,
+    config: &InlayHintsConfig,
+    args: &[Type],
+) -> Vec<String> {
+    args.iter()
+        .map(|it| it.display_truncated(sema.db, config.generic_arg_hints_max_length).to_string())
+        .collect()
+}
+
+fn render_source(
+    sema: &Semantics<'_, RootDatabase>,
+    node: &SyntaxNode,
+    args: &[Type],
+) -> Option<Vec<String>> {
+    let module = sema.scope(node)?.module();
+    args.iter().map(|arg| arg.display_source_code(sema.db, module.into(), false).ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::{
+        inlay_hints::tests::{check_edit, check_with_config, DISABLED_CONFIG},
+        InlayHintsConfig,
+    };
+
+    const GENERIC_ARGS_CONFIG: InlayHintsConfig = InlayHintsConfig {
+        generic_arg_hints: true,
+        generic_arg_hints_max_length: Some(25),
+        ..DISABLED_CONFIG
+    };
+
+    #[track_caller]
+    fn check_generic_args(ra_fixture: &str) {
+        check_with_config(GENERIC_ARGS_CONFIG, ra_fixture);
+    }
+
+    #[test]
+    fn function_and_method_calls() {
+        check_generic_args(
+            r#"
+struct S;
+impl S {
+    fn convert<T>(&self, t: T) -> T { t }
+}
+fn make<T>(t: T) -> T { t }
+fn with_impl_trait(_: impl Sized) {}
+fn f() {
+    make(S);
+  //^^^^ ::<S>
+    make::<u32>(0);
+    S.convert(0u8);
+    //^^^^^^^ ::<u8>
+    S.convert::<u8>(0);
+    with_impl_trait(0);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn type_constructors() {
+        check_generic_args(
+            r#"
+struct Map<K, V>(K, V);
+impl<K, V> Map<K, V> {
+    fn new(k: K, v: V) -> Self { Map(k, v) }
+}
+struct Plain;
+impl Plain {
+    fn new() -> Self { Plain }
+}
+fn f() {
+    let _ = Map::new(0u8, 0u16);
+          //^^^ ::<u8, u16>
+    let _ = Map::<u8, u16>::new(0, 0);
+    let _ = Plain::new();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn trait_qualified_calls() {
+        check_generic_args(
+            r#"
+//- minicore: default, from
+struct Foo;
+impl Default for Foo {
+    fn default() -> Self { Foo }
+}
+impl From<u8> for Foo {
+    fn from(_: u8) -> Self { Foo }
+}
+fn f() {
+    let _: Foo = Default::default();
+               //^^^^^^^ <Foo as
+               //^^^^^^^ >
+    let _: Foo = From::from(0u8);
+               //^^^^ <Foo as
+               //^^^^ <u8>>
+    let _ = Foo::default();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn truncates_long_args() {
+        check_with_config(
+            InlayHintsConfig { generic_arg_hints_max_length: Some(8), ..GENERIC_ARGS_CONFIG },
+            r#"
+struct Wrapper<T>(T);
+fn make<T>(t: T) -> T { t }
+fn f() {
+    make(Wrapper(Wrapper(0u8)));
+  //^^^^ ::<Wrapper<…>>
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn edits() {
+        check_edit(
+            GENERIC_ARGS_CONFIG,
+            r#"
+//- minicore: default
+struct Map<K, V>(K, V);
+impl<K, V> Map<K, V> {
+    fn new(k: K, v: V) -> Self { Map(k, v) }
+}
+impl Default for Map<u8, u8> {
+    fn default() -> Self { Map(0, 0) }
+}
+fn make<T>(t: T) -> T { t }
+fn f() {
+    let _ = make(Map::new(0u8, 0u16));
+    let _: Map<u8, u8> = Default::default();
+}
+"#,
+            expect![[r#"
+                struct Map<K, V>(K, V);
+                impl<K, V> Map<K, V> {
+                    fn new(k: K, v: V) -> Self { Map(k, v) }
+                }
+                impl Default for Map<u8, u8> {
+                    fn default() -> Self { Map(0, 0) }
+                }
+                fn make<T>(t: T) -> T { t }
+                fn f() {
+                    let _ = make::<Map<u8, u16>>(Map::<u8, u16>::new(0u8, 0u16));
+                    let _: Map<u8, u8> = <Map<u8, u8> as Default>::default();
+                }
+            "#]],
+        );
+    }
+}
@@ -0,0 +1,312 @@
+//! Implementation of "implicit trait call" inlay hints:
+//! ```no_run
+//! let sum = a +/* Add::add*/ b;
+//! let file = File::open(path)?/* From::from*/;
+//! for item in items/* IntoIterator::into_iter*/ {}
+//! let len = string/* Deref::deref*/.len();
+//! {
+//!     let guard = mutex.lock();
+//! /* Drop::drop(guard)*/}
+//! ```
+use hir::{AsAssocItem, AssocItemContainer, Function, HirDisplay, Semantics};
+use ide_db::{
+    base_db::{FileId, FileRange},
+    implicit_trait_calls::{drop_calls, implicit_deref_calls, implicit_trait_call},
+    RootDatabase,
+};
+use syntax::ast::{self, AstNode};
+
+use crate::{
+    inlay_hints::drop_order::{self, BodyDrops},
+    InlayHint, InlayHintLabel, InlayHintPosition, InlayHintsConfig, InlayKind, InlayTooltip,
+    TryToNav,
+};
+
+pub(super) fn hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<'_, RootDatabase>,
+    config: &InlayHintsConfig,
+    expr: &ast::Expr,
+) -> Option<()> {
+    if !config.implicit_trait_call_hints {
+        return None;
+    }
+    for func in implicit_deref_calls(sema, expr) {
+        let Some(label) = trait_call_label(sema, func, "") else { continue };
+        acc.push(InlayHint {
+            needs_resolve: label.needs_resolve(),
+            range: expr.syntax().text_range(),
+            kind: InlayKind::ImplicitTraitCall,
+            label,
+            text_edit: None,
+            position: InlayHintPosition::After,
+            pad_left: true,
+            pad_right: false,
+        });
+    }
+
+    let range = match expr {
+        ast::Expr::BinExpr(it) => it.op_token()?.text_range(),
+        ast::Expr::PrefixExpr(it) => it.op_token()?.text_range(),
+        ast::Expr::IndexExpr(it) => it.r_brack_token()?.text_range(),
+        ast::Expr::TryExpr(it) => it.question_mark_token()?.text_range(),
+        ast::Expr::ForExpr(it) => it.iterable()?.syntax().text_range(),
+        _ => return None,
+    };
+    let func = implicit_trait_call(sema, expr)?;
+    let label = trait_call_label(sema, func, "")?;
+    acc.push(InlayHint {
+        needs_resolve: label.needs_resolve(),
+        range,
+        kind: InlayKind::ImplicitTraitCall,
+        label,
+        text_edit: None,
+        position: InlayHintPosition::After,
+        pad_left: true,
+        pad_right: !matches!(expr, ast::Expr::TryExpr(_) | ast::Expr::IndexExpr(_)),
+    });
+    Some(())
+}
+
+/// The `Drop::drop` calls for the values dropped in `block`, including those of their drop glue.
+/// The drop order hints already show these drops, so they are left out when those are enabled.
+pub(super) fn drop_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<'_, RootDatabase>,
+    config: &InlayHintsConfig,
+    body_drops: &mut BodyDrops,
+    file_id: FileId,
+    block: &ast::BlockExpr,
+) -> Option<()> {
+    if !config.implicit_trait_call_hints || config.drop_hints {
+        return None;
+    }
+    let krate = sema.scope(block.syntax())?.krate();
+    for (scope, drops) in body_drops.block_drops(sema, file_id, block)? {
+        let (range, position) = drop_order::hint_position(block, scope)?;
+        let is_end_of_block = matches!(position, InlayHintPosition::Before);
+        for drop in drops {
+            let calls = drop_calls(sema, krate, &drop.ty);
+            if calls.is_empty() {
+                continue;
+            }
+            let (value, _) = drop_order::dropped_value(sema, config, file_id, drop);
+            for (path, func) in calls {
+                let Some(label) = trait_call_label(sema, func, &format!("({value}{path})")) else {
+                    continue;
+                };
+                acc.push(InlayHint {
+                    needs_resolve: label.needs_resolve(),
+                    range,
+                    kind: InlayKind::ImplicitTraitCall,
+                    label,
+                    text_edit: None,
+                    position: if is_end_of_block {
+                        InlayHintPosition::Before
+                    } else {
+                        InlayHintPosition::After
+                    },
+                    pad_left: !is_end_of_block,
+                    pad_right: is_end_of_block,
+                });
+            }
+        }
+    }
+    Some(())
+}
+
+/// `Trait::method` followed by `suffix`, linking to the impl method `func`.
+fn trait_call_label(
+    sema: &Semantics<'_, RootDatabase>,
+    func: Function,
+    suffix: &str,
+) -> Option<InlayHintLabel> {
+    let db = sema.db;
+    let AssocItemContainer::Impl(imp) = func.as_assoc_item(db)?.container(db) else { return None };
+    let trait_ = imp.trait_(db)?;
+
+    let linked_location = func
+        .try_to_nav(db)
+        .map(|it| FileRange { file_id: it.file_id, range: it.focus_or_full_range() });
+    let tooltip = InlayTooltip::Markdown(format!("```rust\n{}\n```", imp.display(db)));
+    Some(InlayHintLabel::simple(
+        format!("{}::{}{suffix}", trait_.name(db).display(db), func.name(db).display(db)),
+        Some(tooltip),
+        linked_location,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        inlay_hints::tests::{check_with_config, DISABLED_CONFIG},
+        InlayHintsConfig,
+    };
+
+    #[track_caller]
+    fn check_implicit_trait_calls(ra_fixture: &str) {
+        check_with_config(
+            InlayHintsConfig { implicit_trait_call_hints: true, ..DISABLED_CONFIG },
+            ra_fixture,
+        );
+    }
+
+    #[test]
+    fn operators() {
+        check_implicit_trait_calls(
+            r#"
+//- minicore: add, deref, index
+struct V;
+impl core::ops::Add for V {
+    type Output = V;
+    fn add(self, _: V) -> V { V }
+}
+impl core::ops::Deref for V {
+    type Target = u8;
+    fn deref(&self) -> &u8 { &0 }
+}
+impl core::ops::Index<usize> for V {
+    type Output = u8;
+    fn index(&self, _: usize) -> &u8 { &0 }
+}
+fn f(a: V, b: V, arr: [u8; 2]) {
+    let _ = a + b;
+            //^ Add::add
+    let _ = *V;
+          //^ Deref::deref
+    let _ = V[0];
+             //^ Index::index
+    let _ = 1 + 2;
+    let _ = *&1;
+    let _ = arr[0];
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn autoderef() {
+        check_implicit_trait_calls(
+            r#"
+//- minicore: deref_mut
+struct Inner;
+impl Inner {
+    fn get(&self) {}
+    fn set(&mut self) {}
+}
+struct Wrapper(Inner);
+impl core::ops::Deref for Wrapper {
+    type Target = Inner;
+    fn deref(&self) -> &Inner { &self.0 }
+}
+impl core::ops::DerefMut for Wrapper {
+    fn deref_mut(&mut self) -> &mut Inner { &mut self.0 }
+}
+fn f(mut w: Wrapper, r: &Inner) {
+    w.get();
+  //^ Deref::deref
+    w.set();
+  //^ DerefMut::deref_mut
+    let _: &Inner = &w;
+                  //^^ Deref::deref
+    r.get();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn drops() {
+        check_implicit_trait_calls(
+            r#"
+//- minicore: drop
+struct Guard;
+impl Drop for Guard {
+    fn drop(&mut self) {}
+}
+struct Pair(Guard, u8);
+fn f(c: bool) {
+    let a = Guard;
+    let _p = Pair(Guard, 0);
+    if c {
+        return;
+            //^ Drop::drop(_p.0)
+            //^ Drop::drop(a)
+    }
+  }
+//^ Drop::drop(_p.0)
+//^ Drop::drop(a)
+"#,
+        );
+    }
+
+    #[test]
+    fn drops_with_drop_order_hints() {
+        check_with_config(
+            InlayHintsConfig {
+                implicit_trait_call_hints: true,
+                drop_hints: true,
+                ..DISABLED_CONFIG
+            },
+            r#"
+//- minicore: drop
+struct Guard;
+impl Drop for Guard {
+    fn drop(&mut self) {}
+}
+fn f() {
+    let a = Guard;
+  }
+//^ drop(a)
+"#,
+        );
+    }
+
+    #[test]
+    fn try_conversion() {
+        check_implicit_trait_calls(
+            r#"
+//- minicore: result, from, try
+struct E1;
+struct E2;
+impl From<E1> for E2 {
+    fn from(_: E1) -> E2 { E2 }
+}
+fn f(r: Result<(), E1>) -> Result<(), E2> {
+    r?;
+   //^ From::from
+    let _ = || -> Result<(), E1> {
+        r?;
+        Ok(())
+    };
+    Ok(())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_loops() {
+        check_implicit_trait_calls(
+            r#"
+//- minicore: iterator
+struct Bag;
+struct Iter;
+impl Iterator for Iter {
+    type Item = u8;
+    fn next(&mut self) -> Option<u8> { None }
+}
+impl IntoIterator for Bag {
+    type Item = u8;
+    type IntoIter = Iter;
+    fn into_iter(self) -> Iter { Iter }
+}
+fn f() {
+    for _ in Bag {}
+           //^^^ IntoIterator::into_iter
+    for _ in Iter {}
+}
+"#,
+        );
+    }
+}
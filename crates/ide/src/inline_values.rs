@@ -0,0 +1,248 @@
+//! Computes the values a debugger shows inline next to the code of a stopped frame.
+
+use hir::{Local, Semantics};
+use ide_db::{
+    base_db::FileId,
+    defs::{Definition, NameClass, NameRefClass},
+    FxHashMap, RootDatabase,
+};
+use syntax::{ast, AstNode, SyntaxNode, TextRange, TextSize};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineValue {
+    pub range: TextRange,
+    pub kind: InlineValueKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InlineValueKind {
+    /// A local, whose value the debugger looks up by its name.
+    Variable(String),
+    /// An expression the debugger evaluates, like a field accessed through `self`.
+    Expression(String),
+}
+
+// Feature: Inline Values
+//
+// While a debugger is stopped, shows the values of the locals, pattern bindings and fields of
+// `self` that are in scope and live at the stopped location next to where they are used.
+// Occurrences of shadowed bindings are left out, as their values can no longer be looked up.
+pub(crate) fn inline_values(
+    db: &RootDatabase,
+    file_id: FileId,
+    range: TextRange,
+    stopped_location: TextRange,
+) -> Vec<InlineValue> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(file_id);
+    let Some(live_locals) = live_locals_at(&sema, file.syntax(), stopped_location.start()) else {
+        return Vec::new();
+    };
+    let is_live = |local: Local| live_locals.get(&local.name(db)) == Some(&local);
+
+    // Values are shown for the code that ran up to the stopped location.
+    let Some(range) = range.intersect(TextRange::up_to(stopped_location.end())) else {
+        return Vec::new();
+    };
+    let mut res = Vec::new();
+    for node in file.syntax().descendants() {
+        if !range.contains_range(node.text_range()) {
+            continue;
+        }
+        if let Some(field_expr) = ast::FieldExpr::cast(node.clone()) {
+            let Some(field) = field_expr.name_ref() else { continue };
+            let self_local = field_expr
+                .expr()
+                .and_then(|receiver| receiver_local(&sema, receiver))
+                .filter(|&local| local.is_self(db) && is_live(local));
+            if self_local.is_some() {
+                res.push(InlineValue {
+                    range: field_expr.syntax().text_range(),
+                    kind: InlineValueKind::Expression(format!("self.{field}")),
+                });
+            }
+            continue;
+        }
+        let local = if let Some(name_ref) = ast::NameRef::cast(node.clone()) {
+            match NameRefClass::classify(&sema, &name_ref) {
+                Some(NameRefClass::Definition(Definition::Local(local))) => local,
+                Some(NameRefClass::FieldShorthand { local_ref, .. }) => local_ref,
+                _ => continue,
+            }
+        } else if let Some(name) = ast::Name::cast(node.clone()) {
+            match NameClass::classify(&sema, &name) {
+                Some(NameClass::Definition(Definition::Local(local))) => local,
+                Some(NameClass::PatFieldShorthand { local_def, .. }) => local_def,
+                _ => continue,
+            }
+        } else {
+            continue;
+        };
+        // `self` itself is left to the variables view, its fields are shown instead.
+        if local.is_self(db) || !is_live(local) {
+            continue;
+        }
+        res.push(InlineValue {
+            range: node.text_range(),
+            kind: InlineValueKind::Variable(local.name(db).display(db).to_string()),
+        });
+    }
+    res
+}
+
+/// The locals in scope at the code starting at `offset`, by name. A shadowed local is not
+/// included.
+fn live_locals_at(
+    sema: &Semantics<'_, RootDatabase>,
+    file: &SyntaxNode,
+    offset: TextSize,
+) -> Option<FxHashMap<hir::Name, Local>> {
+    let mut token = file.token_at_offset(offset).right_biased()?;
+    while token.kind().is_trivia() {
+        token = token.next_token()?;
+    }
+    // The first expression that runs decides what is in scope, so that the bindings of a `let`
+    // statement only become live after its initializer.
+    let node = token.parent()?;
+    let scope = match node.ancestors().find(|it| ast::Stmt::can_cast(it.kind())) {
+        Some(stmt) => match stmt.descendants().find_map(ast::Expr::cast) {
+            Some(expr) => sema.scope(expr.syntax())?,
+            None => sema.scope_at_offset(&node, offset)?,
+        },
+        None => sema.scope_at_offset(&node, offset)?,
+    };
+    let mut res = FxHashMap::default();
+    scope.process_all_names(&mut |name, def| {
+        if let hir::ScopeDef::Local(local) = def {
+            // Scopes are visited from the innermost, so the first local of a name shadows the
+            // others.
+            res.entry(name).or_insert(local);
+        }
+    });
+    Some(res)
+}
+
+fn receiver_local(sema: &Semantics<'_, RootDatabase>, receiver: ast::Expr) -> Option<Local> {
+    let ast::Expr::PathExpr(path_expr) = receiver else { return None };
+    let name_ref = path_expr.path()?.as_single_name_ref()?;
+    match NameRefClass::classify(sema, &name_ref)? {
+        NameRefClass::Definition(Definition::Local(local)) => Some(local),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use itertools::Itertools;
+    use syntax::TextRange;
+
+    use crate::{fixture, InlineValueKind};
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, stopped) = fixture::range(ra_fixture);
+        let text = analysis.file_text(stopped.file_id).unwrap();
+        let range = TextRange::up_to((text.len() as u32).into());
+        let values = analysis.inline_values(stopped.file_id, range, stopped.range).unwrap();
+        let actual = values
+            .into_iter()
+            .map(|it| match it.kind {
+                InlineValueKind::Variable(name) => format!("{:?} variable {name}", it.range),
+                InlineValueKind::Expression(expr) => format!("{:?} expression {expr}", it.range),
+            })
+            .join("\n");
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn locals_and_pattern_bindings() {
+        check(
+            r#"
+fn foo(a: i32, (b, c): (i32, i32)) {
+    let d = a + b;
+    if let Some(e) = Some(c) {
+        let f = e;
+        $0f;$0
+    }
+    let g = d;
+}
+"#,
+            expect![[r#"
+                7..8 variable a
+                16..17 variable b
+                19..20 variable c
+                45..46 variable d
+                49..50 variable a
+                53..54 variable b
+                72..73 variable e
+                82..83 variable c
+                99..100 variable f
+                103..104 variable e
+                114..115 variable f"#]],
+        );
+    }
+
+    #[test]
+    fn shadowed_bindings_are_skipped() {
+        check(
+            r#"
+fn foo() {
+    let x = 1;
+    let y = x;
+    let x = y;
+    {
+        let z = 0;
+    }
+    $0x;$0
+}
+"#,
+            expect![[r#"
+                34..35 variable y
+                49..50 variable x
+                53..54 variable y
+                91..92 variable x"#]],
+        );
+    }
+
+    #[test]
+    fn bindings_after_the_stopped_location_are_skipped() {
+        check(
+            r#"
+fn foo() {
+    let x = 1;
+    $0let y = x;$0
+    let z = y;
+}
+"#,
+            expect![[r#"
+                19..20 variable x
+                38..39 variable x"#]],
+        );
+    }
+
+    #[test]
+    fn fields_of_self() {
+        check(
+            r#"
+struct S { a: i32, b: (i32, i32) }
+impl S {
+    fn foo(&self, other: &S) {
+        let x = self.a + other.a;
+        let S { a, .. } = other;
+        $0let y = self.b.0 + x + a;$0
+    }
+}
+"#,
+            expect![[r#"
+                62..67 variable other
+                87..88 variable x
+                91..97 expression self.a
+                100..105 variable other
+                125..126 variable a
+                135..140 variable other
+                158..164 expression self.b
+                169..170 variable x
+                173..174 variable a"#]],
+        );
+    }
+}
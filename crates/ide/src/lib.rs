@@ -61,6 +61,12 @@ mod view_item_tree;
 mod shuffle_crate_graph;
 mod fetch_crates;
 mod view_memory_layout;
+mod test_explorer;
+mod type_hierarchy;
+mod inline_values;
+mod linked_editing;
+mod document_links;
+mod signature_search;
 
 use std::ffi::OsStr;
 
@@ -75,13 +81,13 @@ use ide_db::{
 };
 use syntax::SourceFile;
 use triomphe::Arc;
-use view_memory_layout::{view_memory_layout, RecursiveMemoryLayout};
 
 use crate::navigation_target::{ToNav, TryToNav};
 
 pub use crate::{
     annotations::{Annotation, AnnotationConfig, AnnotationKind, AnnotationLocation},
     call_hierarchy::CallItem,
+    document_links::{DocumentLink, DocumentLinkTarget},
     expand_macro::ExpandedMacro,
     file_structure::{StructureNode, StructureNodeKind},
     folding_ranges::{Fold, FoldKind},
@@ -95,6 +101,7 @@ pub use crate::{
         InlayFieldsToResolve, InlayHint, InlayHintLabel, InlayHintLabelPart, InlayHintPosition,
         InlayHintsConfig, InlayKind, InlayTooltip, LifetimeElisionHints,
     },
+    inline_values::{InlineValue, InlineValueKind},
     join_lines::JoinLinesConfig,
     markup::Markup,
     moniker::{MonikerDescriptorKind, MonikerKind, MonikerResult, PackageInformation},
@@ -103,13 +110,16 @@ pub use crate::{
     prime_caches::ParallelPrimeCachesProgress,
     references::ReferenceSearchResult,
     rename::RenameError,
-    runnables::{Runnable, RunnableKind, TestId},
+    runnables::{Runnable, RunnableKind, TestConfig, TestId},
     signature_help::SignatureHelp,
+    signature_search::SignatureSearchResult,
     static_index::{StaticIndex, StaticIndexedFile, TokenId, TokenStaticData},
     syntax_highlighting::{
         tags::{Highlight, HlMod, HlMods, HlOperator, HlPunct, HlTag},
         HighlightConfig, HlRange,
     },
+    test_explorer::{TestItem, TestItemKind, TestScope},
+    view_memory_layout::{MemoryLayoutNode, MemoryLayoutNodeKind, RecursiveMemoryLayout},
 };
 pub use hir::Semantics;
 pub use ide_assists::{
@@ -409,6 +419,12 @@ impl Analysis {
     /// Fuzzy searches for a symbol.
     pub fn symbol_search(&self, query: Query) -> Cancellable<Vec<NavigationTarget>> {
         self.with_db(|db| {
+            if query.is_extended() {
+                return symbol_index::world_symbols_extended(db, query)
+                    .into_iter()
+                    .filter_map(|s| s.try_to_nav(db))
+                    .collect();
+            }
             symbol_index::world_symbols(db, query)
                 .into_iter() // xx: should we make this a par iter?
                 .filter_map(|s| s.try_to_nav(db))
@@ -416,6 +432,20 @@ impl Analysis {
         })
     }
 
+    /// Searches for functions whose signature fits `pattern`, resolving its names in `scope`.
+    /// Returns `None` if the pattern isn't a valid `fn(..) -> ..` type.
+    pub fn signature_search(
+        &self,
+        pattern: &str,
+        scope: Option<FileId>,
+        include_libraries: bool,
+        limit: usize,
+    ) -> Cancellable<Option<Vec<SignatureSearchResult>>> {
+        self.with_db(|db| {
+            signature_search::signature_search(db, pattern, scope, include_libraries, limit)
+        })
+    }
+
     /// Returns the definitions from the symbol at `position`.
     pub fn goto_definition(
         &self,
@@ -489,6 +519,16 @@ impl Analysis {
         })
     }
 
+    /// Computes the values a debugger stopped at `stopped_location` shows inline in `range`.
+    pub fn inline_values(
+        &self,
+        file_id: FileId,
+        range: TextRange,
+        stopped_location: TextRange,
+    ) -> Cancellable<Vec<InlineValue>> {
+        self.with_db(|db| inline_values::inline_values(db, file_id, range, stopped_location))
+    }
+
     /// Computes parameter information at the given position.
     pub fn signature_help(&self, position: FilePosition) -> Cancellable<Option<SignatureHelp>> {
         self.with_db(|db| signature_help::signature_help(db, position))
@@ -512,6 +552,38 @@ impl Analysis {
         self.with_db(|db| call_hierarchy::outgoing_calls(db, position))
     }
 
+    /// Computes type hierarchy candidates for the given file position.
+    pub fn type_hierarchy(
+        &self,
+        position: FilePosition,
+    ) -> Cancellable<Option<RangeInfo<Vec<NavigationTarget>>>> {
+        self.with_db(|db| type_hierarchy::type_hierarchy(db, position))
+    }
+
+    /// Computes the supertypes of the trait or type at the given file position.
+    pub fn supertypes(&self, position: FilePosition) -> Cancellable<Option<Vec<NavigationTarget>>> {
+        self.with_db(|db| type_hierarchy::supertypes(db, position))
+    }
+
+    /// Computes the subtypes of the trait at the given file position.
+    pub fn subtypes(&self, position: FilePosition) -> Cancellable<Option<Vec<NavigationTarget>>> {
+        self.with_db(|db| type_hierarchy::subtypes(db, position))
+    }
+
+    /// Computes the ranges of the occurrences of the identifier at the given position that are
+    /// edited together.
+    pub fn linked_editing_ranges(
+        &self,
+        position: FilePosition,
+    ) -> Cancellable<Option<Vec<TextRange>>> {
+        self.with_db(|db| linked_editing::linked_editing_ranges(db, position))
+    }
+
+    /// Computes the links to the files and URLs the given file refers to.
+    pub fn document_links(&self, file_id: FileId) -> Cancellable<Vec<DocumentLink>> {
+        self.with_db(|db| document_links::document_links(db, file_id))
+    }
+
     /// Returns a `mod name;` declaration which created the current module.
     pub fn parent_module(&self, position: FilePosition) -> Cancellable<Vec<NavigationTarget>> {
         self.with_db(|db| parent_module::parent_module(db, position))
@@ -548,17 +620,36 @@ impl Analysis {
     }
 
     /// Returns the set of possible targets to run for the current file.
-    pub fn runnables(&self, file_id: FileId) -> Cancellable<Vec<Runnable>> {
-        self.with_db(|db| runnables::runnables(db, file_id))
+    pub fn runnables(&self, config: &TestConfig, file_id: FileId) -> Cancellable<Vec<Runnable>> {
+        self.with_db(|db| runnables::runnables(db, config, file_id))
+    }
+
+    /// Returns the tests of a crate, ordered such that modules precede their children.
+    pub fn discover_tests_in_crate(
+        &self,
+        config: &TestConfig,
+        crate_id: CrateId,
+    ) -> Cancellable<Vec<TestItem>> {
+        self.with_db(|db| test_explorer::discover_tests_in_crate(db, config, crate_id))
+    }
+
+    /// Returns the tests of the modules defined by the given file, for each crate it belongs to.
+    pub fn discover_tests_in_file(
+        &self,
+        config: &TestConfig,
+        file_id: FileId,
+    ) -> Cancellable<Vec<TestScope>> {
+        self.with_db(|db| test_explorer::discover_tests_in_file(db, config, file_id))
     }
 
     /// Returns the set of tests for the given file position.
     pub fn related_tests(
         &self,
+        config: &TestConfig,
         position: FilePosition,
         search_scope: Option<SearchScope>,
     ) -> Cancellable<Vec<Runnable>> {
-        self.with_db(|db| runnables::related_tests(db, position, search_scope))
+        self.with_db(|db| runnables::related_tests(db, config, position, search_scope))
     }
 
     /// Computes syntax highlighting for the given file
@@ -727,11 +818,23 @@ impl Analysis {
         self.with_db(|db| move_item::move_item(db, range, direction))
     }
 
+    /// Computes the memory layout of the type at `position`, substituting `generic_args` for its
+    /// generic parameters if given.
     pub fn get_recursive_memory_layout(
         &self,
         position: FilePosition,
+        generic_args: &[String],
+    ) -> Cancellable<Option<RecursiveMemoryLayout>> {
+        self.with_db(|db| view_memory_layout::view_memory_layout(db, position, generic_args))
+    }
+
+    /// Computes the memory layout of the type written as `ty` at the root of `file_id`'s crate.
+    pub fn get_recursive_memory_layout_of_type(
+        &self,
+        file_id: FileId,
+        ty: &str,
     ) -> Cancellable<Option<RecursiveMemoryLayout>> {
-        self.with_db(|db| view_memory_layout(db, position))
+        self.with_db(|db| view_memory_layout::view_memory_layout_of_type(db, file_id, ty))
     }
 
     /// Performs an operation on the database that may be canceled.
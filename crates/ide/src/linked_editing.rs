@@ -0,0 +1,255 @@
+//! Ranges of the occurrences of an identifier that are edited together.
+
+use hir::Semantics;
+use ide_db::{
+    base_db::{FilePosition, FileRange},
+    defs::{Definition, IdentClass},
+    helpers::pick_best_token,
+    search::SearchScope,
+    RootDatabase,
+};
+use syntax::{
+    algo::non_trivia_sibling,
+    ast::{self, AstNode, AstToken},
+    Direction, NodeOrToken, SyntaxKind, SyntaxToken, TextRange, T,
+};
+
+use crate::TryToNav;
+
+// Feature: Linked Editing
+//
+// Edits all occurrences of the identifier under the cursor at once, while typing. This works for
+// local bindings, labels and generic parameters within the item that declares them, and for the
+// metavariables of a `macro_rules!` arm.
+//
+// Occurrences that can't be renamed in place, like the shorthand of a record field, turn the
+// feature off, so the rename refactoring has to be used instead.
+pub(crate) fn linked_editing_ranges(
+    db: &RootDatabase,
+    FilePosition { file_id, offset }: FilePosition,
+) -> Option<Vec<TextRange>> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(file_id);
+    let token = pick_best_token(file.syntax().token_at_offset(offset), |kind| match kind {
+        SyntaxKind::IDENT | SyntaxKind::LIFETIME_IDENT => 1,
+        _ => 0,
+    })?;
+
+    if let Some(ranges) = metavariable_ranges(&token) {
+        return Some(ranges);
+    }
+
+    let def = match IdentClass::classify_token(&sema, &token)?.definitions().as_slice() {
+        &[def] => def,
+        _ => return None,
+    };
+    match def {
+        Definition::Local(local) if !local.is_self(db) => (),
+        Definition::Label(_) | Definition::GenericParam(_) => (),
+        _ => return None,
+    }
+
+    let declaration = def.try_to_nav(db)?;
+    let declaration_range = declaration.focus_range?;
+    if declaration.file_id != file_id {
+        return None;
+    }
+    let item = file
+        .syntax()
+        .covering_element(declaration_range)
+        .ancestors()
+        .find(|it| ast::Item::can_cast(it.kind()))?;
+    let scope = SearchScope::file_range(FileRange { file_id, range: item.text_range() });
+
+    let mut ranges = vec![declaration_range];
+    for (_, references) in def.usages(&sema).in_scope(&scope).all() {
+        for reference in references {
+            if is_shorthand(&reference.name) {
+                return None;
+            }
+            ranges.push(reference.range);
+        }
+    }
+    ranges.sort_by_key(|range| range.start());
+    ranges.dedup();
+
+    // Occurrences coming from macro expansions may not map back to the identifier exactly.
+    let text = file.syntax().text();
+    let name = text.slice(declaration_range).to_string();
+    if !ranges.iter().all(|&range| text.slice(range) == name.as_str()) {
+        return None;
+    }
+    Some(ranges)
+}
+
+fn is_shorthand(name: &ast::NameLike) -> bool {
+    match name {
+        ast::NameLike::NameRef(name_ref) => {
+            ast::RecordExprField::for_field_name(name_ref).is_some_and(|it| it.name_ref().is_none())
+        }
+        ast::NameLike::Name(name) => {
+            ast::RecordPatField::for_field_name(name).is_some_and(|it| it.name_ref().is_none())
+        }
+        ast::NameLike::Lifetime(_) => false,
+    }
+}
+
+/// The occurrences of the `$metavariable` under the cursor within its `macro_rules!` arm.
+fn metavariable_ranges(token: &SyntaxToken) -> Option<Vec<TextRange>> {
+    if token.kind() != SyntaxKind::IDENT || !is_metavariable(token) {
+        return None;
+    }
+    let macro_rules = token.parent_ancestors().find_map(ast::MacroRules::cast)?;
+    let body = macro_rules.token_tree()?;
+    let arm = macro_rules_arms(&body).find(|arm| arm.contains_range(token.text_range()))?;
+    let ranges = body
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .filter(|it| {
+            arm.contains_range(it.text_range())
+                && it.kind() == SyntaxKind::IDENT
+                && it.text() == token.text()
+                && is_metavariable(it)
+        })
+        .map(|it| it.text_range())
+        .collect();
+    Some(ranges)
+}
+
+fn is_metavariable(ident: &SyntaxToken) -> bool {
+    let mut prev = ident.prev_token();
+    while let Some(token) = prev.as_ref().filter(|it| ast::Whitespace::can_cast(it.kind())) {
+        prev = token.prev_token();
+    }
+    prev.is_some_and(|it| it.kind() == T![$])
+}
+
+/// The ranges of the `(matcher) => {transcriber}` arms of a `macro_rules!` body.
+fn macro_rules_arms(body: &ast::TokenTree) -> impl Iterator<Item = TextRange> {
+    let mut arms = Vec::new();
+    let mut arm_start = None;
+    let mut arm_end = None;
+    let elements = body.syntax().children_with_tokens();
+    // The first and last elements are the delimiters of the body.
+    for element in elements.skip(1) {
+        if element.kind() == T![;] || non_trivia_sibling(element.clone(), Direction::Next).is_none()
+        {
+            if let (Some(start), Some(end)) = (arm_start.take(), arm_end.take()) {
+                arms.push(TextRange::new(start, end));
+            }
+            continue;
+        }
+        if element.kind().is_trivia() {
+            continue;
+        }
+        arm_start.get_or_insert(element.text_range().start());
+        arm_end = Some(element.text_range().end());
+    }
+    arms.into_iter()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture;
+
+    fn check(ra_fixture: &str) {
+        let (analysis, position, annotations) = fixture::annotations(ra_fixture);
+        let mut expected: Vec<_> = annotations.into_iter().map(|(range, _)| range.range).collect();
+        expected.sort_by_key(|range| range.start());
+        let ranges = analysis.linked_editing_ranges(position).unwrap().unwrap_or_default();
+        assert_eq!(ranges, expected);
+    }
+
+    #[test]
+    fn local() {
+        check(
+            r#"
+fn foo(x: i32) {
+    let y$0 = x;
+     // ^
+    let z = y + y;
+         // ^   ^
+}
+fn bar() {
+    let y = 0;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn shadowing() {
+        check(
+            r#"
+fn foo() {
+    let x = 0;
+    let x$0 = x;
+     // ^
+    x;
+ // ^
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn record_shorthand_disables() {
+        check(
+            r#"
+struct S { x: i32 }
+fn foo() {
+    let x$0 = 0;
+    S { x };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn label() {
+        check(
+            r#"
+fn foo() {
+    'outer$0: loop {
+ // ^^^^^^
+        loop {
+            break 'outer;
+               // ^^^^^^
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generic_param() {
+        check(
+            r#"
+struct S<T>(T);
+fn foo<T$0: Copy>(
+    // ^
+    x: T,
+    // ^
+) -> S<T> {
+    // ^
+    S(x)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn metavariable() {
+        check(
+            r#"
+macro_rules! m {
+    ($x$0:expr, $y:expr) => { $x + $y + $ x };
+   // ^                      ^          ^
+    ($x:expr) => { $x };
+}
+"#,
+        );
+    }
+}
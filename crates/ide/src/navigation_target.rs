@@ -4,8 +4,9 @@ use std::fmt;
 
 use either::Either;
 use hir::{
-    symbols::FileSymbol, AssocItem, FieldSource, HasContainer, HasSource, HirDisplay, HirFileId,
-    InFile, LocalSource, ModuleSource,
+    symbols::{ExtendedFileSymbol, FileSymbol, InnerSymbol},
+    AssocItem, FieldSource, HasContainer, HasSource, HirDisplay, HirFileId, InFile, LocalSource,
+    ModuleSource,
 };
 use ide_db::{
     base_db::{FileId, FileRange},
@@ -204,6 +205,24 @@ impl TryToNav for FileSymbol {
     }
 }
 
+impl TryToNav for ExtendedFileSymbol {
+    fn try_to_nav(&self, db: &RootDatabase) -> Option<NavigationTarget> {
+        let mut nav = self.symbol.try_to_nav(db)?;
+        match self.inner {
+            Some(InnerSymbol::Field(it)) => {
+                nav.kind = Some(SymbolKind::Field);
+                nav.description = Some(it.display(db).to_string());
+            }
+            Some(InnerSymbol::Local(_)) => {
+                nav.kind = Some(SymbolKind::Local);
+                nav.description = None;
+            }
+            None => (),
+        }
+        Some(nav)
+    }
+}
+
 impl TryToNav for Definition {
     fn try_to_nav(&self, db: &RootDatabase) -> Option<NavigationTarget> {
         match self {
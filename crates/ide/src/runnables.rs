@@ -15,12 +15,21 @@ use ide_db::{
 use itertools::Itertools;
 use stdx::{always, format_to};
 use syntax::{
-    ast::{self, AstNode},
-    SmolStr, SyntaxNode,
+    ast::{self, AstNode, AstToken, HasAttrs as _},
+    SmolStr, SyntaxNode, TextRange, T,
 };
 
 use crate::{references, FileId, NavigationTarget, ToNav, TryToNav};
 
+/// Configures how the tests of test frameworks are recognized.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestConfig {
+    /// Paths of attribute macros that turn a function into a test, like `tokio::test`. They are
+    /// only consulted when the macro wasn't expanded, as an expansion is recognized by the
+    /// `#[test]` attribute it produces.
+    pub test_attributes: Vec<String>,
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct Runnable {
     pub use_name_in_title: bool,
@@ -120,7 +129,7 @@ impl Runnable {
 // | VS Code | **rust-analyzer: Run**
 // |===
 // image::https://user-images.githubusercontent.com/48062697/113065583-055aae80-91b1-11eb-958f-d67efcaf6a2f.gif[]
-pub(crate) fn runnables(db: &RootDatabase, file_id: FileId) -> Vec<Runnable> {
+pub(crate) fn runnables(db: &RootDatabase, config: &TestConfig, file_id: FileId) -> Vec<Runnable> {
     let sema = Semantics::new(db);
 
     let mut res = Vec::new();
@@ -152,8 +161,13 @@ pub(crate) fn runnables(db: &RootDatabase, file_id: FileId) -> Vec<Runnable> {
     };
     visit_file_defs(&sema, file_id, &mut |def| {
         let runnable = match def {
-            Definition::Module(it) => runnable_mod(&sema, it),
-            Definition::Function(it) => runnable_fn(&sema, it),
+            Definition::Module(it) => runnable_mod(&sema, config, it),
+            Definition::Function(it) => {
+                runnable_cases(&sema, config, it)
+                    .into_iter()
+                    .for_each(|it| add_opt(Some(it), None));
+                runnable_fn(&sema, config, it)
+            }
             Definition::SelfType(impl_) => runnable_impl(&sema, &impl_),
             _ => None,
         };
@@ -167,9 +181,8 @@ pub(crate) fn runnables(db: &RootDatabase, file_id: FileId) -> Vec<Runnable> {
         if let Definition::SelfType(impl_) = def {
             impl_.items(db).into_iter().for_each(|assoc| {
                 let runnable = match assoc {
-                    hir::AssocItem::Function(it) => {
-                        runnable_fn(&sema, it).or_else(|| module_def_doctest(sema.db, it.into()))
-                    }
+                    hir::AssocItem::Function(it) => runnable_fn(&sema, config, it)
+                        .or_else(|| module_def_doctest(sema.db, it.into())),
                     hir::AssocItem::Const(it) => module_def_doctest(sema.db, it.into()),
                     hir::AssocItem::TypeAlias(it) => module_def_doctest(sema.db, it.into()),
                 };
@@ -179,7 +192,7 @@ pub(crate) fn runnables(db: &RootDatabase, file_id: FileId) -> Vec<Runnable> {
     });
 
     sema.to_module_defs(file_id)
-        .map(|it| runnable_mod_outline_definition(&sema, it))
+        .map(|it| runnable_mod_outline_definition(&sema, config, it))
         .for_each(|it| add_opt(it, None));
 
     res.extend(in_macro_expansion.into_iter().flat_map(|(_, runnables)| {
@@ -206,6 +219,7 @@ pub(crate) fn runnables(db: &RootDatabase, file_id: FileId) -> Vec<Runnable> {
 // |===
 pub(crate) fn related_tests(
     db: &RootDatabase,
+    config: &TestConfig,
     position: FilePosition,
     search_scope: Option<SearchScope>,
 ) -> Vec<Runnable> {
@@ -213,13 +227,14 @@ pub(crate) fn related_tests(
     let mut res: FxHashSet<Runnable> = FxHashSet::default();
     let syntax = sema.parse(position.file_id).syntax().clone();
 
-    find_related_tests(&sema, &syntax, position, search_scope, &mut res);
+    find_related_tests(&sema, config, &syntax, position, search_scope, &mut res);
 
     res.into_iter().collect()
 }
 
 fn find_related_tests(
     sema: &Semantics<'_, RootDatabase>,
+    config: &TestConfig,
     syntax: &SyntaxNode,
     position: FilePosition,
     search_scope: Option<SearchScope>,
@@ -246,12 +261,12 @@ fn find_related_tests(
             if let Some(fn_def) =
                 sema.ancestors_with_macros(name_ref.syntax().clone()).find_map(ast::Fn::cast)
             {
-                if let Some(runnable) = as_test_runnable(sema, &fn_def) {
+                if let Some(runnable) = as_test_runnable(sema, config, &fn_def) {
                     // direct test
                     tests.insert(runnable);
-                } else if let Some(module) = parent_test_module(sema, &fn_def) {
+                } else if let Some(module) = parent_test_module(sema, config, &fn_def) {
                     // indirect test
-                    find_related_tests_in_module(sema, syntax, &fn_def, &module, tests);
+                    find_related_tests_in_module(sema, config, syntax, &fn_def, &module, tests);
                 }
             }
         }
@@ -260,6 +275,7 @@ fn find_related_tests(
 
 fn find_related_tests_in_module(
     sema: &Semantics<'_, RootDatabase>,
+    config: &TestConfig,
     syntax: &SyntaxNode,
     fn_def: &ast::Fn,
     parent_module: &hir::Module,
@@ -279,24 +295,32 @@ fn find_related_tests_in_module(
     let file_id = mod_source.file_id.original_file(sema.db);
     let mod_scope = SearchScope::file_range(FileRange { file_id, range });
     let fn_pos = FilePosition { file_id, offset: fn_name.syntax().text_range().start() };
-    find_related_tests(sema, syntax, fn_pos, Some(mod_scope), tests)
+    find_related_tests(sema, config, syntax, fn_pos, Some(mod_scope), tests)
 }
 
-fn as_test_runnable(sema: &Semantics<'_, RootDatabase>, fn_def: &ast::Fn) -> Option<Runnable> {
+fn as_test_runnable(
+    sema: &Semantics<'_, RootDatabase>,
+    config: &TestConfig,
+    fn_def: &ast::Fn,
+) -> Option<Runnable> {
     if test_related_attribute(fn_def).is_some() {
         let function = sema.to_def(fn_def)?;
-        runnable_fn(sema, function)
+        runnable_fn(sema, config, function)
     } else {
         None
     }
 }
 
-fn parent_test_module(sema: &Semantics<'_, RootDatabase>, fn_def: &ast::Fn) -> Option<hir::Module> {
+fn parent_test_module(
+    sema: &Semantics<'_, RootDatabase>,
+    config: &TestConfig,
+    fn_def: &ast::Fn,
+) -> Option<hir::Module> {
     fn_def.syntax().ancestors().find_map(|node| {
         let module = ast::Module::cast(node)?;
         let module = sema.to_def(&module)?;
 
-        if has_test_function_or_multiple_test_submodules(sema, &module) {
+        if has_test_function_or_multiple_test_submodules(sema, config, &module) {
             Some(module)
         } else {
             None
@@ -306,6 +330,7 @@ fn parent_test_module(sema: &Semantics<'_, RootDatabase>, fn_def: &ast::Fn) -> O
 
 pub(crate) fn runnable_fn(
     sema: &Semantics<'_, RootDatabase>,
+    config: &TestConfig,
     def: hir::Function,
 ) -> Option<Runnable> {
     let kind = if def.is_main(sema.db) {
@@ -327,7 +352,14 @@ pub(crate) fn runnable_fn(
         } else if def.is_bench(sema.db) {
             RunnableKind::Bench { test_id: test_id() }
         } else {
-            return None;
+            let test = framework_test(config, &def.source(sema.db)?.value)?;
+            if test.cases.is_empty() {
+                let attr = TestAttr::from_fn(sema.db, def);
+                RunnableKind::Test { test_id: test_id(), attr }
+            } else {
+                // The cases are tests of a module named after the function.
+                RunnableKind::TestMod { path: test_id().to_string() }
+            }
         }
     };
 
@@ -340,11 +372,186 @@ pub(crate) fn runnable_fn(
     Some(Runnable { use_name_in_title: false, nav, kind, cfg })
 }
 
+/// Creates a runnable for each case of a parameterised test, like `#[case(1)]` of rstest.
+fn runnable_cases(
+    sema: &Semantics<'_, RootDatabase>,
+    config: &TestConfig,
+    def: hir::Function,
+) -> Vec<Runnable> {
+    let db = sema.db;
+    let Some(src) = def.source(db) else { return Vec::new() };
+    let Some(file_id) = src.file_id.file_id() else { return Vec::new() };
+    let Some(test) = framework_test(config, &src.value) else { return Vec::new() };
+    let Some(path) = hir::ModuleDef::from(def).canonical_path(db) else { return Vec::new() };
+    let attr = TestAttr::from_fn(db, def);
+    let cfg = def.attrs(db).cfg();
+    test.cases
+        .into_iter()
+        .map(|(range, name)| {
+            let nav = NavigationTarget {
+                file_id,
+                full_range: range,
+                focus_range: None,
+                name: name.as_str().into(),
+                kind: Some(SymbolKind::Function),
+                container_name: None,
+                description: None,
+                docs: None,
+                alias: None,
+            };
+            let test_id = TestId::Path(format!("{path}::{name}"));
+            Runnable {
+                use_name_in_title: false,
+                nav,
+                kind: RunnableKind::Test { test_id, attr },
+                cfg: cfg.clone(),
+            }
+        })
+        .collect()
+}
+
+/// A test of a test framework whose attribute macro wasn't expanded.
+struct FrameworkTest {
+    /// The range and test name of each case of a parameterised test.
+    cases: Vec<(TextRange, String)>,
+}
+
+fn framework_test(config: &TestConfig, fn_def: &ast::Fn) -> Option<FrameworkTest> {
+    let mut is_test = false;
+    let mut rstest_cases = Vec::new();
+    let mut cases = Vec::new();
+    for attr in fn_def.attrs() {
+        let Some(path) = attr.path() else { continue };
+        let full_path = attr_full_path(&path);
+        if config.test_attributes.contains(&full_path) {
+            is_test = true;
+        }
+        // The `case` attribute is only an inert helper of `rstest`, so it is never imported.
+        let path = path.syntax().text().to_string();
+        if path == "case" || path.starts_with("case::") {
+            let description = path.strip_prefix("case::").map(ToOwned::to_owned);
+            rstest_cases.push((attr.syntax().text_range(), description));
+        } else if full_path == "test_case::test_case" {
+            cases.push((attr.syntax().text_range(), escape_test_name(&test_case_name(&attr))));
+        }
+    }
+    if !is_test {
+        return None;
+    }
+    // rstest numbers its cases from 1, padded to the same width.
+    let width = rstest_cases.len().to_string().len();
+    cases.extend(rstest_cases.iter().enumerate().map(|(idx, (range, description))| {
+        let mut name = format!("case_{:0width$}", idx + 1);
+        if let Some(description) = description {
+            format_to!(name, "_{description}");
+        }
+        (*range, name)
+    }));
+    cases.sort_by_key(|(range, _)| range.start());
+    Some(FrameworkTest { cases })
+}
+
+/// The full path of an attribute, following the `use` item that imports a single segment path,
+/// like `rstest::rstest` for `#[rstest]` after `use rstest::rstest;`.
+fn attr_full_path(path: &ast::Path) -> String {
+    let text = path.syntax().text().to_string();
+    let text = text.trim_start_matches("::");
+    let Some(name) =
+        path.segment().and_then(|it| it.name_ref()).filter(|_| path.qualifier().is_none())
+    else {
+        return text.to_owned();
+    };
+    let use_trees = path
+        .syntax()
+        .ancestors()
+        .flat_map(|it| it.children().filter_map(ast::Use::cast))
+        .filter_map(|it| it.use_tree())
+        .flat_map(|it| {
+            it.syntax().descendants().filter_map(ast::UseTree::cast).collect::<Vec<_>>()
+        });
+    for use_tree in use_trees {
+        if use_tree.use_tree_list().is_some() || use_tree.star_token().is_some() {
+            continue;
+        }
+        let imported = match use_tree.rename() {
+            Some(rename) => rename.name().map(|it| it.text().to_string()),
+            None => use_tree
+                .path()
+                .and_then(|it| it.segment()?.name_ref())
+                .map(|it| it.text().to_string()),
+        };
+        if imported.as_deref() != Some(name.text().as_str()) {
+            continue;
+        }
+        let segments = use_tree
+            .syntax()
+            .ancestors()
+            .filter_map(ast::UseTree::cast)
+            .filter_map(|it| it.path())
+            .map(|it| it.syntax().text().to_string())
+            .collect::<Vec<_>>();
+        return segments.iter().rev().map(|it| it.trim_start_matches("::")).join("::");
+    }
+    text.to_owned()
+}
+
+/// The text test-case derives the name of a `#[test_case(args => expected ; "description")]` from:
+/// its description, or else its arguments and expected result.
+fn test_case_name(attr: &ast::Attr) -> String {
+    let Some(tt) = attr.token_tree() else { return String::new() };
+    // The tokens between the outer parentheses, including those of nested token trees.
+    let tokens =
+        tt.syntax().descendants_with_tokens().filter_map(|it| it.into_token()).collect::<Vec<_>>();
+    let mut tokens = tokens.iter().skip(1).take(tokens.len().saturating_sub(2)).cloned();
+    let mut args = String::new();
+    while let Some(token) = tokens.next() {
+        if token.kind() == T![;] {
+            let description = tokens.find(|it| !it.kind().is_trivia());
+            if let Some(description) = description.and_then(ast::String::cast) {
+                if let Some(description) = description.value() {
+                    return description.into_owned();
+                }
+            }
+            break;
+        }
+        args.push_str(token.text());
+    }
+    match args.split_once("=>") {
+        Some((args, expected)) => format!("{args} expects {expected}"),
+        None => format!("{args} expects"),
+    }
+}
+
+/// Mirrors how test-case turns the description of a case into the name of its test.
+fn escape_test_name(description: &str) -> String {
+    let mut last_under = false;
+    let mut name: String = description
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| {
+            if c.is_alphanumeric() {
+                last_under = false;
+                Some(c)
+            } else if !last_under {
+                last_under = true;
+                Some('_')
+            } else {
+                None
+            }
+        })
+        .collect();
+    if !name.starts_with(|c: char| c == '_' || c.is_ascii_alphabetic()) {
+        name.insert(0, '_');
+    }
+    name
+}
+
 pub(crate) fn runnable_mod(
     sema: &Semantics<'_, RootDatabase>,
+    config: &TestConfig,
     def: hir::Module,
 ) -> Option<Runnable> {
-    if !has_test_function_or_multiple_test_submodules(sema, &def) {
+    if !has_test_function_or_multiple_test_submodules(sema, config, &def) {
         return None;
     }
     let path = def
@@ -389,9 +596,10 @@ pub(crate) fn runnable_impl(
 /// Creates a test mod runnable for outline modules at the top of their definition.
 fn runnable_mod_outline_definition(
     sema: &Semantics<'_, RootDatabase>,
+    config: &TestConfig,
     def: hir::Module,
 ) -> Option<Runnable> {
-    if !has_test_function_or_multiple_test_submodules(sema, &def) {
+    if !has_test_function_or_multiple_test_submodules(sema, config, &def) {
         return None;
     }
     let path = def
@@ -520,8 +728,11 @@ fn has_runnable_doc_test(attrs: &hir::Attrs) -> bool {
 
 // We could create runnables for modules with number_of_test_submodules > 0,
 // but that bloats the runnables for no real benefit, since all tests can be run by the submodule already
+/// Whether the module declares a test, looking at the items produced by macros like `proptest!`
+/// as well, or multiple submodules with tests.
 fn has_test_function_or_multiple_test_submodules(
     sema: &Semantics<'_, RootDatabase>,
+    config: &TestConfig,
     module: &hir::Module,
 ) -> bool {
     let mut number_of_test_submodules = 0;
@@ -529,16 +740,21 @@ fn has_test_function_or_multiple_test_submodules(
     for item in module.declarations(sema.db) {
         match item {
             hir::ModuleDef::Function(f) => {
+                if f.is_test(sema.db) {
+                    return true;
+                }
                 if let Some(it) = f.source(sema.db) {
-                    if test_related_attribute(&it.value).is_some() {
+                    if test_related_attribute(&it.value).is_some()
+                        || framework_test(config, &it.value).is_some()
+                    {
                         return true;
                     }
                 }
             }
-            hir::ModuleDef::Module(submodule) => {
-                if has_test_function_or_multiple_test_submodules(sema, &submodule) {
-                    number_of_test_submodules += 1;
-                }
+            hir::ModuleDef::Module(submodule)
+                if has_test_function_or_multiple_test_submodules(sema, config, &submodule) =>
+            {
+                number_of_test_submodules += 1;
             }
             _ => (),
         }
@@ -555,6 +771,14 @@ mod tests {
 
     use super::{RunnableTestKind::*, *};
 
+    fn test_config() -> TestConfig {
+        TestConfig {
+            test_attributes: ["tokio::test", "rstest::rstest", "test_case::test_case"]
+                .map(ToOwned::to_owned)
+                .to_vec(),
+        }
+    }
+
     fn check(
         ra_fixture: &str,
         // FIXME: fold this into `expect` as well
@@ -562,7 +786,7 @@ mod tests {
         expect: Expect,
     ) {
         let (analysis, position) = fixture::position(ra_fixture);
-        let mut runnables = analysis.runnables(position.file_id).unwrap();
+        let mut runnables = analysis.runnables(&test_config(), position.file_id).unwrap();
         runnables.sort_by_key(|it| (it.nav.full_range.start(), it.nav.name.clone()));
         expect.assert_debug_eq(&runnables);
         assert_eq!(
@@ -573,7 +797,7 @@ mod tests {
 
     fn check_tests(ra_fixture: &str, expect: Expect) {
         let (analysis, position) = fixture::position(ra_fixture);
-        let tests = analysis.related_tests(position, None).unwrap();
+        let tests = analysis.related_tests(&test_config(), position, None).unwrap();
         expect.assert_debug_eq(&tests);
     }
 
@@ -737,6 +961,409 @@ mod not_a_root {
         );
     }
 
+    #[test]
+    fn test_runnables_test_frameworks() {
+        check(
+            r#"
+//- /lib.rs
+$0
+use rstest::rstest;
+
+#[tokio::test]
+async fn async_test() {}
+
+#[rstest]
+#[case(1)]
+#[case::two(2)]
+fn parameterised(#[case] x: u32) {}
+
+#[test_case::test_case(1 ; "the first case")]
+#[test_case::test_case(2)]
+fn described(x: u32) {}
+
+#[not_a_test]
+fn not_a_test() {}
+"#,
+            &[TestMod, Test, TestMod, Test, Test, TestMod, Test, Test],
+            expect![[r#"
+                [
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 0..268,
+                            name: "",
+                            kind: Module,
+                        },
+                        kind: TestMod {
+                            path: "",
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 22..61,
+                            focus_range: 46..56,
+                            name: "async_test",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "async_test",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 63..135,
+                            focus_range: 103..116,
+                            name: "parameterised",
+                            kind: Function,
+                        },
+                        kind: TestMod {
+                            path: "parameterised",
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 73..83,
+                            name: "case_1",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "parameterised::case_1",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 84..99,
+                            name: "case_2_two",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "parameterised::case_2_two",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 137..233,
+                            focus_range: 213..222,
+                            name: "described",
+                            kind: Function,
+                        },
+                        kind: TestMod {
+                            path: "described",
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 137..182,
+                            name: "the_first_case",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "described::the_first_case",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 183..209,
+                            name: "_2_expects",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "described::_2_expects",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_runnables_test_framework_paths() {
+        check(
+            r#"
+//- /lib.rs
+$0
+use tokio::test as tokio_test;
+
+#[tokio_test]
+async fn renamed() {}
+
+#[other::test]
+async fn other_framework() {}
+
+#[test_case::test_case(1, 2 => 3)]
+fn undescribed(x: u32, y: u32) -> u32 { x + y }
+"#,
+            &[TestMod, Test, Test, TestMod],
+            expect![[r#"
+                [
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 0..199,
+                            name: "",
+                            kind: Module,
+                        },
+                        kind: TestMod {
+                            path: "",
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 33..68,
+                            focus_range: 56..63,
+                            name: "renamed",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "renamed",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 116..150,
+                            name: "_1_2_expects_3",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "undescribed::_1_2_expects_3",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 116..198,
+                            focus_range: 154..165,
+                            name: "undescribed",
+                            kind: Function,
+                        },
+                        kind: TestMod {
+                            path: "undescribed",
+                        },
+                        cfg: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_runnables_macro_generated_tests() {
+        check(
+            r#"
+//- /lib.rs
+$0
+macro_rules! proptest {
+    ($(#[test] fn $name:ident($arg:ident in $strategy:expr) $body:block)*) => {
+        $(#[test] fn $name() { let $arg = $strategy; $body })*
+    };
+}
+macro_rules! cases {
+    ($name:ident) => {
+        mod $name {
+            #[test]
+            fn case_1() {}
+        }
+    };
+}
+
+mod tests {
+    proptest! {
+        #[test]
+        fn doesnt_crash(s in 0..10) {}
+    }
+}
+
+cases!(generated);
+"#,
+            &[TestMod, TestMod, Test, Test, TestMod],
+            expect![[r#"
+                [
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 0..419,
+                            name: "",
+                            kind: Module,
+                        },
+                        kind: TestMod {
+                            path: "",
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 308..398,
+                            focus_range: 312..317,
+                            name: "tests",
+                            kind: Module,
+                            description: "mod tests",
+                        },
+                        kind: TestMod {
+                            path: "tests",
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 324..396,
+                            focus_range: 363..375,
+                            name: "doesnt_crash",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "tests::doesnt_crash",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: true,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 400..418,
+                            name: "case_1",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "generated::case_1",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: true,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 400..418,
+                            focus_range: 407..416,
+                            name: "generated",
+                            kind: Module,
+                            description: "mod generated",
+                        },
+                        kind: TestMod {
+                            path: "generated",
+                        },
+                        cfg: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
     #[test]
     fn test_runnables_doc_test() {
         check(
@@ -0,0 +1,320 @@
+//! Searching functions and methods by their type signatures.
+
+use hir::{AssocItem, Crate, Function, HirDisplay, ModuleDef, Semantics};
+use ide_db::{
+    base_db::{salsa::Database, FileId},
+    RootDatabase,
+};
+use syntax::{AstNode, TextSize};
+
+use crate::{NavigationTarget, TryToNav};
+
+#[derive(Debug)]
+pub struct SignatureSearchResult {
+    pub navigation_target: NavigationTarget,
+    /// The signature of the function, rendered like in hover.
+    pub signature: String,
+}
+
+// Feature: Signature Search
+//
+// Finds the functions and methods of the workspace and its dependencies whose signature fits a
+// pattern, like `fn(&str) -> Result<Url, _>`. `_` stands for any type, and generic parameters
+// for consistently the same type: `for<T> fn(&[T]) -> Option<&T>`, which can also be written
+// `<T> fn(&[T]) -> Option<&T>`. The leading `fn` can be omitted. Methods are matched with their
+// receiver as the first parameter.
+//
+// The names in the pattern are resolved at the top of the given file, or at the root of the
+// first workspace crate. Names that can't be resolved match any type of that name. Generic
+// functions match if their generic parameters can be instantiated to fit the pattern, and
+// references on the parameters and on the return type are ignored.
+//
+// Exact matches come first, then those needing fewer instantiated generics or ignored references,
+// with functions of the workspace before those of dependencies. Functions taking and returning
+// nothing but generic parameters, like `transmute`, fit almost any pattern and come last.
+//
+// The search is available with the `rust-analyzer/signatureSearch` LSP request and with the
+// `rust-analyzer signature-search` command.
+pub(crate) fn signature_search(
+    db: &RootDatabase,
+    pattern: &str,
+    scope: Option<FileId>,
+    include_libraries: bool,
+    limit: usize,
+) -> Option<Vec<SignatureSearchResult>> {
+    let sema = Semantics::new(db);
+    let scope = scope.or_else(|| {
+        let krate = Crate::all(db).into_iter().find(|krate| krate.origin(db).is_local())?;
+        Some(krate.root_module().definition_source_file_id(db).original_file(db))
+    })?;
+    let file = sema.parse(scope);
+    let scope = sema.scope_at_offset(file.syntax(), TextSize::from(0))?;
+    let pattern = scope.signature_pattern(&parse_pattern(pattern)?)?;
+
+    let mut matches = Vec::new();
+    for krate in Crate::all(db) {
+        let is_local = krate.origin(db).is_local();
+        if !is_local && !include_libraries {
+            continue;
+        }
+        for func in functions(db, krate) {
+            db.unwind_if_cancelled();
+            if func.num_params(db) != pattern.arity() {
+                continue;
+            }
+            if let Some(distance) = pattern.matches(db, func) {
+                let key =
+                    (is_catch_all(db, func), distance, !is_local, func.name(db).to_smol_str());
+                matches.push((key, func));
+            }
+        }
+    }
+    matches.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+
+    let results = matches
+        .into_iter()
+        .filter_map(|(_, func)| {
+            Some(SignatureSearchResult {
+                navigation_target: func.try_to_nav(db)?,
+                signature: func.display(db).to_string(),
+            })
+        })
+        .take(limit)
+        .collect();
+    Some(results)
+}
+
+/// Parses a pattern, accepting `<T> fn(..)` for `for<T> fn(..)` and leaving out the `fn`.
+fn parse_pattern(pattern: &str) -> Option<syntax::ast::Type> {
+    let pattern = pattern.trim();
+    let (generics, fn_ty) = match pattern.strip_prefix("for").unwrap_or(pattern).trim_start() {
+        it if it.starts_with('<') => {
+            let end = closing_angle(it)? + 1;
+            (format!("for{} ", &it[..end]), it[end..].trim_start())
+        }
+        _ => (String::new(), pattern),
+    };
+    let fn_ty = fn_ty.strip_prefix("fn").unwrap_or(fn_ty).trim_start();
+    syntax::hacks::parse_ty_from_str(&format!("{generics}fn{fn_ty}"))
+}
+
+/// Returns the index of the `>` closing the `<` that `text` starts with, skipping the arrows of
+/// `Fn(..) -> ..` bounds.
+fn closing_angle(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut prev = None;
+    for (idx, c) in text.char_indices() {
+        let is_arrow = prev == Some('-');
+        prev = Some(c);
+        match c {
+            '<' => depth += 1,
+            '>' if !is_arrow => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(idx);
+                }
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+/// Whether all parameters and the return type of `func` are bare generic parameters, as in
+/// `transmute<Src, Dst>(Src) -> Dst`. Such functions match any pattern of their arity.
+fn is_catch_all(db: &RootDatabase, func: Function) -> bool {
+    func.ret_type(db).strip_references().as_type_param(db).is_some()
+        && func
+            .assoc_fn_params(db)
+            .iter()
+            .all(|param| param.ty().strip_references().as_type_param(db).is_some())
+}
+
+/// All functions of `krate`, including associated functions of its impls and traits.
+fn functions(db: &RootDatabase, krate: Crate) -> Vec<Function> {
+    let mut functions = Vec::new();
+    for module in krate.modules(db) {
+        let mut assoc_items = Vec::new();
+        for decl in module.declarations(db) {
+            match decl {
+                ModuleDef::Function(it) => functions.push(it),
+                ModuleDef::Trait(it) => assoc_items.extend(it.items(db)),
+                _ => (),
+            }
+        }
+        assoc_items.extend(module.impl_defs(db).into_iter().flat_map(|it| it.items(db)));
+        functions.extend(assoc_items.into_iter().filter_map(|it| match it {
+            AssocItem::Function(it) => Some(it),
+            _ => None,
+        }));
+    }
+    functions
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use itertools::Itertools;
+
+    use crate::fixture;
+
+    fn check(ra_fixture: &str, pattern: &str, expect: Expect) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let results =
+            analysis.signature_search(pattern, Some(file_id), true, 128).unwrap().unwrap();
+        expect.assert_eq(&results.iter().map(|it| &it.signature).join("\n"));
+    }
+
+    #[test]
+    fn concrete_types_and_wildcards() {
+        check(
+            r#"
+//- minicore: result
+pub struct Url;
+pub struct Error;
+pub fn parse(s: &str) -> Result<Url, Error> { loop {} }
+pub fn parse_lossy(s: &str) -> Url { loop {} }
+pub fn len(s: &str) -> usize { 0 }
+"#,
+            "fn(&str) -> Result<Url, _>",
+            expect!["pub fn parse(s: &str) -> Result<Url, Error>"],
+        );
+    }
+
+    #[test]
+    fn ignores_references() {
+        check(
+            r#"
+pub struct Url;
+pub fn by_ref(url: &Url) -> u32 { 0 }
+pub fn by_value(url: Url) -> u32 { 0 }
+pub fn other(url: Url) -> i32 { 0 }
+"#,
+            "(Url) -> u32",
+            expect![[r#"
+                pub fn by_value(url: Url) -> u32
+                pub fn by_ref(url: &Url) -> u32"#]],
+        );
+    }
+
+    #[test]
+    fn instantiates_generics() {
+        check(
+            r#"
+//- minicore: option
+pub fn first<T>(items: &[T]) -> Option<&T> { None }
+pub fn first_u8(items: &[u8]) -> Option<&u8> { None }
+pub fn mismatch<T, U>(items: &[T]) -> Option<&U> { None }
+"#,
+            "<T> fn(&[T]) -> Option<&T>",
+            expect![[r#"
+                pub fn first<T>(items: &[T]) -> Option<&T>
+                pub fn mismatch<T, U>(items: &[T]) -> Option<&U>
+                pub fn first_u8(items: &[u8]) -> Option<&u8>
+                pub unsafe fn transmute<Src, Dst>(src: Src) -> Dst"#]],
+        );
+        check(
+            r#"
+//- minicore: option
+pub fn first<T>(items: &[T]) -> Option<&T> { None }
+pub fn first_u8(items: &[u8]) -> Option<&u8> { None }
+"#,
+            "fn(&[u8]) -> Option<&u8>",
+            expect![[r#"
+                pub fn first_u8(items: &[u8]) -> Option<&u8>
+                pub fn first<T>(items: &[T]) -> Option<&T>
+                pub unsafe fn transmute<Src, Dst>(src: Src) -> Dst"#]],
+        );
+    }
+
+    #[test]
+    fn ranks_catch_alls_last() {
+        check(
+            r#"
+//- minicore: option
+pub fn zero_extend<T>(x: &T) -> &u64 { loop {} }
+"#,
+            "fn(u32) -> u64",
+            expect![[r#"
+                pub fn zero_extend<T>(x: &T) -> &u64
+                pub unsafe fn transmute<Src, Dst>(src: Src) -> Dst"#]],
+        );
+    }
+
+    #[test]
+    fn nested_generic_parameters() {
+        check(
+            r#"
+//- minicore: option
+pub fn fill<T, U>(items: &mut [U], value: T) -> Option<U> { None }
+pub fn fill_all<T>(items: &mut [T], value: T) -> Option<T> { None }
+"#,
+            "<T: Into<U>, U> fn(&mut [U], T) -> Option<U>",
+            expect![[r#"
+                pub fn fill<T, U>(items: &mut [U], value: T) -> Option<U>
+                pub fn fill_all<T>(items: &mut [T], value: T) -> Option<T>"#]],
+        );
+    }
+
+    #[test]
+    fn fn_trait_bounds() {
+        check(
+            r#"
+pub fn apply<F: Fn(u8) -> u8>(f: F) -> u8 { f(0) }
+pub fn apply_twice<F>(f: F) -> u8 where F: Fn(u8) -> u8 { f(f(0)) }
+pub fn len(s: &str) -> u8 { 0 }
+"#,
+            "<F: Fn(u8) -> u8> fn(F) -> u8",
+            expect![[r#"
+                pub fn apply<F>(f: F) -> u8
+                where
+                    F: Fn(u8) -> u8,
+                pub fn apply_twice<F>(f: F) -> u8
+                where
+                    F: Fn(u8) -> u8,
+                pub fn len(s: &str) -> u8"#]],
+        );
+    }
+
+    #[test]
+    fn methods_and_unresolved_names() {
+        check(
+            r#"
+mod url {
+    pub struct Url;
+    impl Url {
+        pub fn host(&self) -> &str { "" }
+        pub fn port(&self) -> u16 { 0 }
+    }
+}
+pub struct Uri;
+impl Uri {
+    pub fn host(&self) -> &str { "" }
+}
+trait Host {
+    fn host(&self) -> &str;
+}
+"#,
+            "fn(&Url) -> &str",
+            expect![[r#"
+                pub fn host(&self) -> &str
+                fn host(&self) -> &str"#]],
+        );
+    }
+
+    #[test]
+    fn prefers_local_crates() {
+        check(
+            r#"
+//- /main.rs crate:main deps:dep
+pub fn local(x: u32) -> u32 { x }
+//- /dep.rs crate:dep library
+pub fn dependency(x: u32) -> u32 { x }
+"#,
+            "fn(u32) -> u32",
+            expect![[r#"
+                pub fn local(x: u32) -> u32
+                pub fn dependency(x: u32) -> u32"#]],
+        );
+    }
+}
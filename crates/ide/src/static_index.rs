@@ -18,7 +18,7 @@ use crate::{
     inlay_hints::AdjustmentHintsMode,
     moniker::{def_to_moniker, MonikerResult},
     parent_module::crates_for,
-    Analysis, Fold, HoverConfig, HoverResult, InlayHint, InlayHintsConfig, TryToNav,
+    Analysis, Fold, HoverConfig, HoverResult, InlayHint, InlayHintsConfig, TestConfig, TryToNav,
 };
 
 /// A static representation of fully analyzed source code.
@@ -125,7 +125,11 @@ impl StaticIndex<'_> {
                     binding_mode_hints: false,
                     max_length: Some(25),
                     closure_capture_hints: false,
+                    drop_hints: false,
+                    implicit_trait_call_hints: false,
+                    generic_arg_hints: false,
                     closing_brace_hints_min_lines: Some(25),
+                    generic_arg_hints_max_length: Some(25),
                     fields_to_resolve: InlayFieldsToResolve::empty(),
                 },
                 file_id,
@@ -145,6 +149,7 @@ impl StaticIndex<'_> {
             documentation: true,
             keywords: true,
             format: crate::HoverDocFormat::Markdown,
+            test_config: TestConfig { test_attributes: Vec::new() },
         };
         let tokens = tokens.filter(|token| {
             matches!(
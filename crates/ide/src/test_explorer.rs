@@ -0,0 +1,217 @@
+//! Discovers the tests of a crate as a tree of modules and test functions, for clients that
+//! show the tests of a workspace in a dedicated view.
+
+use hir::Semantics;
+use ide_db::{base_db::CrateId, RootDatabase};
+use itertools::Itertools;
+
+use crate::{
+    runnables::{runnable_fn, runnable_mod},
+    FileId, NavigationTarget, Runnable, RunnableKind, TestConfig,
+};
+
+#[derive(Debug)]
+pub struct TestItem {
+    /// The path of the item relative to the crate root, like `tests::it_works`. This is the
+    /// name the test harness reports the test under.
+    pub path: String,
+    /// The path of the module containing the item, or `None` for items of the crate root.
+    pub parent: Option<String>,
+    pub kind: TestItemKind,
+    pub label: String,
+    pub nav: NavigationTarget,
+    pub runnable: Option<Runnable>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestItemKind {
+    Module,
+    Function,
+}
+
+/// The tests found in a file, replacing everything previously known below `module`.
+#[derive(Debug)]
+pub struct TestScope {
+    pub crate_id: CrateId,
+    /// The path of the module defined by the file, or `None` if it is the crate root.
+    pub module: Option<String>,
+    pub tests: Vec<TestItem>,
+}
+
+pub(crate) fn discover_tests_in_crate(
+    db: &RootDatabase,
+    config: &TestConfig,
+    crate_id: CrateId,
+) -> Vec<TestItem> {
+    let sema = Semantics::new(db);
+    let root = hir::Crate::from(crate_id).root_module();
+    let mut res = Vec::new();
+    collect_module(&sema, config, root, None, &mut res);
+    res
+}
+
+pub(crate) fn discover_tests_in_file(
+    db: &RootDatabase,
+    config: &TestConfig,
+    file_id: FileId,
+) -> Vec<TestScope> {
+    let sema = Semantics::new(db);
+    sema.to_module_defs(file_id)
+        .map(|module| {
+            let path = module_path(db, module);
+            let mut tests = Vec::new();
+            collect_module(&sema, config, module, path.as_deref(), &mut tests);
+            TestScope { crate_id: module.krate().into(), module: path, tests }
+        })
+        .collect()
+}
+
+/// Collects the tests declared in `module` and the submodules containing tests, returning
+/// whether there were any.
+fn collect_module(
+    sema: &Semantics<'_, RootDatabase>,
+    config: &TestConfig,
+    module: hir::Module,
+    path: Option<&str>,
+    acc: &mut Vec<TestItem>,
+) -> bool {
+    let db = sema.db;
+    let join = |name: &hir::Name| match path {
+        Some(path) => format!("{path}::{}", name.display(db)),
+        None => name.display(db).to_string(),
+    };
+    let start = acc.len();
+    for def in module.declarations(db) {
+        match def {
+            hir::ModuleDef::Function(it) => {
+                let Some(runnable) = runnable_fn(sema, config, it) else { continue };
+                if !matches!(
+                    runnable.kind,
+                    RunnableKind::Test { .. } | RunnableKind::TestMod { .. }
+                ) {
+                    continue;
+                }
+                acc.push(TestItem {
+                    path: join(&it.name(db)),
+                    parent: path.map(ToOwned::to_owned),
+                    kind: TestItemKind::Function,
+                    label: it.name(db).display(db).to_string(),
+                    nav: runnable.nav.clone(),
+                    runnable: Some(runnable),
+                });
+            }
+            hir::ModuleDef::Module(submodule) => {
+                let Some(name) = submodule.name(db) else { continue };
+                let submodule_path = join(&name);
+                let idx = acc.len();
+                if collect_module(sema, config, submodule, Some(&submodule_path), acc) {
+                    let item = TestItem {
+                        path: submodule_path,
+                        parent: path.map(ToOwned::to_owned),
+                        kind: TestItemKind::Module,
+                        label: name.display(db).to_string(),
+                        nav: NavigationTarget::from_module_to_decl(db, submodule),
+                        runnable: runnable_mod(sema, config, submodule),
+                    };
+                    acc.insert(idx, item);
+                }
+            }
+            _ => (),
+        }
+    }
+    acc.len() != start
+}
+
+fn module_path(db: &RootDatabase, module: hir::Module) -> Option<String> {
+    let path = module
+        .path_to_root(db)
+        .into_iter()
+        .rev()
+        .filter_map(|it| it.name(db))
+        .map(|it| it.display(db).to_string())
+        .join("::");
+    (!path.is_empty()).then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use stdx::format_to;
+
+    use crate::{fixture, TestConfig};
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let crate_id = analysis.crates_for(file_id).unwrap()[0];
+        let mut actual = String::new();
+        let config = TestConfig { test_attributes: Vec::new() };
+        for item in analysis.discover_tests_in_crate(&config, crate_id).unwrap() {
+            format_to!(
+                actual,
+                "{:?} {} (parent: {}) {:?}\n",
+                item.kind,
+                item.path,
+                item.parent.as_deref().unwrap_or("-"),
+                item.nav.full_range
+            );
+        }
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn discovers_nested_tests() {
+        check(
+            r#"
+//- /lib.rs
+#[test]
+fn top_level() {}
+
+fn not_a_test() {}
+
+mod no_tests {
+    fn helper() {}
+}
+
+mod outer {
+    mod inner {
+        #[test]
+        fn inner_test() {}
+    }
+
+    #[test]
+    fn outer_test() {}
+}
+"#,
+            expect![[r#"
+                Module outer (parent: -) 84..198
+                Module outer::inner (parent: outer) 100..160
+                Function outer::inner::inner_test (parent: outer::inner) 120..154
+                Function outer::outer_test (parent: outer) 166..196
+                Function top_level (parent: -) 0..25
+            "#]],
+        );
+    }
+
+    #[test]
+    fn discovers_tests_in_file() {
+        let (analysis, position) = fixture::position(
+            r#"
+//- /lib.rs
+mod foo;
+//- /foo.rs
+$0#[test]
+fn in_foo() {}
+mod bar {
+    #[test]
+    fn in_bar() {}
+}
+"#,
+        );
+        let config = TestConfig { test_attributes: Vec::new() };
+        let scopes = analysis.discover_tests_in_file(&config, position.file_id).unwrap();
+        let [scope] = &scopes[..] else { panic!("{scopes:?}") };
+        assert_eq!(scope.module.as_deref(), Some("foo"));
+        let paths = scope.tests.iter().map(|it| it.path.as_str()).collect::<Vec<_>>();
+        assert_eq!(paths, ["foo::bar", "foo::bar::in_bar", "foo::in_foo"]);
+    }
+}
@@ -0,0 +1,238 @@
+//! Entry point for type-hierarchy
+
+use hir::{Adt, Impl, Semantics, Trait};
+use ide_db::{
+    defs::{Definition, NameClass, NameRefClass},
+    helpers::pick_best_token,
+    search::FileReference,
+    RootDatabase,
+};
+use itertools::Itertools;
+use syntax::{ast, AstNode, SyntaxKind::IDENT, TextRange};
+
+use crate::{FilePosition, NavigationTarget, RangeInfo, TryToNav};
+
+/// An item that can be the root of a type hierarchy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum HierarchyItem {
+    Trait(Trait),
+    Adt(Adt),
+}
+
+impl HierarchyItem {
+    fn to_nav(self, db: &RootDatabase) -> Option<NavigationTarget> {
+        match self {
+            HierarchyItem::Trait(it) => it.try_to_nav(db),
+            HierarchyItem::Adt(it) => it.try_to_nav(db),
+        }
+    }
+}
+
+pub(crate) fn type_hierarchy(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let sema = Semantics::new(db);
+    let (range, items) = items_at(&sema, position)?;
+    let navs = items.into_iter().filter_map(|it| it.to_nav(db)).collect();
+    Some(RangeInfo { range, info: navs })
+}
+
+/// For a trait, its direct supertraits. For a type, the traits it implements, including those
+/// implemented through blanket impls.
+pub(crate) fn supertypes(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<Vec<NavigationTarget>> {
+    let sema = Semantics::new(db);
+    let (_, items) = items_at(&sema, position)?;
+    let traits = items.into_iter().flat_map(|item| match item {
+        HierarchyItem::Trait(trait_) => trait_.direct_supertraits(db),
+        HierarchyItem::Adt(adt) => implemented_traits(db, adt),
+    });
+    Some(traits.unique().filter_map(|it| it.try_to_nav(db)).collect())
+}
+
+/// For a trait, the traits that name it as a supertrait and the types implementing it. Blanket
+/// impls of the trait are listed as themselves. Types have no subtypes.
+pub(crate) fn subtypes(db: &RootDatabase, position: FilePosition) -> Option<Vec<NavigationTarget>> {
+    let sema = Semantics::new(db);
+    let (_, items) = items_at(&sema, position)?;
+    let mut res = Vec::new();
+    for item in items {
+        let HierarchyItem::Trait(trait_) = item else { continue };
+        res.extend(subtraits(&sema, trait_).into_iter().filter_map(|it| it.try_to_nav(db)));
+        res.extend(Impl::all_for_trait(db, trait_).into_iter().filter_map(|imp| {
+            match imp.self_ty(db).as_adt() {
+                Some(adt) => adt.try_to_nav(db),
+                None => imp.try_to_nav(db),
+            }
+        }));
+    }
+    Some(res.into_iter().unique_by(|nav| (nav.file_id, nav.full_range)).collect())
+}
+
+fn items_at(
+    sema: &Semantics<'_, RootDatabase>,
+    FilePosition { file_id, offset }: FilePosition,
+) -> Option<(TextRange, Vec<HierarchyItem>)> {
+    let file = sema.parse(file_id);
+    let token = pick_best_token(file.syntax().token_at_offset(offset), |kind| match kind {
+        IDENT => 1,
+        _ => 0,
+    })?;
+    let range = token.text_range();
+    let items = sema
+        .descend_into_macros(token, offset)
+        .into_iter()
+        .filter_map(|token| token.parent().and_then(ast::NameLike::cast))
+        .filter_map(|node| match &node {
+            ast::NameLike::Name(name) => match NameClass::classify(sema, name)? {
+                NameClass::Definition(it) => Some(it),
+                _ => None,
+            },
+            ast::NameLike::NameRef(name_ref) => match NameRefClass::classify(sema, name_ref)? {
+                NameRefClass::Definition(it) => Some(it),
+                _ => None,
+            },
+            ast::NameLike::Lifetime(_) => None,
+        })
+        .filter_map(|def| match def {
+            Definition::Trait(it) => Some(HierarchyItem::Trait(it)),
+            Definition::Adt(it) => Some(HierarchyItem::Adt(it)),
+            _ => None,
+        })
+        .unique()
+        .collect();
+    Some((range, items))
+}
+
+fn implemented_traits(db: &RootDatabase, adt: Adt) -> Vec<Trait> {
+    let ty = adt.ty(db);
+    let mut res: Vec<_> =
+        Impl::all_for_type(db, ty.clone()).into_iter().filter_map(|imp| imp.trait_(db)).collect();
+
+    // Blanket impls can live in any crate that can see both the trait and the type.
+    let krate = adt.module(db).krate();
+    let crates = krate.transitive_dependencies(db).chain(krate.transitive_reverse_dependencies(db));
+    for krate in crates.unique() {
+        res.extend(Impl::blanket_impls_in_crate(db, krate).into_iter().filter_map(|imp| {
+            let trait_ = imp.trait_(db)?;
+            // The arguments of generic traits can't be inferred from the type alone.
+            if trait_.type_or_const_param_count(db, false) != 0 {
+                return None;
+            }
+            ty.impls_trait(db, trait_, &[]).then_some(trait_)
+        }));
+    }
+    res
+}
+
+fn subtraits(sema: &Semantics<'_, RootDatabase>, trait_: Trait) -> Vec<Trait> {
+    let mut res = Vec::new();
+    for (_, references) in Definition::Trait(trait_).usages(sema).all() {
+        for FileReference { name, .. } in references {
+            let Some(name) = name.as_name_ref() else { continue };
+            let subtrait = sema
+                .ancestors_with_macros(name.syntax().clone())
+                .find_map(ast::Trait::cast)
+                .and_then(|it| sema.to_def(&it));
+            if let Some(subtrait) = subtrait {
+                if subtrait.direct_supertraits(sema.db).contains(&trait_) {
+                    res.push(subtrait);
+                }
+            }
+        }
+    }
+    res.into_iter().unique().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use itertools::Itertools;
+
+    use crate::fixture;
+
+    fn check_hierarchy(
+        ra_fixture: &str,
+        expected: Expect,
+        expected_supertypes: Expect,
+        expected_subtypes: Expect,
+    ) {
+        let (analysis, pos) = fixture::position(ra_fixture);
+
+        let navs = analysis.type_hierarchy(pos).unwrap().unwrap().info;
+        expected.assert_eq(&navs.iter().map(|nav| nav.debug_render()).join("\n"));
+
+        let supertypes = analysis.supertypes(pos).unwrap().unwrap();
+        expected_supertypes
+            .assert_eq(&supertypes.into_iter().map(|nav| nav.debug_render()).join("\n"));
+
+        let subtypes = analysis.subtypes(pos).unwrap().unwrap();
+        expected_subtypes.assert_eq(&subtypes.into_iter().map(|nav| nav.debug_render()).join("\n"));
+    }
+
+    #[test]
+    fn test_type_hierarchy_of_trait() {
+        check_hierarchy(
+            r#"
+trait Base {}
+trait Tr$0ait: Base {}
+trait Sub: Trait {}
+trait Other where Self: Trait {}
+trait Unrelated { fn f(_: impl Trait); }
+struct Foo;
+impl Trait for Foo {}
+impl<T: Sub> Trait for T {}
+"#,
+            expect!["Trait Trait FileId(0) 14..34 20..25"],
+            expect![["Base Trait FileId(0) 0..13 6..10"]],
+            expect![[r#"
+                Sub Trait FileId(0) 35..54 41..44
+                Other Trait FileId(0) 55..87 61..66
+                impl Impl FileId(0) 163..190 186..187
+                Foo Struct FileId(0) 129..140 136..139"#]],
+        );
+    }
+
+    #[test]
+    fn test_type_hierarchy_of_type() {
+        check_hierarchy(
+            r#"
+//- minicore: derive, copy, clone
+trait Marker {}
+trait Generic<T> {}
+trait Named {}
+impl<T: Clone> Named for T {}
+impl<T, U> Generic<U> for T {}
+
+#[derive(Clone, Copy)]
+struct Fo$0o;
+impl Marker for Foo {}
+impl Foo {}
+"#,
+            expect!["Foo Struct FileId(0) 113..147 143..146"],
+            expect![[r#"
+                Copy Trait FileId(1) 152..197 183..187 marker
+                Marker Trait FileId(0) 0..15 6..12
+                Clone Trait FileId(1) 811..896 843..848 clone
+                Named Trait FileId(0) 36..50 42..47"#]],
+            expect![[]],
+        );
+    }
+
+    #[test]
+    fn test_type_hierarchy_on_ref() {
+        check_hierarchy(
+            r#"
+trait Trait {}
+struct Foo;
+impl Tra$0it for Foo {}
+"#,
+            expect![["Trait Trait FileId(0) 0..14 6..11"]],
+            expect![[]],
+            expect![["Foo Struct FileId(0) 15..26 22..25"]],
+        );
+    }
+}
@@ -1,18 +1,34 @@
 use std::fmt;
 
-use hir::{Field, HirDisplay, Layout, Semantics, Type};
+use hir::{Adt, EnumTagEncoding, Field, HirDisplay, Layout, Semantics, Type, Variant};
 use ide_db::{
+    base_db::FileId,
     defs::Definition,
     helpers::{get_definition, pick_best_token},
     RootDatabase,
 };
-use syntax::{AstNode, SyntaxKind};
+use syntax::{ast, AstNode, SyntaxKind, TextSize};
 
 use crate::FilePosition;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryLayoutNodeKind {
+    /// A field of a struct, union, tuple or enum variant.
+    Field,
+    /// A variant of an enum, spanning the whole enum.
+    Variant,
+    /// The bytes an enum stores its discriminant in.
+    Tag,
+    /// The scalar with the most invalid values, which enclosing enums can store their tag in.
+    Niche,
+    /// Bytes that are not covered by any field.
+    Padding,
+}
+
 pub struct MemoryLayoutNode {
     pub item_name: String,
     pub typename: String,
+    pub kind: MemoryLayoutNodeKind,
     pub size: u64,
     pub alignment: u64,
     pub offset: u64,
@@ -25,7 +41,6 @@ pub struct RecursiveMemoryLayout {
     pub nodes: Vec<MemoryLayoutNode>,
 }
 
-// NOTE: this is currently strictly for testing and so isn't super useful as a visualization tool, however it could be adapted to become one?
 impl fmt::Display for RecursiveMemoryLayout {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         fn process(
@@ -36,10 +51,18 @@ impl fmt::Display for RecursiveMemoryLayout {
         ) -> fmt::Result {
             let mut out = "\t".repeat(depth);
             let node = &nodes[idx];
-            out += &format!(
-                "{}: {} (size: {}, align: {}, field offset: {})\n",
-                node.item_name, node.typename, node.size, node.alignment, node.offset
-            );
+            match node.kind {
+                MemoryLayoutNodeKind::Padding => {
+                    out +=
+                        &format!("[padding] (size: {}, field offset: {})\n", node.size, node.offset)
+                }
+                _ => {
+                    out += &format!(
+                        "{}: {} (size: {}, align: {}, field offset: {})\n",
+                        node.item_name, node.typename, node.size, node.alignment, node.offset
+                    )
+                }
+            }
             write!(fmt, "{}", out)?;
             if node.children_start != -1 {
                 for j in nodes[idx].children_start
@@ -76,7 +99,11 @@ impl FieldOrTupleIdx {
 
 // Feature: View Memory Layout
 //
-// Displays the recursive memory layout of a datatype.
+// Displays the recursive memory layout of a datatype, including the variants of enums, where
+// their discriminant and niches are stored, and padding between fields.
+//
+// Generic types can be viewed by placing the cursor on a fully instantiated type, such as
+// `Option<Box<u8>>` in a type annotation, or by passing the generic arguments explicitly.
 //
 // |===
 // | Editor  | Action Name
@@ -86,6 +113,7 @@ impl FieldOrTupleIdx {
 pub(crate) fn view_memory_layout(
     db: &RootDatabase,
     position: FilePosition,
+    generic_args: &[String],
 ) -> Option<RecursiveMemoryLayout> {
     let sema = Semantics::new(db);
     let file = sema.parse(position.file_id);
@@ -95,9 +123,33 @@ pub(crate) fn view_memory_layout(
             _ => 0,
         })?;
 
-    let def = get_definition(&sema, token)?;
+    if generic_args.is_empty() {
+        // Prefer the type as written, so that instantiated generic types can be viewed as well.
+        let written_ty = token
+            .parent_ancestors()
+            .take_while(|it| !ast::Item::can_cast(it.kind()))
+            .find_map(ast::PathType::cast)
+            .and_then(|path_ty| sema.resolve_type(&path_ty.into()))
+            .filter(|ty| !ty.contains_unknown());
+        if let Some(ty) = written_ty {
+            return memory_layout_of_ty(db, &ty, "[ROOT]".to_owned());
+        }
+    }
+
+    let def = get_definition(&sema, token.clone())?;
 
     let ty = match def {
+        Definition::Adt(it) if !generic_args.is_empty() => {
+            let scope = sema.scope(&token.parent()?)?;
+            let args = generic_args
+                .iter()
+                .map(|arg| {
+                    syntax::hacks::parse_ty_from_str(arg)
+                        .map(|arg| scope.speculative_resolve_type(&arg))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            it.ty_with_args(db, &args)
+        }
         Definition::Adt(it) => it.ty(db),
         Definition::TypeAlias(it) => it.ty(db),
         Definition::BuiltinType(it) => it.ty(db),
@@ -109,113 +161,245 @@ pub(crate) fn view_memory_layout(
         _ => return None,
     };
 
-    fn read_layout(
-        nodes: &mut Vec<MemoryLayoutNode>,
-        db: &RootDatabase,
-        ty: &Type,
-        layout: &Layout,
-        parent_idx: usize,
-    ) {
-        let mut fields = ty
-            .fields(db)
-            .into_iter()
-            .map(|(f, ty)| (FieldOrTupleIdx::Field(f), ty))
-            .chain(
-                ty.tuple_fields(db)
-                    .into_iter()
-                    .enumerate()
-                    .map(|(i, ty)| (FieldOrTupleIdx::TupleIdx(i), ty)),
-            )
-            .collect::<Vec<_>>();
+    let item_name = match def {
+        // def is a datatype
+        Definition::Adt(_)
+        | Definition::TypeAlias(_)
+        | Definition::BuiltinType(_)
+        | Definition::SelfType(_) => "[ROOT]".to_owned(),
+
+        // def is an item
+        def => def
+            .name(db)
+            .map(|n| {
+                n.as_str()
+                    .map(|s| s.to_owned())
+                    .unwrap_or_else(|| format!(".{}", n.as_tuple_index().unwrap()))
+            })
+            .unwrap_or("[ROOT]".to_owned()),
+    };
 
-        if fields.len() == 0 {
-            return;
-        }
+    memory_layout_of_ty(db, &ty, item_name)
+}
 
-        fields.sort_by_key(|&(f, _)| match f {
-            FieldOrTupleIdx::Field(f) => layout.field_offset(f).unwrap_or(0),
-            FieldOrTupleIdx::TupleIdx(f) => layout.tuple_field_offset(f).unwrap_or(0),
-        });
-
-        let children_start = nodes.len();
-        nodes[parent_idx].children_start = children_start as i64;
-        nodes[parent_idx].children_len = fields.len() as u64;
-
-        for (field, child_ty) in fields.iter() {
-            if let Ok(child_layout) = child_ty.layout(db) {
-                nodes.push(MemoryLayoutNode {
-                    item_name: field.name(db),
-                    typename: child_ty.display(db).to_string(),
-                    size: child_layout.size(),
-                    alignment: child_layout.align(),
-                    offset: match *field {
-                        FieldOrTupleIdx::Field(f) => layout.field_offset(f).unwrap_or(0),
-                        FieldOrTupleIdx::TupleIdx(f) => layout.tuple_field_offset(f).unwrap_or(0),
-                    },
-                    parent_idx: parent_idx as i64,
-                    children_start: -1,
-                    children_len: 0,
-                });
-            } else {
-                nodes.push(MemoryLayoutNode {
-                    item_name: field.name(db)
-                        + format!("(no layout data: {:?})", child_ty.layout(db).unwrap_err())
-                            .as_ref(),
-                    typename: child_ty.display(db).to_string(),
-                    size: 0,
-                    offset: 0,
-                    alignment: 0,
-                    parent_idx: parent_idx as i64,
-                    children_start: -1,
-                    children_len: 0,
-                });
+/// Computes the memory layout of `ty`, which is resolved as if it was written at the root of the
+/// crate `file_id` belongs to.
+pub(crate) fn view_memory_layout_of_type(
+    db: &RootDatabase,
+    file_id: FileId,
+    ty: &str,
+) -> Option<RecursiveMemoryLayout> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(file_id);
+    let scope = sema.scope_at_offset(file.syntax(), TextSize::from(0))?;
+    let ty = scope.speculative_resolve_type(&syntax::hacks::parse_ty_from_str(ty)?);
+    memory_layout_of_ty(db, &ty, "[ROOT]".to_owned())
+}
+
+fn memory_layout_of_ty(
+    db: &RootDatabase,
+    ty: &Type,
+    item_name: String,
+) -> Option<RecursiveMemoryLayout> {
+    let layout = ty.layout(db).ok()?;
+    let mut nodes = vec![MemoryLayoutNode {
+        item_name,
+        typename: ty.display(db).to_string(),
+        kind: MemoryLayoutNodeKind::Field,
+        size: layout.size(),
+        offset: 0,
+        alignment: layout.align(),
+        parent_idx: -1,
+        children_start: -1,
+        children_len: 0,
+    }];
+    read_layout(&mut nodes, db, ty, &layout, None, 0);
+    Some(RecursiveMemoryLayout { nodes })
+}
+
+/// The part of a layout whose children are listed: either a whole type or, for enums, one of
+/// its variants together with the bytes reserved for the tag.
+type Expansion = (Type, Layout, Option<(Variant, Option<(u64, u64)>)>);
+
+fn read_layout(
+    nodes: &mut Vec<MemoryLayoutNode>,
+    db: &RootDatabase,
+    ty: &Type,
+    layout: &Layout,
+    variant: Option<(Variant, Option<(u64, u64)>)>,
+    parent_idx: usize,
+) {
+    let mut children: Vec<(MemoryLayoutNode, Option<Expansion>)> = Vec::new();
+    let child =
+        |item_name: String, typename: String, kind, size, alignment, offset| MemoryLayoutNode {
+            item_name,
+            typename,
+            kind,
+            size,
+            alignment,
+            offset,
+            parent_idx: parent_idx as i64,
+            children_start: -1,
+            children_len: 0,
+        };
+
+    match (ty.as_adt(), variant) {
+        (Some(Adt::Enum(e)), None) => {
+            let tag = layout.enum_tag(e);
+            if let Some(tag) = &tag {
+                let typename = match tag.encoding {
+                    EnumTagEncoding::Direct => format!("u{}", tag.size * 8),
+                    EnumTagEncoding::Niche { untagged_variant, niche_start } => format!(
+                        "niche in {} starting at {niche_start}",
+                        untagged_variant.name(db).display(db)
+                    ),
+                };
+                children.push((
+                    child(
+                        "[tag]".to_owned(),
+                        typename,
+                        MemoryLayoutNodeKind::Tag,
+                        tag.size,
+                        tag.size,
+                        tag.offset,
+                    ),
+                    None,
+                ));
+            }
+            for v in e.variants(db) {
+                let Some(variant_layout) = layout.variant_layout(v) else { continue };
+                children.push((
+                    child(
+                        v.name(db).display(db).to_string(),
+                        format!("{}::{}", ty.display(db), v.name(db).display(db)),
+                        MemoryLayoutNodeKind::Variant,
+                        layout.size(),
+                        variant_layout.align(),
+                        0,
+                    ),
+                    Some((
+                        ty.clone(),
+                        variant_layout,
+                        Some((v, tag.as_ref().map(|tag| (tag.offset, tag.size)))),
+                    )),
+                ));
             }
         }
+        (_, variant) => {
+            let fields = match variant {
+                Some((v, _)) => ty.variant_fields(db, v),
+                None => ty.fields(db),
+            };
+            let mut fields = fields
+                .into_iter()
+                .map(|(f, ty)| (FieldOrTupleIdx::Field(f), ty))
+                .chain(
+                    ty.tuple_fields(db)
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, ty)| (FieldOrTupleIdx::TupleIdx(i), ty)),
+                )
+                .collect::<Vec<_>>();
+
+            if fields.is_empty() && variant.is_none() {
+                return;
+            }
 
-        for (i, (_, child_ty)) in fields.iter().enumerate() {
-            if let Ok(child_layout) = child_ty.layout(db) {
-                read_layout(nodes, db, &child_ty, &child_layout, children_start + i);
+            let offset_of = |field| match field {
+                FieldOrTupleIdx::Field(f) => layout.field_offset(f).unwrap_or(0),
+                FieldOrTupleIdx::TupleIdx(f) => layout.tuple_field_offset(f).unwrap_or(0),
+            };
+            fields.sort_by_key(|&(f, _)| offset_of(f));
+
+            // Byte ranges that are in use, to find the holes between them.
+            let mut occupied = variant.and_then(|(_, tag)| tag).into_iter().collect::<Vec<_>>();
+            for (field, child_ty) in fields {
+                match child_ty.layout(db) {
+                    Ok(child_layout) => {
+                        occupied.push((offset_of(field), child_layout.size()));
+                        children.push((
+                            child(
+                                field.name(db),
+                                child_ty.display(db).to_string(),
+                                MemoryLayoutNodeKind::Field,
+                                child_layout.size(),
+                                child_layout.align(),
+                                offset_of(field),
+                            ),
+                            Some((child_ty, child_layout, None)),
+                        ));
+                    }
+                    Err(e) => children.push((
+                        child(
+                            field.name(db) + format!("(no layout data: {e:?})").as_ref(),
+                            child_ty.display(db).to_string(),
+                            MemoryLayoutNodeKind::Field,
+                            0,
+                            0,
+                            0,
+                        ),
+                        None,
+                    )),
+                }
             }
+
+            occupied.sort();
+            let mut end = 0;
+            for (offset, size) in occupied.into_iter().chain([(nodes[parent_idx].size, 0)]) {
+                if offset > end {
+                    children.push((
+                        child(
+                            "[padding]".to_owned(),
+                            String::new(),
+                            MemoryLayoutNodeKind::Padding,
+                            offset - end,
+                            1,
+                            end,
+                        ),
+                        None,
+                    ));
+                }
+                end = end.max(offset + size);
+            }
+            children.sort_by_key(|(node, _)| node.offset);
         }
     }
 
-    ty.layout(db)
-        .map(|layout| {
-            let item_name = match def {
-                // def is a datatype
-                Definition::Adt(_)
-                | Definition::TypeAlias(_)
-                | Definition::BuiltinType(_)
-                | Definition::SelfType(_) => "[ROOT]".to_owned(),
-
-                // def is an item
-                def => def
-                    .name(db)
-                    .map(|n| {
-                        n.as_str()
-                            .map(|s| s.to_owned())
-                            .unwrap_or_else(|| format!(".{}", n.as_tuple_index().unwrap()))
-                    })
-                    .unwrap_or("[ROOT]".to_owned()),
-            };
+    if !children.is_empty() && variant.is_none() {
+        if let (Some((offset, size)), Some(count)) = (layout.largest_niche_range(), layout.niches())
+        {
+            children.push((
+                child(
+                    "[niche]".to_owned(),
+                    format!("{count} invalid values"),
+                    MemoryLayoutNodeKind::Niche,
+                    size,
+                    size,
+                    offset,
+                ),
+                None,
+            ));
+        }
+    }
+
+    if children.is_empty() {
+        return;
+    }
+
+    let children_start = nodes.len();
+    nodes[parent_idx].children_start = children_start as i64;
+    nodes[parent_idx].children_len = children.len() as u64;
 
-            let typename = ty.display(db).to_string();
-
-            let mut nodes = vec![MemoryLayoutNode {
-                item_name,
-                typename: typename.clone(),
-                size: layout.size(),
-                offset: 0,
-                alignment: layout.align(),
-                parent_idx: -1,
-                children_start: -1,
-                children_len: 0,
-            }];
-            read_layout(&mut nodes, db, &ty, &layout, 0);
-
-            RecursiveMemoryLayout { nodes }
-        })
-        .ok()
+    let mut expansions = Vec::with_capacity(children.len());
+    for (node, expansion) in children {
+        nodes.push(node);
+        expansions.push(expansion);
+    }
+    for (i, expansion) in expansions.into_iter().enumerate() {
+        if let Some((child_ty, child_layout, variant)) = expansion {
+            read_layout(nodes, db, &child_ty, &child_layout, variant, children_start + i);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -228,7 +412,7 @@ mod tests {
     fn make_memory_layout(ra_fixture: &str) -> Option<RecursiveMemoryLayout> {
         let (analysis, position, _) = fixture::annotations(ra_fixture);
 
-        view_memory_layout(&analysis.db, position)
+        view_memory_layout(&analysis.db, position, &[])
     }
 
     #[test]
@@ -293,6 +477,7 @@ static BLAH$0: bool = 0;
             x: (f64, u8, i64) (size: 24, align: 8, field offset: 0)
             	.0: f64 (size: 8, align: 8, field offset: 0)
             	.1: u8 (size: 1, align: 1, field offset: 8)
+            	[padding] (size: 7, field offset: 9)
             	.2: i64 (size: 8, align: 8, field offset: 16)
         "#]]
         .assert_eq(
@@ -316,7 +501,9 @@ fn main() {
             	b: (i32, u8) (size: 8, align: 4, field offset: 4)
             		.0: i32 (size: 4, align: 4, field offset: 0)
             		.1: u8 (size: 1, align: 1, field offset: 4)
+            		[padding] (size: 3, field offset: 5)
             	c: i8 (size: 1, align: 1, field offset: 12)
+            	[padding] (size: 3, field offset: 13)
         "#]]
         .assert_eq(
             &make_memory_layout(
@@ -341,8 +528,10 @@ struct Blah$0 {
             	b: (i32, u8) (size: 8, align: 4, field offset: 0)
             		.0: i32 (size: 4, align: 4, field offset: 0)
             		.1: u8 (size: 1, align: 1, field offset: 4)
+            		[padding] (size: 3, field offset: 5)
             	a: u32 (size: 4, align: 4, field offset: 8)
             	c: i8 (size: 1, align: 1, field offset: 12)
+            	[padding] (size: 3, field offset: 13)
         "#]]
         .assert_eq(
             &make_memory_layout(
@@ -406,4 +595,148 @@ struct X$0 {
 
         assert_eq!(ml_a.to_string(), ml_b.to_string());
     }
+
+    #[test]
+    fn view_memory_layout_enum() {
+        expect![[r#"
+            [ROOT]: Foo (size: 8, align: 4, field offset: 0)
+            	[tag]: u8 (size: 1, align: 1, field offset: 0)
+            	A: Foo::A (size: 8, align: 4, field offset: 0)
+            		[padding] (size: 3, field offset: 1)
+            		.0: u32 (size: 4, align: 4, field offset: 4)
+            	B: Foo::B (size: 8, align: 2, field offset: 0)
+            		x: u8 (size: 1, align: 1, field offset: 1)
+            		y: u16 (size: 2, align: 2, field offset: 2)
+            		[padding] (size: 4, field offset: 4)
+            	C: Foo::C (size: 8, align: 1, field offset: 0)
+            		[padding] (size: 7, field offset: 1)
+            	[niche]: 253 invalid values (size: 1, align: 1, field offset: 0)
+        "#]]
+        .assert_eq(
+            &make_memory_layout(
+                r#"
+enum Foo$0 {
+    A(u32),
+    B { x: u8, y: u16 },
+    C,
+}
+"#,
+            )
+            .unwrap()
+            .to_string(),
+        );
+    }
+
+    #[test]
+    fn view_memory_layout_niche() {
+        expect![[r#"
+            [ROOT]: Flags (size: 4, align: 2, field offset: 0)
+            	.0: u16 (size: 2, align: 2, field offset: 0)
+            	.1: bool (size: 1, align: 1, field offset: 2)
+            	[padding] (size: 1, field offset: 3)
+            	[niche]: 254 invalid values (size: 1, align: 1, field offset: 2)
+        "#]]
+        .assert_eq(
+            &make_memory_layout(
+                r#"
+//- minicore: option
+struct Flags(u16, bool);
+fn main() {
+    let x: Option<Fla$0gs> = None;
+}
+"#,
+            )
+            .unwrap()
+            .to_string(),
+        );
+    }
+
+    #[test]
+    fn view_memory_layout_instantiated_type() {
+        expect![[r#"
+            [ROOT]: Option<Flags> (size: 4, align: 2, field offset: 0)
+            	[tag]: niche in Some starting at 2 (size: 1, align: 1, field offset: 2)
+            	None: Option<Flags>::None (size: 4, align: 1, field offset: 0)
+            		[padding] (size: 2, field offset: 0)
+            		[padding] (size: 1, field offset: 3)
+            	Some: Option<Flags>::Some (size: 4, align: 2, field offset: 0)
+            		.0: Flags (size: 4, align: 2, field offset: 0)
+            			.0: u16 (size: 2, align: 2, field offset: 0)
+            			.1: bool (size: 1, align: 1, field offset: 2)
+            			[padding] (size: 1, field offset: 3)
+            			[niche]: 254 invalid values (size: 1, align: 1, field offset: 2)
+            	[niche]: 253 invalid values (size: 1, align: 1, field offset: 2)
+        "#]]
+        .assert_eq(
+            &make_memory_layout(
+                r#"
+//- minicore: option
+struct Flags(u16, bool);
+fn main() {
+    let x: Opt$0ion<Flags> = None;
+}
+"#,
+            )
+            .unwrap()
+            .to_string(),
+        );
+    }
+
+    #[test]
+    fn view_memory_layout_generic_args() {
+        let (analysis, position, _) = fixture::annotations(
+            r#"
+struct Pair$0<T, U> {
+    a: T,
+    b: U,
+}
+"#,
+        );
+        expect![[r#"
+            [ROOT]: Pair<u8, (u32, u8)> (size: 12, align: 4, field offset: 0)
+            	b: (u32, u8) (size: 8, align: 4, field offset: 0)
+            		.0: u32 (size: 4, align: 4, field offset: 0)
+            		.1: u8 (size: 1, align: 1, field offset: 4)
+            		[padding] (size: 3, field offset: 5)
+            	a: u8 (size: 1, align: 1, field offset: 8)
+            	[padding] (size: 3, field offset: 9)
+        "#]]
+        .assert_eq(
+            &view_memory_layout(&analysis.db, position, &["u8".to_owned(), "(u32, u8)".to_owned()])
+                .unwrap()
+                .to_string(),
+        );
+        assert!(view_memory_layout(&analysis.db, position, &[]).is_none());
+    }
+
+    #[test]
+    fn view_memory_layout_of_type_text() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+//- minicore: option
+mod m {
+    pub struct Flags(pub u16, pub bool);
+}
+"#,
+        );
+        expect![[r#"
+            [ROOT]: Option<Flags> (size: 4, align: 2, field offset: 0)
+            	[tag]: niche in Some starting at 2 (size: 1, align: 1, field offset: 2)
+            	None: Option<Flags>::None (size: 4, align: 1, field offset: 0)
+            		[padding] (size: 2, field offset: 0)
+            		[padding] (size: 1, field offset: 3)
+            	Some: Option<Flags>::Some (size: 4, align: 2, field offset: 0)
+            		.0: Flags (size: 4, align: 2, field offset: 0)
+            			.0: u16 (size: 2, align: 2, field offset: 0)
+            			.1: bool (size: 1, align: 1, field offset: 2)
+            			[padding] (size: 1, field offset: 3)
+            			[niche]: 254 invalid values (size: 1, align: 1, field offset: 2)
+            	[niche]: 253 invalid values (size: 1, align: 1, field offset: 2)
+        "#]]
+        .assert_eq(
+            &view_memory_layout_of_type(&analysis.db, file_id, "Option<m::Flags>")
+                .unwrap()
+                .to_string(),
+        );
+    }
 }
@@ -8,7 +8,7 @@ mod transcriber;
 use rustc_hash::FxHashMap;
 use syntax::SmolStr;
 
-use crate::{parser::MetaVarKind, tt, ExpandError, ExpandResult};
+use crate::{parser::MetaVarKind, tt, ExpandError, ExpandResult, Expected};
 
 pub(crate) fn expand_rules(
     rules: &[crate::Rule],
@@ -53,6 +53,19 @@ pub(crate) fn expand_rules(
     }
 }
 
+pub(crate) fn expected_next(
+    rules: &[crate::Rule],
+    input: &tt::Subtree,
+    depth: usize,
+    is_2021: bool,
+) -> Vec<Expected> {
+    let mut acc = Vec::new();
+    for rule in rules {
+        matcher::expected_next(&rule.lhs, input, depth, is_2021, &mut acc);
+    }
+    acc
+}
+
 /// The actual algorithm for expansion is not too hard, but is pretty tricky.
 /// `Bindings` structure is the key to understanding what we are doing here.
 ///
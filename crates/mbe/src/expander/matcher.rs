@@ -69,7 +69,7 @@ use crate::{
     parser::{MetaVarKind, Op, RepeatKind, Separator},
     tt,
     tt_iter::TtIter,
-    ExpandError, MetaTemplate, ValueResult,
+    ExpandError, Expected, MetaTemplate, ValueResult,
 };
 
 impl Bindings {
@@ -811,6 +811,165 @@ fn match_meta_var(
     input.expect_fragment(fragment).map(|it| it.map(Fragment::Tokens))
 }
 
+/// Collects what `pattern` accepts right after `input`, which is the prefix of an invocation up
+/// to the cursor. If `depth` is non-zero, the cursor is inside the last token tree of `input`
+/// (recursively, `depth` levels deep), and the expectations are those of that innermost group.
+///
+/// Unlike [`match_`], this backtracks through every alternative instead of running the NFA, which
+/// is fine for the short prefixes completion deals with.
+pub(super) fn expected_next(
+    pattern: &MetaTemplate,
+    input: &tt::Subtree,
+    depth: usize,
+    is_2021: bool,
+    acc: &mut Vec<Expected>,
+) {
+    let mut fuel = EXPECTED_NEXT_FUEL;
+    let conts = vec![Cont::Seq(&pattern.0)];
+    expected_in(conts, TtIter::new(input), depth, is_2021, &mut fuel, acc);
+}
+
+/// Bounds the number of steps [`expected_next`] takes, as repetitions make the search exponential.
+const EXPECTED_NEXT_FUEL: usize = 4096;
+
+/// What is left to match once the current op is done.
+#[derive(Debug, Clone, Copy)]
+enum Cont<'a> {
+    /// The remaining ops of a sequence.
+    Seq(&'a [Op]),
+    /// The end of an iteration of `$(...)` repetition which started with `len` input tokens left.
+    Repeat { op: &'a Op, len: usize },
+}
+
+fn expected_in<'a>(
+    mut conts: Vec<Cont<'a>>,
+    mut src: TtIter<'a>,
+    depth: usize,
+    is_2021: bool,
+    fuel: &mut usize,
+    acc: &mut Vec<Expected>,
+) {
+    let eof = src.peek_n(0).is_none();
+    if *fuel == 0 || (eof && depth > 0) {
+        return;
+    }
+    *fuel -= 1;
+
+    let op = match conts.pop() {
+        None => return,
+        Some(Cont::Seq(ops)) => match ops.split_first() {
+            Some((op, rest)) => {
+                conts.push(Cont::Seq(rest));
+                op
+            }
+            None => return expected_in(conts, src, depth, is_2021, fuel, acc),
+        },
+        Some(Cont::Repeat { op, len }) => {
+            let Op::Repeat { tokens, kind, separator } = op else { return };
+            // Either leave the repetition...
+            expected_in(conts.clone(), src.clone(), depth, is_2021, fuel, acc);
+            if *kind == RepeatKind::ZeroOrOne || src.len() == len {
+                return;
+            }
+            // ...or go for another iteration.
+            if let Some(separator) = separator {
+                if eof {
+                    push(acc, Expected::Token(separator_text(separator)));
+                    return;
+                }
+                if !src.expect_separator(separator) {
+                    return;
+                }
+            }
+            conts.push(Cont::Repeat { op, len: src.len() });
+            conts.push(Cont::Seq(&tokens.0));
+            return expected_in(conts, src, depth, is_2021, fuel, acc);
+        }
+    };
+
+    match op {
+        Op::Var { kind: Some(kind), .. } => {
+            if eof {
+                push(acc, Expected::Fragment(*kind));
+                if *kind != MetaVarKind::Vis {
+                    return;
+                }
+            }
+            let mut fork = src.clone();
+            let ExpandResult { err, .. } = match_meta_var(*kind, &mut fork, is_2021);
+            if err.is_none() {
+                expected_in(conts, fork, depth, is_2021, fuel, acc);
+            } else if depth == 0 && fork.peek_n(0).is_none() {
+                // The fragment is still being typed.
+                push(acc, Expected::Fragment(*kind));
+            }
+        }
+        Op::Literal(lhs) => {
+            if eof {
+                push(acc, Expected::Token(lhs.text.clone()));
+            } else if matches!(src.expect_leaf(), Ok(tt::Leaf::Literal(it)) if it.text == lhs.text)
+            {
+                expected_in(conts, src, depth, is_2021, fuel, acc);
+            }
+        }
+        Op::Ident(lhs) => {
+            if eof {
+                push(acc, Expected::Token(lhs.text.clone()));
+            } else if matches!(src.expect_leaf(), Ok(tt::Leaf::Ident(it)) if it.text == lhs.text) {
+                expected_in(conts, src, depth, is_2021, fuel, acc);
+            }
+        }
+        Op::Punct(lhs) => {
+            if eof {
+                push(acc, Expected::Token(lhs.iter().map(|it| it.char).collect()));
+            } else if matches!(src.expect_glued_punct(), Ok(rhs) if lhs.iter().map(|it| it.char).eq(rhs.iter().map(|it| it.char)))
+            {
+                expected_in(conts, src, depth, is_2021, fuel, acc);
+            }
+        }
+        Op::Subtree { tokens, delimiter } => {
+            if eof {
+                push(acc, Expected::Delimited(delimiter.kind));
+                return;
+            }
+            let Ok(subtree) = src.expect_subtree() else { return };
+            if subtree.delimiter.kind != delimiter.kind {
+                return;
+            }
+            if depth > 0 && src.peek_n(0).is_none() {
+                // The cursor is inside this group, so whatever follows it is irrelevant.
+                let conts = vec![Cont::Seq(&tokens.0)];
+                expected_in(conts, TtIter::new(subtree), depth - 1, is_2021, fuel, acc);
+            } else if match_(tokens, subtree, is_2021).err.is_none() {
+                expected_in(conts, src, depth, is_2021, fuel, acc);
+            }
+        }
+        Op::Repeat { tokens, kind, .. } => {
+            if *kind != RepeatKind::OneOrMore {
+                expected_in(conts.clone(), src.clone(), depth, is_2021, fuel, acc);
+            }
+            conts.push(Cont::Repeat { op, len: src.len() });
+            conts.push(Cont::Seq(&tokens.0));
+            expected_in(conts, src, depth, is_2021, fuel, acc);
+        }
+        Op::Var { kind: None, .. } | Op::Ignore { .. } | Op::Index { .. } | Op::Count { .. } => {}
+    }
+}
+
+fn push(acc: &mut Vec<Expected>, expected: Expected) {
+    if !acc.contains(&expected) {
+        acc.push(expected);
+    }
+}
+
+fn separator_text(separator: &Separator) -> SmolStr {
+    match separator {
+        Separator::Literal(it) => it.text.clone(),
+        Separator::Ident(it) => it.text.clone(),
+        Separator::Puncts(it) => it.iter().map(|it| it.char).collect(),
+    }
+}
+
 fn collect_vars(collector_fun: &mut impl FnMut(SmolStr), pattern: &MetaTemplate) {
     for op in pattern.iter() {
         match op {
@@ -20,11 +20,12 @@ mod token_map;
 
 use ::tt::token_id as tt;
 use stdx::impl_from;
+use syntax::SmolStr;
 
 use std::fmt;
 
 use crate::{
-    parser::{MetaTemplate, MetaVarKind, Op},
+    parser::{MetaTemplate, Op},
     tt_iter::TtIter,
 };
 
@@ -32,6 +33,7 @@ pub use self::tt::{Delimiter, DelimiterKind, Punct};
 pub use ::parser::TopEntryPoint;
 
 pub use crate::{
+    parser::MetaVarKind,
     syntax_bridge::{
         parse_exprs_with_sep, parse_to_token_tree, syntax_node_to_token_map,
         syntax_node_to_token_map_with_modifications, syntax_node_to_token_tree,
@@ -41,6 +43,18 @@ pub use crate::{
     token_map::TokenMap,
 };
 
+/// Something a macro matcher accepts at some point of an invocation, see
+/// [`DeclarativeMacro::expected_next`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Expected {
+    /// A fragment, like `$e:expr`.
+    Fragment(MetaVarKind),
+    /// A literal token of the matcher, like `=>` or `where`.
+    Token(SmolStr),
+    /// A delimited group.
+    Delimited(DelimiterKind),
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum ParseError {
     UnexpectedToken(Box<str>),
@@ -315,6 +329,13 @@ impl DeclarativeMacro {
         expander::expand_rules(&self.rules, &tt, self.is_2021)
     }
 
+    /// Returns what the rules of this macro accept right after `input`, the part of an
+    /// invocation's argument before the cursor. When the cursor sits inside a delimited group,
+    /// that group is the last token tree of `input` (recursively, `depth` groups deep).
+    pub fn expected_next(&self, input: &tt::Subtree, depth: usize) -> Vec<Expected> {
+        expander::expected_next(&self.rules, input, depth, self.is_2021)
+    }
+
     pub fn err(&self) -> Option<&ParseError> {
         self.err.as_deref()
     }
@@ -69,7 +69,7 @@ pub(crate) enum RepeatKind {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub(crate) enum MetaVarKind {
+pub enum MetaVarKind {
     Path,
     Ty,
     Pat,
@@ -190,6 +190,9 @@ pub struct TargetData {
     pub is_proc_macro: bool,
     /// Required features of the target without which it won't build
     pub required_features: Vec<String>,
+    /// Whether the target is built with the default test harness, which `harness = false` in the
+    /// manifest turns off
+    pub harness: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -222,6 +225,47 @@ impl TargetKind {
     }
 }
 
+/// The targets that set `harness = false` in `manifest`, by the kind of their table and the name
+/// they declare.
+fn targets_without_harness(manifest: &str) -> Vec<(TargetKind, Option<String>)> {
+    // Deserialize helpers for the target tables of a manifest
+    #[derive(Deserialize, Default)]
+    #[serde(default)]
+    struct Manifest {
+        lib: Option<ManifestTarget>,
+        bin: Vec<ManifestTarget>,
+        test: Vec<ManifestTarget>,
+        bench: Vec<ManifestTarget>,
+        example: Vec<ManifestTarget>,
+    }
+    #[derive(Deserialize)]
+    struct ManifestTarget {
+        name: Option<String>,
+        harness: Option<bool>,
+    }
+
+    let manifest: Manifest = match toml::from_str(manifest) {
+        Ok(it) => it,
+        Err(e) => {
+            tracing::warn!("Failed to parse manifest: {e}");
+            return Vec::new();
+        }
+    };
+    let tables = [
+        (TargetKind::Lib, Vec::from_iter(manifest.lib)),
+        (TargetKind::Bin, manifest.bin),
+        (TargetKind::Test, manifest.test),
+        (TargetKind::Bench, manifest.bench),
+        (TargetKind::Example, manifest.example),
+    ];
+    tables
+        .into_iter()
+        .flat_map(|(kind, targets)| targets.into_iter().map(move |it| (kind, it)))
+        .filter(|(_, target)| target.harness == Some(false))
+        .map(|(kind, target)| (kind, target.name))
+        .collect()
+}
+
 // Deserialize helper for the cargo metadata
 #[derive(Deserialize, Default)]
 struct PackageMetadata {
@@ -357,16 +401,26 @@ impl CargoWorkspace {
             });
             let pkg_data = &mut packages[pkg];
             pkg_by_id.insert(id, pkg);
+            // `cargo metadata` doesn't report the `harness` key, so it's read from the manifest.
+            let without_harness = std::fs::read_to_string(pkg_data.manifest.as_ref())
+                .map(|manifest| targets_without_harness(&manifest))
+                .unwrap_or_default();
             for meta_tgt in meta_targets {
                 let cargo_metadata::Target { name, kind, required_features, src_path, .. } =
                     meta_tgt;
+                let target_kind = TargetKind::new(&kind);
+                let harness = !without_harness.iter().any(|(table_kind, table_name)| {
+                    *table_kind == target_kind
+                        && (target_kind == TargetKind::Lib || table_name.as_deref() == Some(&*name))
+                });
                 let tgt = targets.alloc(TargetData {
                     package: pkg,
                     name,
                     root: AbsPathBuf::assert(src_path.into()),
-                    kind: TargetKind::new(&kind),
+                    kind: target_kind,
                     is_proc_macro: &*kind == ["proc-macro"],
                     required_features,
+                    harness,
                 });
                 pkg_data.targets.push(tgt);
             }
@@ -542,3 +596,33 @@ fn parse_output_cargo_config_build_target(stdout: String) -> Vec<String> {
     }
     res.unwrap_or_default()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_targets_without_harness() {
+        let manifest = r#"
+[package]
+name = "foo"
+
+[lib]
+harness = false # custom
+
+[[test]]
+name = "integration"
+
+[[test]]
+name = "ui"
+harness = false
+
+[dependencies]
+harness = "1.0"
+"#;
+        assert_eq!(
+            targets_without_harness(manifest),
+            [(TargetKind::Lib, None), (TargetKind::Test, Some("ui".to_owned()))]
+        );
+    }
+}
@@ -78,6 +78,8 @@ fn main() -> anyhow::Result<()> {
         flags::RustAnalyzerCmd::Highlight(cmd) => cmd.run()?,
         flags::RustAnalyzerCmd::AnalysisStats(cmd) => cmd.run(verbosity)?,
         flags::RustAnalyzerCmd::Diagnostics(cmd) => cmd.run()?,
+        flags::RustAnalyzerCmd::MemoryLayout(cmd) => cmd.run()?,
+        flags::RustAnalyzerCmd::SignatureSearch(cmd) => cmd.run()?,
         flags::RustAnalyzerCmd::Ssr(cmd) => cmd.run()?,
         flags::RustAnalyzerCmd::Search(cmd) => cmd.run()?,
         flags::RustAnalyzerCmd::Lsif(cmd) => cmd.run()?,
@@ -208,6 +210,7 @@ fn run_server() -> anyhow::Result<()> {
         .filter(|workspaces| !workspaces.is_empty())
         .unwrap_or_else(|| vec![root_path.clone()]);
     let mut config = Config::new(root_path, capabilities, workspace_roots, is_visual_studio_code);
+    config.update_raw_client_capabilities(&initialize_params["capabilities"]);
     if let Some(json) = initialization_options {
         if let Err(e) = config.update(json) {
             use lsp_types::{
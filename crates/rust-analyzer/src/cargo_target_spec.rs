@@ -24,6 +24,8 @@ pub(crate) struct CargoTargetSpec {
     pub(crate) crate_id: CrateId,
     pub(crate) required_features: Vec<String>,
     pub(crate) features: FxHashSet<String>,
+    /// Whether the target is built with the default test harness.
+    pub(crate) harness: bool,
 }
 
 impl CargoTargetSpec {
@@ -128,10 +130,14 @@ impl CargoTargetSpec {
             &[crate_id, ..] => crate_id,
             _ => return Ok(None),
         };
-        let (cargo_ws, target) = match global_state_snapshot.cargo_target_for_crate_root(crate_id) {
-            Some(it) => it,
-            None => return Ok(None),
-        };
+        Ok(CargoTargetSpec::for_crate(global_state_snapshot, crate_id))
+    }
+
+    pub(crate) fn for_crate(
+        global_state_snapshot: &GlobalStateSnapshot,
+        crate_id: CrateId,
+    ) -> Option<CargoTargetSpec> {
+        let (cargo_ws, target) = global_state_snapshot.cargo_target_for_crate_root(crate_id)?;
 
         let target_data = &cargo_ws[target];
         let package_data = &cargo_ws[target_data.package];
@@ -143,10 +149,11 @@ impl CargoTargetSpec {
             target_kind: target_data.kind,
             required_features: target_data.required_features.clone(),
             features: package_data.features.keys().cloned().collect(),
+            harness: target_data.harness,
             crate_id,
         };
 
-        Ok(Some(res))
+        Some(res)
     }
 
     pub(crate) fn push_to(self, buf: &mut Vec<String>, kind: &RunnableKind) {
@@ -0,0 +1,808 @@
+//! Language support for `Cargo.toml` files: completion of keys, dependencies, features and lint
+//! names, diagnostics for unknown dependency keys and features, and going to the crate of a
+//! dependency.
+//!
+//! Clients only send us `Cargo.toml` documents because we register for them dynamically, see
+//! `GlobalState::register_cargo_toml_capabilities`. Everything about the packages comes from the
+//! `cargo metadata` of the loaded workspaces, so results are as fresh as the last workspace reload.
+
+mod manifest;
+
+use ide::{Cancellable, FileId, TextRange, TextSize};
+use ide_db::generated::lints::{
+    Lint, CLIPPY_LINTS, CLIPPY_LINT_GROUPS, DEFAULT_LINTS, DEFAULT_LINT_GROUPS, RUSTDOC_LINTS,
+    RUSTDOC_LINT_GROUPS,
+};
+use lsp_types::CompletionItemKind;
+use project_model::{Package, ProjectWorkspace, TargetKind};
+use rustc_hash::{FxHashMap, FxHashSet};
+use vfs::{AbsPath, AbsPathBuf};
+
+use crate::{global_state::GlobalStateSnapshot, lsp::to_proto};
+
+use self::manifest::{Key, Node, Value};
+
+const TABLES: &[&str] = &[
+    "package",
+    "package.metadata",
+    "lib",
+    "dependencies",
+    "dev-dependencies",
+    "build-dependencies",
+    "features",
+    "lints",
+    "lints.rust",
+    "lints.clippy",
+    "lints.rustdoc",
+    "workspace",
+    "workspace.package",
+    "workspace.dependencies",
+    "workspace.lints",
+    "workspace.lints.rust",
+    "workspace.lints.clippy",
+    "workspace.lints.rustdoc",
+    "workspace.metadata",
+    "profile.dev",
+    "profile.release",
+    "profile.test",
+    "profile.bench",
+    "patch.crates-io",
+    "badges",
+];
+
+const ARRAY_TABLES: &[&str] = &["bin", "example", "test", "bench"];
+
+const PACKAGE_KEYS: &[&str] = &[
+    "name",
+    "version",
+    "authors",
+    "edition",
+    "rust-version",
+    "description",
+    "documentation",
+    "readme",
+    "homepage",
+    "repository",
+    "license",
+    "license-file",
+    "keywords",
+    "categories",
+    "workspace",
+    "build",
+    "links",
+    "exclude",
+    "include",
+    "publish",
+    "metadata",
+    "default-run",
+    "autobins",
+    "autoexamples",
+    "autotests",
+    "autobenches",
+    "resolver",
+];
+
+const DEPENDENCY_TABLES: &[&str] = &[
+    "dependencies",
+    "dev-dependencies",
+    "build-dependencies",
+    "dev_dependencies",
+    "build_dependencies",
+];
+
+const DEPENDENCY_KEYS: &[&str] = &[
+    "version",
+    "path",
+    "git",
+    "branch",
+    "tag",
+    "rev",
+    "features",
+    "optional",
+    "default-features",
+    "default_features",
+    "package",
+    "registry",
+    "workspace",
+    "public",
+    "artifact",
+    "target",
+    "lib",
+];
+
+const WORKSPACE_KEYS: &[&str] = &[
+    "members",
+    "default-members",
+    "exclude",
+    "resolver",
+    "package",
+    "dependencies",
+    "lints",
+    "metadata",
+];
+
+const TARGET_KEYS: &[&str] = &[
+    "name",
+    "path",
+    "test",
+    "doctest",
+    "bench",
+    "doc",
+    "proc-macro",
+    "harness",
+    "edition",
+    "crate-type",
+    "required-features",
+];
+
+const PROFILE_KEYS: &[&str] = &[
+    "opt-level",
+    "debug",
+    "split-debuginfo",
+    "strip",
+    "debug-assertions",
+    "overflow-checks",
+    "lto",
+    "panic",
+    "incremental",
+    "codegen-units",
+    "rpath",
+    "inherits",
+    "package",
+    "build-override",
+];
+
+const LINT_TOOLS: &[&str] = &["rust", "clippy", "rustdoc"];
+
+/// What the loaded workspaces know about the packages a manifest refers to.
+#[derive(Debug, Default)]
+pub(crate) struct ManifestInfo {
+    /// The directory of the manifest.
+    dir: Option<AbsPathBuf>,
+    packages: Vec<PackageInfo>,
+    /// The packages the manifest's own package depends on, by the name it uses for them.
+    dependencies: FxHashMap<String, usize>,
+    /// The features of the manifest's own package.
+    features: Vec<String>,
+}
+
+#[derive(Debug)]
+struct PackageInfo {
+    name: String,
+    version: String,
+    manifest: AbsPathBuf,
+    is_member: bool,
+    /// Whether the manifest's own package depends on this package.
+    is_dependency: bool,
+    features: Vec<String>,
+    lib_root: Option<AbsPathBuf>,
+}
+
+impl ManifestInfo {
+    pub(crate) fn new(workspaces: &[ProjectWorkspace], path: &AbsPath) -> ManifestInfo {
+        for ws in workspaces {
+            let ProjectWorkspace::Cargo { cargo, .. } = ws else { continue };
+            let own_package = cargo.packages().find(|&pkg| *cargo[pkg].manifest == *path);
+            if own_package.is_none() && *cargo.workspace_root().join("Cargo.toml") != *path {
+                continue;
+            }
+            let own_dependencies =
+                own_package.map(|pkg| &*cargo[pkg].dependencies).unwrap_or_default();
+            let sorted_features = |pkg: Package| {
+                let mut features: Vec<_> = cargo[pkg].features.keys().cloned().collect();
+                features.sort();
+                features
+            };
+            let packages: Vec<_> = cargo.packages().collect();
+            let dependencies = own_dependencies
+                .iter()
+                .filter_map(|dep| {
+                    Some((normalize(&dep.name), packages.iter().position(|&it| it == dep.pkg)?))
+                })
+                .collect();
+            let packages = packages
+                .into_iter()
+                .map(|pkg| {
+                    let data = &cargo[pkg];
+                    let lib_root = data
+                        .targets
+                        .iter()
+                        .find(|&&target| cargo[target].kind == TargetKind::Lib)
+                        .map(|&target| cargo[target].root.clone());
+                    PackageInfo {
+                        name: data.name.clone(),
+                        version: data.version.to_string(),
+                        manifest: data.manifest.to_path_buf(),
+                        is_member: data.is_member,
+                        is_dependency: own_dependencies.iter().any(|dep| dep.pkg == pkg),
+                        features: sorted_features(pkg),
+                        lib_root,
+                    }
+                })
+                .collect();
+            let features = own_package.map(sorted_features).unwrap_or_default();
+            let dir = path.parent().map(AbsPath::to_path_buf);
+            return ManifestInfo { dir, packages, dependencies, features };
+        }
+        ManifestInfo::default()
+    }
+
+    /// The package `dependency` refers to: the package whose manifest is at its `path`, the one
+    /// the manifest's own package depends on under its name, or else the only package with its
+    /// name.
+    fn package(&self, dependency: &Dependency<'_>) -> Option<&PackageInfo> {
+        if let Some(path) = dependency.node.get("path").and_then(Node::as_str) {
+            let manifest = self.dir.as_ref()?.join(path).join("Cargo.toml").normalize();
+            return self.packages.iter().find(|it| it.manifest == manifest);
+        }
+        if let Some(&idx) = self.dependencies.get(&normalize(&dependency.name.text)) {
+            return Some(&self.packages[idx]);
+        }
+        let name = normalize(dependency.package());
+        let mut packages = self.packages.iter().filter(|it| normalize(&it.name) == name);
+        let package = packages.next()?;
+        packages.next().is_none().then_some(package)
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+/// A dependency declared in the manifest, with its version or its table of keys.
+struct Dependency<'a> {
+    /// The path of the table declaring the dependency.
+    table: Vec<&'a str>,
+    name: &'a Key,
+    node: &'a Node,
+}
+
+impl Dependency<'_> {
+    /// The name of the package, which differs from the dependency name for renamed dependencies.
+    fn package(&self) -> &str {
+        self.node.get("package").and_then(Node::as_str).unwrap_or(&self.name.text)
+    }
+}
+
+fn is_dependency_table(path: &[&str]) -> bool {
+    match path {
+        [kind] | ["workspace", kind] | ["target", _, kind] => DEPENDENCY_TABLES.contains(kind),
+        _ => false,
+    }
+}
+
+fn dependencies(root: &Node) -> Vec<Dependency<'_>> {
+    fn collect<'a>(res: &mut Vec<Dependency<'a>>, table: &[&'a str], node: &'a Node) {
+        for (key, value) in node.entries() {
+            if is_dependency_table(table) {
+                res.push(Dependency { table: table.to_vec(), name: key, node: value });
+            } else if table.len() < 3 {
+                collect(res, &[table, &[key.text.as_str()]].concat(), value);
+            }
+        }
+    }
+
+    let mut res = Vec::new();
+    collect(&mut res, &[], root);
+    res
+}
+
+/// The key inserted at the completion position to make half typed manifests parse.
+const MARKER: &str = "intellijRulezz";
+
+/// Where in the manifest a position is.
+#[derive(Debug)]
+enum Context {
+    /// In the name of a table header.
+    Header { range: TextRange, is_array: bool },
+    /// At a key of the table at `path`.
+    Key { path: Vec<String>, range: TextRange },
+    /// In the string value, or a string in the array value, of the key at `path`.
+    String { path: Vec<String>, range: TextRange },
+}
+
+/// Finds the context of `offset`, along with the manifest parsed with the marker inserted there.
+fn context(text: &str, offset: TextSize) -> Option<(Context, Option<Node>)> {
+    let offset = usize::from(offset);
+    let line_start = text[..offset].rfind('\n').map_or(0, |it| it + 1);
+    let line_end = text[offset..].find('\n').map_or(text.len(), |it| offset + it);
+    let line = &text[line_start..line_end];
+    let to_range = |start: usize, end: usize| {
+        TextRange::new(TextSize::from(start as u32), TextSize::from(end as u32))
+    };
+
+    let indent = line.len() - line.trim_start().len();
+    if line[indent..].starts_with('[') {
+        let is_array = line[indent..].starts_with("[[");
+        let name_start = line_start + indent + if is_array { 2 } else { 1 };
+        let name_end = text[name_start..line_end].find(']').map_or(line_end, |it| name_start + it);
+        if !(name_start..=name_end).contains(&offset) {
+            return None;
+        }
+        let name = &text[name_start..name_end];
+        let start = name_start + (name.len() - name.trim_start().len());
+        let end = (name_start + name.trim_end().len()).max(start);
+        return Some((
+            Context::Header { range: to_range(start.min(offset), end.max(offset)), is_array },
+            None,
+        ));
+    }
+
+    // Insert the marker, as a key if needed, so that half typed keys and values parse.
+    let manifest = ["", " = 0"].iter().find_map(|suffix| {
+        manifest::parse(&format!("{}{MARKER}{suffix}{}", &text[..offset], &text[offset..]))
+    })?;
+    let (path, is_key) = marker_path(&manifest, &mut Vec::new())?;
+    let context = if is_key {
+        let is_key_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+        let start = text[..offset].trim_end_matches(is_key_char).len();
+        let end = text[offset..].find(|c| !is_key_char(c)).map_or(text.len(), |it| offset + it);
+        Context::Key { path, range: to_range(start, end) }
+    } else {
+        let quote = text[line_start..offset].rfind(['"', '\''])?;
+        let quote_char = line[quote..].chars().next()?;
+        let end = text[offset..line_end].find(quote_char).map_or(line_end, |it| offset + it);
+        Context::String { path, range: to_range(line_start + quote + 1, end) }
+    };
+    Some((context, Some(manifest)))
+}
+
+/// The path of the key or string containing the marker, and whether it is a key.
+fn marker_path(node: &Node, path: &mut Vec<String>) -> Option<(Vec<String>, bool)> {
+    match &node.value {
+        Value::String(it) => it.contains(MARKER).then(|| (path.clone(), false)),
+        Value::Array(items) => items.iter().find_map(|it| marker_path(it, path)),
+        Value::Table(entries) => entries.iter().find_map(|(key, value)| {
+            if key.text.contains(MARKER) {
+                return Some((path.clone(), true));
+            }
+            path.push(key.text.clone());
+            let res = marker_path(value, path);
+            path.pop();
+            res
+        }),
+        Value::Other => None,
+    }
+}
+
+#[derive(Debug)]
+struct Completion {
+    label: String,
+    kind: CompletionItemKind,
+    detail: Option<String>,
+    documentation: Option<&'static str>,
+}
+
+impl Completion {
+    fn new(label: impl Into<String>, kind: CompletionItemKind) -> Completion {
+        Completion { label: label.into(), kind, detail: None, documentation: None }
+    }
+}
+
+fn completions_at(
+    text: &str,
+    offset: TextSize,
+    info: &ManifestInfo,
+) -> Option<(TextRange, Vec<Completion>)> {
+    let keys = |keys: &[&str]| {
+        keys.iter().map(|&it| Completion::new(it, CompletionItemKind::PROPERTY)).collect()
+    };
+    let (context, manifest) = context(text, offset)?;
+    match context {
+        Context::Header { range, is_array } => {
+            let tables = if is_array { ARRAY_TABLES } else { TABLES };
+            let items =
+                tables.iter().map(|&it| Completion::new(it, CompletionItemKind::MODULE)).collect();
+            Some((range, items))
+        }
+        Context::Key { path, range } => {
+            let path: Vec<&str> = path.iter().map(String::as_str).collect();
+            let items = match path[..] {
+                ["package"] | ["workspace", "package"] => keys(PACKAGE_KEYS),
+                ["workspace"] => keys(WORKSPACE_KEYS),
+                ["lib"] | ["bin"] | ["example"] | ["test"] | ["bench"] => keys(TARGET_KEYS),
+                ["profile", _] => keys(PROFILE_KEYS),
+                ["lints"] | ["workspace", "lints"] => keys(LINT_TOOLS),
+                ["lints", tool] | ["workspace", "lints", tool] => lint_completions(tool),
+                _ if is_dependency_table(&path) => dependency_name_completions(info),
+                [ref table @ .., _] if is_dependency_table(table) => keys(DEPENDENCY_KEYS),
+                _ => return None,
+            };
+            Some((range, items))
+        }
+        Context::String { path, range } => {
+            let path: Vec<&str> = path.iter().map(String::as_str).collect();
+            let items = match path[..] {
+                [ref table @ .., name, key @ ("features" | "version")]
+                    if is_dependency_table(table) =>
+                {
+                    let dependency = dependencies(manifest.as_ref()?)
+                        .into_iter()
+                        .find(|dep| dep.table == table && dep.name.text == name)?;
+                    let package = info.package(&dependency)?;
+                    if key == "version" {
+                        vec![Completion::new(&package.version, CompletionItemKind::VALUE)]
+                    } else {
+                        package
+                            .features
+                            .iter()
+                            .map(|it| Completion::new(it, CompletionItemKind::ENUM_MEMBER))
+                            .collect()
+                    }
+                }
+                ["features", feature] => info
+                    .features
+                    .iter()
+                    .filter(|it| *it != feature)
+                    .map(|it| Completion::new(it, CompletionItemKind::ENUM_MEMBER))
+                    .collect(),
+                _ => return None,
+            };
+            Some((range, items))
+        }
+    }
+}
+
+fn dependency_name_completions(info: &ManifestInfo) -> Vec<Completion> {
+    let mut seen = FxHashSet::default();
+    let mut packages: Vec<_> = info.packages.iter().collect();
+    packages.sort_by_key(|it| (!it.is_member, !it.is_dependency));
+    packages
+        .into_iter()
+        .filter(|it| seen.insert(it.name.as_str()))
+        .map(|it| Completion {
+            detail: Some(if it.is_member {
+                "workspace member".to_owned()
+            } else {
+                it.version.clone()
+            }),
+            ..Completion::new(&it.name, CompletionItemKind::MODULE)
+        })
+        .collect()
+}
+
+fn lint_completions(tool: &str) -> Vec<Completion> {
+    let (lints, groups, prefix): (&[Lint], _, _) = match tool {
+        "rust" => (DEFAULT_LINTS, DEFAULT_LINT_GROUPS, ""),
+        "clippy" => (CLIPPY_LINTS, CLIPPY_LINT_GROUPS, "clippy::"),
+        "rustdoc" => (RUSTDOC_LINTS, RUSTDOC_LINT_GROUPS, "rustdoc::"),
+        _ => return Vec::new(),
+    };
+    lints
+        .iter()
+        .chain(groups.iter().map(|it| &it.lint))
+        .map(|lint| Completion {
+            documentation: Some(lint.description),
+            ..Completion::new(
+                lint.label.strip_prefix(prefix).unwrap_or(lint.label),
+                CompletionItemKind::CONSTANT,
+            )
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+struct ManifestDiagnostic {
+    range: TextRange,
+    code: &'static str,
+    message: String,
+}
+
+fn diagnostics_of(text: &str, info: &ManifestInfo) -> Vec<ManifestDiagnostic> {
+    let Some(manifest) = manifest::parse(text) else { return Vec::new() };
+    let mut res = Vec::new();
+    for dependency in dependencies(&manifest) {
+        let package = info.package(&dependency);
+        for (key, value) in dependency.node.entries() {
+            if !DEPENDENCY_KEYS.contains(&key.text.as_str()) {
+                let mut message =
+                    format!("unknown key `{}` in dependency `{}`", key.text, dependency.name.text);
+                if let Some(suggestion) = DEPENDENCY_KEYS
+                    .iter()
+                    .filter(|it| edit_distance(it, &key.text) <= 2)
+                    .min_by_key(|it| edit_distance(it, &key.text))
+                {
+                    message.push_str(&format!(", did you mean `{suggestion}`?"));
+                }
+                res.push(ManifestDiagnostic {
+                    range: key.range,
+                    code: "unknown-dependency-key",
+                    message,
+                });
+            }
+            let (Some(package), "features", Value::Array(items)) =
+                (package, key.text.as_str(), &value.value)
+            else {
+                continue;
+            };
+            for item in items {
+                let Some(feature) = item.as_str() else { continue };
+                if !package.features.iter().any(|it| it == feature) {
+                    res.push(ManifestDiagnostic {
+                        range: item.range,
+                        code: "unknown-feature",
+                        message: format!("package `{}` has no feature `{feature}`", package.name),
+                    });
+                }
+            }
+        }
+    }
+    res.sort_by_key(|it| it.range.start());
+    res
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// The path of `file_id` if it is a `Cargo.toml` file.
+pub(crate) fn manifest_path(snap: &GlobalStateSnapshot, file_id: FileId) -> Option<AbsPathBuf> {
+    let path = snap.file_id_to_file_path(file_id);
+    let path = path.as_path()?;
+    (path.file_name()? == "Cargo.toml").then(|| path.to_path_buf())
+}
+
+pub(crate) fn completions(
+    snap: &GlobalStateSnapshot,
+    file_id: FileId,
+    path: &AbsPath,
+    offset: TextSize,
+) -> Cancellable<Vec<lsp_types::CompletionItem>> {
+    let text = snap.analysis.file_text(file_id)?;
+    let line_index = snap.file_line_index(file_id)?;
+    let info = ManifestInfo::new(&snap.workspaces, path);
+    let Some((range, items)) = completions_at(&text, offset, &info) else { return Ok(Vec::new()) };
+    let range = to_proto::range(&line_index, range);
+    let items = items
+        .into_iter()
+        .map(|it| lsp_types::CompletionItem {
+            text_edit: Some(lsp_types::CompletionTextEdit::Edit(lsp_types::TextEdit {
+                range,
+                new_text: it.label.clone(),
+            })),
+            label: it.label,
+            kind: Some(it.kind),
+            detail: it.detail,
+            documentation: it.documentation.map(|it| {
+                lsp_types::Documentation::MarkupContent(lsp_types::MarkupContent {
+                    kind: lsp_types::MarkupKind::Markdown,
+                    value: it.to_owned(),
+                })
+            }),
+            ..Default::default()
+        })
+        .collect();
+    Ok(items)
+}
+
+pub(crate) fn diagnostics(
+    snap: &GlobalStateSnapshot,
+    file_id: FileId,
+    path: &AbsPath,
+) -> Cancellable<Vec<lsp_types::Diagnostic>> {
+    let text = snap.analysis.file_text(file_id)?;
+    let line_index = snap.file_line_index(file_id)?;
+    let info = ManifestInfo::new(&snap.workspaces, path);
+    let diagnostics = diagnostics_of(&text, &info)
+        .into_iter()
+        .map(|it| lsp_types::Diagnostic {
+            range: to_proto::range(&line_index, it.range),
+            severity: Some(lsp_types::DiagnosticSeverity::WARNING),
+            code: Some(lsp_types::NumberOrString::String(it.code.to_owned())),
+            source: Some("rust-analyzer".to_owned()),
+            message: it.message,
+            ..Default::default()
+        })
+        .collect();
+    Ok(diagnostics)
+}
+
+/// Goes from the name of a dependency to the root of its library crate.
+pub(crate) fn goto_definition(
+    snap: &GlobalStateSnapshot,
+    file_id: FileId,
+    path: &AbsPath,
+    offset: TextSize,
+) -> Cancellable<Vec<lsp_types::Location>> {
+    let text = snap.analysis.file_text(file_id)?;
+    let Some(manifest) = manifest::parse(&text) else { return Ok(Vec::new()) };
+    let info = ManifestInfo::new(&snap.workspaces, path);
+    let root = dependencies(&manifest)
+        .into_iter()
+        .find(|it| it.name.range.contains_inclusive(offset))
+        .and_then(|it| info.package(&it)?.lib_root.clone());
+    let locations = root
+        .map(|root| lsp_types::Location {
+            uri: to_proto::url_from_abs_path(&root),
+            range: lsp_types::Range::default(),
+        })
+        .into_iter()
+        .collect();
+    Ok(locations)
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use super::*;
+
+    fn info() -> ManifestInfo {
+        let dir = AbsPathBuf::assert(env!("CARGO_MANIFEST_DIR").into());
+        let package =
+            |name: &str, path: &str, is_member, is_dependency, features: &[&str]| PackageInfo {
+                name: name.to_owned(),
+                version: "1.0.0".to_owned(),
+                manifest: dir.join(path).join("Cargo.toml"),
+                is_member,
+                is_dependency,
+                features: features.iter().map(|it| it.to_string()).collect(),
+                lib_root: None,
+            };
+        ManifestInfo {
+            packages: vec![
+                package("serde", "registry/serde", false, true, &["default", "derive", "std"]),
+                package("helper", "helper", true, false, &["fast"]),
+                package("serde", "vendor/serde", false, false, &["alloc"]),
+            ],
+            dependencies: [("serde".to_owned(), 0), ("renamed".to_owned(), 0)]
+                .into_iter()
+                .collect(),
+            features: vec!["default".to_owned(), "extra".to_owned()],
+            dir: Some(dir),
+        }
+    }
+
+    fn check_completions(ra_fixture: &str, expect: Expect) {
+        let offset = ra_fixture.find("$0").unwrap();
+        let text = ra_fixture.replace("$0", "");
+        let offset = TextSize::from(offset as u32);
+        let (range, items) = completions_at(&text, offset, &info()).unwrap_or_default();
+        let mut labels: Vec<_> = items.iter().map(|it| it.label.as_str()).take(8).collect();
+        if items.len() > labels.len() {
+            labels.push("...");
+        }
+        let actual = format!("{:?} {}", &text[range], labels.join(" "));
+        expect.assert_eq(&actual);
+    }
+
+    fn check_diagnostics(text: &str, expect: Expect) {
+        let actual = diagnostics_of(text, &info())
+            .into_iter()
+            .map(|it| format!("{:?} {}: {}\n", &text[it.range], it.code, it.message))
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn complete_tables_and_keys() {
+        check_completions(
+            r#"
+[pack$0
+"#,
+            expect![[
+                r#""pack" package package.metadata lib dependencies dev-dependencies build-dependencies features lints ..."#
+            ]],
+        );
+        check_completions(
+            r#"
+[[$0]]
+"#,
+            expect![[r#""" bin example test bench"#]],
+        );
+        check_completions(
+            r#"
+[package]
+name = "foo"
+ed$0
+"#,
+            expect![[
+                r#""ed" name version authors edition rust-version description documentation readme ..."#
+            ]],
+        );
+        check_completions(
+            r#"
+[dependencies]
+serde = { version = "1", $0 }
+"#,
+            expect![[r#""" version path git branch tag rev features optional ..."#]],
+        );
+    }
+
+    #[test]
+    fn complete_dependencies_and_features() {
+        check_completions(
+            r#"
+[dependencies]
+$0
+"#,
+            expect![[r#""" helper serde"#]],
+        );
+        check_completions(
+            r#"
+[dependencies]
+serde = { version = "1", features = ["de$0"] }
+"#,
+            expect![[r#""de" default derive std"#]],
+        );
+        check_completions(
+            r#"
+[target.'cfg(unix)'.dependencies.renamed]
+package = "serde"
+features = [
+    "std",
+    "$0",
+]
+"#,
+            expect![[r#""" default derive std"#]],
+        );
+        check_completions(
+            r#"
+[features]
+extra = ["$0"]
+"#,
+            expect![[r#""" default"#]],
+        );
+    }
+
+    #[test]
+    fn complete_lints() {
+        check_completions(
+            r#"
+[lints.clippy]
+needless_$0
+"#,
+            expect![[
+                r#""needless_" absolute_paths absurd_extreme_comparisons alloc_instead_of_core allow_attributes allow_attributes_without_reason almost_complete_range almost_swapped approx_constant ..."#
+            ]],
+        );
+    }
+
+    #[test]
+    fn unknown_keys_and_features() {
+        check_diagnostics(
+            r#"
+[dependencies]
+serde = { verison = "1", features = ["derive", "sdt"] }
+helper.features = ["fast", "slow"]
+unknown = { features = ["anything"] }
+
+[dev-dependencies.serde]
+optinal = true
+"#,
+            expect![[r#"
+                "verison" unknown-dependency-key: unknown key `verison` in dependency `serde`, did you mean `version`?
+                "\"sdt\"" unknown-feature: package `serde` has no feature `sdt`
+                "\"slow\"" unknown-feature: package `helper` has no feature `slow`
+                "optinal" unknown-dependency-key: unknown key `optinal` in dependency `serde`, did you mean `optional`?
+            "#]],
+        );
+    }
+
+    #[test]
+    fn features_of_same_named_packages() {
+        check_diagnostics(
+            r#"
+[package.metadata]
+released = 1979-05-27T07:32:00Z
+
+[dependencies]
+serde = { version = "1", features = ["derive"] }
+vendored = { path = "vendor/serde", package = "serde", features = ["derive", "alloc"] }
+"#,
+            expect![[r#"
+                "\"derive\"" unknown-feature: package `serde` has no feature `derive`
+            "#]],
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! The tree of a `Cargo.toml` file, parsed with the `toml` crate, with the ranges of its keys and
+//! values.
+//!
+//! `toml` only knows the ranges of keys and of values written after a `=`, the tables declared by
+//! a `[header]` get an empty range at the start of the file.
+
+use std::fmt;
+
+use ide::{TextRange, TextSize};
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use toml::Spanned;
+
+#[derive(Debug)]
+pub(crate) struct Key {
+    pub(crate) text: String,
+    pub(crate) range: TextRange,
+}
+
+#[derive(Debug)]
+pub(crate) struct Node {
+    pub(crate) value: Value,
+    /// The range of the value, including the quotes of strings.
+    pub(crate) range: TextRange,
+}
+
+#[derive(Debug)]
+pub(crate) enum Value {
+    String(String),
+    Array(Vec<Node>),
+    /// The entries of the table, in source order.
+    Table(Vec<(Key, Node)>),
+    /// Numbers, booleans and dates.
+    Other,
+}
+
+impl Node {
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            Value::String(it) => Some(it),
+            _ => None,
+        }
+    }
+
+    /// The entries of the table, empty for other values.
+    pub(crate) fn entries(&self) -> &[(Key, Node)] {
+        match &self.value {
+            Value::Table(it) => it,
+            _ => &[],
+        }
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<&Node> {
+        self.entries().iter().find(|(it, _)| it.text == key).map(|(_, node)| node)
+    }
+}
+
+/// Parses `text` into the root table, or returns `None` if it isn't valid TOML.
+pub(crate) fn parse(text: &str) -> Option<Node> {
+    toml::from_str(text).ok()
+}
+
+fn range((start, end): (usize, usize)) -> TextRange {
+    TextRange::new(TextSize::from(start as u32), TextSize::from(end as u32))
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Key, D::Error> {
+        let key = Spanned::<String>::deserialize(deserializer)?;
+        Ok(Key { range: range(key.span()), text: key.into_inner() })
+    }
+}
+
+impl<'de> Deserialize<'de> for Node {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Node, D::Error> {
+        let value = Spanned::<Value>::deserialize(deserializer)?;
+        Ok(Node { range: range(value.span()), value: value.into_inner() })
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a TOML value")
+    }
+
+    fn visit_bool<E: de::Error>(self, _: bool) -> Result<Value, E> {
+        Ok(Value::Other)
+    }
+
+    fn visit_i64<E: de::Error>(self, _: i64) -> Result<Value, E> {
+        Ok(Value::Other)
+    }
+
+    fn visit_u64<E: de::Error>(self, _: u64) -> Result<Value, E> {
+        Ok(Value::Other)
+    }
+
+    fn visit_f64<E: de::Error>(self, _: f64) -> Result<Value, E> {
+        Ok(Value::Other)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Value, E> {
+        Ok(Value::String(value.to_owned()))
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<Value, E> {
+        Ok(Value::String(value))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut entries = Vec::new();
+        loop {
+            let key = match map.next_key::<Key>() {
+                Ok(Some(it)) => it,
+                Ok(None) => break,
+                // Dates are passed as a map with a single key that has no range.
+                Err(_) if entries.is_empty() => {
+                    map.next_value::<de::IgnoredAny>()?;
+                    return Ok(Value::Other);
+                }
+                Err(e) => return Err(e),
+            };
+            entries.push((key, map.next_value()?));
+        }
+        Ok(Value::Table(entries))
+    }
+}
@@ -6,6 +6,8 @@ mod symbols;
 mod highlight;
 mod analysis_stats;
 mod diagnostics;
+mod memory_layout;
+mod signature_search;
 mod ssr;
 mod lsif;
 mod scip;
@@ -763,6 +763,10 @@ impl flags::AnalysisStats {
                         skip_glob_imports: true,
                     },
                     prefer_no_std: Default::default(),
+                    enum_variant_size_threshold:
+                        DiagnosticsConfig::DEFAULT_ENUM_VARIANT_SIZE_THRESHOLD,
+                    pass_by_value_size_limit: DiagnosticsConfig::DEFAULT_PASS_BY_VALUE_SIZE_LIMIT,
+                    unreachable_code: true,
                 },
                 ide::AssistResolveStrategy::All,
                 file_id,
@@ -781,6 +785,9 @@ impl flags::AnalysisStats {
                     adjustment_hints_hide_outside_unsafe: false,
                     closure_return_type_hints: ide::ClosureReturnTypeHints::Always,
                     closure_capture_hints: true,
+                    drop_hints: true,
+                    implicit_trait_call_hints: true,
+                    generic_arg_hints: true,
                     binding_mode_hints: true,
                     lifetime_elision_hints: ide::LifetimeElisionHints::Always,
                     param_names_for_lifetime_elision_hints: true,
@@ -789,6 +796,7 @@ impl flags::AnalysisStats {
                     closure_style: hir::ClosureStyle::ImplFn,
                     max_length: Some(25),
                     closing_brace_hints_min_lines: Some(20),
+                    generic_arg_hints_max_length: Some(25),
                     fields_to_resolve: InlayFieldsToResolve::empty(),
                 },
                 file_id,
@@ -806,6 +814,7 @@ impl flags::AnalysisStats {
                         annotate_method_references: false,
                         annotate_enum_variant_references: false,
                         location: ide::AnnotationLocation::AboveName,
+                        test_config: ide::TestConfig { test_attributes: Vec::new() },
                     },
                     file_id,
                 )
@@ -43,7 +43,12 @@ xflags::xflags! {
         }
 
         /// Parse stdin and print the list of symbols.
-        cmd symbols {}
+        cmd symbols {
+            /// Print the symbols matching this workspace symbol search query instead.
+            optional --query query: String
+            /// Search the extended index, which also covers fields, enum variants, locals and docs.
+            optional --extended
+        }
 
         /// Highlight stdin as html.
         cmd highlight {
@@ -98,6 +103,32 @@ xflags::xflags! {
             required path: PathBuf
         }
 
+        /// Print the memory layout of a type, including padding, niches and enum tags.
+        cmd memory-layout {
+            /// Directory with Cargo.toml.
+            required path: PathBuf
+            /// The type to compute the layout of, as written at the root of a workspace crate
+            /// (`Option<Box<my_crate::Foo>>`).
+            required ty: String
+
+            /// Print the layout as JSON instead of text.
+            optional --json
+        }
+
+        /// Search the functions of a workspace and its dependencies by their type signature.
+        cmd signature-search {
+            /// Directory with Cargo.toml.
+            required path: PathBuf
+            /// The signature pattern, resolved at the root of a workspace crate
+            /// (`fn(&str) -> Result<Url, _>`).
+            required pattern: String
+
+            /// Only search the crates of the workspace.
+            optional --workspace-only
+            /// The maximal number of functions to print.
+            optional --limit limit: usize
+        }
+
         cmd diagnostics {
             /// Directory with Cargo.toml.
             required path: PathBuf
@@ -159,6 +190,8 @@ pub enum RustAnalyzerCmd {
     Highlight(Highlight),
     AnalysisStats(AnalysisStats),
     RunTests(RunTests),
+    MemoryLayout(MemoryLayout),
+    SignatureSearch(SignatureSearch),
     Diagnostics(Diagnostics),
     Ssr(Ssr),
     Search(Search),
@@ -178,7 +211,10 @@ pub struct Parse {
 }
 
 #[derive(Debug)]
-pub struct Symbols;
+pub struct Symbols {
+    pub query: Option<String>,
+    pub extended: bool,
+}
 
 #[derive(Debug)]
 pub struct Highlight {
@@ -211,6 +247,23 @@ pub struct RunTests {
     pub path: PathBuf,
 }
 
+#[derive(Debug)]
+pub struct MemoryLayout {
+    pub path: PathBuf,
+    pub ty: String,
+
+    pub json: bool,
+}
+
+#[derive(Debug)]
+pub struct SignatureSearch {
+    pub path: PathBuf,
+    pub pattern: String,
+
+    pub workspace_only: bool,
+    pub limit: Option<usize>,
+}
+
 #[derive(Debug)]
 pub struct Diagnostics {
     pub path: PathBuf,
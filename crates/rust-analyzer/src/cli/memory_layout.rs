@@ -0,0 +1,45 @@
+//! Print the memory layout of a type written in terms of a workspace crate.
+
+use hir::Crate;
+use ide_db::base_db::SourceDatabaseExt;
+use load_cargo::{load_workspace_at, LoadCargoConfig, ProcMacroServerChoice};
+use project_model::{CargoConfig, RustLibSource};
+
+use crate::{cli::flags, lsp::to_proto};
+
+impl flags::MemoryLayout {
+    pub fn run(self) -> anyhow::Result<()> {
+        let cargo_config =
+            CargoConfig { sysroot: Some(RustLibSource::Discover), ..Default::default() };
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: true,
+            with_proc_macro_server: ProcMacroServerChoice::Sysroot,
+            prefill_caches: false,
+        };
+        let (host, _vfs, _proc_macro) =
+            load_workspace_at(&self.path, &cargo_config, &load_cargo_config, &|_| {})?;
+        let db = host.raw_database();
+        let analysis = host.analysis();
+
+        let layout = Crate::all(db)
+            .into_iter()
+            .map(|krate| krate.root_module().definition_source_file_id(db).original_file(db))
+            .filter(|&file_id| !db.source_root(db.file_source_root(file_id)).is_library)
+            .find_map(|file_id| {
+                analysis.get_recursive_memory_layout_of_type(file_id, &self.ty).ok().flatten()
+            });
+        let Some(layout) = layout else {
+            anyhow::bail!("could not compute the layout of `{}`", self.ty);
+        };
+
+        if self.json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&to_proto::recursive_memory_layout(layout))?
+            );
+        } else {
+            print!("{layout}");
+        }
+        Ok(())
+    }
+}
@@ -6,16 +6,14 @@ use std::{
     time::Instant,
 };
 
-use ide::{
-    LineCol, MonikerDescriptorKind, StaticIndex, StaticIndexedFile, TextRange, TokenId,
-    TokenStaticData,
-};
+use ide::{LineCol, StaticIndex, StaticIndexedFile, TextRange, TokenId, TokenStaticData};
 use ide_db::LineIndexDatabase;
 use load_cargo::{load_workspace_at, LoadCargoConfig, ProcMacroServerChoice};
 use scip::types as scip_types;
 
 use crate::{
     cli::flags,
+    external_symbols::moniker_to_symbol,
     line_index::{LineEndings, LineIndex, PositionEncoding},
 };
 
@@ -208,70 +206,10 @@ fn text_range_to_scip_range(line_index: &LineIndex, range: TextRange) -> Vec<i32
     }
 }
 
-fn new_descriptor_str(
-    name: &str,
-    suffix: scip_types::descriptor::Suffix,
-) -> scip_types::Descriptor {
-    scip_types::Descriptor {
-        name: name.to_string(),
-        disambiguator: "".to_string(),
-        suffix: suffix.into(),
-        special_fields: Default::default(),
-    }
-}
-
-fn new_descriptor(name: &str, suffix: scip_types::descriptor::Suffix) -> scip_types::Descriptor {
-    if name.contains('\'') {
-        new_descriptor_str(&format!("`{name}`"), suffix)
-    } else {
-        new_descriptor_str(&name, suffix)
-    }
-}
-
-/// Loosely based on `def_to_moniker`
-///
 /// Only returns a Symbol when it's a non-local symbol.
 ///     So if the visibility isn't outside of a document, then it will return None
 fn token_to_symbol(token: &TokenStaticData) -> Option<scip_types::Symbol> {
-    use scip_types::descriptor::Suffix::*;
-
-    let moniker = token.moniker.as_ref()?;
-
-    let package_name = moniker.package_information.name.clone();
-    let version = moniker.package_information.version.clone();
-    let descriptors = moniker
-        .identifier
-        .description
-        .iter()
-        .map(|desc| {
-            new_descriptor(
-                &desc.name,
-                match desc.desc {
-                    MonikerDescriptorKind::Namespace => Namespace,
-                    MonikerDescriptorKind::Type => Type,
-                    MonikerDescriptorKind::Term => Term,
-                    MonikerDescriptorKind::Method => Method,
-                    MonikerDescriptorKind::TypeParameter => TypeParameter,
-                    MonikerDescriptorKind::Parameter => Parameter,
-                    MonikerDescriptorKind::Macro => Macro,
-                    MonikerDescriptorKind::Meta => Meta,
-                },
-            )
-        })
-        .collect();
-
-    Some(scip_types::Symbol {
-        scheme: "rust-analyzer".into(),
-        package: Some(scip_types::Package {
-            manager: "cargo".to_string(),
-            name: package_name,
-            version: version.unwrap_or_else(|| ".".to_string()),
-            special_fields: Default::default(),
-        })
-        .into(),
-        descriptors,
-        special_fields: Default::default(),
-    })
+    token.moniker.as_ref().map(moniker_to_symbol)
 }
 
 #[cfg(test)]
@@ -0,0 +1,37 @@
+//! Search the functions of a workspace and its dependencies by their type signature.
+
+use ide_db::LineIndexDatabase;
+use load_cargo::{load_workspace_at, LoadCargoConfig, ProcMacroServerChoice};
+use project_model::{CargoConfig, RustLibSource};
+
+use crate::cli::flags;
+
+impl flags::SignatureSearch {
+    pub fn run(self) -> anyhow::Result<()> {
+        let cargo_config =
+            CargoConfig { sysroot: Some(RustLibSource::Discover), ..Default::default() };
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: true,
+            with_proc_macro_server: ProcMacroServerChoice::Sysroot,
+            prefill_caches: false,
+        };
+        let (host, vfs, _proc_macro) =
+            load_workspace_at(&self.path, &cargo_config, &load_cargo_config, &|_| {})?;
+        let db = host.raw_database();
+        let analysis = host.analysis();
+
+        let limit = self.limit.unwrap_or(128);
+        let Some(results) =
+            analysis.signature_search(&self.pattern, None, !self.workspace_only, limit)?
+        else {
+            anyhow::bail!("`{}` is not a signature pattern like `fn(&str) -> Url`", self.pattern);
+        };
+        for result in results {
+            let nav = result.navigation_target;
+            let line_col = db.line_index(nav.file_id).line_col(nav.focus_or_full_range().start());
+            let path = vfs.file_path(nav.file_id);
+            println!("{path}:{}:{}: {}", line_col.line + 1, line_col.col + 1, result.signature);
+        }
+        Ok(())
+    }
+}
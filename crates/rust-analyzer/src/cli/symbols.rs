@@ -1,5 +1,5 @@
 //! Read Rust code on stdin, print syntax tree on stdout.
-use ide::Analysis;
+use ide::{Analysis, Query};
 
 use crate::cli::{flags, read_stdin};
 
@@ -7,6 +7,16 @@ impl flags::Symbols {
     pub fn run(self) -> anyhow::Result<()> {
         let text = read_stdin()?;
         let (analysis, file_id) = Analysis::from_single_file(text);
+        if let Some(query) = self.query {
+            let mut query = Query::new(query);
+            if self.extended {
+                query.extended();
+            }
+            for nav in analysis.symbol_search(query).unwrap() {
+                println!("{nav:?}");
+            }
+            return Ok(());
+        }
         let structure = analysis.file_structure(file_id).unwrap();
         for s in structure {
             println!("{s:?}");
@@ -0,0 +1,221 @@
+//! A history of the completions the user accepted, used to rank the items they pick often or
+//! picked recently higher.
+//!
+//! The client tells us about accepted items through the `rust-analyzer.completionAccepted`
+//! command we attach to completion items. Items are told apart by their label and the path they
+//! import or their kind. The history is kept per workspace in the user's cache directory, so that
+//! it survives `cargo clean`, and never leaves the machine. It is written on the task pool
+//! after items are accepted, coalescing the writes of items accepted in the meantime.
+
+use std::{
+    env,
+    hash::{Hash, Hasher},
+    io,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ide::{CompletionItem, CompletionRelevance};
+use rustc_hash::{FxHashMap, FxHasher};
+use vfs::{AbsPath, AbsPathBuf};
+
+use crate::lsp::ext as lsp_ext;
+
+/// The number of items we remember, the least recently accepted ones are forgotten first.
+const MAX_ENTRIES: usize = 2000;
+
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+const MONTH: u64 = 30 * DAY;
+
+#[derive(Debug, Default)]
+pub(crate) struct CompletionHistory {
+    path: Option<AbsPathBuf>,
+    entries: FxHashMap<Key, Entry>,
+}
+
+/// The label of an item and its [`qualifier`].
+type Key = (String, String);
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    count: u32,
+    last_accepted: u64,
+}
+
+impl CompletionHistory {
+    /// Loads the history stored at `path`, starting with an empty one if there is none yet.
+    pub(crate) fn load(path: AbsPathBuf) -> (CompletionHistory, Option<String>) {
+        let mut res = CompletionHistory { path: None, entries: FxHashMap::default() };
+        let error = match std::fs::read(&path) {
+            Ok(bytes) => {
+                match serde_json::from_slice::<Vec<lsp_ext::CompletionHistoryEntry>>(&bytes) {
+                    Ok(entries) => {
+                        res.entries = entries
+                            .into_iter()
+                            .map(|it| {
+                                (
+                                    (it.label, it.qualifier),
+                                    Entry { count: it.count, last_accepted: it.last_accepted },
+                                )
+                            })
+                            .collect();
+                        None
+                    }
+                    Err(e) => Some(format!("failed to parse completion history {path}: {e}")),
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => Some(format!("failed to read completion history {path}: {e}")),
+        };
+        res.path = Some(path);
+        (res, error)
+    }
+
+    /// Where the history of the workspace at `root` is stored, if the user has a cache directory.
+    pub(crate) fn path_for(root: &AbsPath) -> Option<AbsPathBuf> {
+        let mut hasher = FxHasher::default();
+        root.hash(&mut hasher);
+        let name = root.file_name().and_then(|it| it.to_str()).unwrap_or("workspace");
+        let dir = cache_dir()?.join("rust-analyzer").join("completion-history");
+        Some(dir.join(format!("{name}-{:016x}.json", hasher.finish())))
+    }
+
+    pub(crate) fn path(&self) -> Option<&AbsPath> {
+        self.path.as_deref()
+    }
+
+    pub(crate) fn record(&mut self, label: &str, qualifier: &str) {
+        let now = now();
+        let entry = self
+            .entries
+            .entry((label.to_owned(), qualifier.to_owned()))
+            .or_insert(Entry { count: 0, last_accepted: 0 });
+        entry.count = entry.count.saturating_add(1);
+        entry.last_accepted = now;
+        if self.entries.len() > MAX_ENTRIES {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, it)| it.last_accepted)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.entries.clear();
+    }
+
+    /// The accepted items, most often accepted first.
+    pub(crate) fn entries(&self) -> Vec<lsp_ext::CompletionHistoryEntry> {
+        let mut res: Vec<_> = self
+            .entries
+            .iter()
+            .map(|((label, qualifier), entry)| lsp_ext::CompletionHistoryEntry {
+                label: label.clone(),
+                qualifier: qualifier.clone(),
+                count: entry.count,
+                last_accepted: entry.last_accepted,
+            })
+            .collect();
+        res.sort_by(|a, b| b.count.cmp(&a.count).then(b.last_accepted.cmp(&a.last_accepted)));
+        res
+    }
+
+    /// The [`CompletionRelevance::history_score`] of `item`.
+    pub(crate) fn score(&self, item: &CompletionItem) -> u8 {
+        self.score_at(&(item.label.to_string(), qualifier(item)), now())
+    }
+
+    fn score_at(&self, key: &Key, now: u64) -> u8 {
+        let Some(entry) = self.entries.get(key) else { return 0 };
+        let frequency = match entry.count {
+            0 => 0,
+            1 => 1,
+            2..=3 => 2,
+            4..=7 => 3,
+            _ => 4,
+        };
+        let recency = match now.saturating_sub(entry.last_accepted) {
+            age if age <= HOUR => 4,
+            age if age <= DAY => 3,
+            age if age <= WEEK => 2,
+            age if age <= MONTH => 1,
+            _ => 0,
+        };
+        (frequency + recency).min(CompletionRelevance::MAX_HISTORY_SCORE)
+    }
+}
+
+/// Writes `entries` to `path`, this is done on the task pool.
+pub(crate) fn save(path: &AbsPath, entries: &[lsp_ext::CompletionHistoryEntry]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_vec(entries)?)
+}
+
+/// What tells apart items with the same label: the path of the import they add, or their kind.
+pub(crate) fn qualifier(item: &CompletionItem) -> String {
+    match item.import_to_add.first() {
+        Some((path, _)) => path.clone(),
+        None => item.kind.tag().to_owned(),
+    }
+}
+
+/// The user's cache directory, following the conventions of each platform.
+fn cache_dir() -> Option<AbsPathBuf> {
+    let var = |name| env::var_os(name).filter(|it| !it.is_empty()).map(PathBuf::from);
+    let dir = if cfg!(windows) {
+        var("LOCALAPPDATA")?
+    } else if let Some(dir) = var("XDG_CACHE_HOME") {
+        dir
+    } else if cfg!(target_os = "macos") {
+        var("HOME")?.join("Library").join("Caches")
+    } else {
+        var("HOME")?.join(".cache")
+    };
+    AbsPathBuf::try_from(dir).ok()
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |it| it.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frequent_and_recent_items_score_higher() {
+        let mut history = CompletionHistory::default();
+        let now = 100 * MONTH;
+        let key = |label: &str| (label.to_owned(), "fn".to_owned());
+        history.entries.insert(key("old"), Entry { count: 1, last_accepted: 0 });
+        history.entries.insert(key("recent"), Entry { count: 1, last_accepted: now - 10 });
+        history.entries.insert(key("frequent"), Entry { count: 20, last_accepted: 0 });
+        history.entries.insert(key("both"), Entry { count: 20, last_accepted: now - DAY });
+
+        let scores: Vec<_> = ["unknown", "old", "recent", "frequent", "both"]
+            .iter()
+            .map(|label| history.score_at(&key(label), now))
+            .collect();
+        assert_eq!(scores, [0, 1, 5, 4, 7]);
+    }
+
+    #[test]
+    fn items_are_told_apart_by_qualifier() {
+        let mut history = CompletionHistory::default();
+        history.record("Result", "std::io::Result");
+        history.record("Result", "std::io::Result");
+        history.record("Result", "en");
+
+        let entries: Vec<_> =
+            history.entries().into_iter().map(|it| (it.qualifier, it.count)).collect();
+        assert_eq!(entries, [("std::io::Result".to_owned(), 2), ("en".to_owned(), 1)]);
+    }
+}
@@ -15,7 +15,7 @@ use ide::{
     AssistConfig, CallableSnippets, CompletionConfig, DiagnosticsConfig, ExprFillDefaultMode,
     HighlightConfig, HighlightRelatedConfig, HoverConfig, HoverDocFormat, InlayFieldsToResolve,
     InlayHintsConfig, JoinLinesConfig, MemoryLayoutHoverConfig, MemoryLayoutHoverRenderKind,
-    Snippet, SnippetScope,
+    Snippet, SnippetScope, TestConfig,
 };
 use ide_db::{
     imports::insert_use::{ImportGranularity, InsertUseConfig, PrefixKind},
@@ -219,6 +219,11 @@ config_data! {
         completion_callable_snippets: CallableCompletionDef  = "\"fill_arguments\"",
         /// Whether to show full function/method signatures in completion docs.
         completion_fullFunctionSignatures_enable: bool = "false",
+        /// Whether to rank the completions accepted often or recently in this workspace higher.
+        /// The history of accepted completions is stored in the user's cache directory and never
+        /// leaves the machine. This needs client support
+        /// for the `rust-analyzer.completionAccepted` command.
+        completion_history_enable: bool = "false",
         /// Maximum number of completions to return. If `None`, the limit is infinite.
         completion_limit: Option<usize> = "null",
         /// Whether to show postfix snippets like `dbg`, `if`, `not`, etc.
@@ -273,12 +278,25 @@ config_data! {
         diagnostics_disabled: FxHashSet<String> = "[]",
         /// Whether to show native rust-analyzer diagnostics.
         diagnostics_enable: bool                = "true",
+        /// The size difference in bytes between the largest and the second-largest variant of an
+        /// enum from which on the experimental `large_enum_variant` diagnostic is shown.
+        diagnostics_enumVariantSizeThreshold: usize = "200",
         /// Whether to show experimental rust-analyzer diagnostics that might
         /// have more false positives than usual.
         diagnostics_experimental_enable: bool    = "false",
+        /// The size in bytes of `Copy` arguments passed by value from which on the experimental
+        /// `large_types_passed_by_value` diagnostic is shown.
+        diagnostics_passByValueSizeLimit: usize = "256",
+        /// Whether the client pulls diagnostics with the `textDocument/diagnostic` and
+        /// `workspace/diagnostic` requests instead of having them published by the server. This
+        /// also reports native diagnostics of files that are not open. Requires client support.
+        diagnostics_pull_enable: bool = "false",
         /// Map of prefixes to be substituted when parsing diagnostic file paths.
         /// This should be the reverse mapping of what is passed to `rustc` as `--remap-path-prefix`.
         diagnostics_remapPrefix: FxHashMap<String, String> = "{}",
+        /// Whether to show the experimental diagnostics for unreachable statements, match arms
+        /// and branches.
+        diagnostics_unreachableCode_enable: bool = "false",
         /// List of warnings that should be displayed with hint severity.
         ///
         /// The warnings will be indicated by faded text or three dots in code
@@ -373,12 +391,24 @@ config_data! {
         inlayHints_closureStyle: ClosureStyle                                = "\"impl_fn\"",
         /// Whether to show enum variant discriminant hints.
         inlayHints_discriminantHints_enable: DiscriminantHintsDef            = "\"never\"",
+        /// Whether to show inlay hints for the values dropped at the end of a block, a statement, or
+        /// by leaving the block early, in the order they are dropped.
+        inlayHints_dropHints_enable: bool                                    = "false",
         /// Whether to show inlay hints for type adjustments.
         inlayHints_expressionAdjustmentHints_enable: AdjustmentHintsDef = "\"never\"",
         /// Whether to hide inlay hints for type adjustments outside of `unsafe` blocks.
         inlayHints_expressionAdjustmentHints_hideOutsideUnsafe: bool = "false",
         /// Whether to show inlay hints as postfix ops (`.*` instead of `*`, etc).
         inlayHints_expressionAdjustmentHints_mode: AdjustmentHintsModeDef = "\"prefix\"",
+        /// Whether to show inlay hints for the inferred generic arguments of function and method
+        /// calls, and of the types in paths like `HashMap::new()`.
+        inlayHints_genericArgumentHints_enable: bool                 = "false",
+        /// Maximum length for the types shown in generic argument inlay hints. Null means unlimited.
+        inlayHints_genericArgumentHints_maxLength: Option<usize>     = "25",
+        /// Whether to show inlay hints for the trait methods called by overloaded operators, `?`
+        /// error conversions, `for` loops, autoderef and drops at the end of scopes, linking to the
+        /// impl that is used.
+        inlayHints_implicitTraitCalls_enable: bool                 = "false",
         /// Whether to show inlay type hints for elided lifetimes in function signatures.
         inlayHints_lifetimeElisionHints_enable: LifetimeElisionDef = "\"never\"",
         /// Whether to prefer using parameter names as the name for elided lifetime hints if possible.
@@ -479,6 +509,11 @@ config_data! {
         /// Additional arguments to be passed to cargo for runnables such as
         /// tests or binaries. For example, it may be `--release`.
         runnables_extraArgs: Vec<String>   = "[]",
+        /// Paths of attribute macros that turn a function into a test, in addition to `#[test]`.
+        /// These are used when the macro isn't expanded, for example when proc-macro support is
+        /// disabled. The cases of parameterised tests, like `#[case]` of `rstest` or
+        /// `#[test_case]`, get a runnable each.
+        runnables_testAttributes: Vec<String> = "[\"tokio::test\", \"async_std::test\", \"actix_rt::test\", \"rstest::rstest\", \"test_case::test_case\", \"test_log::test\", \"wasm_bindgen_test::wasm_bindgen_test\"]",
 
         /// Optional path to a rust-analyzer specific target directory.
         /// This prevents rust-analyzer's `cargo check` from locking the `Cargo.lock`
@@ -511,6 +546,12 @@ config_data! {
         /// available on a nightly build.
         rustfmt_rangeFormatting_enable: bool = "false",
 
+        /// Paths to SCIP indices of dependencies, like the ones generated by `rust-analyzer scip`.
+        /// Go to definition and find all references also return the locations these indices record
+        /// for symbols of other crates, which can point to sources that aren't checked out
+        /// locally. Relative paths are resolved against the workspace root.
+        scip_indices: Vec<PathBuf> = "[]",
+
         /// Inject additional highlighting into doc comments.
         ///
         /// When enabled, rust-analyzer will highlight rust source in doc comments as well as intra
@@ -553,9 +594,17 @@ config_data! {
         /// Show documentation.
         signatureInfo_documentation_enable: bool                       = "true",
 
+        /// Whether to discover the tests of the workspace and run them on behalf of
+        /// the client's test explorer.
+        testExplorer: bool = "false",
+
         /// Whether to insert closing angle brackets when typing an opening angle bracket of a generic argument list.
         typing_autoClosingAngleBrackets_enable: bool = "false",
 
+        /// Whether the workspace symbol search also covers struct fields, enum variants, locals of
+        /// the workspace's function bodies and the words of doc comments. Results of such searches are ranked, and
+        /// searching dependencies includes the workspace.
+        workspace_symbol_search_extended: bool = "false",
         /// Workspace symbol search kind.
         workspace_symbol_search_kind: WorkspaceSymbolSearchKindDef = "\"only_types\"",
         /// Limits the number of items returned from a workspace symbol search (Defaults to 128).
@@ -579,6 +628,8 @@ pub struct Config {
     /// The workspace roots as registered by the LSP client
     workspace_roots: Vec<AbsPathBuf>,
     caps: lsp_types::ClientCapabilities,
+    /// The pull diagnostics capabilities of the client, which `lsp_types` doesn't model yet.
+    diagnostic_caps: DiagnosticClientCapabilities,
     root_path: AbsPathBuf,
     data: ConfigData,
     detached_files: Vec<AbsPathBuf>,
@@ -588,6 +639,14 @@ pub struct Config {
 
 type ParallelCachePrimingNumThreads = u8;
 
+#[derive(Debug, Clone, Default)]
+struct DiagnosticClientCapabilities {
+    /// `textDocument.diagnostic.dynamicRegistration`
+    dynamic_registration: bool,
+    /// `workspace.diagnostics.refreshSupport`
+    refresh_support: bool,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum LinkedProject {
     ProjectManifest(ProjectManifest),
@@ -743,6 +802,8 @@ pub struct WorkspaceSymbolConfig {
     pub search_kind: WorkspaceSymbolSearchKind,
     /// How many items are returned at most.
     pub search_limit: usize,
+    /// Whether fields, enum variants, locals and doc comments are searched as well.
+    pub search_extended: bool,
 }
 
 pub struct ClientCommandsConfig {
@@ -751,6 +812,7 @@ pub struct ClientCommandsConfig {
     pub show_reference: bool,
     pub goto_location: bool,
     pub trigger_parameter_hints: bool,
+    pub completion_accepted: bool,
 }
 
 #[derive(Debug)]
@@ -785,6 +847,7 @@ impl Config {
     ) -> Self {
         Config {
             caps,
+            diagnostic_caps: DiagnosticClientCapabilities::default(),
             data: ConfigData::default(),
             detached_files: Vec::new(),
             discovered_projects: Vec::new(),
@@ -795,6 +858,16 @@ impl Config {
         }
     }
 
+    /// Reads the client capabilities `lsp_types` doesn't know about from the raw `capabilities`
+    /// of the `initialize` request.
+    pub fn update_raw_client_capabilities(&mut self, caps: &serde_json::Value) {
+        let flag = |pointer: &str| caps.pointer(pointer).and_then(|it| it.as_bool()) == Some(true);
+        self.diagnostic_caps = DiagnosticClientCapabilities {
+            dynamic_registration: flag("/textDocument/diagnostic/dynamicRegistration"),
+            refresh_support: flag("/workspace/diagnostics/refreshSupport"),
+        };
+    }
+
     pub fn rediscover_workspaces(&mut self) {
         let discovered = ProjectManifest::discover_all(&self.workspace_roots);
         tracing::info!("discovered projects: {:?}", discovered);
@@ -837,6 +910,9 @@ impl Config {
                 SnippetScopeDef::Expr => SnippetScope::Expr,
                 SnippetScopeDef::Type => SnippetScope::Type,
                 SnippetScopeDef::Item => SnippetScope::Item,
+                SnippetScopeDef::ImplBody => SnippetScope::ImplBody,
+                SnippetScopeDef::TestModule => SnippetScope::TestModule,
+                SnippetScopeDef::Pattern => SnippetScope::Pattern,
             };
             match Snippet::new(
                 &def.prefix,
@@ -962,6 +1038,25 @@ impl Config {
         caps.did_save == Some(true) && caps.dynamic_registration == Some(true)
     }
 
+    /// Whether the client can register for `Cargo.toml` documents next to the Rust files it was
+    /// statically told about.
+    pub fn cargo_toml_dynamic_registration(&self) -> bool {
+        (|| {
+            let caps = self.caps.text_document.as_ref()?;
+            Some(
+                caps.synchronization.as_ref()?.dynamic_registration?
+                    && caps.completion.as_ref()?.dynamic_registration?,
+            )
+        })()
+        .unwrap_or_default()
+    }
+
+    pub fn type_hierarchy_dynamic_registration(&self) -> bool {
+        try_or_def!(
+            self.caps.text_document.as_ref()?.type_hierarchy.as_ref()?.dynamic_registration?
+        )
+    }
+
     pub fn did_change_watched_files_dynamic_registration(&self) -> bool {
         try_or_def!(
             self.caps.workspace.as_ref()?.did_change_watched_files.as_ref()?.dynamic_registration?
@@ -1053,6 +1148,10 @@ impl Config {
         )
     }
 
+    pub fn completion_history(&self) -> bool {
+        self.data.completion_history_enable
+    }
+
     pub fn completion_label_details_support(&self) -> bool {
         try_!(self
             .caps
@@ -1105,6 +1204,18 @@ impl Config {
         self.data.diagnostics_enable
     }
 
+    pub fn scip_indices(&self) -> Vec<AbsPathBuf> {
+        self.data.scip_indices.iter().map(|it| self.root_path.join(it)).collect()
+    }
+
+    pub fn pull_diagnostics(&self) -> bool {
+        self.data.diagnostics_pull_enable && self.diagnostic_caps.dynamic_registration
+    }
+
+    pub fn pull_diagnostics_refresh(&self) -> bool {
+        self.pull_diagnostics() && self.diagnostic_caps.refresh_support
+    }
+
     pub fn diagnostics(&self) -> DiagnosticsConfig {
         DiagnosticsConfig {
             enabled: self.data.diagnostics_enable,
@@ -1118,6 +1229,9 @@ impl Config {
             },
             insert_use: self.insert_use_config(),
             prefer_no_std: self.data.imports_prefer_no_std,
+            enum_variant_size_threshold: self.data.diagnostics_enumVariantSizeThreshold as u64,
+            pass_by_value_size_limit: self.data.diagnostics_passByValueSizeLimit as u64,
+            unreachable_code: self.data.diagnostics_unreachableCode_enable,
         }
     }
 
@@ -1370,6 +1484,14 @@ impl Config {
         }
     }
 
+    pub fn test_config(&self) -> TestConfig {
+        TestConfig { test_attributes: self.data.runnables_testAttributes.clone() }
+    }
+
+    pub fn test_explorer(&self) -> bool {
+        self.data.testExplorer
+    }
+
     pub fn inlay_hints(&self) -> InlayHintsConfig {
         let client_capability_fields = self
             .caps
@@ -1414,6 +1536,9 @@ impl Config {
                 ClosureStyle::Hide => hir::ClosureStyle::Hide,
             },
             closure_capture_hints: self.data.inlayHints_closureCaptureHints_enable,
+            drop_hints: self.data.inlayHints_dropHints_enable,
+            implicit_trait_call_hints: self.data.inlayHints_implicitTraitCalls_enable,
+            generic_arg_hints: self.data.inlayHints_genericArgumentHints_enable,
             adjustment_hints: match self.data.inlayHints_expressionAdjustmentHints_enable {
                 AdjustmentHintsDef::Always => ide::AdjustmentHints::Always,
                 AdjustmentHintsDef::Never => match self.data.inlayHints_reborrowHints_enable {
@@ -1443,6 +1568,7 @@ impl Config {
             } else {
                 None
             },
+            generic_arg_hints_max_length: self.data.inlayHints_genericArgumentHints_maxLength,
             fields_to_resolve: InlayFieldsToResolve {
                 resolve_text_edits: client_capability_fields.contains("textEdits"),
                 resolve_hint_tooltip: client_capability_fields.contains("tooltip"),
@@ -1616,6 +1742,7 @@ impl Config {
                 }
             },
             keywords: self.data.hover_documentation_keywords_enable,
+            test_config: self.test_config(),
         }
     }
 
@@ -1632,6 +1759,7 @@ impl Config {
                 WorkspaceSymbolSearchKindDef::AllSymbols => WorkspaceSymbolSearchKind::AllSymbols,
             },
             search_limit: self.data.workspace_symbol_search_limit,
+            search_extended: self.data.workspace_symbol_search_extended,
         }
     }
 
@@ -1676,6 +1804,7 @@ impl Config {
             show_reference: get("rust-analyzer.showReferences"),
             goto_location: get("rust-analyzer.gotoLocation"),
             trigger_parameter_hints: get("editor.action.triggerParameterHints"),
+            completion_accepted: get("rust-analyzer.completionAccepted"),
         }
     }
 
@@ -1823,6 +1952,9 @@ enum SnippetScopeDef {
     Expr,
     Item,
     Type,
+    ImplBody,
+    TestModule,
+    Pattern,
 }
 
 impl Default for SnippetScopeDef {
@@ -2616,6 +2748,32 @@ mod tests {
         text.replace(char::is_whitespace, "")
     }
 
+    #[test]
+    fn pull_diagnostics_requires_client_support() {
+        let mut config = Config::new(
+            AbsPathBuf::try_from(project_root()).unwrap(),
+            Default::default(),
+            vec![],
+            false,
+        );
+        config
+            .update(serde_json::json!({ "diagnostics": { "pull": { "enable": true } } }))
+            .unwrap();
+        assert!(!config.pull_diagnostics());
+
+        config.update_raw_client_capabilities(&serde_json::json!({
+            "textDocument": { "diagnostic": { "dynamicRegistration": true } }
+        }));
+        assert!(config.pull_diagnostics());
+        assert!(!config.pull_diagnostics_refresh());
+
+        config.update_raw_client_capabilities(&serde_json::json!({
+            "textDocument": { "diagnostic": { "dynamicRegistration": true } },
+            "workspace": { "diagnostics": { "refreshSupport": true } }
+        }));
+        assert!(config.pull_diagnostics_refresh());
+    }
+
     #[test]
     fn proc_macro_srv_null() {
         let mut config = Config::new(
@@ -3,15 +3,26 @@ pub(crate) mod to_proto;
 
 use std::mem;
 
-use ide::FileId;
+use ide::{Cancellable, FileId};
 use ide_db::FxHashMap;
 use nohash_hasher::{IntMap, IntSet};
+use parking_lot::Mutex;
 use rustc_hash::FxHashSet;
 use triomphe::Arc;
 
-use crate::{global_state::GlobalStateSnapshot, lsp, lsp_ext};
+use crate::{cargo_toml, global_state::GlobalStateSnapshot, lsp, lsp_ext};
 
 pub(crate) type CheckFixes = Arc<IntMap<usize, IntMap<FileId, Vec<Fix>>>>;
+pub(crate) type CheckDiagnostics = Arc<IntMap<usize, IntMap<FileId, Vec<lsp_types::Diagnostic>>>>;
+/// The diagnostics last pulled for a file under an identifier, with the revision they were
+/// computed in.
+pub(crate) type PulledDiagnosticsCache =
+    Arc<Mutex<FxHashMap<(FileId, Option<String>), (u64, Vec<lsp_types::Diagnostic>)>>>;
+
+/// The identifier under which the diagnostics computed by rust-analyzer itself are pulled.
+pub(crate) const NATIVE_DIAGNOSTICS_IDENTIFIER: &str = "rust-analyzer";
+/// The identifier under which the diagnostics of `cargo check` are pulled.
+pub(crate) const CHECK_DIAGNOSTICS_IDENTIFIER: &str = "rust-analyzer/flycheck";
 
 #[derive(Debug, Default, Clone)]
 pub struct DiagnosticsMapConfig {
@@ -26,7 +37,7 @@ pub(crate) struct DiagnosticCollection {
     // FIXME: should be IntMap<FileId, Vec<ra_id::Diagnostic>>
     pub(crate) native: IntMap<FileId, Vec<lsp_types::Diagnostic>>,
     // FIXME: should be Vec<flycheck::Diagnostic>
-    pub(crate) check: IntMap<usize, IntMap<FileId, Vec<lsp_types::Diagnostic>>>,
+    pub(crate) check: CheckDiagnostics,
     pub(crate) check_fixes: CheckFixes,
     changes: IntSet<FileId>,
 }
@@ -43,15 +54,18 @@ impl DiagnosticCollection {
         if let Some(it) = Arc::make_mut(&mut self.check_fixes).get_mut(&flycheck_id) {
             it.clear();
         }
-        if let Some(it) = self.check.get_mut(&flycheck_id) {
+        if let Some(it) = Arc::make_mut(&mut self.check).get_mut(&flycheck_id) {
             self.changes.extend(it.drain().map(|(key, _value)| key));
         }
     }
 
     pub(crate) fn clear_check_all(&mut self) {
         Arc::make_mut(&mut self.check_fixes).clear();
-        self.changes
-            .extend(self.check.values_mut().flat_map(|it| it.drain().map(|(key, _value)| key)))
+        self.changes.extend(
+            Arc::make_mut(&mut self.check)
+                .values_mut()
+                .flat_map(|it| it.drain().map(|(key, _value)| key)),
+        )
     }
 
     pub(crate) fn clear_native_for(&mut self, file_id: FileId) {
@@ -66,7 +80,11 @@ impl DiagnosticCollection {
         diagnostic: lsp_types::Diagnostic,
         fix: Option<Fix>,
     ) {
-        let diagnostics = self.check.entry(flycheck_id).or_default().entry(file_id).or_default();
+        let diagnostics = Arc::make_mut(&mut self.check)
+            .entry(flycheck_id)
+            .or_default()
+            .entry(file_id)
+            .or_default();
         for existing_diagnostic in diagnostics.iter() {
             if are_diagnostics_equal(existing_diagnostic, &diagnostic) {
                 return;
@@ -108,6 +126,17 @@ impl DiagnosticCollection {
         native.chain(check)
     }
 
+    /// All files that have native or check diagnostics.
+    pub(crate) fn file_ids(&self) -> IntSet<FileId> {
+        let check = self.check.values().flat_map(|it| it.keys());
+        self.native.keys().chain(check).copied().collect()
+    }
+
+    pub(crate) fn mark_all_changed(&mut self) {
+        let file_ids = self.file_ids();
+        self.changes.extend(file_ids);
+    }
+
     pub(crate) fn take_changes(&mut self) -> Option<IntSet<FileId>> {
         if self.changes.is_empty() {
             return None;
@@ -132,31 +161,103 @@ pub(crate) fn fetch_native_diagnostics(
     subscriptions
         .into_iter()
         .filter_map(|file_id| {
-            let line_index = snapshot.file_line_index(file_id).ok()?;
-            let diagnostics = snapshot
-                .analysis
-                .diagnostics(
-                    &snapshot.config.diagnostics(),
-                    ide::AssistResolveStrategy::None,
-                    file_id,
-                )
-                .ok()?
-                .into_iter()
-                .map(move |d| lsp_types::Diagnostic {
-                    range: lsp::to_proto::range(&line_index, d.range),
-                    severity: Some(lsp::to_proto::diagnostic_severity(d.severity)),
-                    code: Some(lsp_types::NumberOrString::String(d.code.as_str().to_string())),
-                    code_description: Some(lsp_types::CodeDescription {
-                        href: lsp_types::Url::parse(&d.code.url()).unwrap(),
-                    }),
-                    source: Some("rust-analyzer".to_string()),
-                    message: d.message,
-                    related_information: None,
-                    tags: d.unused.then(|| vec![lsp_types::DiagnosticTag::UNNECESSARY]),
-                    data: None,
-                })
-                .collect::<Vec<_>>();
+            let diagnostics = native_diagnostics(&snapshot, file_id).ok()?;
             Some((file_id, diagnostics))
         })
         .collect()
 }
+
+pub(crate) fn native_diagnostics(
+    snapshot: &GlobalStateSnapshot,
+    file_id: FileId,
+) -> Cancellable<Vec<lsp_types::Diagnostic>> {
+    if let Some(path) = cargo_toml::manifest_path(snapshot, file_id) {
+        return cargo_toml::diagnostics(snapshot, file_id, &path);
+    }
+    let line_index = snapshot.file_line_index(file_id)?;
+    let diagnostics = snapshot
+        .analysis
+        .diagnostics(&snapshot.config.diagnostics(), ide::AssistResolveStrategy::None, file_id)?
+        .into_iter()
+        .map(move |d| lsp_types::Diagnostic {
+            range: lsp::to_proto::range(&line_index, d.range),
+            severity: Some(lsp::to_proto::diagnostic_severity(d.severity)),
+            code: Some(lsp_types::NumberOrString::String(d.code.as_str().to_string())),
+            code_description: Some(lsp_types::CodeDescription {
+                href: lsp_types::Url::parse(&d.code.url()).unwrap(),
+            }),
+            source: Some("rust-analyzer".to_string()),
+            message: d.message,
+            related_information: None,
+            tags: d.unused.then(|| vec![lsp_types::DiagnosticTag::UNNECESSARY]),
+            data: None,
+        })
+        .collect();
+    Ok(diagnostics)
+}
+
+/// The diagnostics of `file_id` that are pulled under `identifier`, or all of them if the client
+/// didn't register for a specific kind.
+///
+/// They are only computed once per diagnostics revision.
+pub(crate) fn pull_diagnostics(
+    snapshot: &GlobalStateSnapshot,
+    identifier: Option<&str>,
+    file_id: FileId,
+) -> Cancellable<Vec<lsp_types::Diagnostic>> {
+    let key = (file_id, identifier.map(ToOwned::to_owned));
+    if let Some((revision, diagnostics)) = snapshot.pulled_diagnostics_cache.lock().get(&key) {
+        if *revision == snapshot.diagnostics_revision {
+            return Ok(diagnostics.clone());
+        }
+    }
+
+    let mut res = Vec::new();
+    if matches!(identifier, None | Some(NATIVE_DIAGNOSTICS_IDENTIFIER)) {
+        res.extend(native_diagnostics(snapshot, file_id)?);
+    }
+    if matches!(identifier, None | Some(CHECK_DIAGNOSTICS_IDENTIFIER)) {
+        let check = snapshot.check_diagnostics.values().filter_map(|it| it.get(&file_id));
+        res.extend(check.flatten().cloned());
+    }
+    patch_empty_messages(&mut res);
+
+    let mut cache = snapshot.pulled_diagnostics_cache.lock();
+    // A request of an older snapshot may finish last, keep the newer diagnostics then.
+    if cache.get(&key).map_or(true, |(revision, _)| *revision < snapshot.diagnostics_revision) {
+        cache.insert(key, (snapshot.diagnostics_revision, res.clone()));
+    }
+    Ok(res)
+}
+
+/// Identifies the diagnostics of `file_id` in the snapshot's revision, so that the client can be
+/// told they didn't change without computing them.
+pub(crate) fn result_id(snapshot: &GlobalStateSnapshot, file_id: FileId) -> String {
+    format!("{}:{}", file_id.0, snapshot.diagnostics_revision)
+}
+
+/// VSCode assumes diagnostic messages to be non-empty strings, so we need to patch
+/// empty diagnostics. Neither the docs of VSCode nor the LSP spec say whether
+/// diagnostic messages are actually allowed to be empty or not and patching this
+/// in the VSCode client does not work as the assertion happens in the protocol
+/// conversion. So this hack is here to stay, and will be considered a hack
+/// until the LSP decides to state that empty messages are allowed.
+///
+/// See https://github.com/rust-lang/rust-analyzer/issues/11404
+/// See https://github.com/rust-lang/rust-analyzer/issues/13130
+pub(crate) fn patch_empty_messages(diagnostics: &mut [lsp_types::Diagnostic]) {
+    let patch_empty = |message: &mut String| {
+        if message.is_empty() {
+            *message = " ".to_string();
+        }
+    };
+
+    for d in diagnostics {
+        patch_empty(&mut d.message);
+        if let Some(dri) = &mut d.related_information {
+            for dri in dri {
+                patch_empty(&mut dri.message);
+            }
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! Symbol locations recorded by SCIP indices of dependencies.
+//!
+//! Symbols are identified the same way `rust-analyzer scip` identifies them, so an index generated
+//! for a dependency links the symbols the workspace imports from it to their definitions and
+//! references in that dependency.
+
+use ide::{Cancellable, FilePosition, LineCol, MonikerDescriptorKind, MonikerResult};
+use lsp_types::{Location, Position, Range, Url};
+use protobuf::Message;
+use rustc_hash::FxHashMap;
+use scip::types as scip_types;
+use triomphe::Arc;
+use vfs::AbsPathBuf;
+
+use crate::{
+    global_state::GlobalStateSnapshot,
+    line_index::{LineEndings, LineIndex, PositionEncoding},
+    lsp::to_proto,
+};
+
+#[derive(Debug, Default)]
+pub(crate) struct ExternalSymbols {
+    definitions: FxHashMap<String, Vec<Location>>,
+    references: FxHashMap<String, Vec<Location>>,
+}
+
+impl ExternalSymbols {
+    /// Loads the indices at `paths`, returning the errors of those that couldn't be read.
+    pub(crate) fn load(
+        paths: &[AbsPathBuf],
+        encoding: PositionEncoding,
+    ) -> (ExternalSymbols, Vec<String>) {
+        let mut res = ExternalSymbols::default();
+        let mut errors = Vec::new();
+        for path in paths {
+            let index = std::fs::read(path)
+                .map_err(anyhow::Error::from)
+                .and_then(|bytes| Ok(scip_types::Index::parse_from_bytes(&bytes)?))
+                .and_then(|index| res.add_index(index, encoding));
+            if let Err(e) = index {
+                errors.push(format!("failed to load SCIP index {path}: {e}"));
+            }
+        }
+        (res, errors)
+    }
+
+    fn add_index(
+        &mut self,
+        index: scip_types::Index,
+        encoding: PositionEncoding,
+    ) -> anyhow::Result<()> {
+        let mut project_root = Url::parse(&index.metadata.project_root)?;
+        if !project_root.path().ends_with('/') {
+            project_root.set_path(&format!("{}/", project_root.path()));
+        }
+        for document in index.documents {
+            let uri = project_root.join(&document.relative_path)?;
+            let line_index = match encoding {
+                PositionEncoding::Utf8 => None,
+                PositionEncoding::Wide(_) => document_line_index(&document, &uri, encoding),
+            };
+            for occurrence in document.occurrences {
+                if scip::symbol::is_local_symbol(&occurrence.symbol) {
+                    continue;
+                }
+                let Some(range) = scip_range(&occurrence.range, line_index.as_ref()) else {
+                    continue;
+                };
+                let location = Location { uri: uri.clone(), range };
+                let is_definition =
+                    occurrence.symbol_roles & scip_types::SymbolRole::Definition as i32 != 0;
+                let map = if is_definition { &mut self.definitions } else { &mut self.references };
+                map.entry(occurrence.symbol).or_default().push(location);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.definitions.is_empty() && self.references.is_empty()
+    }
+
+    pub(crate) fn definitions(&self, symbol: &str) -> &[Location] {
+        self.definitions.get(symbol).map_or(&[], Vec::as_slice)
+    }
+
+    pub(crate) fn references(&self, symbol: &str) -> &[Location] {
+        self.references.get(symbol).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// The SCIP symbols of the item at `position`.
+pub(crate) fn symbols_at(
+    snap: &GlobalStateSnapshot,
+    position: FilePosition,
+) -> Cancellable<Vec<String>> {
+    if snap.external_symbols.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(monikers) = snap.analysis.moniker(position)? else { return Ok(Vec::new()) };
+    Ok(monikers.info.iter().map(|it| scip::symbol::format_symbol(moniker_to_symbol(it))).collect())
+}
+
+/// Loosely based on `def_to_moniker`
+pub(crate) fn moniker_to_symbol(moniker: &MonikerResult) -> scip_types::Symbol {
+    use scip_types::descriptor::Suffix::*;
+
+    let package_name = moniker.package_information.name.clone();
+    let version = moniker.package_information.version.clone();
+    let descriptors = moniker
+        .identifier
+        .description
+        .iter()
+        .map(|desc| {
+            new_descriptor(
+                &desc.name,
+                match desc.desc {
+                    MonikerDescriptorKind::Namespace => Namespace,
+                    MonikerDescriptorKind::Type => Type,
+                    MonikerDescriptorKind::Term => Term,
+                    MonikerDescriptorKind::Method => Method,
+                    MonikerDescriptorKind::TypeParameter => TypeParameter,
+                    MonikerDescriptorKind::Parameter => Parameter,
+                    MonikerDescriptorKind::Macro => Macro,
+                    MonikerDescriptorKind::Meta => Meta,
+                },
+            )
+        })
+        .collect();
+
+    scip_types::Symbol {
+        scheme: "rust-analyzer".into(),
+        package: Some(scip_types::Package {
+            manager: "cargo".to_string(),
+            name: package_name,
+            version: version.unwrap_or_else(|| ".".to_string()),
+            special_fields: Default::default(),
+        })
+        .into(),
+        descriptors,
+        special_fields: Default::default(),
+    }
+}
+
+fn new_descriptor_str(
+    name: &str,
+    suffix: scip_types::descriptor::Suffix,
+) -> scip_types::Descriptor {
+    scip_types::Descriptor {
+        name: name.to_string(),
+        disambiguator: "".to_string(),
+        suffix: suffix.into(),
+        special_fields: Default::default(),
+    }
+}
+
+fn new_descriptor(name: &str, suffix: scip_types::descriptor::Suffix) -> scip_types::Descriptor {
+    if name.contains('\'') {
+        new_descriptor_str(&format!("`{name}`"), suffix)
+    } else {
+        new_descriptor_str(name, suffix)
+    }
+}
+
+/// The line index to convert the UTF-8 columns of the SCIP index of `document` to the negotiated
+/// `encoding` with. The text of the document is taken from the index if it has it, or read from
+/// disk otherwise; without it the columns are passed on as they are.
+fn document_line_index(
+    document: &scip_types::Document,
+    uri: &Url,
+    encoding: PositionEncoding,
+) -> Option<LineIndex> {
+    let text = if document.text.is_empty() {
+        std::fs::read_to_string(uri.to_file_path().ok()?).ok()?
+    } else {
+        document.text.clone()
+    };
+    let (text, endings) = LineEndings::normalize(text);
+    Some(LineIndex { index: Arc::new(ide::LineIndex::new(&text)), endings, encoding })
+}
+
+/// The inverse of `text_range_to_scip_range` of the SCIP generator: ranges on a single line are
+/// encoded as `[line, start_col, end_col]`, with UTF-8 columns.
+fn scip_range(range: &[i32], line_index: Option<&LineIndex>) -> Option<Range> {
+    let position = |line: i32, col: i32| {
+        let line_col = LineCol { line: line.try_into().ok()?, col: col.try_into().ok()? };
+        match line_index {
+            Some(line_index) => {
+                Some(to_proto::position(line_index, line_index.index.offset(line_col)?))
+            }
+            None => Some(Position { line: line_col.line, character: line_col.col }),
+        }
+    };
+    let (start, end) = match *range {
+        [line, start_col, end_col] => (position(line, start_col)?, position(line, end_col)?),
+        [start_line, start_col, end_line, end_col] => {
+            (position(start_line, start_col)?, position(end_line, end_col)?)
+        }
+        _ => return None,
+    };
+    Some(Range { start, end })
+}
+
+#[cfg(test)]
+mod tests {
+    use ide_db::line_index::WideEncoding;
+
+    use super::*;
+
+    #[test]
+    fn index_locations() {
+        let occurrence =
+            |range: Vec<i32>, symbol: &str, symbol_roles: i32| scip_types::Occurrence {
+                range,
+                symbol: symbol.to_owned(),
+                symbol_roles,
+                ..Default::default()
+            };
+        let definition = scip_types::SymbolRole::Definition as i32;
+        let index = scip_types::Index {
+            metadata: Some(scip_types::Metadata {
+                project_root: "file:///deps/foo".to_owned(),
+                ..Default::default()
+            })
+            .into(),
+            documents: vec![scip_types::Document {
+                relative_path: "src/lib.rs".to_owned(),
+                occurrences: vec![
+                    occurrence(vec![0, 7, 11], "rust-analyzer cargo foo 0.1.0 func().", definition),
+                    occurrence(vec![2, 4, 3, 1], "rust-analyzer cargo foo 0.1.0 func().", 0),
+                    occurrence(vec![2, 0, 1], "local 0", definition),
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let mut symbols = ExternalSymbols::default();
+        symbols.add_index(index, PositionEncoding::Utf8).unwrap();
+
+        let range = |l1, c1, l2, c2| Range::new(Position::new(l1, c1), Position::new(l2, c2));
+        let uri = Url::parse("file:///deps/foo/src/lib.rs").unwrap();
+        assert_eq!(
+            symbols.definitions("rust-analyzer cargo foo 0.1.0 func()."),
+            [Location::new(uri.clone(), range(0, 7, 0, 11))]
+        );
+        assert_eq!(
+            symbols.references("rust-analyzer cargo foo 0.1.0 func()."),
+            [Location::new(uri, range(2, 4, 3, 1))]
+        );
+        assert!(symbols.definitions("local 0").is_empty());
+    }
+
+    #[test]
+    fn wide_columns() {
+        let index = scip_types::Index {
+            metadata: Some(scip_types::Metadata {
+                project_root: "file:///deps/foo".to_owned(),
+                ..Default::default()
+            })
+            .into(),
+            documents: vec![scip_types::Document {
+                relative_path: "src/lib.rs".to_owned(),
+                text: "/* Ä */ fn func() {}\n".to_owned(),
+                occurrences: vec![scip_types::Occurrence {
+                    range: vec![0, 12, 16],
+                    symbol: "rust-analyzer cargo foo 0.1.0 func().".to_owned(),
+                    symbol_roles: scip_types::SymbolRole::Definition as i32,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let mut symbols = ExternalSymbols::default();
+        symbols.add_index(index, PositionEncoding::Wide(WideEncoding::Utf16)).unwrap();
+
+        let uri = Url::parse("file:///deps/foo/src/lib.rs").unwrap();
+        let range = Range::new(Position::new(0, 11), Position::new(0, 15));
+        assert_eq!(
+            symbols.definitions("rust-analyzer cargo foo 0.1.0 func()."),
+            [Location::new(uri, range)]
+        );
+    }
+}
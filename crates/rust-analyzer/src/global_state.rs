@@ -23,8 +23,10 @@ use triomphe::Arc;
 use vfs::{AnchoredPathBuf, Vfs};
 
 use crate::{
+    completion_history::CompletionHistory,
     config::{Config, ConfigError},
-    diagnostics::{CheckFixes, DiagnosticCollection},
+    diagnostics::{CheckDiagnostics, CheckFixes, DiagnosticCollection, PulledDiagnosticsCache},
+    external_symbols::ExternalSymbols,
     line_index::{LineEndings, LineIndex},
     lsp::{from_proto, to_proto::url_from_abs_path},
     lsp_ext,
@@ -33,6 +35,7 @@ use crate::{
     op_queue::OpQueue,
     reload,
     task_pool::TaskPool,
+    test_explorer::TestRunSession,
 };
 
 // Enforces drop order
@@ -66,6 +69,11 @@ pub(crate) struct GlobalState {
     pub(crate) mem_docs: MemDocs,
     pub(crate) source_root_config: SourceRootConfig,
     pub(crate) semantic_tokens_cache: Arc<Mutex<FxHashMap<Url, SemanticTokens>>>,
+    /// Bumped whenever the pulled diagnostics may have changed, identifies their results.
+    pub(crate) diagnostics_revision: u64,
+    pub(crate) pulled_diagnostics_cache: PulledDiagnosticsCache,
+    pub(crate) external_symbols: Arc<ExternalSymbols>,
+    pub(crate) completion_history: Arc<RwLock<CompletionHistory>>,
 
     // status
     pub(crate) shutdown_requested: bool,
@@ -82,6 +90,14 @@ pub(crate) struct GlobalState {
     pub(crate) flycheck_receiver: Receiver<flycheck::Message>,
     pub(crate) last_flycheck_error: Option<String>,
 
+    // Test explorer
+    pub(crate) test_run_session: Option<TestRunSession>,
+    /// The files whose tests are rediscovered by the next test discovery.
+    pub(crate) tests_to_update: FxHashSet<FileId>,
+    /// The scopes reported for the files in which the last discovery found tests, so that their
+    /// removal gets reported when the files are deleted or leave the module tree.
+    pub(crate) files_with_tests: FxHashMap<FileId, Vec<String>>,
+
     // VFS
     pub(crate) loader: Handle<Box<dyn vfs::loader::Handle>, Receiver<vfs::loader::Message>>,
     pub(crate) vfs: Arc<RwLock<(vfs::Vfs, IntMap<FileId, LineEndings>)>>,
@@ -125,6 +141,8 @@ pub(crate) struct GlobalState {
         OpQueue<(), (Arc<Vec<ProjectWorkspace>>, Vec<anyhow::Result<WorkspaceBuildScripts>>)>,
     pub(crate) fetch_proc_macros_queue: OpQueue<Vec<ProcMacroPaths>, bool>,
     pub(crate) prime_caches_queue: OpQueue,
+    pub(crate) discover_tests_queue: OpQueue,
+    pub(crate) save_completion_history_queue: OpQueue,
 }
 
 /// An immutable snapshot of the world's state at a point in time.
@@ -132,8 +150,13 @@ pub(crate) struct GlobalStateSnapshot {
     pub(crate) config: Arc<Config>,
     pub(crate) analysis: Analysis,
     pub(crate) check_fixes: CheckFixes,
+    pub(crate) check_diagnostics: CheckDiagnostics,
     mem_docs: MemDocs,
     pub(crate) semantic_tokens_cache: Arc<Mutex<FxHashMap<Url, SemanticTokens>>>,
+    pub(crate) diagnostics_revision: u64,
+    pub(crate) pulled_diagnostics_cache: PulledDiagnosticsCache,
+    pub(crate) external_symbols: Arc<ExternalSymbols>,
+    pub(crate) completion_history: Arc<RwLock<CompletionHistory>>,
     vfs: Arc<RwLock<(vfs::Vfs, IntMap<FileId, LineEndings>)>>,
     pub(crate) workspaces: Arc<Vec<ProjectWorkspace>>,
     // used to signal semantic highlighting to fall back to syntax based highlighting until proc-macros have been loaded
@@ -180,6 +203,10 @@ impl GlobalState {
             diagnostics: Default::default(),
             mem_docs: MemDocs::default(),
             semantic_tokens_cache: Arc::new(Default::default()),
+            diagnostics_revision: 0,
+            pulled_diagnostics_cache: Arc::new(Default::default()),
+            external_symbols: Arc::new(ExternalSymbols::default()),
+            completion_history: Arc::new(RwLock::new(CompletionHistory::default())),
             shutdown_requested: false,
             send_hint_refresh_query: false,
             last_reported_status: None,
@@ -196,6 +223,10 @@ impl GlobalState {
             flycheck_receiver,
             last_flycheck_error: None,
 
+            test_run_session: None,
+            tests_to_update: FxHashSet::default(),
+            files_with_tests: FxHashMap::default(),
+
             vfs: Arc::new(RwLock::new((vfs::Vfs::default(), IntMap::default()))),
             vfs_config_version: 0,
             vfs_progress_config_version: 0,
@@ -209,9 +240,13 @@ impl GlobalState {
             fetch_proc_macros_queue: OpQueue::default(),
 
             prime_caches_queue: OpQueue::default(),
+            discover_tests_queue: OpQueue::default(),
+            save_completion_history_queue: OpQueue::default(),
         };
         // Apply any required database inputs from the config.
         this.update_configuration(config);
+        this.load_external_symbols();
+        this.load_completion_history();
         this
     }
 
@@ -285,7 +320,13 @@ impl GlobalState {
                 let vfs_path = &vfs.file_path(file.file_id);
                 if let Some(path) = vfs_path.as_path() {
                     let path = path.to_path_buf();
-                    if reload::should_refresh_for_change(&path, file.change_kind) {
+                    // Edits to an open `Cargo.toml` refresh the workspace once they are saved,
+                    // not on every keystroke.
+                    let is_unsaved_edit = file.change_kind == vfs::ChangeKind::Modify
+                        && self.mem_docs.contains(vfs_path);
+                    if !is_unsaved_edit
+                        && reload::should_refresh_for_change(&path, file.change_kind)
+                    {
                         workspace_structure_change = Some((path.clone(), false));
                     }
                     if file.is_created_or_deleted() {
@@ -353,6 +394,10 @@ impl GlobalState {
                 });
         }
 
+        if self.config.test_explorer() {
+            self.update_tests(changed_files.iter().map(|file| file.file_id).collect());
+        }
+
         true
     }
 
@@ -363,8 +408,13 @@ impl GlobalState {
             analysis: self.analysis_host.analysis(),
             vfs: Arc::clone(&self.vfs),
             check_fixes: Arc::clone(&self.diagnostics.check_fixes),
+            check_diagnostics: Arc::clone(&self.diagnostics.check),
             mem_docs: self.mem_docs.clone(),
             semantic_tokens_cache: Arc::clone(&self.semantic_tokens_cache),
+            diagnostics_revision: self.diagnostics_revision,
+            pulled_diagnostics_cache: Arc::clone(&self.pulled_diagnostics_cache),
+            external_symbols: Arc::clone(&self.external_symbols),
+            completion_history: Arc::clone(&self.completion_history),
             proc_macros_loaded: !self.config.expand_proc_macros()
                 || *self.fetch_proc_macros_queue.last_op_result(),
             flycheck: self.flycheck.clone(),
@@ -499,6 +549,23 @@ impl GlobalStateSnapshot {
         self.vfs_read().memory_usage()
     }
 
+    /// The Rust files of the workspace, leaving out those of libraries.
+    pub(crate) fn local_rust_files(&self) -> Cancellable<Vec<FileId>> {
+        let files = self
+            .vfs_read()
+            .iter()
+            .filter(|(_, path)| path.name_and_extension().is_some_and(|(_, ext)| ext == Some("rs")))
+            .map(|(file_id, _)| file_id)
+            .collect::<Vec<_>>();
+        let mut res = Vec::new();
+        for file_id in files {
+            if !self.analysis.is_library_file(file_id)? {
+                res.push(file_id);
+            }
+        }
+        Ok(res)
+    }
+
     pub(crate) fn file_exists(&self, file_id: FileId) -> bool {
         self.vfs.read().0.exists(file_id)
     }
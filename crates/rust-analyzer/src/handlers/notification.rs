@@ -16,7 +16,7 @@ use crate::{
     config::Config,
     global_state::GlobalState,
     lsp::{from_proto, utils::apply_document_changes},
-    lsp_ext::RunFlycheckParams,
+    lsp_ext::{self, RunFlycheckParams},
     mem_docs::DocumentData,
     reload,
 };
@@ -341,3 +341,22 @@ pub(crate) fn handle_run_flycheck(
     }
     Ok(())
 }
+
+pub(crate) fn handle_completion_accepted(
+    state: &mut GlobalState,
+    params: lsp_ext::CompletionAcceptedParams,
+) -> anyhow::Result<()> {
+    if !state.config.completion_history() {
+        return Ok(());
+    }
+    state.completion_history.write().record(&params.label, &params.qualifier);
+    state.save_completion_history_queue.request_op("completion accepted".to_owned(), ());
+    Ok(())
+}
+
+pub(crate) fn handle_abort_run_test(state: &mut GlobalState, _: ()) -> anyhow::Result<()> {
+    if state.test_run_session.take().is_some() {
+        state.send_notification::<lsp_ext::EndRunTest>(());
+    }
+    Ok(())
+}
@@ -11,20 +11,22 @@ use std::{
 use anyhow::Context;
 
 use ide::{
-    AnnotationConfig, AssistKind, AssistResolveStrategy, Cancellable, FilePosition, FileRange,
-    HoverAction, HoverGotoTypeData, InlayFieldsToResolve, Query, RangeInfo, ReferenceCategory,
-    Runnable, RunnableKind, SingleResolve, SourceChange, TextEdit,
+    AnnotationConfig, AnnotationKind, AssistKind, AssistResolveStrategy, Cancellable, FilePosition,
+    FileRange, HoverAction, HoverGotoTypeData, InlayFieldsToResolve, Query, RangeInfo,
+    ReferenceCategory, Runnable, RunnableKind, SingleResolve, SourceChange, TextEdit,
 };
-use ide_db::SymbolKind;
+use ide_db::{FxHashMap, SymbolKind};
 use lsp_server::ErrorCode;
 use lsp_types::{
     CallHierarchyIncomingCall, CallHierarchyIncomingCallsParams, CallHierarchyItem,
     CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
     CodeLens, CompletionItem, FoldingRange, FoldingRangeParams, HoverContents, InlayHint,
-    InlayHintParams, Location, LocationLink, Position, PrepareRenameResponse, Range, RenameParams,
-    ResourceOp, ResourceOperationKind, SemanticTokensDeltaParams, SemanticTokensFullDeltaResult,
-    SemanticTokensParams, SemanticTokensRangeParams, SemanticTokensRangeResult,
-    SemanticTokensResult, SymbolInformation, SymbolTag, TextDocumentIdentifier, Url, WorkspaceEdit,
+    InlayHintParams, InlineValue, InlineValueParams, Location, LocationLink, Position,
+    PrepareRenameResponse, Range, RenameParams, ResourceOp, ResourceOperationKind,
+    SemanticTokensDeltaParams, SemanticTokensFullDeltaResult, SemanticTokensParams,
+    SemanticTokensRangeParams, SemanticTokensRangeResult, SemanticTokensResult, SymbolInformation,
+    SymbolTag, TextDocumentIdentifier, TypeHierarchyItem, TypeHierarchyPrepareParams,
+    TypeHierarchySubtypesParams, TypeHierarchySupertypesParams, Url, WorkspaceEdit,
 };
 use project_model::{ManifestPath, ProjectWorkspace, TargetKind};
 use serde_json::json;
@@ -35,8 +37,11 @@ use vfs::{AbsPath, AbsPathBuf, FileId, VfsPath};
 
 use crate::{
     cargo_target_spec::CargoTargetSpec,
+    cargo_toml,
     config::{Config, RustfmtConfig, WorkspaceSymbolConfig},
+    diagnostics,
     diff::diff,
+    external_symbols,
     global_state::{GlobalState, GlobalStateSnapshot},
     line_index::LineEndings,
     lsp::{
@@ -48,6 +53,7 @@ use crate::{
         self, CrateInfoResult, ExternalDocsPair, ExternalDocsResponse, FetchDependencyListParams,
         FetchDependencyListResult, PositionOrRange, ViewCrateGraphParams, WorkspaceSymbolParams,
     },
+    test_explorer::{self, TestRunSession},
 };
 
 pub(crate) fn handle_workspace_reload(state: &mut GlobalState, _: ()) -> anyhow::Result<()> {
@@ -134,6 +140,25 @@ pub(crate) fn handle_shuffle_crate_graph(state: &mut GlobalState, _: ()) -> anyh
     Ok(())
 }
 
+pub(crate) fn handle_run_test(
+    state: &mut GlobalState,
+    params: lsp_ext::RunTestParams,
+) -> anyhow::Result<()> {
+    let _p = profile::span("handle_run_test");
+    // Only one run at a time, the results of both would be indistinguishable otherwise.
+    if state.test_run_session.take().is_some() {
+        state.send_notification::<lsp_ext::EndRunTest>(());
+    }
+    let runs = test_explorer::plan_test_runs(&state.snapshot(), &params)?;
+    state.test_run_session = TestRunSession::start(runs, &mut |error| {
+        state.send_notification::<lsp_ext::AppendOutputToRunTest>(error + "\n")
+    });
+    if state.test_run_session.is_none() {
+        state.send_notification::<lsp_ext::EndRunTest>(());
+    }
+    Ok(())
+}
+
 pub(crate) fn handle_syntax_tree(
     snap: GlobalStateSnapshot,
     params: lsp_ext::SyntaxTreeParams,
@@ -461,6 +486,7 @@ pub(crate) fn handle_workspace_symbol(
     let config = snap.config.workspace_symbol();
     let (all_symbols, libs) = decide_search_scope_and_kind(&params, &config);
     let limit = config.search_limit;
+    let extended = config.search_extended;
 
     let query = {
         let query: String = params.query.chars().filter(|&c| c != '#' && c != '*').collect();
@@ -471,12 +497,18 @@ pub(crate) fn handle_workspace_symbol(
         if libs {
             q.libs();
         }
+        if extended {
+            q.extended();
+        }
         q.limit(limit);
         q
     };
     let mut res = exec_query(&snap, query)?;
     if res.is_empty() && !all_symbols {
         let mut query = Query::new(params.query);
+        if extended {
+            query.extended();
+        }
         query.limit(limit);
         res = exec_query(&snap, query)?;
     }
@@ -615,13 +647,46 @@ pub(crate) fn handle_goto_definition(
 ) -> anyhow::Result<Option<lsp_types::GotoDefinitionResponse>> {
     let _p = profile::span("handle_goto_definition");
     let position = from_proto::file_position(&snap, params.text_document_position_params)?;
-    let nav_info = match snap.analysis.goto_definition(position)? {
-        None => return Ok(None),
-        Some(it) => it,
+    if let Some(path) = cargo_toml::manifest_path(&snap, position.file_id) {
+        let locations =
+            cargo_toml::goto_definition(&snap, position.file_id, &path, position.offset)?;
+        return Ok(Some(locations.into()));
+    }
+    let nav_info = snap.analysis.goto_definition(position)?;
+    if let Some(nav_info) = nav_info.as_ref().filter(|it| !it.info.is_empty()) {
+        let src = FileRange { file_id: position.file_id, range: nav_info.range };
+        let res = to_proto::goto_definition_response(&snap, Some(src), nav_info.info.clone())?;
+        return Ok(Some(res));
+    }
+
+    // Definitions in dependencies that are only known through their SCIP indices.
+    let external: Vec<_> = external_symbols::symbols_at(&snap, position)?
+        .iter()
+        .flat_map(|symbol| snap.external_symbols.definitions(symbol))
+        .cloned()
+        .collect();
+    if external.is_empty() {
+        return Ok(None);
+    }
+    if !snap.config.location_link() {
+        return Ok(Some(external.into()));
+    }
+    let origin_selection_range = match nav_info {
+        Some(nav_info) => {
+            Some(to_proto::range(&snap.file_line_index(position.file_id)?, nav_info.range))
+        }
+        None => None,
     };
-    let src = FileRange { file_id: position.file_id, range: nav_info.range };
-    let res = to_proto::goto_definition_response(&snap, Some(src), nav_info.info)?;
-    Ok(Some(res))
+    let links = external
+        .into_iter()
+        .map(|it| lsp_types::LocationLink {
+            origin_selection_range,
+            target_uri: it.uri,
+            target_range: it.range,
+            target_selection_range: it.range,
+        })
+        .collect::<Vec<_>>();
+    Ok(Some(links.into()))
 }
 
 pub(crate) fn handle_goto_declaration(
@@ -736,6 +801,17 @@ pub(crate) fn handle_parent_module(
     Ok(Some(res))
 }
 
+pub(crate) fn handle_discover_test(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::DiscoverTestParams,
+) -> anyhow::Result<lsp_ext::DiscoverTestResults> {
+    let _p = profile::span("handle_discover_test");
+    match params.test_id {
+        Some(id) => test_explorer::discover_tests_below(&snap, &id),
+        None => Ok(test_explorer::discover_test_roots(&snap.workspaces)),
+    }
+}
+
 pub(crate) fn handle_runnables(
     snap: GlobalStateSnapshot,
     params: lsp_ext::RunnablesParams,
@@ -757,7 +833,7 @@ pub(crate) fn handle_runnables(
     };
 
     let mut res = Vec::new();
-    for runnable in snap.analysis.runnables(file_id)? {
+    for runnable in snap.analysis.runnables(&snap.config.test_config(), file_id)? {
         if should_skip_for_offset(&runnable, offset) {
             continue;
         }
@@ -838,7 +914,7 @@ pub(crate) fn handle_related_tests(
     let _p = profile::span("handle_related_tests");
     let position = from_proto::file_position(&snap, params)?;
 
-    let tests = snap.analysis.related_tests(position, None)?;
+    let tests = snap.analysis.related_tests(&snap.config.test_config(), position, None)?;
     let mut res = Vec::new();
     for it in tests {
         if let Ok(runnable) = to_proto::runnable(&snap, it) {
@@ -856,11 +932,15 @@ pub(crate) fn handle_completion(
     let _p = profile::span("handle_completion");
     let text_document_position = params.text_document_position.clone();
     let position = from_proto::file_position(&snap, params.text_document_position)?;
+    if let Some(path) = cargo_toml::manifest_path(&snap, position.file_id) {
+        let items = cargo_toml::completions(&snap, position.file_id, &path, position.offset)?;
+        return Ok(Some(items.into()));
+    }
     let completion_trigger_character =
         params.context.and_then(|ctx| ctx.trigger_character).and_then(|s| s.chars().next());
 
     let completion_config = &snap.config.completion();
-    let items = match snap.analysis.completions(
+    let mut items = match snap.analysis.completions(
         completion_config,
         position,
         completion_trigger_character,
@@ -868,6 +948,12 @@ pub(crate) fn handle_completion(
         None => return Ok(None),
         Some(items) => items,
     };
+    if snap.config.completion_history() {
+        let history = snap.completion_history.read();
+        for item in &mut items {
+            item.relevance.history_score = history.score(item);
+        }
+    }
     let line_index = snap.file_line_index(position.file_id)?;
 
     let items =
@@ -877,6 +963,22 @@ pub(crate) fn handle_completion(
     Ok(Some(completion_list.into()))
 }
 
+pub(crate) fn handle_completion_history(
+    snap: GlobalStateSnapshot,
+    _: (),
+) -> anyhow::Result<Vec<lsp_ext::CompletionHistoryEntry>> {
+    Ok(snap.completion_history.read().entries())
+}
+
+pub(crate) fn handle_reset_completion_history(
+    state: &mut GlobalState,
+    _: (),
+) -> anyhow::Result<()> {
+    state.completion_history.write().reset();
+    state.save_completion_history_queue.request_op("completion history reset".to_owned(), ());
+    Ok(())
+}
+
 pub(crate) fn handle_completion_resolve(
     snap: GlobalStateSnapshot,
     mut original_completion: CompletionItem,
@@ -1070,7 +1172,7 @@ pub(crate) fn handle_references(
     };
 
     let include_declaration = params.context.include_declaration;
-    let locations = refs
+    let mut locations: Vec<_> = refs
         .into_iter()
         .flat_map(|refs| {
             let decl = if include_declaration {
@@ -1095,6 +1197,17 @@ pub(crate) fn handle_references(
         .filter_map(|frange| to_proto::location(&snap, frange).ok())
         .collect();
 
+    // References in dependencies that are only known through their SCIP indices.
+    // Locations that were also found locally are skipped.
+    for symbol in external_symbols::symbols_at(&snap, position)? {
+        let mut external = snap.external_symbols.references(&symbol).to_vec();
+        if include_declaration {
+            external.extend(snap.external_symbols.definitions(&symbol).iter().cloned());
+        }
+        external.retain(|it| !locations.contains(it));
+        locations.extend(external);
+    }
+
     Ok(Some(locations))
 }
 
@@ -1305,26 +1418,25 @@ pub(crate) fn handle_code_lens(
 
     let annotations = snap.analysis.annotations(
         &AnnotationConfig {
-            binary_target: cargo_target_spec
-                .map(|spec| {
-                    matches!(
-                        spec.target_kind,
-                        TargetKind::Bin | TargetKind::Example | TargetKind::Test
-                    )
-                })
-                .unwrap_or(false),
+            binary_target: cargo_target_spec.as_ref().is_some_and(is_binary_target),
             annotate_runnables: lens_config.runnable(),
             annotate_impls: lens_config.implementations,
             annotate_references: lens_config.refs_adt,
             annotate_method_references: lens_config.method_refs,
             annotate_enum_variant_references: lens_config.enum_variant_refs,
             location: lens_config.location.into(),
+            test_config: snap.config.test_config(),
         },
         file_id,
     )?;
 
     let mut res = Vec::new();
     for a in annotations {
+        if let AnnotationKind::Runnable(runnable) = &a.kind {
+            if should_skip_target(runnable, cargo_target_spec.as_ref()) {
+                continue;
+            }
+        }
         to_proto::code_lens(&mut res, &snap, a)?;
     }
 
@@ -1482,6 +1594,71 @@ pub(crate) fn handle_inlay_hints_resolve(
     Ok(original_hint)
 }
 
+pub(crate) fn handle_linked_editing_range(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::LinkedEditingRangeParams,
+) -> anyhow::Result<Option<lsp_types::LinkedEditingRanges>> {
+    let _p = profile::span("handle_linked_editing_range");
+    let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+    let line_index = snap.file_line_index(position.file_id)?;
+
+    let ranges = match snap.analysis.linked_editing_ranges(position)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+    let ranges = ranges.into_iter().map(|it| to_proto::range(&line_index, it)).collect();
+    Ok(Some(lsp_types::LinkedEditingRanges { ranges, word_pattern: None }))
+}
+
+pub(crate) fn handle_moniker(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::MonikerParams,
+) -> anyhow::Result<Option<Vec<lsp_types::Moniker>>> {
+    let _p = profile::span("handle_moniker");
+    let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+
+    let monikers = match snap.analysis.moniker(position)? {
+        None => return Ok(None),
+        Some(it) => it.info,
+    };
+    Ok(Some(monikers.iter().map(to_proto::moniker).collect()))
+}
+
+pub(crate) fn handle_document_link(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::DocumentLinkParams,
+) -> anyhow::Result<Option<Vec<lsp_types::DocumentLink>>> {
+    let _p = profile::span("handle_document_link");
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let line_index = snap.file_line_index(file_id)?;
+
+    let links = snap.analysis.document_links(file_id)?;
+    let links = links
+        .into_iter()
+        .filter_map(|it| to_proto::document_link(&snap, &line_index, it))
+        .collect();
+    Ok(Some(links))
+}
+
+pub(crate) fn handle_inline_values(
+    snap: GlobalStateSnapshot,
+    params: InlineValueParams,
+) -> anyhow::Result<Option<Vec<InlineValue>>> {
+    let _p = profile::span("handle_inline_values");
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let line_index = snap.file_line_index(file_id)?;
+    let range = from_proto::text_range(&line_index, params.range)?;
+    let stopped_location = from_proto::text_range(&line_index, params.context.stopped_location)?;
+
+    let res = snap
+        .analysis
+        .inline_values(file_id, range, stopped_location)?
+        .into_iter()
+        .map(|it| to_proto::inline_value(&line_index, it))
+        .collect();
+    Ok(Some(res))
+}
+
 pub(crate) fn handle_call_hierarchy_prepare(
     snap: GlobalStateSnapshot,
     params: CallHierarchyPrepareParams,
@@ -1574,6 +1751,143 @@ pub(crate) fn handle_call_hierarchy_outgoing(
     Ok(Some(res))
 }
 
+pub(crate) fn handle_type_hierarchy_prepare(
+    snap: GlobalStateSnapshot,
+    params: TypeHierarchyPrepareParams,
+) -> anyhow::Result<Option<Vec<TypeHierarchyItem>>> {
+    let _p = profile::span("handle_type_hierarchy_prepare");
+    let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+
+    let nav_info = match snap.analysis.type_hierarchy(position)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+
+    let RangeInfo { range: _, info: navs } = nav_info;
+    let res = navs
+        .into_iter()
+        .map(|it| to_proto::type_hierarchy_item(&snap, it))
+        .collect::<Cancellable<Vec<_>>>()?;
+
+    Ok(Some(res))
+}
+
+pub(crate) fn handle_type_hierarchy_supertypes(
+    snap: GlobalStateSnapshot,
+    params: TypeHierarchySupertypesParams,
+) -> anyhow::Result<Option<Vec<TypeHierarchyItem>>> {
+    let _p = profile::span("handle_type_hierarchy_supertypes");
+    let fpos = type_hierarchy_item_position(&snap, params.item)?;
+
+    let navs = match snap.analysis.supertypes(fpos)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+
+    let res = navs
+        .into_iter()
+        .map(|it| to_proto::type_hierarchy_item(&snap, it))
+        .collect::<Cancellable<Vec<_>>>()?;
+
+    Ok(Some(res))
+}
+
+pub(crate) fn handle_type_hierarchy_subtypes(
+    snap: GlobalStateSnapshot,
+    params: TypeHierarchySubtypesParams,
+) -> anyhow::Result<Option<Vec<TypeHierarchyItem>>> {
+    let _p = profile::span("handle_type_hierarchy_subtypes");
+    let fpos = type_hierarchy_item_position(&snap, params.item)?;
+
+    let navs = match snap.analysis.subtypes(fpos)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+
+    let res = navs
+        .into_iter()
+        .map(|it| to_proto::type_hierarchy_item(&snap, it))
+        .collect::<Cancellable<Vec<_>>>()?;
+
+    Ok(Some(res))
+}
+
+fn type_hierarchy_item_position(
+    snap: &GlobalStateSnapshot,
+    item: TypeHierarchyItem,
+) -> anyhow::Result<FilePosition> {
+    let doc = TextDocumentIdentifier::new(item.uri);
+    let frange = from_proto::file_range(snap, &doc, item.selection_range)?;
+    Ok(FilePosition { file_id: frange.file_id, offset: frange.range.start() })
+}
+
+pub(crate) fn handle_document_diagnostics(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::DocumentDiagnosticParams,
+) -> anyhow::Result<lsp_ext::DocumentDiagnosticReport> {
+    let _p = profile::span("handle_document_diagnostics");
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+
+    let result_id = diagnostics::result_id(&snap, file_id);
+    if params.previous_result_id.as_ref() == Some(&result_id) {
+        return Ok(lsp_ext::DocumentDiagnosticReport::Unchanged { result_id });
+    }
+    // Like published diagnostics, those of libraries are not reported.
+    let items = if snap.analysis.is_library_file(file_id)? {
+        Vec::new()
+    } else {
+        diagnostics::pull_diagnostics(&snap, params.identifier.as_deref(), file_id)?
+    };
+    Ok(lsp_ext::DocumentDiagnosticReport::Full { result_id: Some(result_id), items })
+}
+
+pub(crate) fn handle_workspace_diagnostics(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::WorkspaceDiagnosticParams,
+) -> anyhow::Result<lsp_ext::WorkspaceDiagnosticReport> {
+    let _p = profile::span("handle_workspace_diagnostics");
+    let identifier = params.identifier.as_deref();
+    let mut previous_result_ids: FxHashMap<_, _> =
+        params.previous_result_ids.into_iter().map(|it| (it.uri, it.value)).collect();
+
+    let mut items = Vec::new();
+    for file_id in snap.local_rust_files()? {
+        let uri = snap.file_id_to_url(file_id);
+        let previous_result_id = previous_result_ids.remove(&uri);
+        let version = snap.url_file_version(&uri);
+        let result_id = diagnostics::result_id(&snap, file_id);
+        if previous_result_id.as_ref() == Some(&result_id) {
+            items.push(lsp_ext::WorkspaceDocumentDiagnosticReport::Unchanged {
+                uri,
+                version,
+                result_id,
+            });
+            continue;
+        }
+        let diagnostics = diagnostics::pull_diagnostics(&snap, identifier, file_id)?;
+        // Files without diagnostics are only reported when they had some before.
+        if diagnostics.is_empty() && previous_result_id.is_none() {
+            continue;
+        }
+        items.push(lsp_ext::WorkspaceDocumentDiagnosticReport::Full {
+            uri,
+            version,
+            result_id: Some(result_id),
+            items: diagnostics,
+        });
+    }
+    // The remaining files were removed from the workspace, so their diagnostics are cleared.
+    items.extend(previous_result_ids.into_keys().map(|uri| {
+        lsp_ext::WorkspaceDocumentDiagnosticReport::Full {
+            uri,
+            version: None,
+            result_id: None,
+            items: Vec::new(),
+        }
+    }));
+    Ok(lsp_ext::WorkspaceDiagnosticReport { items })
+}
+
 pub(crate) fn handle_semantic_tokens_full(
     snap: GlobalStateSnapshot,
     params: SemanticTokensParams,
@@ -1752,32 +2066,45 @@ pub(crate) fn handle_move_item(
     }
 }
 
+pub(crate) fn handle_signature_search(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::SignatureSearchParams,
+) -> anyhow::Result<Vec<lsp_ext::SignatureSearchResult>> {
+    let _p = profile::span("handle_signature_search");
+    let scope = params.text_document.map(|it| from_proto::file_id(&snap, &it.uri)).transpose()?;
+    let libs = params.search_scope != Some(lsp_ext::WorkspaceSymbolSearchScope::Workspace);
+    let limit = snap.config.workspace_symbol().search_limit;
+
+    let Some(results) = snap.analysis.signature_search(&params.query, scope, libs, limit)? else {
+        return Err(invalid_params_error(format!(
+            "`{}` is not a signature pattern like `fn(&str) -> Result<Url, _>`",
+            params.query
+        ))
+        .into());
+    };
+    results
+        .into_iter()
+        .map(|it| {
+            let nav = it.navigation_target;
+            Ok(lsp_ext::SignatureSearchResult {
+                name: nav.name.to_string(),
+                container_name: nav.container_name.as_ref().map(|it| it.to_string()),
+                signature: it.signature,
+                location: to_proto::location_from_nav(&snap, nav)?,
+            })
+        })
+        .collect()
+}
+
 pub(crate) fn handle_view_recursive_memory_layout(
     snap: GlobalStateSnapshot,
-    params: lsp_types::TextDocumentPositionParams,
+    params: lsp_ext::ViewRecursiveMemoryLayoutParams,
 ) -> anyhow::Result<Option<lsp_ext::RecursiveMemoryLayout>> {
     let _p = profile::span("view_recursive_memory_layout");
-    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
-    let line_index = snap.file_line_index(file_id)?;
-    let offset = from_proto::offset(&line_index, params.position)?;
+    let position = from_proto::file_position(&snap, params.text_document_position_params)?;
 
-    let res = snap.analysis.get_recursive_memory_layout(FilePosition { file_id, offset })?;
-    Ok(res.map(|it| lsp_ext::RecursiveMemoryLayout {
-        nodes: it
-            .nodes
-            .iter()
-            .map(|n| lsp_ext::MemoryLayoutNode {
-                item_name: n.item_name.clone(),
-                typename: n.typename.clone(),
-                size: n.size,
-                offset: n.offset,
-                alignment: n.alignment,
-                parent_idx: n.parent_idx,
-                children_start: n.children_start,
-                children_len: n.children_len,
-            })
-            .collect(),
-    }))
+    let res = snap.analysis.get_recursive_memory_layout(position, &params.generic_args)?;
+    Ok(res.map(to_proto::recursive_memory_layout))
 }
 
 fn to_command_link(command: lsp_types::Command, tooltip: String) -> lsp_ext::CommandLink {
@@ -1900,6 +2227,29 @@ fn goto_type_action_links(
     })
 }
 
+fn goto_impl_action_links(
+    snap: &GlobalStateSnapshot,
+    nav_targets: &[HoverGotoTypeData],
+) -> Option<lsp_ext::CommandLinkGroup> {
+    if !snap.config.hover_actions().implementations
+        || nav_targets.is_empty()
+        || !snap.config.client_commands().goto_location
+    {
+        return None;
+    }
+
+    Some(lsp_ext::CommandLinkGroup {
+        title: Some("Go to ".into()),
+        commands: nav_targets
+            .iter()
+            .filter_map(|it| {
+                to_proto::command::goto_location(snap, &it.nav)
+                    .map(|cmd| to_command_link(cmd, it.mod_path.clone()))
+            })
+            .collect(),
+    })
+}
+
 fn prepare_hover_actions(
     snap: &GlobalStateSnapshot,
     actions: &[HoverAction],
@@ -1911,6 +2261,7 @@ fn prepare_hover_actions(
             HoverAction::Reference(position) => show_ref_command_link(snap, position),
             HoverAction::Runnable(r) => runnable_action_links(snap, r.clone()),
             HoverAction::GoToType(targets) => goto_type_action_links(snap, targets),
+            HoverAction::GoToImpl(targets) => goto_impl_action_links(snap, targets),
         })
         .collect()
 }
@@ -1920,13 +2271,24 @@ fn should_skip_target(runnable: &Runnable, cargo_spec: Option<&CargoTargetSpec>)
         RunnableKind::Bin => {
             // Do not suggest binary run on other target than binary
             match &cargo_spec {
-                Some(spec) => !matches!(
-                    spec.target_kind,
-                    TargetKind::Bin | TargetKind::Example | TargetKind::Test
-                ),
+                Some(spec) => !is_binary_target(spec),
                 None => true,
             }
         }
+        // Tests of a target without the default harness are neither compiled nor run.
+        RunnableKind::Test { .. } | RunnableKind::TestMod { .. } | RunnableKind::Bench { .. } => {
+            cargo_spec.is_some_and(|spec| !spec.harness)
+        }
+        RunnableKind::DocTest { .. } => false,
+    }
+}
+
+/// Whether the `main` of the target is run, which is the case for test targets only when they
+/// don't use the default harness.
+fn is_binary_target(spec: &CargoTargetSpec) -> bool {
+    match spec.target_kind {
+        TargetKind::Bin | TargetKind::Example => true,
+        TargetKind::Test => !spec.harness,
         _ => false,
     }
 }
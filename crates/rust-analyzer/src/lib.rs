@@ -20,9 +20,12 @@ macro_rules! eprintln {
 
 mod caps;
 mod cargo_target_spec;
+mod cargo_toml;
+mod completion_history;
 mod diagnostics;
 mod diff;
 mod dispatch;
+mod external_symbols;
 mod global_state;
 mod line_index;
 mod main_loop;
@@ -30,6 +33,7 @@ mod mem_docs;
 mod op_queue;
 mod reload;
 mod task_pool;
+mod test_explorer;
 mod version;
 
 mod handlers {
@@ -185,11 +185,21 @@ pub struct ExpandedMacro {
 pub enum ViewRecursiveMemoryLayout {}
 
 impl Request for ViewRecursiveMemoryLayout {
-    type Params = lsp_types::TextDocumentPositionParams;
+    type Params = ViewRecursiveMemoryLayoutParams;
     type Result = Option<RecursiveMemoryLayout>;
     const METHOD: &'static str = "rust-analyzer/viewRecursiveMemoryLayout";
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewRecursiveMemoryLayoutParams {
+    #[serde(flatten)]
+    pub text_document_position_params: lsp_types::TextDocumentPositionParams,
+    /// Types to instantiate the generic parameters of the type under the cursor with.
+    #[serde(default)]
+    pub generic_args: Vec<String>,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct RecursiveMemoryLayout {
@@ -201,6 +211,7 @@ pub struct RecursiveMemoryLayout {
 pub struct MemoryLayoutNode {
     pub item_name: String,
     pub typename: String,
+    pub kind: MemoryLayoutNodeKind,
     pub size: u64,
     pub offset: u64,
     pub alignment: u64,
@@ -209,6 +220,16 @@ pub struct MemoryLayoutNode {
     pub children_len: u64,
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum MemoryLayoutNodeKind {
+    Field,
+    Variant,
+    Tag,
+    Niche,
+    Padding,
+}
+
 pub enum CancelFlycheck {}
 
 impl Notification for CancelFlycheck {
@@ -243,6 +264,48 @@ pub struct RunFlycheckParams {
     pub text_document: Option<TextDocumentIdentifier>,
 }
 
+pub enum CompletionAccepted {}
+
+impl Notification for CompletionAccepted {
+    type Params = CompletionAcceptedParams;
+    const METHOD: &'static str = "rust-analyzer/completionAccepted";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionAcceptedParams {
+    pub label: String,
+    /// The path of the import the item adds, or its kind.
+    pub qualifier: String,
+}
+
+pub enum CompletionHistory {}
+
+impl Request for CompletionHistory {
+    type Params = ();
+    type Result = Vec<CompletionHistoryEntry>;
+    const METHOD: &'static str = "rust-analyzer/completionHistory";
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionHistoryEntry {
+    pub label: String,
+    /// The path of the import the item adds, or its kind.
+    pub qualifier: String,
+    pub count: u32,
+    /// Seconds since the Unix epoch.
+    pub last_accepted: u64,
+}
+
+pub enum ResetCompletionHistory {}
+
+impl Request for ResetCompletionHistory {
+    type Params = ();
+    type Result = ();
+    const METHOD: &'static str = "rust-analyzer/resetCompletionHistory";
+}
+
 pub enum MatchingBrace {}
 
 impl Request for MatchingBrace {
@@ -350,6 +413,120 @@ pub struct TestInfo {
     pub runnable: Runnable,
 }
 
+pub enum DiscoverTest {}
+
+impl Request for DiscoverTest {
+    type Params = DiscoverTestParams;
+    type Result = DiscoverTestResults;
+    const METHOD: &'static str = "experimental/discoverTest";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoverTestParams {
+    /// The item to discover the children of, or `None` for the packages and their targets.
+    pub test_id: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoverTestResults {
+    pub tests: Vec<TestItem>,
+    /// The items whose descendants are replaced by `tests`, or `None` if `tests` replaces the
+    /// whole tree.
+    pub scope: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TestItem {
+    pub id: String,
+    pub label: String,
+    pub kind: TestItemKind,
+    /// Whether the children of this item are only sent on a `DiscoverTest` request for it.
+    pub can_resolve_children: bool,
+    pub parent: Option<String>,
+    pub text_document: Option<TextDocumentIdentifier>,
+    pub range: Option<Range>,
+    pub runnable: Option<Runnable>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TestItemKind {
+    Package,
+    Target,
+    Module,
+    Test,
+}
+
+pub enum DiscoveredTests {}
+
+impl Notification for DiscoveredTests {
+    type Params = DiscoverTestResults;
+    const METHOD: &'static str = "experimental/discoveredTests";
+}
+
+pub enum RunTest {}
+
+impl Request for RunTest {
+    type Params = RunTestParams;
+    type Result = ();
+    const METHOD: &'static str = "experimental/runTest";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RunTestParams {
+    /// The items to run, or `None` to run all tests.
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+}
+
+pub enum ChangeTestState {}
+
+impl Notification for ChangeTestState {
+    type Params = ChangeTestStateParams;
+    const METHOD: &'static str = "experimental/changeTestState";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeTestStateParams {
+    pub test_id: String,
+    pub state: TestState,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase", tag = "tag")]
+pub enum TestState {
+    Passed,
+    Failed { message: String },
+    Skipped,
+    Started,
+}
+
+pub enum AppendOutputToRunTest {}
+
+impl Notification for AppendOutputToRunTest {
+    type Params = String;
+    const METHOD: &'static str = "experimental/appendOutputToRunTest";
+}
+
+pub enum EndRunTest {}
+
+impl Notification for EndRunTest {
+    type Params = ();
+    const METHOD: &'static str = "experimental/endRunTest";
+}
+
+pub enum AbortRunTest {}
+
+impl Notification for AbortRunTest {
+    type Params = ();
+    const METHOD: &'static str = "experimental/abortRunTest";
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct InlayHintsParams {
@@ -380,6 +557,35 @@ pub struct SsrParams {
     pub selections: Vec<lsp_types::Range>,
 }
 
+pub enum SignatureSearch {}
+
+impl Request for SignatureSearch {
+    type Params = SignatureSearchParams;
+    type Result = Vec<SignatureSearchResult>;
+    const METHOD: &'static str = "rust-analyzer/signatureSearch";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureSearchParams {
+    /// A signature pattern, like `fn(&str) -> Result<Url, _>`.
+    pub query: String,
+    /// The document at the top of which the names in `query` are resolved. Defaults to the root
+    /// of the first workspace crate.
+    pub text_document: Option<lsp_types::TextDocumentIdentifier>,
+    /// Whether to search dependencies too, which is the default.
+    pub search_scope: Option<WorkspaceSymbolSearchScope>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureSearchResult {
+    pub name: String,
+    pub container_name: Option<String>,
+    pub signature: String,
+    pub location: lsp_types::Location,
+}
+
 pub enum ServerStatusNotification {}
 
 impl Notification for ServerStatusNotification {
@@ -675,6 +881,104 @@ impl Request for OnTypeFormatting {
     const METHOD: &'static str = "textDocument/onTypeFormatting";
 }
 
+/// The inline value request is sent from the client to the server to compute the values that
+/// are shown inline while debugging. This is the same as lsp_types::request::InlineValueRequest,
+/// but with a list of values as its result, as the specification has it.
+#[derive(Debug)]
+pub enum InlineValueRequest {}
+
+impl Request for InlineValueRequest {
+    type Params = lsp_types::InlineValueParams;
+    type Result = Option<Vec<lsp_types::InlineValue>>;
+    const METHOD: &'static str = "textDocument/inlineValue";
+}
+
+/// The pull diagnostics requests of LSP 3.17, which the version of `lsp_types` we use doesn't
+/// define yet.
+#[derive(Debug)]
+pub enum DocumentDiagnosticRequest {}
+
+impl Request for DocumentDiagnosticRequest {
+    type Params = DocumentDiagnosticParams;
+    type Result = DocumentDiagnosticReport;
+    const METHOD: &'static str = "textDocument/diagnostic";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentDiagnosticParams {
+    pub text_document: TextDocumentIdentifier,
+    pub identifier: Option<String>,
+    pub previous_result_id: Option<String>,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+    #[serde(flatten)]
+    pub partial_result_params: PartialResultParams,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum DocumentDiagnosticReport {
+    #[serde(rename_all = "camelCase")]
+    Full { result_id: Option<String>, items: Vec<lsp_types::Diagnostic> },
+    #[serde(rename_all = "camelCase")]
+    Unchanged { result_id: String },
+}
+
+#[derive(Debug)]
+pub enum WorkspaceDiagnosticRequest {}
+
+impl Request for WorkspaceDiagnosticRequest {
+    type Params = WorkspaceDiagnosticParams;
+    type Result = WorkspaceDiagnosticReport;
+    const METHOD: &'static str = "workspace/diagnostic";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceDiagnosticParams {
+    pub identifier: Option<String>,
+    pub previous_result_ids: Vec<PreviousResultId>,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+    #[serde(flatten)]
+    pub partial_result_params: PartialResultParams,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PreviousResultId {
+    pub uri: Url,
+    pub value: String,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct WorkspaceDiagnosticReport {
+    pub items: Vec<WorkspaceDocumentDiagnosticReport>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum WorkspaceDocumentDiagnosticReport {
+    #[serde(rename_all = "camelCase")]
+    Full {
+        uri: Url,
+        version: Option<i32>,
+        result_id: Option<String>,
+        items: Vec<lsp_types::Diagnostic>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Unchanged { uri: Url, version: Option<i32>, result_id: String },
+}
+
+#[derive(Debug)]
+pub enum WorkspaceDiagnosticRefresh {}
+
+impl Request for WorkspaceDiagnosticRefresh {
+    type Params = ();
+    type Result = ();
+    const METHOD: &'static str = "workspace/diagnostic/refresh";
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CompletionResolveData {
     pub position: lsp_types::TextDocumentPositionParams,
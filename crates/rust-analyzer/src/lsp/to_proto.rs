@@ -10,8 +10,9 @@ use ide::{
     CompletionItemKind, CompletionRelevance, Documentation, FileId, FileRange, FileSystemEdit,
     Fold, FoldKind, Highlight, HlMod, HlOperator, HlPunct, HlRange, HlTag, Indel,
     InlayFieldsToResolve, InlayHint, InlayHintLabel, InlayHintLabelPart, InlayKind, Markup,
-    NavigationTarget, ReferenceCategory, RenameError, Runnable, Severity, SignatureHelp,
-    SnippetEdit, SourceChange, StructureNodeKind, SymbolKind, TextEdit, TextRange, TextSize,
+    MemoryLayoutNodeKind, NavigationTarget, RecursiveMemoryLayout, ReferenceCategory, RenameError,
+    Runnable, Severity, SignatureHelp, SnippetEdit, SourceChange, StructureNodeKind, SymbolKind,
+    TextEdit, TextRange, TextSize,
 };
 use ide_db::rust_doc::format_docs;
 use itertools::Itertools;
@@ -20,7 +21,9 @@ use vfs::AbsPath;
 
 use crate::{
     cargo_target_spec::CargoTargetSpec,
+    completion_history,
     config::{CallInfoConfig, Config},
+    external_symbols,
     global_state::GlobalStateSnapshot,
     line_index::{LineEndings, LineIndex, PositionEncoding},
     lsp::{
@@ -253,7 +256,7 @@ fn completion_item(
     let text_edit = {
         let mut text_edit = None;
         let source_range = item.source_range;
-        for indel in item.text_edit {
+        for indel in &item.text_edit {
             if indel.delete.contains_range(source_range) {
                 // Extract this indel as the main edit
                 text_edit = Some(if indel.delete == source_range {
@@ -278,7 +281,12 @@ fn completion_item(
 
     let insert_text_format = item.is_snippet.then_some(lsp_types::InsertTextFormat::SNIPPET);
     let tags = item.deprecated.then(|| vec![lsp_types::CompletionItemTag::DEPRECATED]);
-    let command = if item.trigger_call_info && config.client_commands().trigger_parameter_hints {
+    let client_commands = config.client_commands();
+    let trigger_parameter_hints = item.trigger_call_info && client_commands.trigger_parameter_hints;
+    let command = if config.completion_history() && client_commands.completion_accepted {
+        let qualifier = completion_history::qualifier(&item);
+        Some(command::completion_accepted(&item.label, qualifier, trigger_parameter_hints))
+    } else if trigger_parameter_hints {
         Some(command::trigger_parameter_hints())
     } else {
         None
@@ -1217,6 +1225,58 @@ impl From<lsp_ext::SnippetTextEdit>
     }
 }
 
+/// Identifies the symbol by its SCIP symbol, like in the indices generated by `rust-analyzer scip`.
+pub(crate) fn moniker(moniker: &ide::MonikerResult) -> lsp_types::Moniker {
+    lsp_types::Moniker {
+        scheme: "scip".to_owned(),
+        identifier: scip::symbol::format_symbol(external_symbols::moniker_to_symbol(moniker)),
+        unique: lsp_types::UniquenessLevel::Global,
+        kind: Some(match moniker.kind {
+            ide::MonikerKind::Import => lsp_types::MonikerKind::Import,
+            ide::MonikerKind::Export => lsp_types::MonikerKind::Export,
+        }),
+    }
+}
+
+pub(crate) fn document_link(
+    snap: &GlobalStateSnapshot,
+    line_index: &LineIndex,
+    link: ide::DocumentLink,
+) -> Option<lsp_types::DocumentLink> {
+    let target = match link.target {
+        ide::DocumentLinkTarget::File(file_id) => snap.file_id_to_url(file_id),
+        ide::DocumentLinkTarget::Path(path) => snap.anchored_path(&path),
+        ide::DocumentLinkTarget::Url(url) => lsp_types::Url::parse(&url).ok()?,
+    };
+    Some(lsp_types::DocumentLink {
+        range: range(line_index, link.range),
+        target: Some(target),
+        tooltip: None,
+        data: None,
+    })
+}
+
+pub(crate) fn inline_value(
+    line_index: &LineIndex,
+    inline_value: ide::InlineValue,
+) -> lsp_types::InlineValue {
+    let range = range(line_index, inline_value.range);
+    match inline_value.kind {
+        ide::InlineValueKind::Variable(name) => {
+            lsp_types::InlineValue::VariableLookup(lsp_types::InlineValueVariableLookup {
+                range,
+                variable_name: Some(name),
+                case_sensitive_lookup: true,
+            })
+        }
+        ide::InlineValueKind::Expression(expression) => {
+            lsp_types::InlineValue::EvaluatableExpression(
+                lsp_types::InlineValueEvaluatableExpression { range, expression: Some(expression) },
+            )
+        }
+    }
+}
+
 pub(crate) fn call_hierarchy_item(
     snap: &GlobalStateSnapshot,
     target: NavigationTarget,
@@ -1237,6 +1297,26 @@ pub(crate) fn call_hierarchy_item(
     })
 }
 
+pub(crate) fn type_hierarchy_item(
+    snap: &GlobalStateSnapshot,
+    target: NavigationTarget,
+) -> Cancellable<lsp_types::TypeHierarchyItem> {
+    let name = target.name.to_string();
+    let detail = target.description.clone();
+    let kind = target.kind.map(symbol_kind).unwrap_or(lsp_types::SymbolKind::STRUCT);
+    let (uri, range, selection_range) = location_info(snap, target)?;
+    Ok(lsp_types::TypeHierarchyItem {
+        name,
+        kind,
+        tags: None,
+        detail,
+        uri,
+        range,
+        selection_range,
+        data: None,
+    })
+}
+
 pub(crate) fn code_action_kind(kind: AssistKind) -> lsp_types::CodeActionKind {
     match kind {
         AssistKind::None | AssistKind::Generate => lsp_types::CodeActionKind::EMPTY,
@@ -1290,9 +1370,16 @@ pub(crate) fn runnable(
     let spec = CargoTargetSpec::for_file(snap, runnable.nav.file_id)?;
     let workspace_root = spec.as_ref().map(|it| it.workspace_root.clone());
     let target = spec.as_ref().map(|s| s.target.clone());
+    // The `main` of a test target without the default harness is run by `cargo test`.
+    let is_test_target = spec
+        .as_ref()
+        .is_some_and(|it| it.target_kind == project_model::TargetKind::Test && !it.harness);
     let (cargo_args, executable_args) =
         CargoTargetSpec::runnable_args(snap, spec, &runnable.kind, &runnable.cfg);
-    let label = runnable.label(target);
+    let label = match (&runnable.kind, target) {
+        (ide::RunnableKind::Bin, Some(target)) if is_test_target => format!("test {target}"),
+        (_, target) => runnable.label(target),
+    };
     let location = location_link(snap, None, runnable.nav)?;
 
     Ok(lsp_ext::Runnable {
@@ -1310,6 +1397,32 @@ pub(crate) fn runnable(
     })
 }
 
+pub(crate) fn test_item(
+    snap: &GlobalStateSnapshot,
+    target_id: &str,
+    test_item: ide::TestItem,
+) -> Cancellable<lsp_ext::TestItem> {
+    let line_index = snap.file_line_index(test_item.nav.file_id)?;
+    Ok(lsp_ext::TestItem {
+        id: format!("{target_id}::{}", test_item.path),
+        label: test_item.label,
+        kind: match test_item.kind {
+            ide::TestItemKind::Module => lsp_ext::TestItemKind::Module,
+            ide::TestItemKind::Function => lsp_ext::TestItemKind::Test,
+        },
+        can_resolve_children: false,
+        parent: Some(match test_item.parent {
+            Some(parent) => format!("{target_id}::{parent}"),
+            None => target_id.to_owned(),
+        }),
+        text_document: Some(lsp_types::TextDocumentIdentifier {
+            uri: url(snap, test_item.nav.file_id),
+        }),
+        range: Some(range(&line_index, test_item.nav.full_range)),
+        runnable: test_item.runnable.map(|it| runnable(snap, it)).transpose()?,
+    })
+}
+
 pub(crate) fn code_lens(
     acc: &mut Vec<lsp_types::CodeLens>,
     snap: &GlobalStateSnapshot,
@@ -1536,6 +1649,24 @@ pub(crate) mod command {
             arguments: None,
         }
     }
+
+    /// Reports the acceptance of a completion item back to the server, triggering parameter hints
+    /// afterwards if requested.
+    pub(crate) fn completion_accepted(
+        label: &str,
+        qualifier: String,
+        trigger_parameter_hints: bool,
+    ) -> lsp_types::Command {
+        let params = lsp_ext::CompletionAcceptedParams { label: label.to_owned(), qualifier };
+        lsp_types::Command {
+            title: "completionAccepted".into(),
+            command: "rust-analyzer.completionAccepted".into(),
+            arguments: Some(vec![
+                to_value(params).unwrap(),
+                to_value(trigger_parameter_hints).unwrap(),
+            ]),
+        }
+    }
 }
 
 pub(crate) fn implementation_title(count: usize) -> String {
@@ -1566,6 +1697,34 @@ pub(crate) fn markup_content(
     lsp_types::MarkupContent { kind, value }
 }
 
+pub(crate) fn recursive_memory_layout(
+    layout: RecursiveMemoryLayout,
+) -> lsp_ext::RecursiveMemoryLayout {
+    lsp_ext::RecursiveMemoryLayout {
+        nodes: layout
+            .nodes
+            .into_iter()
+            .map(|n| lsp_ext::MemoryLayoutNode {
+                item_name: n.item_name,
+                typename: n.typename,
+                kind: match n.kind {
+                    MemoryLayoutNodeKind::Field => lsp_ext::MemoryLayoutNodeKind::Field,
+                    MemoryLayoutNodeKind::Variant => lsp_ext::MemoryLayoutNodeKind::Variant,
+                    MemoryLayoutNodeKind::Tag => lsp_ext::MemoryLayoutNodeKind::Tag,
+                    MemoryLayoutNodeKind::Niche => lsp_ext::MemoryLayoutNodeKind::Niche,
+                    MemoryLayoutNodeKind::Padding => lsp_ext::MemoryLayoutNodeKind::Padding,
+                },
+                size: n.size,
+                offset: n.offset,
+                alignment: n.alignment,
+                parent_idx: n.parent_idx,
+                children_start: n.children_start,
+                children_len: n.children_len,
+            })
+            .collect(),
+    }
+}
+
 pub(crate) fn rename_error(err: RenameError) -> LspError {
     // This is wrong, but we don't have a better alternative I suppose?
     // https://github.com/microsoft/language-server-protocol/issues/1341
@@ -1,24 +1,29 @@
 //! The main loop of `rust-analyzer` responsible for dispatching LSP
 //! requests/replies and notifications back to the client.
 use std::{
-    fmt,
+    fmt, mem,
     time::{Duration, Instant},
 };
 
 use always_assert::always;
-use crossbeam_channel::{select, Receiver};
-use flycheck::FlycheckHandle;
+use crossbeam_channel::{never, select, Receiver};
+use flycheck::{CargoTestMessage, FlycheckHandle};
 use ide_db::base_db::{SourceDatabaseExt, VfsPath};
 use lsp_server::{Connection, Notification, Request};
 use lsp_types::notification::Notification as _;
 use stdx::thread::ThreadIntent;
 use triomphe::Arc;
-use vfs::FileId;
+use vfs::{AbsPathBuf, FileId};
 
 use crate::{
+    completion_history,
     config::Config,
-    diagnostics::fetch_native_diagnostics,
+    diagnostics::{
+        fetch_native_diagnostics, patch_empty_messages, CHECK_DIAGNOSTICS_IDENTIFIER,
+        NATIVE_DIAGNOSTICS_IDENTIFIER,
+    },
     dispatch::{NotificationDispatcher, RequestDispatcher},
+    external_symbols::ExternalSymbols,
     global_state::{file_id_to_url, url_to_file_id, GlobalState},
     lsp::{
         from_proto,
@@ -26,6 +31,7 @@ use crate::{
     },
     lsp_ext,
     reload::{BuildDataProgress, ProcMacroProgress, ProjectWorkspaceProgress},
+    test_explorer,
 };
 
 pub fn main_loop(config: Config, connection: Connection) -> anyhow::Result<()> {
@@ -58,6 +64,7 @@ enum Event {
     Task(Task),
     Vfs(vfs::loader::Message),
     Flycheck(flycheck::Message),
+    TestResult(Option<CargoTestMessage>),
 }
 
 #[derive(Debug)]
@@ -69,6 +76,13 @@ pub(crate) enum Task {
     FetchWorkspace(ProjectWorkspaceProgress),
     FetchBuildData(BuildDataProgress),
     LoadProcMacros(ProcMacroProgress),
+    DiscoverTest(Vec<(FileId, Option<lsp_ext::DiscoverTestResults>)>),
+    LoadExternalSymbols {
+        indices: Vec<AbsPathBuf>,
+        external_symbols: ExternalSymbols,
+        errors: Vec<String>,
+    },
+    SaveCompletionHistory(Result<(), String>),
 }
 
 #[derive(Debug)]
@@ -106,6 +120,7 @@ impl fmt::Debug for Event {
             Event::Task(it) => fmt::Debug::fmt(it, f),
             Event::Vfs(it) => fmt::Debug::fmt(it, f),
             Event::Flycheck(it) => fmt::Debug::fmt(it, f),
+            Event::TestResult(it) => fmt::Debug::fmt(it, f),
         }
     }
 }
@@ -117,6 +132,15 @@ impl GlobalState {
         if self.config.did_save_text_document_dynamic_registration() {
             self.register_did_save_capability();
         }
+        if self.config.type_hierarchy_dynamic_registration() {
+            self.register_type_hierarchy_capability();
+        }
+        if self.config.pull_diagnostics() {
+            self.register_diagnostic_capability();
+        }
+        if self.config.cargo_toml_dynamic_registration() {
+            self.register_cargo_toml_capabilities();
+        }
 
         self.fetch_workspaces_queue.request_op("startup".to_string(), false);
         if let Some((cause, force_crate_graph_reload)) =
@@ -174,6 +198,134 @@ impl GlobalState {
         );
     }
 
+    /// `ServerCapabilities` has no field for type hierarchies yet, so the capability is announced
+    /// as an experimental one, and registered dynamically for clients that support it.
+    fn register_type_hierarchy_capability(&mut self) {
+        let registration_options = lsp_types::TypeHierarchyRegistrationOptions {
+            text_document_registration_options: lsp_types::TextDocumentRegistrationOptions {
+                document_selector: Some(vec![lsp_types::DocumentFilter {
+                    language: None,
+                    scheme: None,
+                    pattern: Some("**/*.rs".into()),
+                }]),
+            },
+            type_hierarchy_options: Default::default(),
+            static_registration_options: Default::default(),
+        };
+
+        let registration = lsp_types::Registration {
+            id: "textDocument/prepareTypeHierarchy".to_string(),
+            method: "textDocument/prepareTypeHierarchy".to_string(),
+            register_options: Some(serde_json::to_value(registration_options).unwrap()),
+        };
+        self.send_request::<lsp_types::request::RegisterCapability>(
+            lsp_types::RegistrationParams { registrations: vec![registration] },
+            |_, _| (),
+        );
+    }
+
+    /// Native and check diagnostics are registered under separate identifiers, so that clients
+    /// keep them apart and pull each on its own.
+    fn register_diagnostic_capability(&mut self) {
+        let registrations = [NATIVE_DIAGNOSTICS_IDENTIFIER, CHECK_DIAGNOSTICS_IDENTIFIER]
+            .into_iter()
+            .map(|identifier| lsp_types::Registration {
+                id: format!("textDocument/diagnostic/{identifier}"),
+                method: "textDocument/diagnostic".to_string(),
+                register_options: Some(serde_json::json!({
+                    "documentSelector": [
+                        { "pattern": "**/*.rs" },
+                        { "pattern": "**/Cargo.toml" },
+                    ],
+                    "identifier": identifier,
+                    "interFileDependencies": true,
+                    "workspaceDiagnostics": true,
+                })),
+            })
+            .collect();
+        self.send_request::<lsp_types::request::RegisterCapability>(
+            lsp_types::RegistrationParams { registrations },
+            |_, _| (),
+        );
+    }
+
+    fn unregister_diagnostic_capability(&mut self) {
+        let unregisterations = [NATIVE_DIAGNOSTICS_IDENTIFIER, CHECK_DIAGNOSTICS_IDENTIFIER]
+            .into_iter()
+            .map(|identifier| lsp_types::Unregistration {
+                id: format!("textDocument/diagnostic/{identifier}"),
+                method: "textDocument/diagnostic".to_string(),
+            })
+            .collect();
+        self.send_request::<lsp_types::request::UnregisterCapability>(
+            lsp_types::UnregistrationParams { unregisterations },
+            |_, _| (),
+        );
+    }
+
+    /// Switches between publishing diagnostics and having the client pull them, when the
+    /// `diagnostics.pull.enable` setting changed.
+    pub(crate) fn update_diagnostics_mode(&mut self) {
+        if self.config.pull_diagnostics() {
+            self.register_diagnostic_capability();
+            // Clear what we published, the client pulls the diagnostics from now on.
+            for file_id in self.diagnostics.file_ids() {
+                let uri = file_id_to_url(&self.vfs.read().0, file_id);
+                self.send_notification::<lsp_types::notification::PublishDiagnostics>(
+                    lsp_types::PublishDiagnosticsParams {
+                        uri,
+                        diagnostics: Vec::new(),
+                        version: None,
+                    },
+                );
+            }
+        } else {
+            self.unregister_diagnostic_capability();
+            self.diagnostics.mark_all_changed();
+            self.update_diagnostics();
+        }
+    }
+
+    /// Clients only send us the documents of the languages we are registered for, so we ask for
+    /// `Cargo.toml` files to be synced explicitly, to offer completions and go to definition in
+    /// them.
+    fn register_cargo_toml_capabilities(&mut self) {
+        let document_selector = serde_json::json!([{ "pattern": "**/Cargo.toml" }]);
+        let registrations = [
+            ("textDocument/didOpen", serde_json::json!({ "documentSelector": document_selector })),
+            (
+                "textDocument/didChange",
+                serde_json::json!({
+                    "documentSelector": document_selector,
+                    "syncKind": lsp_types::TextDocumentSyncKind::INCREMENTAL,
+                }),
+            ),
+            ("textDocument/didClose", serde_json::json!({ "documentSelector": document_selector })),
+            (
+                "textDocument/completion",
+                serde_json::json!({
+                    "documentSelector": document_selector,
+                    "triggerCharacters": ["[", ".", "\""],
+                }),
+            ),
+            (
+                "textDocument/definition",
+                serde_json::json!({ "documentSelector": document_selector }),
+            ),
+        ]
+        .into_iter()
+        .map(|(method, register_options)| lsp_types::Registration {
+            id: format!("{method}/cargo-toml"),
+            method: method.to_string(),
+            register_options: Some(register_options),
+        })
+        .collect();
+        self.send_request::<lsp_types::request::RegisterCapability>(
+            lsp_types::RegistrationParams { registrations },
+            |_, _| (),
+        );
+    }
+
     fn next_event(&self, inbox: &Receiver<lsp_server::Message>) -> Option<Event> {
         select! {
             recv(inbox) -> msg =>
@@ -190,6 +342,9 @@ impl GlobalState {
 
             recv(self.flycheck_receiver) -> task =>
                 Some(Event::Flycheck(task.unwrap())),
+
+            recv(self.test_run_session.as_ref().map(|s| s.handle.receiver()).unwrap_or(&never())) -> message =>
+                Some(Event::TestResult(message.ok())),
         }
     }
 
@@ -284,11 +439,26 @@ impl GlobalState {
                     self.handle_flycheck_msg(message);
                 }
             }
+            Event::TestResult(message) => {
+                let _p = profile::span("GlobalState::handle_event/test_result");
+                self.handle_cargo_test_msg(message);
+                // Coalesce many test result updates into a single loop turn
+                while let Some(message) = self
+                    .test_run_session
+                    .as_ref()
+                    .and_then(|it| it.handle.receiver().try_recv().ok())
+                {
+                    self.handle_cargo_test_msg(Some(message));
+                }
+            }
         }
         let event_handling_duration = loop_start.elapsed();
 
         let state_changed = self.process_changes();
         let memdocs_added_or_removed = self.mem_docs.take_changes();
+        if !was_quiescent || state_changed {
+            self.diagnostics_revision += 1;
+        }
 
         if self.is_quiescent() {
             let became_quiescent = !(was_quiescent
@@ -331,47 +501,40 @@ impl GlobalState {
             let update_diagnostics = (!was_quiescent || state_changed || memdocs_added_or_removed)
                 && self.config.publish_diagnostics();
             if update_diagnostics {
-                self.update_diagnostics()
+                if self.config.pull_diagnostics() {
+                    // The client pulls the diagnostics of the documents it opens by itself, it
+                    // only needs to be told when the analysis changed.
+                    if (!was_quiescent || state_changed) && self.config.pull_diagnostics_refresh() {
+                        self.send_request::<lsp_ext::WorkspaceDiagnosticRefresh>((), |_, _| ());
+                    }
+                } else {
+                    self.update_diagnostics()
+                }
             }
         }
 
         if let Some(diagnostic_changes) = self.diagnostics.take_changes() {
-            for file_id in diagnostic_changes {
-                let uri = file_id_to_url(&self.vfs.read().0, file_id);
-                let mut diagnostics =
-                    self.diagnostics.diagnostics_for(file_id).cloned().collect::<Vec<_>>();
-
-                // VSCode assumes diagnostic messages to be non-empty strings, so we need to patch
-                // empty diagnostics. Neither the docs of VSCode nor the LSP spec say whether
-                // diagnostic messages are actually allowed to be empty or not and patching this
-                // in the VSCode client does not work as the assertion happens in the protocol
-                // conversion. So this hack is here to stay, and will be considered a hack
-                // until the LSP decides to state that empty messages are allowed.
-
-                // See https://github.com/rust-lang/rust-analyzer/issues/11404
-                // See https://github.com/rust-lang/rust-analyzer/issues/13130
-                let patch_empty = |message: &mut String| {
-                    if message.is_empty() {
-                        *message = " ".to_string();
-                    }
-                };
-
-                for d in &mut diagnostics {
-                    patch_empty(&mut d.message);
-                    if let Some(dri) = &mut d.related_information {
-                        for dri in dri {
-                            patch_empty(&mut dri.message);
-                        }
-                    }
+            if self.config.pull_diagnostics() {
+                self.diagnostics_revision += 1;
+                // The check diagnostics changed, let the client ask for them again.
+                if self.config.pull_diagnostics_refresh() {
+                    self.send_request::<lsp_ext::WorkspaceDiagnosticRefresh>((), |_, _| ());
+                }
+            } else {
+                for file_id in diagnostic_changes {
+                    let uri = file_id_to_url(&self.vfs.read().0, file_id);
+                    let mut diagnostics =
+                        self.diagnostics.diagnostics_for(file_id).cloned().collect::<Vec<_>>();
+                    patch_empty_messages(&mut diagnostics);
+
+                    let version = from_proto::vfs_path(&uri)
+                        .map(|path| self.mem_docs.get(&path).map(|it| it.version))
+                        .unwrap_or_default();
+
+                    self.send_notification::<lsp_types::notification::PublishDiagnostics>(
+                        lsp_types::PublishDiagnosticsParams { uri, diagnostics, version },
+                    );
                 }
-
-                let version = from_proto::vfs_path(&uri)
-                    .map(|path| self.mem_docs.get(&path).map(|it| it.version))
-                    .unwrap_or_default();
-
-                self.send_notification::<lsp_types::notification::PublishDiagnostics>(
-                    lsp_types::PublishDiagnosticsParams { uri, diagnostics, version },
-                );
             }
         }
 
@@ -395,6 +558,14 @@ impl GlobalState {
             self.prime_caches(cause);
         }
 
+        if let Some((cause, ())) = self.discover_tests_queue.should_start_op() {
+            self.discover_tests(cause);
+        }
+
+        if let Some((cause, ())) = self.save_completion_history_queue.should_start_op() {
+            self.save_completion_history(cause);
+        }
+
         self.update_status_or_notify();
 
         let loop_duration = loop_start.elapsed();
@@ -452,6 +623,60 @@ impl GlobalState {
         });
     }
 
+    /// Queues the changed `files` for test discovery, unless they can't contain tests. Changes
+    /// made while a discovery is running are coalesced into the next one.
+    pub(crate) fn update_tests(&mut self, files: Vec<FileId>) {
+        let db = self.analysis_host.raw_database();
+        let config = self.config.test_config();
+        let files = files
+            .into_iter()
+            .filter(|&file_id| {
+                !db.source_root(db.file_source_root(file_id)).is_library
+                    && (self.files_with_tests.contains_key(&file_id)
+                        || test_explorer::may_contain_tests(&db.file_text(file_id), &config))
+            })
+            .collect::<Vec<_>>();
+        self.tests_to_update.extend(files);
+        if !self.tests_to_update.is_empty() {
+            self.discover_tests_queue.request_op("files changed".to_owned(), ());
+        }
+    }
+
+    fn discover_tests(&mut self, cause: String) {
+        tracing::debug!(%cause, "will discover tests");
+        let files = mem::take(&mut self.tests_to_update);
+        self.task_pool.handle.spawn(ThreadIntent::Worker, {
+            let snapshot = self.snapshot();
+            move || {
+                let tests = files
+                    .into_iter()
+                    .map(|file_id| {
+                        let tests = test_explorer::discover_tests_in_file(&snapshot, file_id);
+                        (file_id, tests.ok().flatten())
+                    })
+                    .collect();
+                Task::DiscoverTest(tests)
+            }
+        });
+    }
+
+    fn save_completion_history(&mut self, cause: String) {
+        tracing::debug!(%cause, "will save the completion history");
+        let history = self.completion_history.read();
+        let Some(path) = history.path().map(ToOwned::to_owned) else {
+            drop(history);
+            self.save_completion_history_queue.op_completed(());
+            return;
+        };
+        let entries = history.entries();
+        drop(history);
+        self.task_pool.handle.spawn(ThreadIntent::Worker, move || {
+            Task::SaveCompletionHistory(
+                completion_history::save(&path, &entries).map_err(|e| format!("{path}: {e}")),
+            )
+        });
+    }
+
     fn update_status_or_notify(&mut self) {
         let status = self.current_status();
         if self.last_reported_status.as_ref() != Some(&status) {
@@ -523,6 +748,11 @@ impl GlobalState {
                             self.fetch_build_data_queue
                                 .request_op(format!("workspace updated"), ());
                         }
+                        if self.config.test_explorer() && workspaces_updated {
+                            self.send_notification::<lsp_ext::DiscoveredTests>(
+                                test_explorer::discover_test_roots(&self.workspaces),
+                            );
+                        }
 
                         (Progress::End, None)
                     }
@@ -567,6 +797,50 @@ impl GlobalState {
                     self.report_progress("Loading", state, msg, None, None);
                 }
             }
+            Task::DiscoverTest(tests) => {
+                self.discover_tests_queue.op_completed(());
+                for (file_id, it) in tests {
+                    let previous_scope = self.files_with_tests.remove(&file_id);
+                    let it = match it {
+                        Some(it) => {
+                            if !it.tests.is_empty() {
+                                let scope = it.scope.clone().unwrap_or_default();
+                                self.files_with_tests.insert(file_id, scope);
+                            }
+                            it
+                        }
+                        // The file was deleted or is no longer part of a crate, so the tests
+                        // reported for it are gone.
+                        None => match previous_scope {
+                            Some(scope) => lsp_ext::DiscoverTestResults {
+                                tests: Vec::new(),
+                                scope: Some(scope),
+                            },
+                            None => continue,
+                        },
+                    };
+                    self.send_notification::<lsp_ext::DiscoveredTests>(it);
+                }
+            }
+            Task::SaveCompletionHistory(result) => {
+                self.save_completion_history_queue.op_completed(());
+                if let Err(e) = result {
+                    tracing::error!("failed to save the completion history: {e}");
+                }
+            }
+            Task::LoadExternalSymbols { indices, external_symbols, errors } => {
+                // The indices may have been reconfigured while they were loading.
+                if indices != self.config.scip_indices() {
+                    return;
+                }
+                self.external_symbols = Arc::new(external_symbols);
+                if !errors.is_empty() {
+                    self.show_and_log_error(
+                        "failed to load SCIP indices".to_owned(),
+                        Some(errors.join("\n")),
+                    );
+                }
+            }
         }
     }
 
@@ -607,6 +881,40 @@ impl GlobalState {
         }
     }
 
+    fn handle_cargo_test_msg(&mut self, message: Option<CargoTestMessage>) {
+        let Some(session) = &self.test_run_session else { return };
+        match message {
+            Some(CargoTestMessage::Test { name, state }) => {
+                let state = match state {
+                    flycheck::TestState::Started => lsp_ext::TestState::Started,
+                    flycheck::TestState::Ok => lsp_ext::TestState::Passed,
+                    flycheck::TestState::Ignored => lsp_ext::TestState::Skipped,
+                    flycheck::TestState::Failed { stdout } => {
+                        lsp_ext::TestState::Failed { message: stdout }
+                    }
+                };
+                let test_id = format!("{}::{name}", session.target_id);
+                self.send_notification::<lsp_ext::ChangeTestState>(
+                    lsp_ext::ChangeTestStateParams { test_id, state },
+                );
+            }
+            Some(CargoTestMessage::Suite) => (),
+            Some(CargoTestMessage::Custom { text }) => {
+                self.send_notification::<lsp_ext::AppendOutputToRunTest>(text + "\n");
+            }
+            // The run of this target has finished, continue with the next one.
+            None => {
+                let session = self.test_run_session.take().unwrap();
+                self.test_run_session = session.next(&mut |error| {
+                    self.send_notification::<lsp_ext::AppendOutputToRunTest>(error + "\n")
+                });
+                if self.test_run_session.is_none() {
+                    self.send_notification::<lsp_ext::EndRunTest>(());
+                }
+            }
+        }
+    }
+
     fn handle_flycheck_msg(&mut self, message: flycheck::Message) {
         match message {
             flycheck::Message::AddDiagnostic { id, workspace_root, diagnostic } => {
@@ -712,6 +1020,10 @@ impl GlobalState {
             .on_sync_mut::<lsp_ext::RebuildProcMacros>(handlers::handle_proc_macros_rebuild)
             .on_sync_mut::<lsp_ext::MemoryUsage>(handlers::handle_memory_usage)
             .on_sync_mut::<lsp_ext::ShuffleCrateGraph>(handlers::handle_shuffle_crate_graph)
+            .on_sync_mut::<lsp_ext::RunTest>(handlers::handle_run_test)
+            .on_sync_mut::<lsp_ext::ResetCompletionHistory>(
+                handlers::handle_reset_completion_history,
+            )
             // Request handlers which are related to the user typing
             // are run on the main thread to reduce latency:
             .on_sync::<lsp_ext::JoinLines>(handlers::handle_join_lines)
@@ -742,9 +1054,13 @@ impl GlobalState {
             .on_latency_sensitive::<lsp_request::SemanticTokensRangeRequest>(
                 handlers::handle_semantic_tokens_range,
             )
+            .on_latency_sensitive::<lsp_ext::DocumentDiagnosticRequest>(
+                handlers::handle_document_diagnostics,
+            )
             // All other request handlers
             .on::<lsp_ext::FetchDependencyList>(handlers::fetch_dependency_list)
             .on::<lsp_ext::AnalyzerStatus>(handlers::handle_analyzer_status)
+            .on::<lsp_ext::CompletionHistory>(handlers::handle_completion_history)
             .on::<lsp_ext::SyntaxTree>(handlers::handle_syntax_tree)
             .on::<lsp_ext::ViewHir>(handlers::handle_view_hir)
             .on::<lsp_ext::ViewMir>(handlers::handle_view_mir)
@@ -755,6 +1071,7 @@ impl GlobalState {
             .on::<lsp_ext::ExpandMacro>(handlers::handle_expand_macro)
             .on::<lsp_ext::ParentModule>(handlers::handle_parent_module)
             .on::<lsp_ext::Runnables>(handlers::handle_runnables)
+            .on::<lsp_ext::DiscoverTest>(handlers::handle_discover_test)
             .on::<lsp_ext::RelatedTests>(handlers::handle_related_tests)
             .on::<lsp_ext::CodeActionRequest>(handlers::handle_code_action)
             .on::<lsp_ext::CodeActionResolveRequest>(handlers::handle_code_action_resolve)
@@ -763,6 +1080,7 @@ impl GlobalState {
             .on::<lsp_ext::OpenCargoToml>(handlers::handle_open_cargo_toml)
             .on::<lsp_ext::MoveItem>(handlers::handle_move_item)
             .on::<lsp_ext::WorkspaceSymbol>(handlers::handle_workspace_symbol)
+            .on::<lsp_ext::WorkspaceDiagnosticRequest>(handlers::handle_workspace_diagnostics)
             .on::<lsp_request::DocumentSymbolRequest>(handlers::handle_document_symbol)
             .on::<lsp_request::GotoDefinition>(handlers::handle_goto_definition)
             .on::<lsp_request::GotoDeclaration>(handlers::handle_goto_declaration)
@@ -770,6 +1088,7 @@ impl GlobalState {
             .on::<lsp_request::GotoTypeDefinition>(handlers::handle_goto_type_definition)
             .on_no_retry::<lsp_request::InlayHintRequest>(handlers::handle_inlay_hints)
             .on::<lsp_request::InlayHintResolveRequest>(handlers::handle_inlay_hints_resolve)
+            .on::<lsp_ext::InlineValueRequest>(handlers::handle_inline_values)
             .on::<lsp_request::CodeLensRequest>(handlers::handle_code_lens)
             .on::<lsp_request::CodeLensResolve>(handlers::handle_code_lens_resolve)
             .on::<lsp_request::FoldingRangeRequest>(handlers::handle_folding_range)
@@ -778,11 +1097,18 @@ impl GlobalState {
             .on::<lsp_request::Rename>(handlers::handle_rename)
             .on::<lsp_request::References>(handlers::handle_references)
             .on::<lsp_request::DocumentHighlightRequest>(handlers::handle_document_highlight)
+            .on::<lsp_request::LinkedEditingRange>(handlers::handle_linked_editing_range)
+            .on::<lsp_request::DocumentLinkRequest>(handlers::handle_document_link)
+            .on::<lsp_request::MonikerRequest>(handlers::handle_moniker)
             .on::<lsp_request::CallHierarchyPrepare>(handlers::handle_call_hierarchy_prepare)
             .on::<lsp_request::CallHierarchyIncomingCalls>(handlers::handle_call_hierarchy_incoming)
             .on::<lsp_request::CallHierarchyOutgoingCalls>(handlers::handle_call_hierarchy_outgoing)
+            .on::<lsp_request::TypeHierarchyPrepare>(handlers::handle_type_hierarchy_prepare)
+            .on::<lsp_request::TypeHierarchySupertypes>(handlers::handle_type_hierarchy_supertypes)
+            .on::<lsp_request::TypeHierarchySubtypes>(handlers::handle_type_hierarchy_subtypes)
             .on::<lsp_request::WillRenameFiles>(handlers::handle_will_rename_files)
             .on::<lsp_ext::Ssr>(handlers::handle_ssr)
+            .on::<lsp_ext::SignatureSearch>(handlers::handle_signature_search)
             .on::<lsp_ext::ViewRecursiveMemoryLayout>(handlers::handle_view_recursive_memory_layout)
             .finish();
     }
@@ -815,6 +1141,8 @@ impl GlobalState {
             .on_sync_mut::<lsp_ext::CancelFlycheck>(handlers::handle_cancel_flycheck)?
             .on_sync_mut::<lsp_ext::ClearFlycheck>(handlers::handle_clear_flycheck)?
             .on_sync_mut::<lsp_ext::RunFlycheck>(handlers::handle_run_flycheck)?
+            .on_sync_mut::<lsp_ext::AbortRunTest>(handlers::handle_abort_run_test)?
+            .on_sync_mut::<lsp_ext::CompletionAccepted>(handlers::handle_completion_accepted)?
             .finish();
         Ok(())
     }
@@ -31,7 +31,9 @@ use triomphe::Arc;
 use vfs::{AbsPath, ChangeKind};
 
 use crate::{
+    completion_history::CompletionHistory,
     config::{Config, FilesWatcher, LinkedProject},
+    external_symbols::ExternalSymbols,
     global_state::GlobalState,
     lsp_ext,
     main_loop::Task,
@@ -72,6 +74,8 @@ impl GlobalState {
     pub(crate) fn update_configuration(&mut self, config: Config) {
         let _p = profile::span("GlobalState::update_configuration");
         let old_config = mem::replace(&mut self.config, Arc::new(config));
+        // The diagnostics config may have changed.
+        self.diagnostics_revision += 1;
         if self.config.lru_parse_query_capacity() != old_config.lru_parse_query_capacity() {
             self.analysis_host.update_lru_capacity(self.config.lru_parse_query_capacity());
         }
@@ -85,6 +89,15 @@ impl GlobalState {
         } else if self.config.flycheck() != old_config.flycheck() {
             self.reload_flycheck();
         }
+        if self.config.scip_indices() != old_config.scip_indices() {
+            self.load_external_symbols();
+        }
+        if self.config.completion_history() != old_config.completion_history() {
+            self.load_completion_history();
+        }
+        if self.config.pull_diagnostics() != old_config.pull_diagnostics() {
+            self.update_diagnostics_mode();
+        }
 
         if self.analysis_host.raw_database().expand_proc_attr_macros()
             != self.config.expand_proc_attr_macros()
@@ -96,6 +109,43 @@ impl GlobalState {
         }
     }
 
+    pub(crate) fn load_external_symbols(&mut self) {
+        let indices = self.config.scip_indices();
+        if indices.is_empty() {
+            self.external_symbols = Arc::new(ExternalSymbols::default());
+            return;
+        }
+        let encoding = self.config.position_encoding();
+        self.task_pool.handle.spawn(ThreadIntent::Worker, move || {
+            let _p = profile::span("GlobalState::load_external_symbols");
+            let (external_symbols, errors) = ExternalSymbols::load(&indices, encoding);
+            Task::LoadExternalSymbols { indices, external_symbols, errors }
+        });
+    }
+
+    pub(crate) fn load_completion_history(&mut self) {
+        if !self.config.completion_history() {
+            *self.completion_history.write() = CompletionHistory::default();
+            return;
+        }
+        let path = CompletionHistory::path_for(self.config.root_path());
+        if self.completion_history.read().path() == path.as_deref() {
+            return;
+        }
+        let Some(path) = path else {
+            *self.completion_history.write() = CompletionHistory::default();
+            return;
+        };
+        let (completion_history, error) = CompletionHistory::load(path);
+        *self.completion_history.write() = completion_history;
+        if let Some(error) = error {
+            self.show_and_log_error(
+                "failed to load the completion history".to_owned(),
+                Some(error),
+            );
+        }
+    }
+
     pub(crate) fn current_status(&self) -> lsp_ext::ServerStatusParams {
         let mut status = lsp_ext::ServerStatusParams {
             health: lsp_ext::Health::Ok,
@@ -400,6 +450,7 @@ impl GlobalState {
             // FIXME: can we abort the build scripts here?
             self.workspaces = Arc::new(workspaces);
         }
+        self.load_completion_history();
 
         if let FilesWatcher::Client = self.config.files().watcher {
             let registration_options = lsp_types::DidChangeWatchedFilesRegistrationOptions {
@@ -0,0 +1,372 @@
+//! Maps the tests of the workspace to the items of the test explorer extension, and runs
+//! them with `cargo test`.
+//!
+//! Test items are identified by their path in the tree:
+//!
+//! * a package by its `--package` flag, like `foo`,
+//! * a target by its package and kind, like `foo/lib` or `foo/test/integration`,
+//! * a module or test by its target and its path inside the crate, like `foo/lib::tests::it_works`.
+
+use std::{collections::VecDeque, process::Command};
+
+use flycheck::CargoTestHandle;
+use ide::{Cancellable, FileId, RunnableKind, TestConfig, TestItemKind};
+use project_model::{ProjectWorkspace, TargetKind};
+use vfs::AbsPathBuf;
+
+use crate::{
+    cargo_target_spec::CargoTargetSpec,
+    global_state::GlobalStateSnapshot,
+    lsp::{to_proto, utils::invalid_params_error},
+    lsp_ext,
+};
+
+/// A target of a workspace member that may contain tests.
+pub(crate) struct TestTarget {
+    pub(crate) id: String,
+    pub(crate) package_id: String,
+    pub(crate) label: String,
+    pub(crate) root: AbsPathBuf,
+    /// Whether the target uses the default harness, without which its tests can't be listed or
+    /// filtered, so that it is run as a whole.
+    pub(crate) harness: bool,
+}
+
+pub(crate) fn test_targets(workspaces: &[ProjectWorkspace]) -> Vec<TestTarget> {
+    let mut res = Vec::new();
+    for ws in workspaces {
+        let ProjectWorkspace::Cargo { cargo, .. } = ws else { continue };
+        for pkg in cargo.packages() {
+            let package = &cargo[pkg];
+            if !package.is_member {
+                continue;
+            }
+            let package_id = cargo.package_flag(package);
+            for &tgt in &package.targets {
+                let target = &cargo[tgt];
+                let (id, label) = match target.kind {
+                    TargetKind::Lib if !target.is_proc_macro => {
+                        (format!("{package_id}/lib"), "lib".to_owned())
+                    }
+                    TargetKind::Bin => (
+                        format!("{package_id}/bin/{}", target.name),
+                        format!("bin {}", target.name),
+                    ),
+                    TargetKind::Test => (
+                        format!("{package_id}/test/{}", target.name),
+                        format!("test {}", target.name),
+                    ),
+                    _ => continue,
+                };
+                res.push(TestTarget {
+                    id,
+                    package_id: package_id.clone(),
+                    label,
+                    root: target.root.clone(),
+                    harness: target.harness,
+                });
+            }
+        }
+    }
+    res
+}
+
+/// The packages and targets of the workspace, whose children are discovered lazily.
+pub(crate) fn discover_test_roots(workspaces: &[ProjectWorkspace]) -> lsp_ext::DiscoverTestResults {
+    let mut tests = Vec::new();
+    let mut package_id = None;
+    for target in test_targets(workspaces) {
+        if package_id.as_ref() != Some(&target.package_id) {
+            tests.push(package_item(&target.package_id));
+            package_id = Some(target.package_id.clone());
+        }
+        tests.push(target_item(target));
+    }
+    lsp_ext::DiscoverTestResults { tests, scope: None }
+}
+
+/// The children of the package, target or module `id`.
+pub(crate) fn discover_tests_below(
+    snap: &GlobalStateSnapshot,
+    id: &str,
+) -> anyhow::Result<lsp_ext::DiscoverTestResults> {
+    let (target_id, path) = split_test_id(id);
+    let targets = test_targets(&snap.workspaces);
+    if !target_id.contains('/') {
+        let tests = targets.into_iter().filter(|it| it.package_id == id).map(target_item).collect();
+        return Ok(lsp_ext::DiscoverTestResults { tests, scope: Some(vec![id.to_owned()]) });
+    }
+    let Some(target) = targets.iter().find(|it| it.id == target_id) else {
+        return Err(invalid_params_error(format!("unknown test item `{id}`")).into());
+    };
+    let mut tests = Vec::new();
+    if let Some(file_id) = target_file_id(snap, target).filter(|_| target.harness) {
+        for &crate_id in snap.analysis.crates_for(file_id)?.iter().take(1) {
+            for item in
+                snap.analysis.discover_tests_in_crate(&snap.config.test_config(), crate_id)?
+            {
+                let is_below = match path {
+                    Some(path) => {
+                        item.path.strip_prefix(path).is_some_and(|it| it.starts_with("::"))
+                    }
+                    None => true,
+                };
+                if is_below {
+                    tests.push(to_proto::test_item(snap, &target.id, item)?);
+                }
+            }
+        }
+    }
+    Ok(lsp_ext::DiscoverTestResults { tests, scope: Some(vec![id.to_owned()]) })
+}
+
+/// The tests of `file_id`, replacing what was previously known about the modules it defines.
+pub(crate) fn discover_tests_in_file(
+    snap: &GlobalStateSnapshot,
+    file_id: FileId,
+) -> Cancellable<Option<lsp_ext::DiscoverTestResults>> {
+    let scopes = snap.analysis.discover_tests_in_file(&snap.config.test_config(), file_id)?;
+    if scopes.is_empty() {
+        return Ok(None);
+    }
+    let targets = test_targets(&snap.workspaces);
+    let mut tests = Vec::new();
+    let mut scope = Vec::new();
+    for test_scope in scopes {
+        let Some(spec) = CargoTargetSpec::for_crate(snap, test_scope.crate_id) else { continue };
+        let Some(target) = targets.iter().find(|it| is_target_of(it, &spec) && it.harness) else {
+            continue;
+        };
+        scope.push(match &test_scope.module {
+            Some(module) => format!("{}::{module}", target.id),
+            None => target.id.clone(),
+        });
+        for item in test_scope.tests {
+            tests.push(to_proto::test_item(snap, &target.id, item)?);
+        }
+    }
+    Ok((!scope.is_empty()).then_some(lsp_ext::DiscoverTestResults { tests, scope: Some(scope) }))
+}
+
+/// A `cargo test` invocation for a single target.
+pub(crate) struct TestRun {
+    target_id: String,
+    command: Command,
+    test_args: Vec<String>,
+}
+
+pub(crate) fn plan_test_runs(
+    snap: &GlobalStateSnapshot,
+    params: &lsp_ext::RunTestParams,
+) -> Cancellable<Vec<TestRun>> {
+    let config = snap.config.runnables();
+    let excludes = params.exclude.as_deref().unwrap_or_default();
+    let mut res = Vec::new();
+    for target in test_targets(&snap.workspaces) {
+        let mut whole_target = params.include.is_none();
+        let mut includes = Vec::new();
+        for id in params.include.iter().flatten() {
+            match split_test_id(id) {
+                (it, None) if it == target.id || it == target.package_id => whole_target = true,
+                (it, Some(path)) if it == target.id => includes.push(path),
+                _ => (),
+            }
+        }
+        // Targets without the default harness can only be run as a whole.
+        if !whole_target && (includes.is_empty() || !target.harness) {
+            continue;
+        }
+        let skips = excludes
+            .iter()
+            .filter_map(|id| match split_test_id(id) {
+                (it, Some(path)) if it == target.id => Some(path),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let Some(file_id) = target_file_id(snap, &target) else { continue };
+
+        // The harness matches filters and skips as substrings of the test paths, unless
+        // `--exact` is given for both. Modules are thus expanded to the tests they contain.
+        let mut test_args = Vec::new();
+        if target.harness && (!whole_target || !skips.is_empty()) {
+            let tests = test_functions(snap, file_id)?;
+            let below = |paths: &[&str]| {
+                tests
+                    .iter()
+                    .filter(|test| paths.iter().any(|path| is_below(test, path)))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            };
+            let skipped = below(&skips);
+            if whole_target {
+                for test in skipped {
+                    test_args.push("--skip".to_owned());
+                    test_args.push(test);
+                }
+            } else {
+                test_args = below(&includes);
+                test_args.retain(|test| !skipped.contains(test));
+                if test_args.is_empty() {
+                    continue;
+                }
+            }
+            test_args.push("--exact".to_owned());
+        }
+
+        let Some(spec) = CargoTargetSpec::for_file(snap, file_id)? else { continue };
+        let workspace_root = spec.workspace_root.clone();
+        let (cargo_args, _) = CargoTargetSpec::runnable_args(
+            snap,
+            Some(spec),
+            &RunnableKind::TestMod { path: String::new() },
+            &None,
+        );
+
+        let mut command = match &config.override_cargo {
+            Some(cargo) => Command::new(cargo),
+            None => Command::new(toolchain::cargo()),
+        };
+        command.current_dir(&workspace_root);
+        command.envs(snap.config.extra_env());
+        command.args(cargo_args.iter().chain(&config.cargo_extra_args));
+
+        res.push(TestRun { target_id: target.id, command, test_args });
+    }
+    Ok(res)
+}
+
+/// The runs of a `RunTest` request, executed one target at a time.
+pub(crate) struct TestRunSession {
+    pub(crate) target_id: String,
+    pub(crate) handle: CargoTestHandle,
+    pending: VecDeque<TestRun>,
+}
+
+impl TestRunSession {
+    /// Starts the first of `runs` that can be spawned, reporting the errors of those that
+    /// can't to `on_error`.
+    pub(crate) fn start(
+        runs: impl IntoIterator<Item = TestRun>,
+        on_error: &mut dyn FnMut(String),
+    ) -> Option<TestRunSession> {
+        let mut pending: VecDeque<_> = runs.into_iter().collect();
+        while let Some(run) = pending.pop_front() {
+            let formatted_command = format!("{:?}", run.command);
+            match CargoTestHandle::spawn(run.command, &run.test_args) {
+                Ok(handle) => {
+                    return Some(TestRunSession { target_id: run.target_id, handle, pending })
+                }
+                Err(error) => on_error(format!(
+                    "Failed to run the following command: {formatted_command} error={error}"
+                )),
+            }
+        }
+        None
+    }
+
+    /// Starts the next run once the current one has finished.
+    pub(crate) fn next(self, on_error: &mut dyn FnMut(String)) -> Option<TestRunSession> {
+        TestRunSession::start(self.pending, on_error)
+    }
+}
+
+fn split_test_id(id: &str) -> (&str, Option<&str>) {
+    match id.split_once("::") {
+        Some((target, path)) => (target, Some(path)),
+        None => (id, None),
+    }
+}
+
+/// The paths of the test functions of the crate whose root is `file_id`.
+fn test_functions(snap: &GlobalStateSnapshot, file_id: FileId) -> Cancellable<Vec<String>> {
+    let mut res = Vec::new();
+    for &crate_id in snap.analysis.crates_for(file_id)?.iter().take(1) {
+        let tests = snap.analysis.discover_tests_in_crate(&snap.config.test_config(), crate_id)?;
+        res.extend(
+            tests.into_iter().filter(|it| it.kind == TestItemKind::Function).map(|it| it.path),
+        );
+    }
+    Ok(res)
+}
+
+/// Whether the test `test` is `path` itself or belongs to the module `path`.
+fn is_below(test: &str, path: &str) -> bool {
+    test.strip_prefix(path).is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+/// Whether a file has a `#[test]` attribute or one of the configured test attributes, so that it
+/// may declare tests. Attributes are matched by their last path segment, like the `test` of
+/// `#[tokio::test]`.
+pub(crate) fn may_contain_tests(text: &str, config: &TestConfig) -> bool {
+    let last_segment = |path: &str| path.rsplit("::").next().unwrap_or(path).trim().to_owned();
+    text.split("#[").skip(1).any(|attr| {
+        let path = attr.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':')).next();
+        let name = last_segment(path.unwrap_or_default());
+        name == "test" || config.test_attributes.iter().any(|it| last_segment(it) == name)
+    })
+}
+
+fn is_target_of(target: &TestTarget, spec: &CargoTargetSpec) -> bool {
+    let id = match spec.target_kind {
+        TargetKind::Lib => format!("{}/lib", spec.package),
+        TargetKind::Bin => format!("{}/bin/{}", spec.package, spec.target),
+        TargetKind::Test => format!("{}/test/{}", spec.package, spec.target),
+        _ => return false,
+    };
+    target.id == id
+}
+
+fn target_file_id(snap: &GlobalStateSnapshot, target: &TestTarget) -> Option<FileId> {
+    snap.url_to_file_id(&to_proto::url_from_abs_path(&target.root)).ok()
+}
+
+fn package_item(package_id: &str) -> lsp_ext::TestItem {
+    lsp_ext::TestItem {
+        id: package_id.to_owned(),
+        label: package_id.to_owned(),
+        kind: lsp_ext::TestItemKind::Package,
+        can_resolve_children: false,
+        parent: None,
+        text_document: None,
+        range: None,
+        runnable: None,
+    }
+}
+
+fn target_item(target: TestTarget) -> lsp_ext::TestItem {
+    lsp_ext::TestItem {
+        text_document: Some(lsp_types::TextDocumentIdentifier {
+            uri: to_proto::url_from_abs_path(&target.root),
+        }),
+        id: target.id,
+        label: target.label,
+        kind: lsp_ext::TestItemKind::Target,
+        can_resolve_children: target.harness,
+        parent: Some(target.package_id),
+        range: None,
+        runnable: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_below() {
+        assert!(is_below("tests::it_works", "tests::it_works"));
+        assert!(is_below("tests::it_works", "tests"));
+        assert!(!is_below("tests::it_works_too", "tests::it_works"));
+        assert!(!is_below("tests_extra::it_works", "tests"));
+    }
+
+    #[test]
+    fn test_may_contain_tests() {
+        let config = TestConfig { test_attributes: vec!["rstest::rstest".to_owned()] };
+        assert!(may_contain_tests("#[test]\nfn it_works() {}", &config));
+        assert!(may_contain_tests("#[tokio::test(flavor = \"multi_thread\")]", &config));
+        assert!(may_contain_tests("#[rstest]\nfn case() {}", &config));
+        assert!(!may_contain_tests("#[cfg(test)]\nfn test_helper() {}", &config));
+        assert!(!may_contain_tests("#[derive(Debug)]\nstruct Test;", &config));
+    }
+}
@@ -13,3 +13,13 @@ pub fn parse_expr_from_str(s: &str) -> Option<ast::Expr> {
     }
     Some(expr)
 }
+
+pub fn parse_ty_from_str(s: &str) -> Option<ast::Type> {
+    let s = s.trim();
+    let file = ast::SourceFile::parse(&format!("type _T = {s};"));
+    let ty = file.syntax_node().descendants().find_map(ast::Type::cast)?;
+    if ty.syntax().text() != s {
+        return None;
+    }
+    Some(ty)
+}
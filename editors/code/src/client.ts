@@ -397,6 +397,7 @@ class ExperimentalFeatures implements lc.StaticFeature {
                     "rust-analyzer.showReferences",
                     "rust-analyzer.gotoLocation",
                     "editor.action.triggerParameterHints",
+                    "rust-analyzer.completionAccepted",
                 ],
             },
             ...capabilities.experimental,
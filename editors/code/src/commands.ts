@@ -109,6 +109,33 @@ export function triggerParameterHints(_: CtxInit): Cmd {
     };
 }
 
+export function completionAccepted(ctx: CtxInit): Cmd {
+    return async (params: ra.CompletionAcceptedParams, triggerParameterHints: boolean) => {
+        await ctx.client.sendNotification(ra.completionAccepted, params);
+        if (triggerParameterHints) {
+            await vscode.commands.executeCommand("rust-analyzer.triggerParameterHints");
+        }
+    };
+}
+
+export function viewCompletionHistory(ctx: CtxInit): Cmd {
+    return async () => {
+        const entries = await ctx.client.sendRequest(ra.completionHistory);
+        const content = entries
+            .map((it) => {
+                const lastAccepted = new Date(it.lastAccepted * 1000).toLocaleString();
+                return `${it.count}\t${lastAccepted}\t${it.label}\t${it.qualifier}`;
+            })
+            .join("\n");
+        const document = await vscode.workspace.openTextDocument({ content });
+        await vscode.window.showTextDocument(document, vscode.ViewColumn.Two, true);
+    };
+}
+
+export function resetCompletionHistory(ctx: CtxInit): Cmd {
+    return async () => ctx.client.sendRequest(ra.resetCompletionHistory);
+}
+
 export function openLogs(ctx: CtxInit): Cmd {
     return async () => {
         if (ctx.client.outputChannel) {
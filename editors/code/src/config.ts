@@ -330,6 +330,10 @@ export class Config {
         return this.get<boolean>("showDependenciesExplorer");
     }
 
+    get testExplorer() {
+        return this.get<boolean>("testExplorer");
+    }
+
     get statusBarClickAction() {
         return this.get<string>("statusBar.clickAction");
     }
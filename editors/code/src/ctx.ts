@@ -22,6 +22,7 @@ import {
 import { execRevealDependency } from "./commands";
 import { PersistentState } from "./persistent_state";
 import { bootstrap } from "./bootstrap";
+import { prepareTestExplorer } from "./test_explorer";
 
 // We only support local folders, not eg. Live Share (`vlsl:` scheme), so don't activate if
 // only those are in use. We use "Empty" to represent these scenarios
@@ -245,6 +246,10 @@ export class Ctx {
         if (this.config.showDependenciesExplorer) {
             this.prepareTreeDependenciesView(client);
         }
+
+        if (this.config.testExplorer) {
+            prepareTestExplorer(client).forEach((it) => this.pushClientCleanup(it));
+        }
     }
 
     private prepareTreeDependenciesView(client: lc.LanguageClient) {
@@ -31,6 +31,12 @@ export const analyzerStatus = new lc.RequestType<AnalyzerStatusParams, string, v
 );
 export const cancelFlycheck = new lc.NotificationType0("rust-analyzer/cancelFlycheck");
 export const clearFlycheck = new lc.NotificationType0("rust-analyzer/clearFlycheck");
+export const completionAccepted = new lc.NotificationType<CompletionAcceptedParams>(
+    "rust-analyzer/completionAccepted",
+);
+export const completionHistory = new lc.RequestType0<CompletionHistoryEntry[], void>(
+    "rust-analyzer/completionHistory",
+);
 export const expandMacro = new lc.RequestType<ExpandMacroParams, ExpandedMacro | null, void>(
     "rust-analyzer/expandMacro",
 );
@@ -41,6 +47,9 @@ export const relatedTests = new lc.RequestType<lc.TextDocumentPositionParams, Te
 );
 export const reloadWorkspace = new lc.RequestType0<null, void>("rust-analyzer/reloadWorkspace");
 export const rebuildProcMacros = new lc.RequestType0<null, void>("rust-analyzer/rebuildProcMacros");
+export const resetCompletionHistory = new lc.RequestType0<null, void>(
+    "rust-analyzer/resetCompletionHistory",
+);
 
 export const runFlycheck = new lc.NotificationType<{
     textDocument: lc.TextDocumentIdentifier | null;
@@ -150,12 +159,32 @@ export const parentModule = new lc.RequestType<
 export const runnables = new lc.RequestType<RunnablesParams, Runnable[], void>(
     "experimental/runnables",
 );
+export const discoverTest = new lc.RequestType<DiscoverTestParams, DiscoverTestResults, void>(
+    "experimental/discoverTest",
+);
+export const discoveredTests = new lc.NotificationType<DiscoverTestResults>(
+    "experimental/discoveredTests",
+);
+export const runTest = new lc.RequestType<RunTestParams, void, void>("experimental/runTest");
+export const abortRunTest = new lc.NotificationType0("experimental/abortRunTest");
+export const endRunTest = new lc.NotificationType0("experimental/endRunTest");
+export const appendOutputToRunTest = new lc.NotificationType<string>(
+    "experimental/appendOutputToRunTest",
+);
+export const changeTestState = new lc.NotificationType<ChangeTestStateParams>(
+    "experimental/changeTestState",
+);
 export const serverStatus = new lc.NotificationType<ServerStatusParams>(
     "experimental/serverStatus",
 );
 export const ssr = new lc.RequestType<SsrParams, lc.WorkspaceEdit, void>("experimental/ssr");
+export const signatureSearch = new lc.RequestType<
+    SignatureSearchParams,
+    SignatureSearchResult[],
+    void
+>("rust-analyzer/signatureSearch");
 export const viewRecursiveMemoryLayout = new lc.RequestType<
-    lc.TextDocumentPositionParams,
+    ViewRecursiveMemoryLayoutParams,
     RecursiveMemoryLayout | null,
     void
 >("rust-analyzer/viewRecursiveMemoryLayout");
@@ -190,6 +219,29 @@ export type Runnable = {
         overrideCargo?: string;
     };
 };
+export type TestItemKind = "package" | "target" | "module" | "test";
+export type TestItem = {
+    id: string;
+    label: string;
+    kind: TestItemKind;
+    canResolveChildren: boolean;
+    parent?: string | undefined;
+    textDocument?: lc.TextDocumentIdentifier | undefined;
+    range?: lc.Range | undefined;
+    runnable?: Runnable | undefined;
+};
+export type DiscoverTestParams = { testId?: string | undefined };
+export type DiscoverTestResults = { tests: TestItem[]; scope?: string[] | undefined };
+export type RunTestParams = {
+    include?: string[] | undefined;
+    exclude?: string[] | undefined;
+};
+export type TestState =
+    | { tag: "started" }
+    | { tag: "passed" }
+    | { tag: "skipped" }
+    | { tag: "failed"; message: string };
+export type ChangeTestStateParams = { testId: string; state: TestState };
 export type RunnablesParams = {
     textDocument: lc.TextDocumentIdentifier;
     position: lc.Position | null;
@@ -206,10 +258,25 @@ export type SsrParams = {
     position: lc.Position;
     selections: readonly lc.Range[];
 };
+export type SignatureSearchParams = {
+    query: string;
+    textDocument?: lc.TextDocumentIdentifier;
+    searchScope?: "workspace" | "workspaceAndDependencies";
+};
+export type SignatureSearchResult = {
+    name: string;
+    containerName?: string;
+    signature: string;
+    location: lc.Location;
+};
 
+export type ViewRecursiveMemoryLayoutParams = lc.TextDocumentPositionParams & {
+    genericArgs?: string[];
+};
 export type RecursiveMemoryLayoutNode = {
     item_name: string;
     typename: string;
+    kind: "field" | "variant" | "tag" | "niche" | "padding";
     size: number;
     alignment: number;
     offset: number;
@@ -220,3 +287,14 @@ export type RecursiveMemoryLayoutNode = {
 export type RecursiveMemoryLayout = {
     nodes: RecursiveMemoryLayoutNode[];
 };
+
+export type CompletionAcceptedParams = {
+    label: string;
+    qualifier: string;
+};
+export type CompletionHistoryEntry = {
+    label: string;
+    qualifier: string;
+    count: number;
+    lastAccepted: number;
+};
@@ -0,0 +1,104 @@
+import * as vscode from "vscode";
+import type * as lc from "vscode-languageclient/node";
+import * as ra from "./lsp_ext";
+
+import type { Disposable } from "./ctx";
+
+export function prepareTestExplorer(client: lc.LanguageClient): Disposable[] {
+    const controller = vscode.tests.createTestController("rustAnalyzerTestController", "Rust");
+    const idToTest = new Map<string, vscode.TestItem>();
+    let currentTestRun: vscode.TestRun | undefined;
+
+    const addTest = (item: ra.TestItem) => {
+        const parentList = item.parent ? idToTest.get(item.parent)?.children : controller.items;
+        if (!parentList) {
+            return;
+        }
+        const uri = item.textDocument
+            ? client.protocol2CodeConverter.asUri(item.textDocument.uri)
+            : undefined;
+        const test = controller.createTestItem(item.id, item.label, uri);
+        test.range = item.range ? client.protocol2CodeConverter.asRange(item.range) : undefined;
+        test.canResolveChildren = item.canResolveChildren;
+        idToTest.set(item.id, test);
+        parentList.add(test);
+    };
+
+    const removeDescendants = (items: vscode.TestItemCollection) => {
+        items.forEach((it) => {
+            removeDescendants(it.children);
+            idToTest.delete(it.id);
+        });
+        items.replace([]);
+    };
+
+    const addTestGroup = (results: ra.DiscoverTestResults) => {
+        if (results.scope) {
+            for (const id of results.scope) {
+                const test = idToTest.get(id);
+                if (test) {
+                    removeDescendants(test.children);
+                }
+            }
+        } else {
+            idToTest.clear();
+            controller.items.replace([]);
+        }
+        for (const item of results.tests) {
+            addTest(item);
+        }
+    };
+
+    controller.resolveHandler = async (item) => {
+        const results = await client.sendRequest(ra.discoverTest, { testId: item?.id });
+        addTestGroup(results);
+    };
+
+    controller.createRunProfile(
+        "Run Tests",
+        vscode.TestRunProfileKind.Run,
+        async (request, token) => {
+            currentTestRun = controller.createTestRun(request);
+            token.onCancellationRequested(async () => {
+                await client.sendNotification(ra.abortRunTest);
+            });
+            await client.sendRequest(ra.runTest, {
+                include: request.include?.map((it) => it.id),
+                exclude: request.exclude?.map((it) => it.id),
+            });
+        },
+        true,
+    );
+
+    return [
+        controller,
+        client.onNotification(ra.discoveredTests, addTestGroup),
+        client.onNotification(ra.endRunTest, () => {
+            currentTestRun?.end();
+            currentTestRun = undefined;
+        }),
+        client.onNotification(ra.appendOutputToRunTest, (output) => {
+            currentTestRun?.appendOutput(output.replace(/\n/g, "\r\n"));
+        }),
+        client.onNotification(ra.changeTestState, ({ testId, state }) => {
+            const test = idToTest.get(testId);
+            if (!currentTestRun || !test) {
+                return;
+            }
+            switch (state.tag) {
+                case "started":
+                    currentTestRun.started(test);
+                    break;
+                case "passed":
+                    currentTestRun.passed(test);
+                    break;
+                case "skipped":
+                    currentTestRun.skipped(test);
+                    break;
+                case "failed":
+                    currentTestRun.failed(test, new vscode.TestMessage(state.message));
+                    break;
+            }
+        }),
+    ];
+}